@@ -0,0 +1,154 @@
+//! This module provides decoders for Code128 barcodes.
+//!
+//! Each 11-module character is matched to its symbol value, and the stop character and the
+//! modulo-103 check character are verified. The values are then interpreted in the character
+//! set selected by the start character, and by any switches and shifts that follow it. FNC1 is
+//! decoded as the `FNC1` character, so GS1-128 data can be recognised by its leading FNC1.
+
+use ::decode::{Decoded, Symbology, either_direction};
+use ::sym::code128::{CharacterSet, CODE128_CHARS, CODE128_STOP, FNC1, FNC1_VALUE, SHIFT};
+
+// The modules of each character.
+const CHAR_LEN: usize = 11;
+
+/// Decodes a Code128 barcode.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_code128(modules: &[u8]) -> Result<Decoded, String> {
+    either_direction(modules, code128)
+}
+
+fn code128(modules: &[u8]) -> Result<Decoded, String> {
+    let stop = CODE128_STOP.len();
+
+    // At least a start, one data and a check character.
+    if modules.len() < CHAR_LEN * 3 + stop || !(modules.len() - stop).is_multiple_of(CHAR_LEN) {
+        return Err(format!("Invalid number of modules: {}", modules.len()));
+    }
+
+    if modules[modules.len() - stop..] != CODE128_STOP {
+        return Err("Invalid stop character".to_string());
+    }
+
+    let mut values = modules[..modules.len() - stop].chunks(CHAR_LEN)
+        .enumerate()
+        .map(|(i, chunk)| match CODE128_CHARS.iter().position(|enc| enc[..] == chunk[..]) {
+            Some(v) => Ok(v as u8),
+            None => Err(format!("Invalid character at module {}", i * CHAR_LEN)),
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    let check = values.pop().unwrap();
+    let sum = values.iter()
+        .enumerate()
+        .fold(0, |acc, (i, &v)| acc + (v as usize) * i.max(1));
+
+    if sum % 103 != check as usize {
+        return Err(format!("Invalid check character: {}", check));
+    }
+
+    let (data, sets) = interpret(&values)?;
+
+    Ok(Decoded{symbology: Symbology::Code128, data, check: Some(check), sets})
+}
+
+// Interprets the symbol values, beginning with the start character, as data. Returns the
+// data and the character sets used.
+fn interpret(values: &[u8]) -> Result<(String, Vec<CharacterSet>), String> {
+    let mut set = match values[0] {
+        103 => CharacterSet::A,
+        104 => CharacterSet::B,
+        105 => CharacterSet::C,
+        v => return Err(format!("Invalid start character: {}", v)),
+    };
+    let mut sets = vec![set];
+    let mut data = String::new();
+    let mut i = 1;
+
+    while i < values.len() {
+        let v = values[i];
+        let switch = [CharacterSet::A, CharacterSet::B, CharacterSet::C].iter()
+            .cloned()
+            .find(|&to| set.switch_value(to) == Some(v));
+
+        if let Some(to) = switch {
+            set = to;
+            sets.push(set);
+        } else if v == FNC1_VALUE {
+            data.push(FNC1);
+        } else if set == CharacterSet::C && v < 100 {
+            data.push_str(&format!("{:02}", v));
+        } else if v == SHIFT && set != CharacterSet::C && i + 1 < values.len() {
+            let other = if set == CharacterSet::A { CharacterSet::B } else { CharacterSet::A };
+
+            i += 1;
+            data.push(char_in(other, values[i])?);
+        } else {
+            data.push(char_in(set, v)?);
+        }
+
+        i += 1;
+    }
+
+    Ok((data, sets))
+}
+
+// Returns the character of a value in character set A or B.
+fn char_in(set: CharacterSet, v: u8) -> Result<char, String> {
+    match set {
+        _ if v >= 96 => Err(format!("Unsupported function character: {}", v)),
+        CharacterSet::A if v >= 64 => Ok((v - 64) as char),
+        _ => Ok((v + 32) as char),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::decode::code128::*;
+    use ::sym::code128::*;
+    use ::sym::gs1_128::*;
+
+    #[test]
+    fn code128_round_trip() {
+        for data in &["Hello123", "1234567890", "ABC\tdef\r\n", "a1b2c3d4e5f6", "\x01lower"] {
+            let code128 = Code128::auto(data.to_string()).unwrap();
+            let decoded = decode_code128(&code128.encode()).unwrap();
+
+            assert_eq!(decoded.symbology, Symbology::Code128);
+            assert_eq!(decoded.data, data.to_string());
+            assert_eq!(decoded.check, Some(code128.checksum_value()));
+        }
+    }
+
+    #[test]
+    fn code128_sets() {
+        let code128 = Code128::new("ÀHELLOĆ1234Ɓabc".to_string()).unwrap();
+        let decoded = decode_code128(&code128.encode()).unwrap();
+
+        assert_eq!(decoded.data, "HELLO1234abc");
+        assert_eq!(decoded.sets, vec![CharacterSet::A, CharacterSet::C, CharacterSet::B]);
+    }
+
+    #[test]
+    fn gs1_128_as_code128() {
+        let gs1 = GS1_128::new("(00)106141411234567897".to_string()).unwrap();
+        let decoded = decode_code128(&gs1.encode()).unwrap();
+
+        assert_eq!(decoded.data, format!("{}00106141411234567897", FNC1));
+        assert_eq!(decoded.check, Some(34));
+    }
+
+    #[test]
+    fn invalid_code128() {
+        let mut modules = Code128::new("ƁHello123".to_string()).unwrap().encode();
+        let len = modules.len();
+
+        assert!(decode_code128(&modules[..len - 2]).is_err());
+
+        // Replace the 'e' with an 'f'.
+        modules[22..33].copy_from_slice(&CODE128_CHARS[70]);
+        assert!(decode_code128(&modules).unwrap_err().starts_with("Invalid check character"));
+
+        modules[len - 4] = 0;
+        assert_eq!(decode_code128(&modules), Err("Invalid stop character".to_string()));
+    }
+}
@@ -0,0 +1,110 @@
+//! This module provides decoders for Code39 barcodes.
+//!
+//! The start and stop characters ('*') and the narrow spaces between the characters are
+//! verified. The modulo-43 check character is optional in Code39, so it is only verified, and
+//! removed from the data, by `decode_code39_with_checksum`.
+
+use ::decode::{Decoded, Symbology, either_direction};
+use ::sym::code39::{CODE39_CHARS, CODE39_GUARD};
+
+// The modules of each character, including the space that follows it.
+const CHAR_LEN: usize = 13;
+
+/// Decodes a Code39 barcode, without a check character.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_code39(modules: &[u8]) -> Result<Decoded, String> {
+    either_direction(modules, |m| code39(m, false))
+}
+
+/// Decodes a Code39 barcode whose last character is a modulo-43 check character.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_code39_with_checksum(modules: &[u8]) -> Result<Decoded, String> {
+    either_direction(modules, |m| code39(m, true))
+}
+
+fn code39(modules: &[u8], checksum: bool) -> Result<Decoded, String> {
+    let guard = CODE39_GUARD.len();
+
+    if modules.len() < guard * 2 + 1 + CHAR_LEN || !(modules.len() - guard * 2 - 1).is_multiple_of(CHAR_LEN) {
+        return Err(format!("Invalid number of modules: {}", modules.len()));
+    }
+
+    if modules[..guard] != CODE39_GUARD || modules[modules.len() - guard..] != CODE39_GUARD ||
+        modules[guard] != 0 {
+        return Err("Invalid start or stop character".to_string());
+    }
+
+    let payload = &modules[guard + 1..modules.len() - guard];
+    let mut indices = vec![];
+
+    for (i, chunk) in payload.chunks(CHAR_LEN).enumerate() {
+        let position = guard + 1 + i * CHAR_LEN;
+
+        match CODE39_CHARS.iter().position(|&(_, enc)| enc[..] == chunk[..CHAR_LEN - 1]) {
+            Some(index) if chunk[CHAR_LEN - 1] == 0 => indices.push(index),
+            _ => return Err(format!("Invalid character at module {}", position)),
+        }
+    }
+
+    let mut check = None;
+
+    if checksum {
+        let index = indices.pop().unwrap();
+
+        if indices.is_empty() || indices.iter().sum::<usize>() % CODE39_CHARS.len() != index {
+            return Err(format!("Invalid check character: {}", CODE39_CHARS[index].0));
+        }
+
+        check = Some(index as u8);
+    }
+
+    Ok(Decoded{symbology: Symbology::Code39, data: indices.iter().map(|&i| CODE39_CHARS[i].0).collect(),
+               check, sets: vec![]})
+}
+
+#[cfg(test)]
+mod tests {
+    use ::decode::code39::*;
+    use ::sym::code39::*;
+
+    #[test]
+    fn code39_round_trip() {
+        for data in &["1ISTHELONELIESTNUMBER", "AB2C1674+1", "$ -./%", "0"] {
+            let code39 = Code39::new(data.to_string()).unwrap();
+            let decoded = decode_code39(&code39.encode()).unwrap();
+
+            assert_eq!(decoded.symbology, Symbology::Code39);
+            assert_eq!(decoded.data, data.to_string());
+            assert_eq!(decoded.check, None);
+        }
+    }
+
+    #[test]
+    fn code39_with_checksum() {
+        let code39 = Code39::with_checksum("AB2C1674+1".to_string()).unwrap();
+        let mut modules = vec![0; 10];
+        modules.extend(code39.encode().iter().rev());
+
+        let decoded = decode_code39_with_checksum(&modules).unwrap();
+
+        assert_eq!(decoded.data, "AB2C1674+1");
+        assert_eq!(decoded.check, Some(CODE39_CHARS.iter().position(|&(c, _)| Some(c) == code39.checksum_char()).unwrap() as u8));
+
+        // Without a check character, the last data character is taken as one.
+        let code39 = Code39::new("AB2C1674+1".to_string()).unwrap();
+        assert_eq!(decode_code39_with_checksum(&code39.encode()), Err("Invalid check character: 1".to_string()));
+    }
+
+    #[test]
+    fn invalid_code39() {
+        let mut modules = Code39::new("ABC".to_string()).unwrap().encode();
+
+        assert!(decode_code39(&modules[..modules.len() - 1]).is_err());
+
+        modules[13] = 0;
+        assert_eq!(decode_code39(&modules), Err("Invalid character at module 13".to_string()));
+
+        modules[2] = 1;
+        assert_eq!(decode_code39(&modules), Err("Invalid start or stop character".to_string()));
+    }
+}
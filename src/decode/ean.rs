@@ -0,0 +1,169 @@
+//! This module provides decoders for EAN-13 and EAN-8 barcodes.
+//!
+//! The left, middle and right guards are verified, and each digit is matched against the EAN
+//! encodings. The first digit of an EAN-13 barcode is not encoded directly, but derived from the
+//! parities of the five left-hand digits that follow the second. The check digit is verified
+//! last, and is included in the decoded data.
+
+use ::decode::{Decoded, Symbology, either_direction};
+use ::sym::helpers;
+use ::sym::ean13::{EAN_ENCODINGS, EAN_LEFT_GUARD, EAN_MIDDLE_GUARD, EAN_PARITY, EAN_RIGHT_GUARD};
+
+// The encodings tried for each side: odd or even parity on the left, and the right side
+// encodings.
+const LEFT_SETS: [usize; 2] = [0, 1];
+const RIGHT_SETS: [usize; 1] = [2];
+
+// The digits of one half of a barcode, each with the encoding set that matched it.
+type Half = Vec<(u8, usize)>;
+
+/// Decodes an EAN-13 (or UPC-A) barcode.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_ean13(modules: &[u8]) -> Result<Decoded, String> {
+    either_direction(modules, ean13)
+}
+
+/// Decodes an EAN-8 barcode.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_ean8(modules: &[u8]) -> Result<Decoded, String> {
+    either_direction(modules, ean8)
+}
+
+fn ean13(modules: &[u8]) -> Result<Decoded, String> {
+    let (left, right) = halves(modules, 6)?;
+
+    if left[0].1 != 0 {
+        return Err("Invalid parity for the second digit".to_string());
+    }
+
+    let parity: Vec<usize> = left[1..].iter().map(|&(_, p)| p).collect();
+    let first = match EAN_PARITY.iter().position(|p| p[..] == parity[..]) {
+        Some(d) => d as u8,
+        None => return Err("Invalid parity pattern".to_string()),
+    };
+
+    let digits: Vec<u8> = Some(first).into_iter()
+        .chain(left.iter().chain(right.iter()).map(|&(d, _)| d))
+        .collect();
+
+    checked(Symbology::EAN13, &digits)
+}
+
+fn ean8(modules: &[u8]) -> Result<Decoded, String> {
+    let (left, right) = halves(modules, 4)?;
+
+    if left.iter().any(|&(_, p)| p != 0) {
+        return Err("Invalid parity for the left digits".to_string());
+    }
+
+    let digits: Vec<u8> = left.iter().chain(right.iter()).map(|&(d, _)| d).collect();
+
+    checked(Symbology::EAN8, &digits)
+}
+
+// Verifies the guards around two halves of `half` digits each, and returns the digits of
+// each half.
+fn halves(modules: &[u8], half: usize) -> Result<(Half, Half), String> {
+    let len = 3 + half * 7 + 5 + half * 7 + 3;
+
+    if modules.len() != len {
+        return Err(format!("Expected {} modules, found {}", len, modules.len()));
+    }
+
+    let middle = 3 + half * 7;
+
+    if modules[..3] != EAN_LEFT_GUARD || modules[middle..middle + 5] != EAN_MIDDLE_GUARD ||
+        modules[len - 3..] != EAN_RIGHT_GUARD {
+        return Err("Invalid guard pattern".to_string());
+    }
+
+    Ok((digits(&modules[3..middle], 3, &LEFT_SETS)?, digits(&modules[middle + 5..len - 3], middle + 5, &RIGHT_SETS)?))
+}
+
+// Matches each 7-module character against the encodings of the given sets. `offset` is the
+// position of the first module, for errors.
+fn digits(modules: &[u8], offset: usize, sets: &[usize]) -> Result<Half, String> {
+    modules.chunks(7).enumerate().map(|(i, chunk)| {
+        sets.iter()
+            .flat_map(|&set| (0..10).map(move |d| (d as u8, set)))
+            .find(|&(d, set)| EAN_ENCODINGS[set][d as usize][..] == chunk[..])
+            .ok_or_else(|| format!("Invalid character at module {}", offset + i * 7))
+    }).collect()
+}
+
+// Verifies the check digit, the last of the digits.
+fn checked(symbology: Symbology, digits: &[u8]) -> Result<Decoded, String> {
+    let (&check, data) = digits.split_last().unwrap();
+
+    if helpers::modulo_10_checksum(data) != check {
+        return Err(format!("Invalid check digit: {}", check));
+    }
+
+    Ok(Decoded{symbology, data: digits.iter().map(|&d| (b'0' + d) as char).collect(), check: None, sets: vec![]})
+}
+
+#[cfg(test)]
+mod tests {
+    use ::decode::ean::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::upca::*;
+
+    #[test]
+    fn ean13_round_trip() {
+        for first in 0..10 {
+            let data = format!("{}50103131130", first);
+            let ean13 = EAN13::new(data.clone()).unwrap();
+            let decoded = decode_ean13(&ean13.encode()).unwrap();
+
+            assert_eq!(decoded.symbology, Symbology::EAN13);
+            assert_eq!(decoded.data, format!("{}{}", data, ean13.checksum_digit()));
+        }
+    }
+
+    #[test]
+    fn ean8_round_trip() {
+        for data in &["5512345", "0000000", "9638507"] {
+            let ean8 = EAN8::new(data.to_string()).unwrap();
+            let decoded = decode_ean8(&ean8.encode()).unwrap();
+
+            assert_eq!(decoded.symbology, Symbology::EAN8);
+            assert_eq!(decoded.data, format!("{}{}", data, ean8.checksum_digit()));
+        }
+    }
+
+    #[test]
+    fn upca_as_ean13() {
+        let upca = UPCA::new("01234512345".to_string()).unwrap();
+
+        assert_eq!(decode_ean13(&upca.encode()).unwrap().data, "0012345123450");
+    }
+
+    #[test]
+    fn ean13_with_quiet_zones_reversed() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let mut modules = vec![0; 11];
+        modules.extend(ean13.encode().iter().rev());
+        modules.extend(vec![0; 7]);
+
+        assert_eq!(decode_ean13(&modules).unwrap().data, "7501031311309");
+    }
+
+    #[test]
+    fn invalid_ean13() {
+        let mut modules = EAN13::new("750103131130".to_string()).unwrap().encode();
+
+        assert_eq!(decode_ean8(&modules), Err("Expected 67 modules, found 95".to_string()));
+
+        // Replace the check digit (9) with the right side encoding of 8.
+        modules[85..92].copy_from_slice(&EAN_ENCODINGS[2][8]);
+        assert_eq!(decode_ean13(&modules), Err("Invalid check digit: 8".to_string()));
+
+        modules[46] = 0;
+        assert_eq!(decode_ean13(&modules), Err("Invalid guard pattern".to_string()));
+
+        let mut modules = EAN13::new("750103131130".to_string()).unwrap().encode();
+        modules[10] = 1 - modules[10];
+        assert!(decode_ean13(&modules).is_err());
+    }
+}
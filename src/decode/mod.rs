@@ -0,0 +1,103 @@
+//! This module provides decoders, which recover the data of a barcode from its modules: the
+//! binary digits of an `EncodedBarcode`, as produced by the types in `sym`, or as sampled from
+//! a scan line. 1 = bar, 0 = no bar.
+//!
+//! Light modules before and after the symbol, such as its quiet zones, are ignored, and a
+//! symbol read from right to left is decoded as if it had been read from left to right.
+//! Barcodes can also be decoded from the widths of their bars and spaces (see `widths`).
+//!
+//! This module defines decoders for:
+//!   * EAN-13 (and so UPC-A, as EAN-13 beginning with 0)
+//!   * EAN-8
+//!   * Code39
+//!   * Code128
+
+pub mod code128;
+pub mod code39;
+pub mod ean;
+pub mod widths;
+
+use ::sym::code128::CharacterSet;
+
+/// The symbologies that can be decoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symbology {
+    EAN13,
+    EAN8,
+    Code39,
+    Code128,
+}
+
+/// A decoded barcode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decoded {
+    /// The symbology of the barcode.
+    pub symbology: Symbology,
+    /// The data. EAN check digits are included, but other check characters are not.
+    pub data: String,
+    /// The value of the check character that was verified, if it is not part of the data.
+    pub check: Option<u8>,
+    /// The character sets of a Code128 barcode: the start set, then each set switched to.
+    pub sets: Vec<CharacterSet>,
+}
+
+/// A decoder of a single symbology.
+pub type Decoder = fn(&[u8]) -> Result<Decoded, String>;
+
+// The decoders tried, in order, by `decode`.
+const DECODERS: [Decoder; 4] = [ean::decode_ean13, ean::decode_ean8, code128::decode_code128, code39::decode_code39];
+
+/// Decodes a barcode of any of the supported symbologies.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode(modules: &[u8]) -> Result<Decoded, String> {
+    DECODERS.iter()
+        .filter_map(|decoder| decoder(modules).ok())
+        .next()
+        .ok_or_else(|| "No supported barcode found".to_string())
+}
+
+// Returns the modules from the first bar to the last.
+fn trim(modules: &[u8]) -> &[u8] {
+    match (modules.iter().position(|&m| m == 1), modules.iter().rposition(|&m| m == 1)) {
+        (Some(start), Some(end)) => &modules[start..end + 1],
+        _ => &[],
+    }
+}
+
+// Decodes the trimmed modules, or else the same modules reversed. The error is that of the
+// forward reading.
+fn either_direction<F>(modules: &[u8], decode: F) -> Result<Decoded, String>
+    where F: Fn(&[u8]) -> Result<Decoded, String> {
+    let modules = trim(modules);
+
+    decode(modules).or_else(|e| {
+        let reversed: Vec<u8> = modules.iter().rev().cloned().collect();
+
+        decode(&reversed).map_err(|_| e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ::decode::*;
+    use ::sym::ean13::EAN13;
+    use ::sym::ean8::EAN8;
+    use ::sym::code39::Code39;
+    use ::sym::code128::Code128;
+
+    #[test]
+    fn decode_any() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap().encode();
+        let ean8 = EAN8::new("5512345".to_string()).unwrap().encode();
+
+        let code39 = Code39::new("AB2C1674+1".to_string()).unwrap().encode();
+        let code128 = Code128::new("ƁHello123".to_string()).unwrap().encode();
+
+        assert_eq!(decode(&ean13).unwrap().symbology, Symbology::EAN13);
+        assert_eq!(decode(&ean8).unwrap().data, "55123457");
+        assert_eq!(decode(&code39).unwrap().symbology, Symbology::Code39);
+        assert_eq!(decode(&code128).unwrap().data, "Hello123");
+        assert!(decode(&[0, 1, 0, 1, 1, 0]).is_err());
+        assert!(decode(&[]).is_err());
+    }
+}
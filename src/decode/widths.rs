@@ -0,0 +1,250 @@
+//! This module decodes barcodes from the widths of their bars and spaces, such as the times
+//! between the edges seen by a hardware scanner, rather than from exact modules.
+//!
+//! The widths alternate between bars and spaces, beginning with the first bar, and may be in any
+//! unit. They are grouped into the characters of each symbology in turn, and each character is
+//! classified on its own: its width is divided by the number of modules it spans, and each of
+//! its elements rounded to a whole number of those modules. Changes in scanning speed across
+//! the symbol, Code39 wide elements of two to three times the narrow width, and bars printed a
+//! little wider or narrower than their spaces are all tolerated. The modules are then decoded as
+//! usual.
+
+use ::decode::{Decoded, Decoder, Symbology, code128, code39, ean};
+use ::sym::EncodedBarcode;
+use std::iter::repeat_n;
+
+/// The default tolerance: the furthest an element may be from the whole number of modules it
+/// is classified as, in modules.
+pub const DEFAULT_TOLERANCE: f64 = 0.5;
+
+// The symbologies tried, in order, by `decode_widths`.
+const SYMBOLOGIES: [Symbology; 4] = [Symbology::EAN13, Symbology::EAN8, Symbology::Code128, Symbology::Code39];
+
+/// Decodes a barcode of any of the supported symbologies from the widths of its bars and
+/// spaces, with the default tolerance. A symbol read from right to left is also decoded.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_widths(widths: &[f64]) -> Result<Decoded, String> {
+    decode_widths_with_tolerance(widths, DEFAULT_TOLERANCE)
+}
+
+/// Decodes a barcode from the widths of its bars and spaces, as `decode_widths`, with the given
+/// tolerance in modules.
+/// Returns Result<Decoded, String> indicating decoding success.
+pub fn decode_widths_with_tolerance(widths: &[f64], tolerance: f64) -> Result<Decoded, String> {
+    if widths.iter().any(|&w| !(w.is_finite() && w > 0.0)) {
+        return Err("Widths must be positive".to_string());
+    }
+
+    let reversed: Vec<f64> = widths.iter().rev().cloned().collect();
+    let mut error = "No supported barcode found".to_string();
+
+    for &symbology in &SYMBOLOGIES {
+        for w in &[widths, &reversed[..]] {
+            match classify(w, symbology, tolerance).and_then(|modules| decoder(symbology)(&modules)) {
+                Ok(decoded) => return Ok(decoded),
+                Err(e) => if layout(symbology, w.len()).is_some() { error = e },
+            }
+        }
+    }
+
+    Err(error)
+}
+
+/// Classifies the widths of the bars and spaces of a barcode of the given symbology as whole
+/// modules, character by character.
+/// Returns Result<EncodedBarcode, String> indicating classification success.
+pub fn classify(widths: &[f64], symbology: Symbology, tolerance: f64) -> Result<EncodedBarcode, String> {
+    let groups = match layout(symbology, widths.len()) {
+        Some(groups) => groups,
+        None => return Err(format!("Invalid number of elements for {:?}: {}", symbology, widths.len())),
+    };
+    let mut modules = vec![];
+    let mut start = 0;
+
+    for (elements, len) in groups {
+        let counts = normalize(&widths[start..start + elements], len, tolerance)
+            .map_err(|i| format!("Element {} is out of tolerance", start + i))?;
+
+        for (i, &count) in counts.iter().enumerate() {
+            let bar = (start + i) % 2 == 0;
+            modules.extend(repeat_n(if bar { 1 } else { 0 }, count as usize));
+        }
+
+        start += elements;
+    }
+
+    Ok(modules)
+}
+
+// Returns the decoder of the modules of a symbology.
+fn decoder(symbology: Symbology) -> Decoder {
+    match symbology {
+        Symbology::EAN13 => ean::decode_ean13,
+        Symbology::EAN8 => ean::decode_ean8,
+        Symbology::Code39 => code39::decode_code39,
+        Symbology::Code128 => code128::decode_code128,
+    }
+}
+
+// Returns the number of elements and modules of each character (or guard) of a symbol of
+// `len` elements, or None if the symbology has no symbol of that many elements.
+fn layout(symbology: Symbology, len: usize) -> Option<Vec<(usize, u32)>> {
+    let ean = |half| {
+        let mut groups = vec![(3, 3)];
+        groups.extend(repeat_n((4, 7), half));
+        groups.push((5, 5));
+        groups.extend(repeat_n((4, 7), half));
+        groups.push((3, 3));
+        groups
+    };
+
+    let groups = match symbology {
+        Symbology::EAN13 => ean(6),
+        Symbology::EAN8 => ean(4),
+        // Characters of six elements, then the stop character of seven.
+        Symbology::Code128 if len >= 6 * 3 + 7 && (len - 7).is_multiple_of(6) => {
+            let mut groups = vec![(6, 11); (len - 7) / 6];
+            groups.push((7, 13));
+            groups
+        },
+        // The start character, then each character, followed by the space between characters,
+        // and the stop character.
+        Symbology::Code39 if len >= 10 * 2 + 9 && (len - 9).is_multiple_of(10) => {
+            let mut groups: Vec<(usize, u32)> = repeat_n(vec![(9, 12), (1, 1)], (len - 9) / 10).flatten().collect();
+            groups.push((9, 12));
+            groups
+        },
+        _ => return None,
+    };
+
+    if groups.iter().map(|&(elements, _)| elements).sum::<usize>() == len { Some(groups) } else { None }
+}
+
+// Rounds the widths of the elements of a character to whole modules, so that together they
+// span `len` modules. Returns the index of the first element that is further than the
+// tolerance from its count.
+fn normalize(widths: &[f64], len: u32, tolerance: f64) -> Result<Vec<u32>, usize> {
+    let unit = widths.iter().sum::<f64>() / len as f64;
+    let exact: Vec<f64> = widths.iter().map(|w| w / unit).collect();
+    let mut counts: Vec<u32> = exact.iter().map(|e| (e.round() as u32).max(1)).collect();
+
+    // Rounding may leave the character a module too wide or narrow, so the elements that were
+    // rounded furthest are corrected.
+    loop {
+        let total: u32 = counts.iter().sum();
+        let residual = |i: usize| exact[i] - counts[i] as f64;
+
+        if total < len {
+            let i = (0..counts.len()).max_by(|&a, &b| residual(a).total_cmp(&residual(b))).unwrap();
+            counts[i] += 1;
+        } else if total > len {
+            match (0..counts.len()).filter(|&i| counts[i] > 1).min_by(|&a, &b| residual(a).total_cmp(&residual(b))) {
+                Some(i) => counts[i] -= 1,
+                None => return Err(0),
+            }
+        } else {
+            break;
+        }
+    }
+
+    match (0..counts.len()).find(|&i| (exact[i] - counts[i] as f64).abs() > tolerance) {
+        Some(i) => Err(i),
+        None => Ok(counts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::decode::widths::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::code39::*;
+    use ::sym::code128::*;
+
+    // Returns the widths of the runs of bars and spaces, each module `scale` wide.
+    fn runs(modules: &[u8], scale: f64) -> Vec<f64> {
+        let mut widths: Vec<f64> = vec![];
+        let mut previous = None;
+
+        for &m in modules {
+            if previous == Some(m) {
+                *widths.last_mut().unwrap() += scale;
+            } else {
+                widths.push(scale);
+            }
+
+            previous = Some(m);
+        }
+
+        widths
+    }
+
+    #[test]
+    fn ean_from_widths() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+
+        assert_eq!(decode_widths(&runs(&ean13.encode(), 3.0)).unwrap().data, "7501031311309");
+        assert_eq!(decode_widths(&runs(&ean8.encode(), 0.25)).unwrap().data, "55123457");
+        assert_eq!(classify(&runs(&ean13.encode(), 2.0), Symbology::EAN13, 0.0).unwrap(), ean13.encode());
+    }
+
+    #[test]
+    fn widths_with_changing_speed() {
+        let code128 = Code128::auto("Hello123".to_string()).unwrap();
+        let widths = runs(&code128.encode(), 10.0);
+
+        // The scan slows down from one end of the symbol to the other, to half speed.
+        let n = widths.len() as f64;
+        let slowed: Vec<f64> = widths.iter().enumerate().map(|(i, w)| w * (1.0 + i as f64 / n)).collect();
+
+        assert_eq!(decode_widths(&slowed).unwrap().data, "Hello123");
+    }
+
+    #[test]
+    fn widths_with_ink_spread() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+
+        // Bars are 0.3 modules too wide and spaces 0.3 modules too narrow, read in reverse.
+        let spread: Vec<f64> = runs(&ean13.encode(), 1.0).iter()
+            .enumerate()
+            .map(|(i, w)| if i % 2 == 0 { w + 0.3 } else { w - 0.3 })
+            .rev()
+            .collect();
+
+        assert_eq!(decode_widths(&spread).unwrap().data, "7501031311309");
+        assert!(decode_widths_with_tolerance(&spread, 0.2).is_err());
+    }
+
+    #[test]
+    fn code39_with_wide_ratio() {
+        let code39 = Code39::new("AB2C1674+1".to_string()).unwrap();
+
+        // Wide elements three times the narrow width, and wider spaces between characters.
+        let widths: Vec<f64> = runs(&code39.encode(), 1.0).iter()
+            .enumerate()
+            .map(|(i, &w)| if i % 10 == 9 { 2.0 } else if w > 1.0 { 3.0 } else { 1.0 })
+            .collect();
+
+        let decoded = decode_widths(&widths).unwrap();
+
+        assert_eq!(decoded.symbology, Symbology::Code39);
+        assert_eq!(decoded.data, "AB2C1674+1");
+    }
+
+    #[test]
+    fn invalid_widths() {
+        let mut widths = runs(&EAN13::new("750103131130".to_string()).unwrap().encode(), 1.0);
+
+        assert_eq!(decode_widths(&widths[..10]), Err("No supported barcode found".to_string()));
+        assert_eq!(classify(&widths[..10], Symbology::EAN13, 0.5), Err("Invalid number of elements for EAN13: 10".to_string()));
+
+        // The widest space of the first left-hand character is half as wide.
+        widths[5] = 1.5;
+        assert!(classify(&widths, Symbology::EAN13, 0.5).is_ok());
+        assert_eq!(classify(&widths, Symbology::EAN13, 0.3), Err("Element 4 is out of tolerance".to_string()));
+
+        widths[5] = -1.0;
+        assert_eq!(decode_widths(&widths), Err("Widths must be positive".to_string()));
+    }
+}
@@ -0,0 +1,189 @@
+//! This module provides types for generating ANSI terminal representations of barcodes, which
+//! can be scanned straight off the screen.
+//!
+//! Each character cell holds two pixels, one above the other, drawn with the upper half block
+//! character '▀' and 24-bit foreground and background colors. This doubles the vertical
+//! density, so that a module `xdim` characters wide is also `xdim` pixels high and square.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The ANSI barcode generator type.
+pub struct ANSI {
+    /// The height of the barcode in pixels, two to each line.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For ANSI, each will be ```self.xdim``` characters wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for ANSI {
+    fn default() -> ANSI {
+        ANSI::new()
+    }
+}
+
+// The escape sequence that restores the terminal's own colors.
+const RESET: &str = "\x1b[0m";
+
+impl ANSI {
+    /// Returns a new ANSI with default values.
+    pub fn new() -> ANSI {
+        ANSI{height: 20, xdim: 1, quiet_zone: 4, foreground: Color::BLACK, background: Color::WHITE,
+             rotation: Rotation::Normal}
+    }
+
+    /// Returns this generator with the given options applied, with widths in characters and
+    /// heights in half characters.
+    pub fn with_options(self, options: &Options) -> ANSI {
+        ANSI{height: options.height_pixels(), xdim: options.xdim_pixels(),
+             quiet_zone: options.quiet_zone_modules(),
+             foreground: options.foreground, background: options.background, rotation: options.rotation}
+    }
+
+    // Returns the color of a pixel, treating pixels below the image as background.
+    fn color(&self, pixels: &Pixels, x: usize, y: usize) -> Color {
+        if y >= pixels.height as usize {
+            return self.background;
+        }
+
+        match (pixels.data[y * pixels.stride() + x / 8] >> (7 - x % 8)) & 1 {
+            1 => self.foreground,
+            _ => self.background,
+        }
+    }
+
+    // Draws one bit per pixel as lines of half blocks, changing colors only where they
+    // differ from the previous character.
+    fn lines(&self, pixels: Pixels) -> String {
+        let mut lines = vec![];
+
+        for y in (0..pixels.height as usize).step_by(2) {
+            let mut line = String::new();
+            let mut current = None;
+
+            for x in 0..pixels.width as usize {
+                let colors = (self.color(&pixels, x, y), self.color(&pixels, x, y + 1));
+
+                if current != Some(colors) {
+                    let (top, bottom) = (colors.0.rgb, colors.1.rgb);
+                    line.push_str(&format!("\x1b[38;2;{};{};{};48;2;{};{};{}m", top[0], top[1], top[2],
+                                           bottom[0], bottom[1], bottom[2]));
+                    current = Some(colors);
+                }
+
+                line.push('▀');
+            }
+
+            line.push_str(RESET);
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    // Returns a raw pixel generator with the same dimensions.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.raw().generate(barcode).map(|p| self.lines(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        self.raw().generate_bars(bars).map(|p| self.lines(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        self.raw().generate_rows(rows).map(|p| self.lines(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.lines(p))
+    }
+}
+
+impl Generator for ANSI {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        ANSI::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::ansi::*;
+
+    const DARK: &str = "\x1b[38;2;0;0;0;48;2;0;0;0m";
+    const LIGHT: &str = "\x1b[38;2;255;255;255;48;2;255;255;255m";
+
+    #[test]
+    fn code_39_as_ansi() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let ansi = ANSI{height: 4, quiet_zone: 1, ..ANSI::new()};
+        let generated = ansi.generate(&code39.encode()).unwrap();
+        let lines: Vec<&str> = generated.split('\n').collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], lines[1]);
+        assert!(lines[0].starts_with(&format!("{}▀{}▀{}▀▀{}▀", LIGHT, DARK, LIGHT, DARK)));
+        assert!(lines[0].ends_with(&format!("{}▀\x1b[0m", LIGHT)));
+        assert_eq!(lines[0].matches('▀').count(), 40);
+    }
+
+    #[test]
+    fn ansi_odd_height() {
+        let ansi = ANSI{height: 3, quiet_zone: 0, ..ANSI::new()};
+        let generated = ansi.generate(&vec![1]).unwrap();
+
+        assert_eq!(generated, format!("{}▀\x1b[0m\n\x1b[38;2;0;0;0;48;2;255;255;255m▀\x1b[0m", DARK));
+    }
+
+    #[test]
+    fn bars_as_ansi() {
+        let ansi = ANSI{height: 6, quiet_zone: 0, ..ANSI::new()};
+        let generated = ansi.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        // The descender starts on the third pixel row, so is light on the first line.
+        assert!(generated.starts_with(&format!("{}▀{}▀▀\x1b[0m\n", DARK, LIGHT)));
+    }
+
+    #[test]
+    fn qr_as_ansi() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = ANSI::new().generate_matrix(&qr.encode()).unwrap();
+
+        // 29 pixel rows make 15 lines.
+        assert_eq!(generated.split('\n').count(), 15);
+        assert!(generated.split('\n').all(|l| l.matches('▀').count() == 29));
+    }
+}
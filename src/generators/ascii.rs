@@ -1,8 +1,17 @@
 //! This module provides types for generating ASCII representations of barcodes. This is useful for
 //! testing and simple verification of barcode correctness.
+//!
+//! Bars are drawn with '#' by default, or with the full block character '█' for a solid preview
+//! in terminals that support it.
 
 use ::sym::EncodedBarcode;
-use std::iter::repeat;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use std::iter::repeat_n;
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
 
 /// The ASCII barcode generator type.
 pub struct ASCII {
@@ -11,38 +20,113 @@ pub struct ASCII {
     /// The X dimension. Specifies the width of the "narrow" bars. 
     /// For ASCII, each will be ```self.xdim``` characters wide.
     pub xdim: usize,
+    /// The characters drawn for spaces and bars, in that order.
+    pub chars: [char; 2],
 }
 
 /// Maps binary digits to ASCII representation (0=' ', 1='#')
 pub const ASCII_CHARS: [char; 2] = [' ', '#'];
 
+/// Maps binary digits to block characters (0=' ', 1='█')
+pub const BLOCK_CHARS: [char; 2] = [' ', '█'];
+
+impl Default for ASCII {
+    fn default() -> ASCII {
+        ASCII::new()
+    }
+}
+
 impl ASCII {
     /// Returns a new ASCII with default values.
     pub fn new() -> ASCII {
-        ASCII{height: 10, xdim: 1}
+        ASCII{height: 10, xdim: 1, chars: ASCII_CHARS}
+    }
+
+    /// Returns a new ASCII with default values that draws bars with block characters.
+    pub fn blocks() -> ASCII {
+        ASCII{chars: BLOCK_CHARS, ..ASCII::new()}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in characters.
+    pub fn with_options(self, options: &Options) -> ASCII {
+        ASCII{height: options.height_pixels() as usize, xdim: options.xdim_pixels() as usize, ..self}
     }
 
     fn generate_row(&self, barcode: &EncodedBarcode) -> String {
         barcode.iter()
-               .flat_map(|&d| repeat(ASCII_CHARS[d as usize]).take(self.xdim))
+               .flat_map(|&d| repeat_n(self.chars[d as usize], self.xdim))
                .collect()
     }
 
     /// Generates the given EncodedBarcode. Returns a String.
     pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
         let mut output = String::new();
-        let row = self.generate_row(&barcode);
+        let row = self.generate_row(barcode);
 
         for (i, _l) in (0..self.height).enumerate() {
             output.push_str(&row[..]);
 
             if i < self.height - 1 {
-                output.push_str("\n");
+                output.push('\n');
             }
         }
 
         Ok(output)
     }
+
+    // Returns whether a bar in the given state covers row `r`. The rows are split into
+    // thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    fn covers_row(&self, bar: BarState, r: usize) -> bool {
+        let section = self.height / 3;
+        let upper = r < section;
+        let lower = r >= self.height - section;
+
+        match bar {
+            BarState::Full => true,
+            BarState::Ascender => !lower,
+            BarState::Descender => !upper,
+            BarState::Tracker => !upper && !lower,
+        }
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        let rows: Vec<String> = (0..self.height).map(|r| {
+            let cells: Vec<String> = bars.iter()
+                .map(|&b| repeat_n(self.chars[self.covers_row(b, r) as usize], self.xdim).collect())
+                .collect();
+
+            cells.join(&repeat_n(self.chars[0], self.xdim).collect::<String>())
+        }).collect();
+
+        Ok(rows.join("\n"))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is repeated for its
+    /// height, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        let lines: Vec<String> = rows.iter()
+            .flat_map(|row| repeat_n(self.generate_row(&row.modules), row.height as usize))
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each row of modules is one line
+    /// high, so `self.height` is not used. Returns a String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        let lines: Vec<String> = matrix.rows().iter().map(|r| self.generate_row(r)).collect();
+
+        Ok(lines.join("\n"))
+    }
+}
+
+impl Generator for ASCII {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        ASCII::generate(self, barcode).map_err(GeneratorError::from)
+    }
 }
 
 #[cfg(test)]
@@ -51,6 +135,9 @@ mod tests {
     use ::sym::ean8::*;
     use ::sym::ean_supp::*;
     use ::sym::code39::*;
+    use ::sym::pharmacode::*;
+    use ::sym::databar::*;
+    use ::sym2d::qr::*;
     use ::generators::ascii::*;
 
     #[test]
@@ -77,7 +164,7 @@ mod tests {
     #[test]
     fn ean_13_as_ascii_small_height_double_width() {
         let ean13 = EAN13::new("750103131130".to_string()).unwrap();
-        let ascii = ASCII{height: 6, xdim: 2};
+        let ascii = ASCII{height: 6, xdim: 2, chars: ASCII_CHARS};
         let generated = ascii.generate(&ean13.encode()).unwrap();
 
         assert_eq!(generated,
@@ -115,7 +202,7 @@ mod tests {
     #[test]
     fn ean_8_as_ascii_small_height_double_width() {
         let ean8 = EAN8::new("1234567".to_string()).unwrap();
-        let ascii = ASCII{height: 5, xdim: 2};
+        let ascii = ASCII{height: 5, xdim: 2, chars: ASCII_CHARS};
         let generated = ascii.generate(&ean8.encode()).unwrap();
 
         assert_eq!(generated,
@@ -152,7 +239,7 @@ mod tests {
     #[test]
     fn code_39_as_ascii_small_height_double_weight() {
         let code39 = Code39::new("1234".to_string()).unwrap();
-        let ascii = ASCII{height: 7, xdim: 2};
+        let ascii = ASCII{height: 7, xdim: 2, chars: ASCII_CHARS};
         let generated = ascii.generate(&code39.encode()).unwrap();
 
         assert_eq!(generated,
@@ -206,6 +293,70 @@ mod tests {
 # ## ##   # # #  ### #  #   # #   # ## #   # ##
 # ## ##   # # #  ### #  #   # #   # ## #   # ##
 # ## ##   # # #  ### #  #   # #   # ## #   # ##
+".trim().to_string());
+    }
+
+    #[test]
+    fn pharmacode_two_track_as_ascii() {
+        let pharmacode = PharmacodeTwoTrack::new(1234).unwrap();
+        let ascii = ASCII{height: 6, xdim: 1, chars: ASCII_CHARS};
+        let generated = ascii.generate_bars(&pharmacode.encode()).unwrap();
+
+        assert_eq!(generated,
+"
+    # #   #  
+    # #   #  
+# # # # # # #
+# # # # # # #
+# #   # # # #
+# #   # # # #
+".trim_matches('\n').to_string());
+    }
+
+    #[test]
+    fn databar_stacked_as_ascii() {
+        let databar = DataBarStacked::new("2001234567890".to_string()).unwrap();
+        let ascii = ASCII::new();
+        let generated = ascii.generate_rows(&databar.encode()).unwrap();
+
+        assert_eq!(generated,
+"
+ # #   ### #     #  #######    # #  ## ## ##### # 
+ # #   ### #     #  #######    # #  ## ## ##### # 
+ # #   ### #     #  #######    # #  ## ## ##### # 
+ # #   ### #     #  #######    # #  ## ## ##### # 
+ # #   ### #     #  #######    # #  ## ## ##### # 
+    ### # # ## ## #  # # # ## # # #  #  #  # #    
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+# ##     #  # #  # ##        ###   ## ## ##   ## #
+".trim_matches('\n').to_string());
+    }
+
+    #[test]
+    fn qr_as_ascii() {
+        let qr = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::Q).unwrap();
+        let ascii = ASCII{height: 1, xdim: 2, chars: ASCII_CHARS};
+        let generated = ascii.generate_matrix(&qr.encode()).unwrap();
+
+        assert_eq!(generated.lines().next().unwrap(), "##############  ####        ##############");
+        assert_eq!(generated.lines().count(), 21);
+    }
+
+    #[test]
+    fn ean_8_as_blocks() {
+        let ean8 = EAN8::new("1234567".to_string()).unwrap();
+        let ascii = ASCII{height: 2, ..ASCII::blocks()};
+        let generated = ascii.generate(&ean8.encode()).unwrap();
+
+        assert_eq!(generated,
+"
+█ █  ██  █  █  ██ ████ █ █   ██ █ █ █  ███ █ █    █   █  ███  █ █ █
+█ █  ██  █  █  ██ ████ █ █   ██ █ █ █  ███ █ █    █   █  ███  █ █ █
 ".trim().to_string());
     }
 }
@@ -0,0 +1,226 @@
+//! This module provides types for generating EPL2 (Eltron Programming Language) labels, for
+//! the Eltron and Zebra desktop printers that do not speak ZPL.
+//!
+//! The symbologies that EPL2 printers support natively are sent as `B` barcode commands. Any
+//! other encoded barcode can be sent as a `GW` graphic of its modules.
+//!
+//! All dimensions are in printer dots.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym::code39::Code39;
+use ::sym::code128::{Code128, CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C, FNC1};
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::two_of_five::I2of5;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
+
+/// The EPL2 barcode generator type.
+pub struct EPL2 {
+    /// The height of the barcode in dots.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For EPL2, each will be ```self.xdim``` dots wide.
+    pub xdim: u32,
+    /// The position of the top left of the barcode on the label.
+    pub origin: (u32, u32),
+    /// Whether the printer should print the human-readable text below native barcodes.
+    /// Graphics never include it.
+    pub human_readable: bool,
+}
+
+impl Default for EPL2 {
+    fn default() -> EPL2 {
+        EPL2::new()
+    }
+}
+
+// Formats digits as command data.
+fn digits(data: &[u8]) -> String {
+    data.iter().map(|d| d.to_string()).collect()
+}
+
+impl EPL2 {
+    /// Returns a new EPL2 with default values.
+    pub fn new() -> EPL2 {
+        EPL2{height: 100, xdim: 2, origin: (50, 50), human_readable: false}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in printer dots.
+    pub fn with_options(self, options: &Options) -> EPL2 {
+        EPL2{height: options.height_pixels(), xdim: options.xdim_pixels(), ..self}
+    }
+
+    // Wraps a command in a label that clears the image buffer first and prints one copy.
+    fn label(&self, command: &[u8]) -> Vec<u8> {
+        let mut label = b"\nN\n".to_vec();
+        label.extend(command);
+        label.extend(b"\nP1\n");
+        label
+    }
+
+    // Builds a native barcode command with the given barcode selection and data. The wide
+    // bars of Code39 and Interleaved 2 of 5 are twice the width of the narrow ones, as
+    // encoded.
+    fn native(&self, selection: &str, data: &str) -> String {
+        let data = data.replace('\\', "\\\\").replace('"', "\\\"");
+        let command = format!("B{},{},0,{},{},{},{},{},\"{}\"", self.origin.0, self.origin.1,
+                              selection, self.xdim, 2 * self.xdim, self.height,
+                              if self.human_readable { 'B' } else { 'N' }, data);
+
+        String::from_utf8(self.label(command.as_bytes())).unwrap()
+    }
+
+    // Builds a graphic from one bit per pixel. EPL2 prints black for 0 bits.
+    fn graphic(&self, pixels: Pixels) -> Vec<u8> {
+        let mut command = format!("GW{},{},{},{},", self.origin.0, self.origin.1, pixels.stride(),
+                                  pixels.height).into_bytes();
+        command.extend(pixels.data.iter().map(|b| !b));
+
+        self.label(&command)
+    }
+
+    // Returns a raw pixel generator with the same dimensions and no quiet zone, which the
+    // label layout provides.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: 0, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode as a graphic. Returns the bytes of the label.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        self.raw().generate(barcode).map(|p| self.graphic(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars) as a graphic. Returns the bytes
+    /// of the label.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        self.raw().generate_bars(bars).map(|p| self.graphic(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode) as a graphic. Each row is
+    /// ```row.height``` modules high, so `self.height` is not used. Returns the bytes of the
+    /// label.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        self.raw().generate_rows(rows).map(|p| self.graphic(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode) as a graphic. Each module is square,
+    /// so `self.height` is not used. Returns the bytes of the label.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.graphic(p))
+    }
+
+    /// Generates the given Code39 barcode as a native `B` command. Returns a String.
+    pub fn generate_code39(&self, barcode: &Code39) -> String {
+        let data: String = barcode.raw_data().iter().collect();
+
+        self.native(if barcode.checksum_required() { "3C" } else { "3" }, &data)
+    }
+
+    /// Generates the given Code128 barcode as a native `B` command, with the character sets
+    /// chosen by the printer. Returns Result<String, &str>, as EPL2 cannot encode FNC1 outside
+    /// of UCC/EAN-128 data.
+    pub fn generate_code128(&self, barcode: &Code128) -> Result<String, &str> {
+        let data = barcode.raw_data();
+
+        if data.contains(&FNC1) {
+            return Err("EPL2 cannot encode FNC1 in Code128 data");
+        }
+
+        let data: String = data.iter()
+            .filter(|c| ![CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C].contains(c))
+            .collect();
+
+        Ok(self.native("1", &data))
+    }
+
+    /// Generates the given EAN13 barcode as a native `B` command. The printer calculates the
+    /// check digit. Returns a String.
+    pub fn generate_ean13(&self, barcode: &EAN13) -> String {
+        self.native("E30", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given EAN8 barcode as a native `B` command. The printer calculates the
+    /// check digit. Returns a String.
+    pub fn generate_ean8(&self, barcode: &EAN8) -> String {
+        self.native("E80", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given UPCA barcode as a native `B` command. The printer calculates the
+    /// check digit. Returns a String.
+    pub fn generate_upca(&self, barcode: &UPCA) -> String {
+        self.native("UA0", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given I2of5 barcode as a native `B` command. Returns a String.
+    pub fn generate_i2of5(&self, barcode: &I2of5) -> String {
+        self.native("2", &digits(barcode.raw_data()))
+    }
+}
+
+impl Generator for EPL2 {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        EPL2::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::code128::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::upca::*;
+    use ::sym::two_of_five::*;
+    use ::generators::epl2::*;
+
+    #[test]
+    fn code_39_as_epl2_graphic() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let epl2 = EPL2{height: 1, xdim: 1, ..EPL2::new()};
+        let generated = epl2.generate(&code39.encode()).unwrap();
+
+        let mut expected = b"\nN\nGW50,50,5,1,".to_vec();
+        expected.extend(&[!0x96, !0xD6, !0xA5, !0xA5, !0xB4]);
+        expected.extend(b"\nP1\n");
+        assert_eq!(generated, expected);
+    }
+
+    #[test]
+    fn code_39_as_epl2() {
+        let epl2 = EPL2{human_readable: true, ..EPL2::new()};
+
+        assert_eq!(epl2.generate_code39(&Code39::new("TEST8052".to_string()).unwrap()),
+                   "\nN\nB50,50,0,3,2,4,100,B,\"TEST8052\"\nP1\n");
+        assert!(epl2.generate_code39(&Code39::with_checksum("TEST".to_string()).unwrap())
+                    .contains("B50,50,0,3C,2,4,100,B,\"TEST\""));
+    }
+
+    #[test]
+    fn code_128_as_epl2() {
+        let epl2 = EPL2{origin: (0, 10), xdim: 3, height: 60, ..EPL2::new()};
+
+        assert!(epl2.generate_code128(&Code128::new("ÀHELLOĆ1234".to_string()).unwrap()).unwrap()
+                    .contains("B0,10,0,1,3,6,60,N,\"HELLO1234\""));
+        assert!(epl2.generate_code128(&Code128::auto("a\"b\\".to_string()).unwrap()).unwrap()
+                    .contains("\"a\\\"b\\\\\""));
+        assert!(epl2.generate_code128(&Code128::new("Ćƒ1234".to_string()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn ean_upc_and_i2of5_as_epl2() {
+        let epl2 = EPL2::new();
+
+        assert!(epl2.generate_ean13(&EAN13::new("750103131130".to_string()).unwrap()).contains(",E30,2,4,100,N,\"750103131130\""));
+        assert!(epl2.generate_ean8(&EAN8::new("5512345".to_string()).unwrap()).contains(",E80,2,4,100,N,\"5512345\""));
+        assert!(epl2.generate_upca(&UPCA::new("12345678901".to_string()).unwrap()).contains(",UA0,2,4,100,N,\"12345678901\""));
+        assert!(epl2.generate_i2of5(&I2of5::new("1234".to_string()).unwrap()).contains(",2,2,4,100,N,\"1234\""));
+    }
+}
@@ -0,0 +1,277 @@
+//! This module provides types for generating EPS (Encapsulated PostScript) representations of
+//! barcodes, for prepress pipelines that place vector artwork. The barcode is drawn with filled
+//! rectangles, and the bounding box covers the symbol and its quiet zone.
+//!
+//! All dimensions are in millimeters.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::pdf::POINTS_PER_MM;
+use ::generators::{Generator, GeneratorError, bearer_layout, number, Counter};
+use ::sym::itf14::BearerBars;
+use std::io::{self, Write};
+use ::generators::options::{Options, Rotation};
+
+/// The EPS barcode generator type.
+pub struct EPS {
+    /// The height of the barcode in millimeters.
+    pub height: f64,
+    /// The X dimension. Specifies the width of the "narrow" bars, in millimeters.
+    pub xdim: f64,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+    /// The bar width reduction in millimeters. Bars are drawn this much narrower around
+    /// their centers, by at most half the X dimension. Modules of 2D barcodes are not reduced.
+    pub bwr: f64,
+}
+
+impl Default for EPS {
+    fn default() -> EPS {
+        EPS::new()
+    }
+}
+
+// A rectangle in millimeters from the top left: x, y, width and height.
+type Rect = (f64, f64, f64, f64);
+
+// Formats a color for the `setrgbcolor` operator.
+fn color(c: Color) -> String {
+    c.rgb.iter().map(|&v| number(v as f64 / 255.0)).collect::<Vec<String>>().join(" ")
+}
+
+impl EPS {
+    /// Returns a new EPS with default values.
+    pub fn new() -> EPS {
+        EPS{height: 20.0, xdim: 0.33, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+            rotation: Rotation::Normal, bwr: 0.0}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in millimeters.
+    pub fn with_options(self, options: &Options) -> EPS {
+        EPS{height: options.height_millimeters(), xdim: options.xdim_millimeters(),
+            quiet_zone: options.quiet_zone_modules(),
+            foreground: options.foreground, background: options.background, rotation: options.rotation,
+            bwr: options.bwr_millimeters()}
+    }
+
+    // Builds the document from the dark rectangles of a symbol of the given size in
+    // millimeters, not counting the quiet zone.
+    fn document(&self, width: f64, height: f64, rects: &[Rect], vertical_quiet_zone: bool) -> String {
+        let mut eps = vec![];
+
+        self.write_document(width, height, rects, vertical_quiet_zone, &mut eps).expect("Writing to a Vec cannot fail");
+        String::from_utf8(eps).expect("EPS documents are ASCII")
+    }
+
+    // Writes the document to `writer` as it is built. Returns the number of bytes written.
+    fn write_document<W: Write>(&self, width: f64, height: f64, rects: &[Rect], vertical_quiet_zone: bool,
+                                writer: &mut W) -> io::Result<usize> {
+        let mut eps = Counter::new(writer);
+        let margin = self.quiet_zone as f64 * self.xdim;
+        let top = if vertical_quiet_zone { margin } else { 0.0 };
+        let (total_width, total_height) = (width + 2.0 * margin, height + 2.0 * top);
+        let (image_width, image_height) = self.rotation.size(total_width, total_height);
+        let (box_width, box_height) = (image_width * POINTS_PER_MM, image_height * POINTS_PER_MM);
+
+        writeln!(eps, "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 {} {}\n%%HiResBoundingBox: 0 0 {} {}\n%%Creator: barcoders\n%%EndComments",
+                 box_width.ceil(), box_height.ceil(), number(box_width), number(box_height))?;
+        writeln!(eps, "{} setrgbcolor\n0 0 {} {} rectfill\n{} setrgbcolor",
+                 color(self.background), number(box_width), number(box_height), color(self.foreground))?;
+
+        for &(x, y, w, h) in rects {
+            let (x, y, w, h) = self.rotation.rect((x + margin, y + top, w, h), total_width, total_height);
+            // PostScript coordinates start at the bottom left.
+            let bottom = (image_height - y - h) * POINTS_PER_MM;
+
+            writeln!(eps, "{} {} {} {} rectfill", number(x * POINTS_PER_MM), number(bottom),
+                     number(w * POINTS_PER_MM), number(h * POINTS_PER_MM))?;
+        }
+
+        eps.write_all(b"showpage\n%%EOF\n")?;
+        Ok(eps.count)
+    }
+
+    // The rectangles of the runs of dark modules of one row, at the given y position and
+    // height.
+    fn row_rects(&self, modules: &[u8], y: f64, height: f64) -> Vec<Rect> {
+        let mut rects = vec![];
+        let mut start = None;
+
+        for (i, &m) in modules.iter().chain([0].iter()).enumerate() {
+            match (m, start) {
+                (1, None) => start = Some(i),
+                (0, Some(s)) => {
+                    rects.push((s as f64 * self.xdim, y, (i - s) as f64 * self.xdim, height));
+                    start = None;
+                },
+                _ => (),
+            }
+        }
+
+        rects
+    }
+
+    // The rectangles of one row of bars, narrowed by the bar width reduction.
+    fn bar_rects(&self, modules: &[u8], y: f64, height: f64) -> Vec<Rect> {
+        let reduction = self.bwr.max(0.0).min(self.xdim / 2.0);
+
+        self.row_rects(modules, y, height)
+            .into_iter()
+            .map(|(x, y, w, h)| (x + reduction / 2.0, y, w - reduction, h))
+            .collect()
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        let rects = self.bar_rects(barcode, 0.0, self.height);
+
+        Ok(self.document(barcode.len() as f64 * self.xdim, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedBarcode of an ITF-14 symbol, surrounded by the given bearer
+    /// bars. The quiet zone of the bearer bars replaces `self.quiet_zone`, and the bearer bars
+    /// are not narrowed by the bar width reduction. Returns a String.
+    pub fn generate_with_bearer_bars(&self, barcode: &EncodedBarcode, bearer_bars: &BearerBars) -> Result<String, &str> {
+        let (mut rects, (left, top), (width, height)) =
+            bearer_layout(bearer_bars, self.xdim, barcode.len() as f64 * self.xdim, self.height);
+        rects.extend(self.bar_rects(barcode, top, self.height).into_iter().map(|(x, y, w, h)| (x + left, y, w, h)));
+
+        Ok(EPS{quiet_zone: 0, ..*self}.document(width, height, &rects, false))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        let section = self.height / 3.0;
+        let rects: Vec<Rect> = bars.iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                let (y, h) = match b {
+                    BarState::Full => (0.0, self.height),
+                    BarState::Ascender => (0.0, self.height - section),
+                    BarState::Descender => (section, self.height - section),
+                    BarState::Tracker => (section, self.height - 2.0 * section),
+                };
+
+                (2.0 * i as f64 * self.xdim, y, self.xdim, h)
+            })
+            .collect();
+        let width = (2 * bars.len()).saturating_sub(1) as f64 * self.xdim;
+
+        Ok(self.document(width, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        let mut rects = vec![];
+        let mut y = 0.0;
+
+        for row in rows {
+            let height = row.height as f64 * self.xdim;
+
+            rects.extend(self.bar_rects(&row.modules, y, height));
+            y += height;
+        }
+
+        let width = rows.iter().map(|r| r.modules.len()).max().unwrap_or(0) as f64 * self.xdim;
+
+        Ok(self.document(width, y, &rects, false))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        let rects: Vec<Rect> = matrix.rows()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| self.row_rects(row, y as f64 * self.xdim, self.xdim))
+            .collect();
+
+        Ok(self.document(matrix.width as f64 * self.xdim, matrix.height as f64 * self.xdim, &rects, true))
+    }
+}
+
+impl Generator for EPS {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        EPS::generate(self, barcode).map_err(GeneratorError::from)
+    }
+
+    fn generate_to<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, GeneratorError> {
+        let rects = self.bar_rects(barcode, 0.0, self.height);
+
+        self.write_document(barcode.len() as f64 * self.xdim, self.height, &rects, false, writer)
+            .map_err(|_| GeneratorError::new("Could not write barcode."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::eps::*;
+
+    #[test]
+    fn code_39_as_eps() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let eps = EPS{height: 25.4, xdim: 25.4 / 72.0, quiet_zone: 0, ..EPS::new()};
+        let generated = eps.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 38 72\n%%HiResBoundingBox: 0 0 38 72\n"));
+        assert!(generated.contains("1 1 1 setrgbcolor\n0 0 38 72 rectfill\n0 0 0 setrgbcolor\n0 0 1 72 rectfill\n3 0 1 72 rectfill\n5 0 2 72 rectfill\n"));
+        assert!(generated.ends_with("showpage\n%%EOF\n"));
+    }
+
+    #[test]
+    fn eps_bounding_box_rounds_up() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let generated = EPS::new().generate(&code39.encode()).unwrap();
+
+        // 58 modules of 0.33mm.
+        assert!(generated.contains("%%BoundingBox: 0 0 55 57\n%%HiResBoundingBox: 0 0 54.255 56.693\n"));
+    }
+
+    #[test]
+    fn code_39_as_upside_down_eps() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let eps = EPS{height: 25.4, xdim: 25.4 / 72.0, quiet_zone: 0, rotation: Rotation::UpsideDown, ..EPS::new()};
+        let generated = eps.generate(&code39.encode()).unwrap();
+
+        // The guard is at the right.
+        assert!(generated.contains("%%BoundingBox: 0 0 38 72\n"));
+        assert!(generated.contains("0 0 0 setrgbcolor\n37 0 1 72 rectfill\n34 0 1 72 rectfill\n31 0 2 72 rectfill\n"));
+    }
+
+    #[test]
+    fn bars_as_eps() {
+        let eps = EPS{height: 3.0 * 25.4 / 72.0, xdim: 25.4 / 72.0, quiet_zone: 1, ..EPS::new()};
+        let generated = eps.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        assert!(generated.contains("%%BoundingBox: 0 0 5 3\n"));
+        assert!(generated.contains("1 0 1 3 rectfill\n3 0 1 2 rectfill\n"));
+    }
+
+    #[test]
+    fn qr_as_eps() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let eps = EPS{xdim: 0.5, quiet_zone: 4, ..EPS::new()};
+        let generated = eps.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.contains("%%BoundingBox: 0 0 42 42\n"));
+    }
+}
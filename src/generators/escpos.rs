@@ -0,0 +1,313 @@
+//! This module provides types for generating ESC/POS commands, to print barcodes on
+//! point-of-sale receipt printers.
+//!
+//! The symbologies that ESC/POS printers support natively are sent as `GS k` barcode commands.
+//! Any other encoded barcode can be sent as a `GS v 0` raster bit image of its modules.
+//!
+//! All dimensions are in printer dots.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym::code39::Code39;
+use ::sym::code128::{CharacterSet, Code128, FNC1_VALUE, SHIFT};
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::two_of_five::I2of5;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use std::ops::Range;
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
+
+/// The ESC/POS barcode generator type.
+pub struct ESCPOS {
+    /// The height of the barcode in dots. Native barcodes can be at most 255 dots high.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For ESC/POS, each will be ```self.xdim``` dots wide. Native barcodes need 2 to 6.
+    pub xdim: u32,
+    /// Whether the printer should print the human-readable text below native barcodes.
+    /// Raster images never include it.
+    pub human_readable: bool,
+}
+
+impl Default for ESCPOS {
+    fn default() -> ESCPOS {
+        ESCPOS::new()
+    }
+}
+
+// The heights and module widths of native barcodes.
+const NATIVE_HEIGHTS: Range<u32> = 1..256;
+const NATIVE_XDIMS: Range<u32> = 2..7;
+
+// The barcode system numbers of `GS k` (function B).
+const UPCA_SYSTEM: u8 = 65;
+const EAN13_SYSTEM: u8 = 67;
+const EAN8_SYSTEM: u8 = 68;
+const CODE39_SYSTEM: u8 = 69;
+const ITF_SYSTEM: u8 = 70;
+const CODE128_SYSTEM: u8 = 73;
+
+// Returns the code that selects a Code128 character set, after '{'.
+fn set_code(set: CharacterSet) -> u8 {
+    match set {
+        CharacterSet::A => b'A',
+        CharacterSet::B => b'B',
+        CharacterSet::C => b'C',
+    }
+}
+
+// Converts digits to their ASCII characters.
+fn digits(data: &[u8]) -> Vec<u8> {
+    data.iter().map(|d| b'0' + d).collect()
+}
+
+impl ESCPOS {
+    /// Returns a new ESCPOS with default values.
+    pub fn new() -> ESCPOS {
+        ESCPOS{height: 80, xdim: 3, human_readable: false}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in printer dots.
+    pub fn with_options(self, options: &Options) -> ESCPOS {
+        ESCPOS{height: options.height_pixels(), xdim: options.xdim_pixels(), ..self}
+    }
+
+    // Builds a native barcode command, preceded by the commands that set the position of the
+    // human-readable text, the height and the module width.
+    fn native(&self, system: u8, data: &[u8]) -> Result<Vec<u8>, &str> {
+        if !NATIVE_HEIGHTS.contains(&self.height) {
+            return Err("Native ESC/POS barcodes must be 1-255 dots high");
+        }
+
+        if !NATIVE_XDIMS.contains(&self.xdim) {
+            return Err("Native ESC/POS barcodes must have an X dimension of 2-6 dots");
+        }
+
+        if data.len() > 255 {
+            return Err("Data too long for a native ESC/POS barcode");
+        }
+
+        let mut command = vec![0x1D, b'H', if self.human_readable { 2 } else { 0 },
+                               0x1D, b'h', self.height as u8,
+                               0x1D, b'w', self.xdim as u8,
+                               0x1D, b'k', system, data.len() as u8];
+        command.extend(data);
+        Ok(command)
+    }
+
+    // Builds a raster bit image from one bit per pixel, with 1 for black.
+    fn raster(&self, pixels: Pixels) -> Vec<u8> {
+        let (width, height) = (pixels.stride(), pixels.height as usize);
+        let mut command = vec![0x1D, b'v', b'0', 0,
+                               (width % 256) as u8, (width / 256) as u8,
+                               (height % 256) as u8, (height / 256) as u8];
+        command.extend(pixels.data);
+        command
+    }
+
+    // Returns a raw pixel generator with the same dimensions and no quiet zone, which the
+    // receipt margins provide.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: 0, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode as a raster bit image. Returns the bytes of the
+    /// command.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        self.raw().generate(barcode).map(|p| self.raster(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars) as a raster bit image. Returns
+    /// the bytes of the command.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        self.raw().generate_bars(bars).map(|p| self.raster(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode) as a raster bit image. Each row
+    /// is ```row.height``` modules high, so `self.height` is not used. Returns the bytes of
+    /// the command.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        self.raw().generate_rows(rows).map(|p| self.raster(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode) as a raster bit image. Each module is
+    /// square, so `self.height` is not used. Returns the bytes of the command.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.raster(p))
+    }
+
+    /// Generates the given Code39 barcode as a native `GS k` command, with any check
+    /// character included in the data. Returns the bytes of the commands.
+    pub fn generate_code39(&self, barcode: &Code39) -> Result<Vec<u8>, &str> {
+        let mut data: String = barcode.raw_data().iter().collect();
+
+        if barcode.checksum_required() {
+            data.extend(barcode.checksum_char());
+        }
+
+        self.native(CODE39_SYSTEM, data.as_bytes())
+    }
+
+    /// Generates the given Code128 barcode as a native `GS k` command, with the same start
+    /// character, character set switches and shifts as its symbol. Returns the bytes of the
+    /// commands.
+    pub fn generate_code128(&self, barcode: &Code128) -> Result<Vec<u8>, &str> {
+        let values = barcode.values();
+        let mut set = match values[0] {
+            103 => CharacterSet::A,
+            104 => CharacterSet::B,
+            _ => CharacterSet::C,
+        };
+        let mut data = vec![b'{', set_code(set)];
+        let mut shifted = false;
+
+        for &v in &values[1..] {
+            let switch = [CharacterSet::A, CharacterSet::B, CharacterSet::C].iter()
+                .cloned()
+                .find(|&to| set.switch_value(to) == Some(v));
+            // A shifted character is in the other of sets A and B, and may look like a switch.
+            let active = match set {
+                CharacterSet::A if shifted => CharacterSet::B,
+                CharacterSet::B if shifted => CharacterSet::A,
+                _ => set,
+            };
+
+            match switch {
+                Some(to) if !shifted => {
+                    data.extend(&[b'{', set_code(to)]);
+                    set = to;
+                },
+                _ if v == FNC1_VALUE => data.extend(b"{1"),
+                _ if active == CharacterSet::C => data.push(v),
+                _ if v == SHIFT && !shifted => {
+                    data.extend(b"{S");
+                    shifted = true;
+                    continue;
+                },
+                _ => match v {
+                    96 => data.extend(b"{3"),
+                    97 => data.extend(b"{2"),
+                    _ if v > 97 => data.extend(b"{4"),
+                    _ if active == CharacterSet::A && v >= 64 => data.push(v - 64),
+                    // '{' itself is sent twice.
+                    91 => data.extend(b"{{"),
+                    _ => data.push(v + 32),
+                },
+            }
+
+            shifted = false;
+        }
+
+        self.native(CODE128_SYSTEM, &data)
+    }
+
+    /// Generates the given EAN13 barcode as a native `GS k` command. The printer calculates
+    /// the check digit. Returns the bytes of the commands.
+    pub fn generate_ean13(&self, barcode: &EAN13) -> Result<Vec<u8>, &str> {
+        self.native(EAN13_SYSTEM, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given EAN8 barcode as a native `GS k` command. The printer calculates
+    /// the check digit. Returns the bytes of the commands.
+    pub fn generate_ean8(&self, barcode: &EAN8) -> Result<Vec<u8>, &str> {
+        self.native(EAN8_SYSTEM, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given UPCA barcode as a native `GS k` command. The printer calculates
+    /// the check digit. Returns the bytes of the commands.
+    pub fn generate_upca(&self, barcode: &UPCA) -> Result<Vec<u8>, &str> {
+        self.native(UPCA_SYSTEM, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given I2of5 barcode as a native `GS k` (ITF) command. Returns the bytes
+    /// of the commands.
+    pub fn generate_i2of5(&self, barcode: &I2of5) -> Result<Vec<u8>, &str> {
+        self.native(ITF_SYSTEM, &digits(barcode.raw_data()))
+    }
+}
+
+impl Generator for ESCPOS {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        ESCPOS::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::code128::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::upca::*;
+    use ::sym::two_of_five::*;
+    use ::generators::escpos::*;
+
+    #[test]
+    fn code_39_as_escpos_raster() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let escpos = ESCPOS{height: 2, xdim: 1, ..ESCPOS::new()};
+        let generated = escpos.generate(&code39.encode()).unwrap();
+
+        assert_eq!(generated, vec![0x1D, b'v', b'0', 0, 5, 0, 2, 0,
+                                   0x96, 0xD6, 0xA5, 0xA5, 0xB4, 0x96, 0xD6, 0xA5, 0xA5, 0xB4]);
+    }
+
+    #[test]
+    fn code_39_as_escpos() {
+        let escpos = ESCPOS{human_readable: true, ..ESCPOS::new()};
+        let generated = escpos.generate_code39(&Code39::with_checksum("TEST".to_string()).unwrap()).unwrap();
+
+        assert_eq!(generated, b"\x1DH\x02\x1Dh\x50\x1Dw\x03\x1DkE\x05TESTE".to_vec());
+    }
+
+    #[test]
+    fn code_128_as_escpos() {
+        let escpos = ESCPOS::new();
+        let manual = escpos.generate_code128(&Code128::new("ÀHELLOĆ1234ƒ".to_string()).unwrap()).unwrap();
+        let auto = escpos.generate_code128(&Code128::auto("a{b".to_string()).unwrap()).unwrap();
+
+        assert_eq!(&manual[12..], b"\x0D{AHELLO{C\x0C\x22{1");
+        assert_eq!(&auto[12..], b"\x06{Ba{{b");
+    }
+
+    #[test]
+    fn code_128_with_control_characters_as_escpos() {
+        let escpos = ESCPOS::new();
+        let switched = escpos.generate_code128(&Code128::auto("\x01lower".to_string()).unwrap()).unwrap();
+        let shifted = escpos.generate_code128(&Code128::auto("a\nb".to_string()).unwrap()).unwrap();
+        let digits = escpos.generate_code128(&Code128::auto("ABC123456".to_string()).unwrap()).unwrap();
+
+        assert_eq!(&switched[12..], b"\x0A{B{S\x01lower");
+        assert_eq!(&shifted[12..], b"\x07{Ba{S\x0Ab");
+        assert_eq!(&digits[12..], b"\x0A{BABC{C\x0C\x22\x38");
+    }
+
+    #[test]
+    fn ean_upc_and_i2of5_as_escpos() {
+        let escpos = ESCPOS::new();
+
+        assert_eq!(&escpos.generate_ean13(&EAN13::new("750103131130".to_string()).unwrap()).unwrap()[11..],
+                   b"C\x0C750103131130");
+        assert_eq!(&escpos.generate_ean8(&EAN8::new("5512345".to_string()).unwrap()).unwrap()[11..],
+                   b"D\x075512345");
+        assert_eq!(&escpos.generate_upca(&UPCA::new("12345678901".to_string()).unwrap()).unwrap()[11..],
+                   b"A\x0B12345678901");
+        assert_eq!(&escpos.generate_i2of5(&I2of5::new("1234".to_string()).unwrap()).unwrap()[11..],
+                   b"F\x041234");
+    }
+
+    #[test]
+    fn invalid_escpos_native_dimensions() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+
+        assert!(ESCPOS{height: 256, ..ESCPOS::new()}.generate_code39(&code39).is_err());
+        assert!(ESCPOS{xdim: 1, ..ESCPOS::new()}.generate_code39(&code39).is_err());
+        assert!(ESCPOS{height: 256, xdim: 1, ..ESCPOS::new()}.generate(&code39.encode()).is_ok());
+    }
+}
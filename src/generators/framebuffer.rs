@@ -0,0 +1,204 @@
+//! This module provides types for generating packed monochrome framebuffers of barcodes, in
+//! the form that thermal printhead drivers consume: one bit per dot, most significant bit
+//! first, with 1 for dots to burn.
+//!
+//! Dimensions are given in millimeters and converted to whole dots at the printer's
+//! resolution, so every module is the same number of dots wide.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::sym::itf14::BearerBars;
+use ::generators::options::{Options, Rotation, MM_PER_INCH};
+
+/// A packed monochrome bitmap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bitmap {
+    /// The width of the barcode in dots.
+    pub width: u32,
+    /// The height of the barcode in dots.
+    pub height: u32,
+    /// The number of bytes in each row, including any padding after the barcode.
+    pub stride: usize,
+    /// The rows of dots from the top.
+    pub data: Vec<u8>,
+}
+
+// The rows are written with their padding, as printhead drivers consume them.
+impl AsRef<[u8]> for Bitmap {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// The framebuffer barcode generator type.
+pub struct Framebuffer {
+    /// The resolution of the printer, in dots per inch.
+    pub dpi: u32,
+    /// The height of the barcode in millimeters.
+    pub height: f64,
+    /// The X dimension. Specifies the width of the "narrow" bars, in millimeters. It is
+    /// rounded to the nearest whole number of dots, and at least one.
+    pub xdim: f64,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The number of bytes in each row, such as the width of the printhead. If None, rows
+    /// are only as long as the barcode needs.
+    pub stride: Option<usize>,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+    /// The bar width reduction in millimeters, rounded to the nearest whole number of dots.
+    /// Bars are burned this many dots narrower, but at least one dot wide.
+    pub bwr: f64,
+}
+
+impl Default for Framebuffer {
+    fn default() -> Framebuffer {
+        Framebuffer::new()
+    }
+}
+
+impl Framebuffer {
+    /// Returns a new Framebuffer with default values, for a 203 dpi printhead.
+    pub fn new() -> Framebuffer {
+        Framebuffer{dpi: 203, height: 15.0, xdim: 0.25, quiet_zone: 10, stride: None, rotation: Rotation::Normal,
+                    bwr: 0.0}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in millimeters
+    /// at the resolution of the options.
+    pub fn with_options(self, options: &Options) -> Framebuffer {
+        Framebuffer{dpi: options.dpi, height: options.height_millimeters(),
+                    xdim: options.xdim_millimeters(), quiet_zone: options.quiet_zone_modules(),
+                    rotation: options.rotation, bwr: options.bwr_millimeters(), ..self}
+    }
+
+    // Converts millimeters to the nearest whole number of dots.
+    fn dots(&self, mm: f64) -> u32 {
+        (mm * self.dpi as f64 / MM_PER_INCH).round() as u32
+    }
+
+    /// Returns the width of each module in dots.
+    pub fn module_dots(&self) -> u32 {
+        self.dots(self.xdim).max(1)
+    }
+
+    // Returns a raw pixel generator with the dimensions in dots.
+    fn raw(&self) -> Raw {
+        Raw{height: self.dots(self.height), xdim: self.module_dots(), quiet_zone: self.quiet_zone,
+            rotation: self.rotation, bwr: self.dots(self.bwr), ..Raw::mono()}
+    }
+
+    // Copies the rows of one bit per pixel into rows of the chosen stride.
+    fn bitmap(&self, pixels: Result<Pixels, &str>) -> Result<Bitmap, &str> {
+        let pixels = pixels.map_err(|_| "Could not draw barcode.")?;
+        let stride = self.stride.unwrap_or(pixels.stride());
+
+        if stride < pixels.stride() {
+            return Err("Barcode is wider than the framebuffer stride");
+        }
+
+        let mut data = vec![0; stride * pixels.height as usize];
+
+        for (row, dots) in data.chunks_mut(stride).zip(pixels.data.chunks(pixels.stride())) {
+            row[..dots.len()].copy_from_slice(dots);
+        }
+
+        Ok(Bitmap{width: pixels.width, height: pixels.height, stride, data})
+    }
+
+    /// Generates the given EncodedBarcode. Returns a Bitmap.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Bitmap, &str> {
+        self.bitmap(self.raw().generate(barcode))
+    }
+
+    /// Generates the given EncodedBarcode of an ITF-14 symbol, surrounded by the given bearer
+    /// bars. The quiet zone of the bearer bars replaces `self.quiet_zone`. Returns a Bitmap.
+    pub fn generate_with_bearer_bars(&self, barcode: &EncodedBarcode, bearer_bars: &BearerBars) -> Result<Bitmap, &str> {
+        self.bitmap(self.raw().generate_with_bearer_bars(barcode, bearer_bars))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a Bitmap.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Bitmap, &str> {
+        self.bitmap(self.raw().generate_bars(bars))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a Bitmap.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Bitmap, &str> {
+        self.bitmap(self.raw().generate_rows(rows))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// Bitmap.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Bitmap, &str> {
+        self.bitmap(self.raw().generate_matrix(matrix))
+    }
+}
+
+impl Generator for Framebuffer {
+    type Output = Bitmap;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Bitmap, GeneratorError> {
+        Framebuffer::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym2d::qr::*;
+    use ::generators::framebuffer::*;
+
+    #[test]
+    fn framebuffer_module_dots() {
+        assert_eq!(Framebuffer::new().module_dots(), 2);
+        assert_eq!(Framebuffer{dpi: 300, xdim: 0.33, ..Framebuffer::new()}.module_dots(), 4);
+        assert_eq!(Framebuffer{dpi: 203, xdim: 0.05, ..Framebuffer::new()}.module_dots(), 1);
+    }
+
+    #[test]
+    fn code_39_as_framebuffer() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let framebuffer = Framebuffer{dpi: 254, height: 0.2, xdim: 0.1, quiet_zone: 0, ..Framebuffer::new()};
+        let generated = framebuffer.generate(&code39.encode()).unwrap();
+
+        assert_eq!((generated.width, generated.height, generated.stride), (38, 2, 5));
+        assert_eq!(generated.data, vec![0x96, 0xD6, 0xA5, 0xA5, 0xB4, 0x96, 0xD6, 0xA5, 0xA5, 0xB4]);
+    }
+
+    #[test]
+    fn code_39_as_framebuffer_with_stride() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let framebuffer = Framebuffer{dpi: 254, height: 0.2, xdim: 0.1, quiet_zone: 0, stride: Some(8), ..Framebuffer::new()};
+        let generated = framebuffer.generate(&code39.encode()).unwrap();
+
+        assert_eq!(generated.stride, 8);
+        assert_eq!(generated.data, vec![0x96, 0xD6, 0xA5, 0xA5, 0xB4, 0, 0, 0, 0x96, 0xD6, 0xA5, 0xA5, 0xB4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn framebuffer_too_narrow() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let framebuffer = Framebuffer{stride: Some(4), ..Framebuffer::new()};
+
+        assert!(framebuffer.generate(&code39.encode()).is_err());
+    }
+
+    #[test]
+    fn qr_as_framebuffer() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let framebuffer = Framebuffer{stride: Some(48), quiet_zone: 4, ..Framebuffer::new()};
+        let generated = framebuffer.generate_matrix(&qr.encode()).unwrap();
+
+        // 29 modules of 2 dots, on a 384 dot printhead.
+        assert_eq!((generated.width, generated.height), (58, 58));
+        assert_eq!(generated.data.len(), 48 * 58);
+    }
+}
@@ -0,0 +1,609 @@
+//! This module provides the human-readable interpretation (HRI) of barcodes: the text printed
+//! beneath or above the bars, so the data can be read and keyed in by hand.
+//!
+//! The text is split into groups, each centered over a span of modules. EAN and UPC symbols
+//! have their own grouping, with digits in the quiet zones outside the guards, and their guard
+//! bars extend into the line of text, as their specifications require. Raster output
+//! draws the text with a built-in 5x7 bitmap font, scaled to whole pixels, and SVG output
+//! with a monospace `<text>` element.
+//!
+//! With the `font` feature, the text can be drawn with a TrueType or OpenType `Font` instead,
+//! such as OCR-B. SVG output then draws the outlines of its glyphs as paths, so the font does
+//! not need to be installed where the SVG is shown.
+
+use ::sym::{EncodedBarcode, EncodedGuards};
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::ean_supp::{EAN13WithSupplement, Supplementable, UPCAWithSupplement, WithSupplement};
+use ::sym::upca::UPCA;
+#[cfg(feature = "font")]
+use ::ab_glyph::{self, Font as AbFont, FontVec, GlyphId, OutlineCurve, Point, PxScale, ScaleFont};
+#[cfg(feature = "font")]
+use std::fmt;
+#[cfg(feature = "font")]
+use std::sync::Arc;
+
+/// The width of each character of the bitmap font, in font pixels.
+pub const GLYPH_WIDTH: u32 = 5;
+/// The height of each character of the bitmap font, in font pixels.
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// The characters from space to '~' of the bitmap font. Each character is five columns from
+/// the left, with the top row in the least significant bit.
+const GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // Space
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Where the text is drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextPosition {
+    /// Beneath the bars.
+    Below,
+    /// Above the bars.
+    Above,
+}
+
+/// A TrueType or OpenType font for the text. Clones share the loaded font.
+#[cfg(feature = "font")]
+#[derive(Clone)]
+pub struct Font {
+    font: Arc<FontVec>,
+}
+
+#[cfg(feature = "font")]
+impl Font {
+    /// Loads a font from the contents of a TTF or OTF file.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Font, &'static str> {
+        match FontVec::try_from_vec(data) {
+            Ok(font) => Ok(Font{font: Arc::new(font)}),
+            Err(_) => Err("Could not read font."),
+        }
+    }
+
+    // Returns the glyphs of the text with their positions from the start of the text, and
+    // the width of the text, at the given size in pixels.
+    fn layout(&self, text: &str, size: u32) -> (Vec<(GlyphId, f32)>, f32) {
+        let scaled = self.font.as_scaled(PxScale::from(size as f32));
+        let mut glyphs: Vec<(GlyphId, f32)> = vec![];
+        let mut x = 0.0;
+
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+
+            if let Some(&(previous, _)) = glyphs.last() {
+                x += scaled.kern(previous, id);
+            }
+
+            glyphs.push((id, x));
+            x += scaled.h_advance(id);
+        }
+
+        (glyphs, x)
+    }
+
+    // Draws the text centered on `center`, below `top`, calling `dark` with the position of
+    // each pixel that is at least half covered.
+    fn draw<F: FnMut(i64, i64)>(&self, text: &str, center: i64, top: u32, size: u32, mut dark: F) {
+        let scaled = self.font.as_scaled(PxScale::from(size as f32));
+        let (glyphs, width) = self.layout(text, size);
+        let (left, baseline) = (center as f32 - width / 2.0, top as f32 + scaled.ascent());
+
+        for (id, x) in glyphs {
+            let glyph = id.with_scale_and_position(size as f32, ab_glyph::point(left + x, baseline));
+
+            if let Some(outlined) = scaled.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+
+                outlined.draw(|gx, gy, coverage| if coverage >= 0.5 {
+                    dark(bounds.min.x as i64 + gx as i64, bounds.min.y as i64 + gy as i64);
+                });
+            }
+        }
+    }
+
+    /// Returns the outlines of the text centered on `center`, below `top`, at the given size
+    /// in pixels, as the data of an SVG path.
+    pub fn path(&self, text: &str, center: f32, top: f32, size: u32) -> String {
+        let scaled = self.font.as_scaled(PxScale::from(size as f32));
+        let (glyphs, width) = self.layout(text, size);
+        let (left, baseline) = (center - width / 2.0, top + scaled.ascent());
+        let (h_factor, v_factor) = (scaled.h_scale_factor(), scaled.v_scale_factor());
+        let mut path = String::new();
+
+        for (id, x) in glyphs {
+            // Font units have y going up.
+            let point = |p: &Point| format!("{} {}", number(left + x + p.x * h_factor),
+                                            number(baseline - p.y * v_factor));
+            let mut end: Option<Point> = None;
+
+            for curve in self.font.outline(id).map_or(vec![], |o| o.curves) {
+                let (start, last) = match curve {
+                    OutlineCurve::Line(p0, p1) | OutlineCurve::Quad(p0, _, p1) |
+                    OutlineCurve::Cubic(p0, _, _, p1) => (p0, p1),
+                };
+
+                if end != Some(start) {
+                    if end.is_some() {
+                        path.push('Z');
+                    }
+
+                    path.push_str(&format!("M{}", point(&start)));
+                }
+
+                path.push_str(&match curve {
+                    OutlineCurve::Line(_, p1) => format!("L{}", point(&p1)),
+                    OutlineCurve::Quad(_, p1, p2) => format!("Q{} {}", point(&p1), point(&p2)),
+                    OutlineCurve::Cubic(_, p1, p2, p3) => format!("C{} {} {}", point(&p1), point(&p2), point(&p3)),
+                });
+                end = Some(last);
+            }
+
+            if end.is_some() {
+                path.push('Z');
+            }
+        }
+
+        path
+    }
+}
+
+#[cfg(feature = "font")]
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        self.font.as_slice() == other.font.as_slice()
+    }
+}
+
+#[cfg(feature = "font")]
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Font {{ {} bytes }}", self.font.as_slice().len())
+    }
+}
+
+/// A run of text centered over a span of modules.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextGroup {
+    /// The text.
+    pub text: String,
+    /// The first module of the span, counted from the start of the barcode. Negative values
+    /// start in the quiet zone on the left.
+    pub start: i32,
+    /// The number of modules in the span.
+    pub width: u32,
+}
+
+/// The human-readable text of a barcode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HRI {
+    /// The groups of text.
+    pub groups: Vec<TextGroup>,
+    /// The height of the text in pixels. Raster output scales the bitmap font by whole
+    /// multiples of its height, so it may be drawn smaller.
+    pub font_size: u32,
+    /// Where the text is drawn.
+    pub position: TextPosition,
+    /// The modules whose bars extend into the line of text, such as the guards of EAN and
+    /// UPC symbols. If empty, no bars extend.
+    pub guards: EncodedGuards,
+    /// The font of the text, instead of the built-in bitmap font.
+    #[cfg(feature = "font")]
+    pub font: Option<Font>,
+}
+
+impl HRI {
+    /// Returns new HRI with the given text centered under a barcode of `width` modules.
+    pub fn new(text: &str, width: u32) -> HRI {
+        HRI::with_groups(vec![group(text, 0, width)])
+    }
+
+    /// Returns new HRI for an EAN-13 barcode: the first digit left of the left guard, then
+    /// six digits under each half.
+    pub fn ean13(ean13: &EAN13) -> HRI {
+        let digits = digits(ean13.raw_data(), ean13.checksum_digit());
+
+        HRI{guards: ean13.encode_guards(),
+            ..HRI::with_groups(vec![group(&digits[0..1], -7, 7), group(&digits[1..7], 3, 42),
+                                    group(&digits[7..13], 50, 42)])}
+    }
+
+    /// Returns new HRI for an EAN-13 barcode with a supplement: the EAN-13 grouping, and the
+    /// digits of the supplement over its modules.
+    pub fn ean13_with_supplement(barcode: &EAN13WithSupplement) -> HRI {
+        HRI::ean13(barcode.main()).with_supplement(barcode)
+    }
+
+    /// Returns new HRI for a UPC-A barcode with a supplement: the UPC-A grouping, and the
+    /// digits of the supplement over its modules.
+    pub fn upca_with_supplement(barcode: &UPCAWithSupplement) -> HRI {
+        HRI::upca(barcode.main()).with_supplement(barcode)
+    }
+
+    // Returns this HRI of a main symbol with a group for the digits of its supplement.
+    fn with_supplement<T: Supplementable>(mut self, barcode: &WithSupplement<T>) -> HRI {
+        let supplement = barcode.supplement();
+        let digits = digits(supplement.raw_data(), supplement.checksum_digit());
+        let start = barcode.supplement_start();
+
+        self.groups.push(group(&digits[..supplement.raw_data().len()], start as i32,
+                               (barcode.encode().len() - start) as u32));
+        self.guards = barcode.encode_guards();
+        self
+    }
+
+    /// Returns new HRI for an EAN-8 barcode: four digits under each half.
+    pub fn ean8(ean8: &EAN8) -> HRI {
+        let digits = digits(ean8.raw_data(), ean8.checksum_digit());
+
+        HRI{guards: ean8.encode_guards(),
+            ..HRI::with_groups(vec![group(&digits[0..4], 3, 28), group(&digits[4..8], 36, 28)])}
+    }
+
+    /// Returns new HRI for a UPC-A barcode: the number system digit left of the left guard,
+    /// five digits under each half and the check digit right of the right guard.
+    pub fn upca(upca: &UPCA) -> HRI {
+        let digits = digits(upca.raw_data(), upca.checksum_digit());
+
+        HRI{guards: upca.encode_guards(),
+            ..HRI::with_groups(vec![group(&digits[0..1], -7, 7), group(&digits[1..6], 10, 35),
+                                    group(&digits[6..11], 50, 35), group(&digits[11..12], 95, 7)])}
+    }
+
+    // Returns new HRI with the given groups, 10 pixels high beneath the bars.
+    fn with_groups(groups: Vec<TextGroup>) -> HRI {
+        HRI{groups, font_size: 10, position: TextPosition::Below, guards: vec![],
+            #[cfg(feature = "font")]
+            font: None}
+    }
+
+    /// Returns the space between the text and the bars, and below (or above) the text, in
+    /// pixels.
+    pub fn gap(&self) -> u32 {
+        self.scale()
+    }
+
+    /// Returns the height of the line of text, including its gaps, in pixels.
+    pub fn line_height(&self) -> u32 {
+        self.font_size + 2 * self.gap()
+    }
+
+    /// Returns how far the guard bars extend into the line of text, in pixels: five modules,
+    /// as the EAN and UPC specifications give, but no further than the line.
+    pub fn guard_extension(&self, xdim: u32) -> u32 {
+        if self.guards.is_empty() {
+            return 0;
+        }
+
+        (5 * xdim).min(self.line_height())
+    }
+
+    /// Splits the modules of a barcode into its normal bars and its guard bars.
+    pub fn split_guards(&self, barcode: &EncodedBarcode) -> (EncodedBarcode, EncodedBarcode) {
+        let guard = |i: usize| self.guards.get(i) == Some(&1);
+        let bars = barcode.iter().enumerate().map(|(i, &m)| if guard(i) { 0 } else { m }).collect();
+        let guards = barcode.iter().enumerate().map(|(i, &m)| if guard(i) { m } else { 0 }).collect();
+
+        (bars, guards)
+    }
+
+    /// Returns the number of pixels in each pixel of the bitmap font.
+    pub fn scale(&self) -> u32 {
+        (self.font_size / GLYPH_HEIGHT).max(1)
+    }
+
+    /// Draws the line of text, `width` pixels wide. `offset` is the position of the first
+    /// module of the barcode and `xdim` the width of each module, in pixels. Returns
+    /// ```self.line_height()``` rows of `width` pixels, true where dark.
+    pub fn bitmap(&self, width: u32, offset: u32, xdim: u32) -> Vec<bool> {
+        let height = self.line_height();
+        let mut dark = vec![false; (width * height) as usize];
+
+        {
+            let mut set = |x: i64, y: i64| if x >= 0 && x < width as i64 && y >= 0 && y < height as i64 {
+                dark[y as usize * width as usize + x as usize] = true;
+            };
+
+            for group in &self.groups {
+                let center = offset as i64 + (2 * group.start as i64 + group.width as i64) * xdim as i64 / 2;
+
+                #[cfg(feature = "font")]
+                {
+                    if let Some(ref font) = self.font {
+                        font.draw(&group.text, center, self.gap(), self.font_size, &mut set);
+                        continue;
+                    }
+                }
+
+                self.draw_glyphs(&group.text, center, &mut set);
+            }
+        }
+
+        dark
+    }
+
+    // Draws the text with the bitmap font centered on `center`, calling `dark` with the
+    // position of each dark pixel.
+    fn draw_glyphs<F: FnMut(i64, i64)>(&self, text: &str, center: i64, mut dark: F) {
+        let scale = self.scale();
+        let top = self.gap() + self.font_size.saturating_sub(GLYPH_HEIGHT * scale);
+        let chars: Vec<char> = text.chars().collect();
+        let text_width = (chars.len() as u32 * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale;
+        let left = center - text_width as i64 / 2;
+
+        for (i, &c) in chars.iter().enumerate() {
+            let glyph_left = left + (i as u32 * (GLYPH_WIDTH + 1) * scale) as i64;
+
+            for (column, bits) in glyph(c).iter().enumerate() {
+                for row in (0..GLYPH_HEIGHT).filter(|r| bits >> r & 1 == 1) {
+                    for dx in 0..scale {
+                        for dy in 0..scale {
+                            dark(glyph_left + (column as u32 * scale + dx) as i64, (top + row * scale + dy) as i64);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the columns of the bitmap font for a character. Characters outside of printable
+/// ASCII are drawn as '?'.
+pub fn glyph(c: char) -> [u8; 5] {
+    match c {
+        ' '..='~' => GLYPHS[c as usize - 32],
+        _ => GLYPHS['?' as usize - 32],
+    }
+}
+
+// Formats a number of pixels with at most two decimal places.
+#[cfg(feature = "font")]
+fn number(n: f32) -> String {
+    if n.abs() < 0.005 {
+        return "0".to_string();
+    }
+
+    let s = format!("{:.2}", n);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Returns the digits of an EAN or UPC barcode with its check digit, as text.
+fn digits(data: &[u8], checksum: u8) -> String {
+    data.iter().chain([checksum].iter()).map(|d| (b'0' + d) as char).collect()
+}
+
+// Returns a group of text over the given modules.
+fn group(text: &str, start: i32, width: u32) -> TextGroup {
+    TextGroup{text: text.to_string(), start, width}
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::ean_supp::*;
+    use ::sym::upca::*;
+    use ::generators::hri::*;
+    #[cfg(feature = "font")]
+    use std::fs;
+
+    // DejaVu Sans is not on every system, so the tests that draw with it pass without it.
+    #[cfg(feature = "font")]
+    fn dejavu_sans() -> Option<Font> {
+        fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf").ok().map(|data| Font::from_bytes(data).unwrap())
+    }
+
+    fn texts(hri: &HRI) -> Vec<&str> {
+        hri.groups.iter().map(|g| &g.text[..]).collect()
+    }
+
+    #[test]
+    fn ean13_grouping() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let hri = HRI::ean13(&ean13);
+
+        assert_eq!(texts(&hri), vec!["7", "501031", "311309"]);
+        assert_eq!(hri.groups[0], TextGroup{text: "7".to_string(), start: -7, width: 7});
+    }
+
+    #[test]
+    fn ean13_with_supplement_grouping() {
+        let barcode = EAN13WithSupplement::new("978185102353".to_string(), "51299".to_string()).unwrap();
+        let hri = HRI::ean13_with_supplement(&barcode);
+
+        assert_eq!(texts(&hri), vec!["9", "781851", "023530", "51299"]);
+        assert_eq!(hri.groups[3], TextGroup{text: "51299".to_string(), start: 104, width: 47});
+        assert_eq!(hri.guards.len(), 151);
+
+        let barcode = UPCAWithSupplement::new("07012345678".to_string(), "12".to_string()).unwrap();
+        let hri = HRI::upca_with_supplement(&barcode);
+
+        assert_eq!(texts(&hri), vec!["0", "70123", "45678", "7", "12"]);
+        assert_eq!(hri.guards.len(), 124);
+    }
+
+    #[test]
+    fn ean8_and_upca_grouping() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let upca = UPCA::new("12345678999".to_string()).unwrap();
+
+        assert_eq!(texts(&HRI::ean8(&ean8)), vec!["5512", "3457"]);
+        assert_eq!(texts(&HRI::upca(&upca)), vec!["1", "23456", "78999", "9"]);
+    }
+
+    #[test]
+    fn guards() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let hri = HRI::ean8(&ean8);
+        let (bars, guards) = hri.split_guards(&ean8.encode());
+
+        assert_eq!(&bars[0..4], &[0, 0, 0, 0]);
+        assert_eq!(&guards[0..4], &[1, 0, 1, 0]);
+        assert_eq!(&guards[31..36], &[0, 1, 0, 1, 0]);
+        assert_eq!(hri.guard_extension(2), 10);
+        assert_eq!(hri.guard_extension(3), 12);
+        assert_eq!(HRI::new("5512", 67).guard_extension(2), 0);
+    }
+
+    #[test]
+    fn text_bitmap() {
+        let hri = HRI{font_size: 7, ..HRI::new("1", 5)};
+        let dark = hri.bitmap(7, 1, 1);
+
+        assert_eq!(hri.line_height(), 9);
+        // The '1' is centered, below a gap of one pixel.
+        assert!(dark[0..7].iter().all(|&d| !d));
+        assert_eq!(&dark[7..14], &[false, false, false, true, false, false, false]);
+        assert_eq!(&dark[7 * 7..8 * 7], &[false, false, true, true, true, false, false]);
+    }
+
+    #[test]
+    fn text_bitmap_scale() {
+        let hri = HRI{font_size: 15, ..HRI::new("-", 20)};
+        let dark = hri.bitmap(20, 0, 1);
+
+        // Twice the size, with the extra pixel above.
+        assert_eq!(hri.scale(), 2);
+        assert_eq!(dark.len(), 20 * 19);
+        assert_eq!(dark.iter().filter(|&&d| d).count(), 5 * 2 * 2);
+        assert!(dark[(3 + 6) * 20 + 5..(3 + 6) * 20 + 15].iter().all(|&d| d));
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        assert_eq!(glyph('é'), glyph('?'));
+        assert_eq!(glyph(' '), [0; 5]);
+    }
+
+    #[test]
+    #[cfg(feature = "font")]
+    fn invalid_font() {
+        assert_eq!(Font::from_bytes(vec![0; 16]), Err("Could not read font."));
+    }
+
+    #[test]
+    #[cfg(feature = "font")]
+    fn text_bitmap_with_font() {
+        let font = match dejavu_sans() {
+            Some(font) => font,
+            None => return,
+        };
+        let hri = HRI{font_size: 20, font: Some(font), ..HRI::new("l", 10)};
+        let dark = hri.bitmap(10, 0, 1);
+        let column = |x: usize| (0..hri.line_height() as usize).filter(|&y| dark[y * 10 + x]).count();
+
+        // The stem of the 'l' is centered, and fits between the gaps.
+        assert!(column(4) >= 12 && column(5) == column(4));
+        assert_eq!(column(0) + column(9), 0);
+        assert!(dark[0..30].iter().chain(dark[dark.len() - 30..].iter()).all(|&d| !d));
+    }
+
+    #[test]
+    #[cfg(feature = "font")]
+    fn text_path_with_font() {
+        let font = match dejavu_sans() {
+            Some(font) => font,
+            None => return,
+        };
+        let path = font.path("l", 10.0, 0.0, 20);
+
+        // A single rectangle.
+        assert!(path.starts_with("M"));
+        assert_eq!(path.matches('M').count(), 1);
+        assert_eq!(path.matches('Z').count(), 1);
+        assert_eq!(font.path("", 10.0, 0.0, 20), "");
+    }
+}
@@ -0,0 +1,223 @@
+//! This module provides types for generating HTML representations of barcodes, for pages that
+//! cannot load images. The barcode is drawn with plain `div` elements and inline CSS, so the
+//! snippet needs no stylesheet, but each element also carries a class for custom styling.
+//!
+//! Each row of the barcode is a flex container of runs of modules: dark runs are filled with
+//! the foreground color, and light runs let the background show through.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
+
+/// The HTML barcode generator type.
+pub struct HTML {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For HTML, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The class of the outer element. Rows and bars have this class followed by "-row" and
+    /// "-bar".
+    pub class: String,
+}
+
+impl Default for HTML {
+    fn default() -> HTML {
+        HTML::new()
+    }
+}
+
+impl HTML {
+    /// Returns a new HTML with default values.
+    pub fn new() -> HTML {
+        HTML{height: 80, xdim: 1, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+             class: "barcode".to_string()}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> HTML {
+        HTML{height: options.height_pixels(), xdim: options.xdim_pixels(),
+             quiet_zone: options.quiet_zone_modules(),
+             foreground: options.foreground, background: options.background, ..self}
+    }
+
+    // Wraps the rows in the outer element, which draws the background and the quiet zone.
+    fn document(&self, rows: &[String], vertical_quiet_zone: bool) -> String {
+        let margin = self.quiet_zone * self.xdim;
+        let top = if vertical_quiet_zone { margin } else { 0 };
+
+        format!("<div class=\"{}\" style=\"display:inline-block;background:{};padding:{}px {}px\">\n{}</div>",
+                self.class, self.background.to_hex(), top, margin, rows.concat())
+    }
+
+    // A bar of the given width and height, at the given distance from the top of its row.
+    fn bar(&self, width: u32, height: u32, top: u32) -> String {
+        let offset = if top > 0 { format!(";margin-top:{}px", top) } else { String::new() };
+
+        format!("<div class=\"{}-bar\" style=\"width:{}px;height:{}px;background:{}{}\"></div>",
+                self.class, width, height, self.foreground.to_hex(), offset)
+    }
+
+    // A space of the given width.
+    fn space(&self, width: u32) -> String {
+        format!("<div style=\"width:{}px\"></div>", width)
+    }
+
+    // A row of elements of the given height.
+    fn row(&self, elements: &[String], height: u32) -> String {
+        format!("<div class=\"{}-row\" style=\"display:flex;align-items:flex-start;height:{}px\">{}</div>\n",
+                self.class, height, elements.concat())
+    }
+
+    // A row of modules, with one element per run of dark or light modules.
+    fn module_row(&self, modules: &[u8], height: u32) -> String {
+        let mut elements = vec![];
+        let mut start = 0;
+
+        for i in 1..=modules.len() {
+            if i == modules.len() || modules[i] != modules[start] {
+                let width = (i - start) as u32 * self.xdim;
+
+                elements.push(match modules[start] {
+                    1 => self.bar(width, height, 0),
+                    _ => self.space(width),
+                });
+                start = i;
+            }
+        }
+
+        self.row(&elements, height)
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        Ok(self.document(&[self.module_row(barcode, self.height)], false))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        let section = self.height / 3;
+        let mut elements = vec![];
+
+        for (i, &b) in bars.iter().enumerate() {
+            let (y, h) = match b {
+                BarState::Full => (0, self.height),
+                BarState::Ascender => (0, self.height - section),
+                BarState::Descender => (section, self.height - section),
+                BarState::Tracker => (section, self.height - 2 * section),
+            };
+
+            if i > 0 {
+                elements.push(self.space(self.xdim));
+            }
+
+            elements.push(self.bar(self.xdim, h, y));
+        }
+
+        Ok(self.document(&[self.row(&elements, self.height)], false))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        let rows: Vec<String> = rows.iter()
+            .map(|r| self.module_row(&r.modules, r.height * self.xdim))
+            .collect();
+
+        Ok(self.document(&rows, false))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        let rows: Vec<String> = matrix.rows()
+            .iter()
+            .map(|r| self.module_row(r, self.xdim))
+            .collect();
+
+        Ok(self.document(&rows, true))
+    }
+}
+
+impl Generator for HTML {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        HTML::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym::databar::*;
+    use ::sym2d::qr::*;
+    use ::generators::html::*;
+
+    #[test]
+    fn code_39_as_html() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let html = HTML{height: 20, xdim: 2, ..HTML::new()};
+        let generated = html.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("<div class=\"barcode\" style=\"display:inline-block;background:#ffffff;padding:0px 20px\">\n<div class=\"barcode-row\" style=\"display:flex;align-items:flex-start;height:20px\">"));
+        // The guard begins with a narrow bar, then a wide bar after a wide space.
+        assert!(generated.contains("<div class=\"barcode-bar\" style=\"width:2px;height:20px;background:#000000\"></div><div style=\"width:4px\"></div><div class=\"barcode-bar\" style=\"width:2px;height:20px;background:#000000\"></div><div style=\"width:2px\"></div><div class=\"barcode-bar\" style=\"width:4px;height:20px;background:#000000\"></div>"));
+        assert!(generated.ends_with("</div>\n</div>"));
+        assert_eq!(generated.matches("-bar\"").count(), 15);
+    }
+
+    #[test]
+    fn code_39_as_html_with_class_and_colors() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let html = HTML{class: "sku".to_string(), foreground: Color::new(0x11, 0x22, 0x33),
+                        background: Color::new(0xff, 0xee, 0xdd), ..HTML::new()};
+        let generated = html.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("<div class=\"sku\" style=\"display:inline-block;background:#ffeedd;"));
+        assert!(generated.contains("<div class=\"sku-bar\" style=\"width:1px;height:80px;background:#112233\">"));
+    }
+
+    #[test]
+    fn bars_as_html() {
+        let html = HTML{height: 30, quiet_zone: 0, ..HTML::new()};
+        let generated = html.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        assert!(generated.contains("<div class=\"barcode-bar\" style=\"width:1px;height:30px;background:#000000\"></div><div style=\"width:1px\"></div><div class=\"barcode-bar\" style=\"width:1px;height:20px;background:#000000;margin-top:10px\"></div>"));
+    }
+
+    #[test]
+    fn databar_stacked_as_html() {
+        let databar = DataBarStacked::new("2001234567890".to_string()).unwrap();
+        let generated = HTML::new().generate_rows(&databar.encode()).unwrap();
+
+        assert_eq!(generated.matches("barcode-row").count(), databar.encode().len());
+    }
+
+    #[test]
+    fn qr_as_html() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let html = HTML{xdim: 3, quiet_zone: 4, ..HTML::new()};
+        let generated = html.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.contains("padding:12px 12px"));
+        assert_eq!(generated.matches("barcode-row").count(), 21);
+        // The top row starts with the finder pattern, seven modules wide.
+        assert!(generated.contains("height:3px\"><div class=\"barcode-bar\" style=\"width:21px;height:3px;"));
+    }
+}
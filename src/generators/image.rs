@@ -1,15 +1,37 @@
-//! This module provides types for generating GIF representations of barcodes. 
+//! This module provides types for generating GIF, PNG, BMP, JPEG and WebP representations of barcodes.
+//!
+//! This module is only available with the `image` feature, and WebP output with the `webp`
+//! feature. Both are enabled by default.
 
 extern crate image;
 
 use ::sym::EncodedBarcode;
-use image::GenericImage;
-use image::ImageBuffer;
+use ::generators::{data_uri, Color, Counter, Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+use ::generators::hri::{HRI, TextPosition};
+use image::{GenericImage, ImageBuffer};
 use std::fs::File;
+use std::io::Write;
+use std::path::Path;
 
 const IMAGE_BAR_WIDTH: u32 = 1;
 
-/// The GIF barcode generator type.
+// Returns whether a color can be drawn in a grayscale image.
+fn is_opaque_gray(color: Color) -> bool {
+    color.is_opaque() && color.rgb[0] == color.rgb[1] && color.rgb[1] == color.rgb[2]
+}
+
+// Rotates an image clockwise.
+fn rotate(pixels: image::DynamicImage, rotation: Rotation) -> image::DynamicImage {
+    match rotation {
+        Rotation::Normal => pixels,
+        Rotation::Clockwise90 => pixels.rotate90(),
+        Rotation::UpsideDown => pixels.rotate180(),
+        Rotation::Clockwise270 => pixels.rotate270(),
+    }
+}
+
+/// The image barcode generator type. Each variant is an output format.
 pub enum Image {
     GIF {
         /// The height of the barcode in pixels.
@@ -17,6 +39,14 @@ pub enum Image {
         /// The X dimension. Specifies the width of the "narrow" bars. 
         /// For GIF, each will be ```self.xdim * IMAGE_BAR_WIDTH``` pixels wide.
         xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+        /// The color of the bars.
+        foreground: Color,
+        /// The color of the spaces and the margin.
+        background: Color,
+        /// The clockwise rotation of the whole image, including the margin.
+        rotation: Rotation,
     },
     PNG {
         /// The height of the barcode in pixels.
@@ -24,56 +54,309 @@ pub enum Image {
         /// The X dimension. Specifies the width of the "narrow" bars. 
         /// For PNG, each will be ```self.xdim * IMAGE_BAR_WIDTH``` pixels wide.
         xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+        /// The color of the bars.
+        foreground: Color,
+        /// The color of the spaces and the margin, which may be transparent.
+        background: Color,
+        /// The clockwise rotation of the whole image, including the margin.
+        rotation: Rotation,
+    },
+    BMP {
+        /// The height of the barcode in pixels.
+        height: u32,
+        /// The X dimension. Specifies the width of the "narrow" bars. 
+        /// For BMP, each will be ```self.xdim * IMAGE_BAR_WIDTH``` pixels wide.
+        xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+        /// The color of the bars.
+        foreground: Color,
+        /// The color of the spaces and the margin.
+        background: Color,
+        /// The clockwise rotation of the whole image, including the margin.
+        rotation: Rotation,
+    },
+    JPEG {
+        /// The height of the barcode in pixels.
+        height: u32,
+        /// The X dimension. Specifies the width of the "narrow" bars. 
+        /// For JPEG, each will be ```self.xdim * IMAGE_BAR_WIDTH``` pixels wide.
+        xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+        /// The color of the bars.
+        foreground: Color,
+        /// The color of the spaces and the margin.
+        background: Color,
+        /// The clockwise rotation of the whole image, including the margin.
+        rotation: Rotation,
+        /// The encoding quality, from 1 (smallest) to 100 (best). Lower qualities blur the
+        /// edges of the bars, so scanners may struggle with them.
+        quality: u8,
+    },
+    /// Lossless WebP.
+    #[cfg(feature = "webp")]
+    WEBP {
+        /// The height of the barcode in pixels.
+        height: u32,
+        /// The X dimension. Specifies the width of the "narrow" bars. 
+        /// For WebP, each will be ```self.xdim * IMAGE_BAR_WIDTH``` pixels wide.
+        xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+        /// The color of the bars.
+        foreground: Color,
+        /// The color of the spaces and the margin.
+        background: Color,
+        /// The clockwise rotation of the whole image, including the margin.
+        rotation: Rotation,
     }
 }
 
 impl Image {
     /// Returns a new GIF with default values.
     pub fn gif() -> Image {
-        Image::GIF{height: 80, xdim: 1}
+        Image::GIF{height: 80, xdim: 1, margin: 0, foreground: Color::BLACK, background: Color::WHITE,
+                   rotation: Rotation::Normal}
     }
 
     /// Returns a new PNG with default values.
     pub fn png() -> Image {
-        Image::PNG{height: 80, xdim: 1}
+        Image::PNG{height: 80, xdim: 1, margin: 0, foreground: Color::BLACK, background: Color::WHITE,
+                   rotation: Rotation::Normal}
     }
 
-    /// Generates the given EncodedBarcode. Returns a usize indicating the number of bytes written.
-    pub fn generate(&self, barcode: &EncodedBarcode, path: &mut File) -> Result<usize, &str> {
-        let (xdim, height, format) = match *self {
-            Image::GIF{height: h, xdim: x} => (x, h, image::GIF),
-            Image::PNG{height: h, xdim: x} => (x, h, image::PNG),
-        };
+    /// Returns a new BMP with default values.
+    pub fn bmp() -> Image {
+        Image::BMP{height: 80, xdim: 1, margin: 0, foreground: Color::BLACK, background: Color::WHITE,
+                   rotation: Rotation::Normal}
+    }
+
+    /// Returns a new JPEG with default values.
+    pub fn jpeg() -> Image {
+        Image::JPEG{height: 80, xdim: 1, margin: 0, foreground: Color::BLACK, background: Color::WHITE,
+                    rotation: Rotation::Normal, quality: 90}
+    }
+
+    /// Returns a new WebP with default values.
+    #[cfg(feature = "webp")]
+    pub fn webp() -> Image {
+        Image::WEBP{height: 80, xdim: 1, margin: 0, foreground: Color::BLACK, background: Color::WHITE,
+                    rotation: Rotation::Normal}
+    }
+
+    // Returns the height, X dimension, margin and colors of any format, to change them.
+    fn fields_mut(&mut self) -> (&mut u32, &mut u32, &mut u32, &mut Color, &mut Color) {
+        match *self {
+            Image::GIF{ref mut height, ref mut xdim, ref mut margin, ref mut foreground, ref mut background, ..} |
+            Image::PNG{ref mut height, ref mut xdim, ref mut margin, ref mut foreground, ref mut background, ..} |
+            Image::BMP{ref mut height, ref mut xdim, ref mut margin, ref mut foreground, ref mut background, ..} |
+            Image::JPEG{ref mut height, ref mut xdim, ref mut margin, ref mut foreground, ref mut background, ..} =>
+                (height, xdim, margin, foreground, background),
+            #[cfg(feature = "webp")]
+            Image::WEBP{ref mut height, ref mut xdim, ref mut margin, ref mut foreground, ref mut background, ..} =>
+                (height, xdim, margin, foreground, background),
+        }
+    }
+
+    // Returns the height, X dimension, margin and colors of any format.
+    fn fields(&self) -> (u32, u32, u32, Color, Color) {
+        match *self {
+            Image::GIF{height, xdim, margin, foreground, background, ..} |
+            Image::PNG{height, xdim, margin, foreground, background, ..} |
+            Image::BMP{height, xdim, margin, foreground, background, ..} |
+            Image::JPEG{height, xdim, margin, foreground, background, ..} => (height, xdim, margin, foreground, background),
+            #[cfg(feature = "webp")]
+            Image::WEBP{height, xdim, margin, foreground, background, ..} => (height, xdim, margin, foreground, background),
+        }
+    }
+
+    // Returns the rotation of any format.
+    fn rotation(&self) -> Rotation {
+        match *self {
+            Image::GIF{rotation, ..} |
+            Image::PNG{rotation, ..} |
+            Image::BMP{rotation, ..} |
+            Image::JPEG{rotation, ..} => rotation,
+            #[cfg(feature = "webp")]
+            Image::WEBP{rotation, ..} => rotation,
+        }
+    }
+
+    // Returns the rotation of any format, to change it.
+    fn rotation_mut(&mut self) -> &mut Rotation {
+        match *self {
+            Image::GIF{ref mut rotation, ..} |
+            Image::PNG{ref mut rotation, ..} |
+            Image::BMP{ref mut rotation, ..} |
+            Image::JPEG{ref mut rotation, ..} => rotation,
+            #[cfg(feature = "webp")]
+            Image::WEBP{ref mut rotation, ..} => rotation,
+        }
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(mut self, options: &Options) -> Image {
+        {
+            let (height, xdim, margin, foreground, background) = self.fields_mut();
+            *height = options.height_pixels();
+            *xdim = options.xdim_pixels();
+            *margin = options.quiet_zone_pixels();
+            *foreground = options.foreground;
+            *background = options.background;
+        }
+
+        *self.rotation_mut() = options.rotation;
+
+        self
+    }
+
+    /// Returns the MIME type of the output format, such as "image/png".
+    pub fn mime_type(&self) -> &'static str {
+        match *self {
+            Image::GIF{..} => "image/gif",
+            Image::PNG{..} => "image/png",
+            Image::BMP{..} => "image/bmp",
+            Image::JPEG{..} => "image/jpeg",
+            #[cfg(feature = "webp")]
+            Image::WEBP{..} => "image/webp",
+        }
+    }
+
+    /// Generates the given EncodedBarcode, writing the image to `writer`. Returns a usize
+    /// indicating the number of bytes of pixel data written.
+    pub fn generate<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, &str> {
+        self.draw(barcode, None, writer)
+    }
+
+    /// Generates the given EncodedBarcode with its human-readable text beneath or above the
+    /// bars, and its guard bars extended into the text, writing the image to `writer`. The
+    /// text may extend into the margin. Returns a usize indicating the number of bytes of
+    /// pixel data written.
+    pub fn generate_with_text<W: Write>(&self, barcode: &EncodedBarcode, text: &HRI, writer: &mut W) -> Result<usize, &str> {
+        self.draw(barcode, Some(text), writer)
+    }
+
+    // Draws the bars and, if given, the line of text, and encodes the image.
+    fn draw<W: Write>(&self, barcode: &EncodedBarcode, text: Option<&HRI>, writer: &mut W) -> Result<usize, &str> {
+        let (height, xdim, margin, foreground, background) = self.fields();
+        let transparent = !foreground.is_opaque() || !background.is_opaque();
 
-        let width = (barcode.len() as u32) * (xdim * IMAGE_BAR_WIDTH);
-        let mut buffer = ImageBuffer::new(width, height);
-        let mut pos = 0;
-        
-        for y in 0..height {
-            for &b in barcode {
-                let size = xdim * IMAGE_BAR_WIDTH;
+        match *self {
+            Image::PNG{..} => (),
+            _ if transparent => return Err("Only PNG images can have transparent colors."),
+            _ => (),
+        }
 
-                if b == 0 {
-                    for p in 0..size {
-                        buffer.put_pixel(pos + p, y, image::Luma([255 as u8]));
-                    }
-                }
+        let size = xdim * IMAGE_BAR_WIDTH;
+        let width = (barcode.len() as u32) * size + 2 * margin;
+        let line_height = text.map_or(0, |t| t.line_height());
+        let (bars_top, text_top) = match text.map(|t| t.position) {
+            Some(TextPosition::Above) => (margin + line_height, margin),
+            _ => (margin, margin + height),
+        };
+        let text_bitmap = text.map_or(vec![], |t| t.bitmap(width, margin, size));
+        let (bars, guards) = text.map_or((barcode.clone(), vec![]), |t| t.split_guards(barcode));
+        let extension = text.map_or(0, |t| t.guard_extension(size));
+        let guards_top = if text.map(|t| t.position) == Some(TextPosition::Above) { bars_top - extension } else { bars_top };
+        let color = |x: u32, y: u32| {
+            let module = |modules: &EncodedBarcode| x >= margin && modules.get(((x - margin) / size) as usize) == Some(&1);
+            let dark = (y >= bars_top && y < bars_top + height && module(&bars)) ||
+                (y >= guards_top && y < guards_top + height + extension && module(&guards)) ||
+                (y >= text_top && y < text_top + line_height && text_bitmap[((y - text_top) * width + x) as usize]);
+
+            if dark { foreground } else { background }
+        };
+        let total_height = height + line_height + 2 * margin;
+
+        let pixels = if is_opaque_gray(foreground) && is_opaque_gray(background) {
+            image::ImageLuma8(ImageBuffer::from_fn(width, total_height, |x, y| image::Luma([color(x, y).rgb[0]])))
+        } else if transparent {
+            image::ImageRgba8(ImageBuffer::from_fn(width, total_height, |x, y| {
+                let c = color(x, y);
+                image::Rgba([c.rgb[0], c.rgb[1], c.rgb[2], c.alpha])
+            }))
+        } else {
+            image::ImageRgb8(ImageBuffer::from_fn(width, total_height, |x, y| image::Rgb(color(x, y).rgb)))
+        };
 
-                pos += size;
-            }
+        self.encode(&rotate(pixels, self.rotation()), writer)
+    }
 
-            pos = 0;
+    // Encodes the image in the output format. Returns the number of bytes of pixel data.
+    fn encode<W: Write>(&self, pixels: &image::DynamicImage, writer: &mut W) -> Result<usize, &str> {
+        let (width, height) = pixels.dimensions();
+        let data = pixels.raw_pixels();
+
+        let result = match *self {
+            Image::GIF{..} => pixels.save(writer, image::GIF).is_ok(),
+            Image::PNG{..} => pixels.save(writer, image::PNG).is_ok(),
+            Image::BMP{..} => pixels.save(writer, image::BMP).is_ok(),
+            Image::JPEG{quality: q, ..} => {
+                image::jpeg::JPEGEncoder::new_with_quality(writer, q)
+                    .encode(&data, width, height, pixels.color())
+                    .is_ok()
+            },
+            #[cfg(feature = "webp")]
+            Image::WEBP{..} => {
+                let color = match pixels.color() {
+                    image::Gray(_) => ::image_webp::ColorType::L8,
+                    image::RGB(_) => ::image_webp::ColorType::Rgb8,
+                    _ => ::image_webp::ColorType::Rgba8,
+                };
+
+                ::image_webp::WebPEncoder::new(writer)
+                    .encode(&data, width, height, color)
+                    .is_ok()
+            },
+        };
+
+        if result {
+            Ok(data.len())
+        } else {
+            Err("Could not encode image.")
         }
+    }
 
-        let buflen = buffer.len();
+    /// Generates the given EncodedBarcode as a base64 `data:` URI, ready to use as the `src`
+    /// of an `<img>` element. Returns a String.
+    pub fn generate_data_uri(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        let mut bytes = vec![];
+        self.generate(barcode, &mut bytes)?;
 
-        match image::ImageLuma8(buffer).save(path, format) {
-            Ok(_) => Ok(buflen),
-            _ => Err("Could not encode image."),
+        Ok(data_uri(self.mime_type(), &bytes))
+    }
+
+    /// Generates the given EncodedBarcode, creating (or replacing) the image file at `path`.
+    /// Returns a usize indicating the number of bytes of pixel data written.
+    pub fn generate_to_path<P: AsRef<Path>>(&self, barcode: &EncodedBarcode, path: P) -> Result<usize, &str> {
+        match File::create(path) {
+            Ok(mut file) => self.generate(barcode, &mut file),
+            Err(_) => Err("Could not create file."),
         }
     }
 }
 
+impl Generator for Image {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        let mut buffer = vec![];
+        Image::generate(self, barcode, &mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    fn generate_to<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, GeneratorError> {
+        let mut counter = Counter::new(writer);
+        Image::generate(self, barcode, &mut counter)?;
+
+        Ok(counter.count)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate image;
@@ -83,13 +366,16 @@ mod tests {
     use ::sym::code39::*;
     use ::sym::ean_supp::*;
     use ::generators::image::*;
+    use ::generators::Color;
+    use ::generators::hri::HRI;
+    use ::generators::options::{Options, Rotation};
     use std::fs::File;
     use std::path::Path;
 
-    const TEST_DATA_BASE: &'static str = "./target/debug";
+    const TEST_DATA_BASE: &str = "./target/debug";
 
     fn open_file(name: &'static str) -> File {
-        File::create(&Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
+        File::create(Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
     }
 
     #[test]
@@ -108,7 +394,8 @@ mod tests {
         let mut path = open_file("ean13.png");
 
         let ean13 = EAN13::new("750103131130".to_string()).unwrap();
-        let png = Image::PNG{height: 100, xdim: 1};
+        let png = Image::PNG{height: 100, xdim: 1, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = png.generate(&ean13.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 9500);
@@ -119,7 +406,8 @@ mod tests {
         let mut path = open_file("code39.png");
 
         let code39 = Code39::new("ILOVEMEL".to_string()).unwrap();
-        let png = Image::PNG{height: 60, xdim: 1};
+        let png = Image::PNG{height: 60, xdim: 1, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = png.generate(&code39.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 7740);
@@ -130,7 +418,8 @@ mod tests {
         let mut path = open_file("code39.gif");
 
         let code39 = Code39::new("WIKIPEDIA".to_string()).unwrap();
-        let gif = Image::GIF{height: 60, xdim: 1};
+        let gif = Image::GIF{height: 60, xdim: 1, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = gif.generate(&code39.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 8520);
@@ -141,7 +430,8 @@ mod tests {
         let mut path = open_file("ean8.png");
 
         let ean8 = EAN8::new("5512345".to_string()).unwrap();
-        let png = Image::PNG{height: 70, xdim: 2};
+        let png = Image::PNG{height: 70, xdim: 2, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = png.generate(&ean8.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 9380);
@@ -152,7 +442,8 @@ mod tests {
         let mut path = open_file("ean8.gif");
 
         let ean8 = EAN8::new("9992227".to_string()).unwrap();
-        let gif = Image::GIF{height: 70, xdim: 2};
+        let gif = Image::GIF{height: 70, xdim: 2, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = gif.generate(&ean8.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 9380);
@@ -163,7 +454,8 @@ mod tests {
         let mut path = open_file("ean2.png");
 
         let ean2 = EANSUPP::new("94".to_string()).unwrap();
-        let png = Image::PNG{height: 70, xdim: 2};
+        let png = Image::PNG{height: 70, xdim: 2, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = png.generate(&ean2.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 2800);
@@ -174,9 +466,209 @@ mod tests {
         let mut path = open_file("ean5.gif");
 
         let ean5 = EANSUPP::new("51234".to_string()).unwrap();
-        let gif = Image::GIF{height: 70, xdim: 2};
+        let gif = Image::GIF{height: 70, xdim: 2, margin: 0,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
         let generated = gif.generate(&ean5.encode(), &mut path).unwrap();
 
         assert_eq!(generated, 6580);
     }
+
+    #[test]
+    fn ean8_as_png_with_margin() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let png = Image::PNG{height: 70, xdim: 2, margin: 10,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
+        let generated = png.generate_to_path(&ean8.encode(), Path::new(&format!("{}/{}", TEST_DATA_BASE, "ean8_margin.png"))).unwrap();
+
+        assert_eq!(generated, (67 * 2 + 20) * (70 + 20));
+    }
+
+    #[test]
+    fn ean8_as_png_in_memory() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let png = Image::png();
+        let mut bytes = vec![];
+        let generated = png.generate(&ean8.encode(), &mut bytes).unwrap();
+
+        assert_eq!(generated, 67 * 80);
+        assert_eq!(&bytes[1..4], b"PNG");
+    }
+
+    #[test]
+    fn ean8_as_transparent_png() {
+        let mut path = open_file("ean8_transparent.png");
+
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let png = Image::PNG{height: 70, xdim: 2, margin: 10,
+                             foreground: Color::new(0, 0, 128), background: Color::TRANSPARENT,
+                             rotation: Rotation::Normal};
+        let mut bytes = vec![];
+        let generated = png.generate(&ean8.encode(), &mut bytes).unwrap();
+        path.write_all(&bytes).unwrap();
+
+        assert_eq!(generated, (67 * 2 + 20) * (70 + 20) * 4);
+
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgba();
+        assert_eq!(decoded.get_pixel(0, 0).data, [255, 255, 255, 0]);
+        assert_eq!(decoded.get_pixel(10, 10).data, [0, 0, 128, 255]);
+    }
+
+    #[test]
+    fn ean8_as_colored_gif_and_jpeg() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let gif = Image::GIF{height: 70, xdim: 2, margin: 10,
+                             foreground: Color::new(0, 0, 128), background: Color::new(255, 255, 192),
+                             rotation: Rotation::Normal};
+        let jpeg = Image::JPEG{height: 70, xdim: 2, margin: 10, foreground: Color::new(0, 0, 128),
+                               background: Color::new(255, 255, 192), rotation: Rotation::Normal, quality: 100};
+        let mut bytes = vec![];
+
+        assert_eq!(gif.generate(&ean8.encode(), &mut bytes).unwrap(), (67 * 2 + 20) * (70 + 20) * 3);
+
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgba();
+        assert_eq!(decoded.get_pixel(0, 0).data, [255, 255, 192, 255]);
+        assert_eq!(decoded.get_pixel(10, 10).data, [0, 0, 128, 255]);
+
+        bytes.clear();
+        assert_eq!(jpeg.generate(&ean8.encode(), &mut bytes).unwrap(), (67 * 2 + 20) * (70 + 20) * 3);
+        assert_eq!(&bytes[0..2], &[0xFF, 0xD8]);
+    }
+
+    #[test]
+    fn transparent_bmp() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let bmp = Image::BMP{height: 10, xdim: 1, margin: 2,
+                             foreground: Color::BLACK, background: Color::TRANSPARENT,
+                             rotation: Rotation::Normal};
+
+        assert_eq!(bmp.generate(&ean8.encode(), &mut vec![]), Err("Only PNG images can have transparent colors."));
+    }
+
+    #[test]
+    fn ean13_as_png_with_text() {
+        let mut path = open_file("ean13_text.png");
+
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let hri = HRI{font_size: 14, ..HRI::ean13(&ean13)};
+        let png = Image::PNG{height: 60, xdim: 2, margin: 22,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
+        let mut bytes = vec![];
+        let generated = png.generate_with_text(&ean13.encode(), &hri, &mut bytes).unwrap();
+        path.write_all(&bytes).unwrap();
+
+        // The text is 18 pixels high with its gaps.
+        assert_eq!(generated, (95 * 2 + 44) * (60 + 18 + 44));
+
+        let decoded = image::load_from_memory(&bytes).unwrap().to_luma();
+        assert_eq!(decoded.get_pixel(22, 22 + 59).data, [0]);
+        // Only the six guard bars extend below the others.
+        assert_eq!((0..234).filter(|&x| decoded.get_pixel(x, 22 + 61).data == [0]).count(), 6 * 2);
+        // The top of the '7' left of the guard.
+        assert_eq!(decoded.get_pixel(12, 22 + 62).data, [0]);
+    }
+
+    #[test]
+    fn ean8_as_rotated_png() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let options = Options{rotation: Rotation::Clockwise90, ..Options::new()};
+        let png = Image::PNG{height: 70, xdim: 2, margin: 10,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
+        let mut bytes = vec![];
+        png.with_options(&options).generate(&ean8.encode(), &mut bytes).unwrap();
+
+        let decoded = image::load_from_memory(&bytes).unwrap().to_luma();
+        let (width, height) = decoded.dimensions();
+
+        assert_eq!(height, 67 * options.xdim_pixels() + 2 * options.quiet_zone_pixels());
+        assert_eq!(width, options.height_pixels() + 2 * options.quiet_zone_pixels());
+        // The left guard bar is at the top, across the whole height of the bars.
+        let top = options.quiet_zone_pixels();
+        assert_eq!(decoded.get_pixel(width / 2, top).data, [0]);
+        assert_eq!(decoded.get_pixel(width / 2, top - 1).data, [255]);
+        assert_eq!(decoded.get_pixel(width / 2, top + options.xdim_pixels()).data, [255]);
+    }
+
+    #[test]
+    fn code39_as_bmp() {
+        let mut path = open_file("code39.bmp");
+
+        let code39 = Code39::new("TEST8052".to_string()).unwrap();
+        let bmp = Image::bmp();
+        let generated = bmp.generate(&code39.encode(), &mut path).unwrap();
+
+        assert_eq!(generated, 129 * 80);
+    }
+
+    #[test]
+    fn ean8_as_bmp_in_memory() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let bmp = Image::BMP{height: 10, xdim: 1, margin: 2,
+                             foreground: Color::BLACK, background: Color::WHITE, rotation: Rotation::Normal};
+        let mut bytes = vec![];
+        bmp.generate(&ean8.encode(), &mut bytes).unwrap();
+
+        assert_eq!(&bytes[0..2], b"BM");
+    }
+
+    #[test]
+    fn code39_as_jpeg() {
+        let mut path = open_file("code39.jpg");
+
+        let code39 = Code39::new("TEST8052".to_string()).unwrap();
+        let jpeg = Image::jpeg();
+        let generated = jpeg.generate(&code39.encode(), &mut path).unwrap();
+
+        assert_eq!(generated, 129 * 80);
+    }
+
+    #[test]
+    fn ean8_as_jpeg_quality() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let mut low = vec![];
+        let mut high = vec![];
+        Image::JPEG{height: 40, xdim: 2, margin: 10,
+                    foreground: Color::BLACK, background: Color::WHITE,
+                    rotation: Rotation::Normal, quality: 10}.generate(&ean8.encode(), &mut low).unwrap();
+        Image::JPEG{height: 40, xdim: 2, margin: 10,
+                    foreground: Color::BLACK, background: Color::WHITE,
+                    rotation: Rotation::Normal, quality: 100}.generate(&ean8.encode(), &mut high).unwrap();
+
+        assert_eq!(&low[0..2], &[0xFF, 0xD8]);
+        assert!(low.len() < high.len());
+    }
+
+    #[test]
+    #[cfg(feature = "webp")]
+    fn code39_as_webp() {
+        let mut path = open_file("code39.webp");
+
+        let code39 = Code39::new("TEST8052".to_string()).unwrap();
+        let webp = Image::webp();
+        let generated = webp.generate(&code39.encode(), &mut path).unwrap();
+
+        assert_eq!(generated, 129 * 80);
+    }
+
+    #[test]
+    #[cfg(feature = "webp")]
+    fn ean8_as_webp_in_memory() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let webp = Image::WEBP{height: 40, xdim: 2, margin: 10,
+                               foreground: Color::BLACK, background: Color::WHITE,
+                               rotation: Rotation::Normal};
+        let mut bytes = vec![];
+        webp.generate(&ean8.encode(), &mut bytes).unwrap();
+
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WEBP");
+    }
+
+    #[test]
+    fn ean8_as_png_data_uri() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let generated = Image::png().generate_data_uri(&ean8.encode()).unwrap();
+
+        assert!(generated.starts_with("data:image/png;base64,iVBORw0KGgo"));
+        assert!(Image::gif().generate_data_uri(&ean8.encode()).unwrap().starts_with("data:image/gif;base64,R0lGOD"));
+    }
 }
@@ -0,0 +1,143 @@
+//! This module provides types for generating inline images of barcodes for terminals, using the
+//! Kitty graphics protocol or the iTerm2 inline image protocol. Both send the barcode as a
+//! base64-encoded PNG in an escape sequence.
+//!
+//! This module is only available with the `image` feature, which is enabled by default.
+
+use ::sym::EncodedBarcode;
+use ::generators::base64;
+use ::generators::Color;
+use ::generators::image::Image;
+use ::generators::options::{Options, Rotation};
+use ::generators::{Generator, GeneratorError};
+
+// The largest amount of base64 data in each chunk of the Kitty graphics protocol.
+const KITTY_CHUNK: usize = 4096;
+
+/// The inline image barcode generator type. Each variant is a terminal protocol.
+pub enum InlineImage {
+    Kitty {
+        /// The height of the barcode in pixels.
+        height: u32,
+        /// The X dimension. Specifies the width of the "narrow" bars, in pixels.
+        xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+    },
+    ITerm2 {
+        /// The height of the barcode in pixels.
+        height: u32,
+        /// The X dimension. Specifies the width of the "narrow" bars, in pixels.
+        xdim: u32,
+        /// The width of the light margin on each side of the barcode, in pixels.
+        margin: u32,
+    },
+}
+
+impl InlineImage {
+    /// Returns a new Kitty graphics protocol image with default values.
+    pub fn kitty() -> InlineImage {
+        InlineImage::Kitty{height: 80, xdim: 2, margin: 20}
+    }
+
+    /// Returns a new iTerm2 inline image with default values.
+    pub fn iterm2() -> InlineImage {
+        InlineImage::ITerm2{height: 80, xdim: 2, margin: 20}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(mut self, options: &Options) -> InlineImage {
+        match self {
+            InlineImage::Kitty{ref mut height, ref mut xdim, ref mut margin} |
+            InlineImage::ITerm2{ref mut height, ref mut xdim, ref mut margin} => {
+                *height = options.height_pixels();
+                *xdim = options.xdim_pixels();
+                *margin = options.quiet_zone_pixels();
+            },
+        }
+
+        self
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String of escape sequences.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        let (height, xdim, margin) = match *self {
+            InlineImage::Kitty{height: h, xdim: x, margin: m} => (h, x, m),
+            InlineImage::ITerm2{height: h, xdim: x, margin: m} => (h, x, m),
+        };
+
+        let mut png = vec![];
+        let image = Image::PNG{height, xdim, margin, foreground: Color::BLACK, background: Color::WHITE,
+                               rotation: Rotation::Normal};
+        image.generate(barcode, &mut png).map_err(|_| "Could not encode image.")?;
+        let data = base64(&png);
+
+        Ok(match *self {
+            InlineImage::Kitty{..} => {
+                let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+
+                chunks.iter()
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        let more = (i + 1 < chunks.len()) as u8;
+                        let keys = if i == 0 { format!("a=T,f=100,m={}", more) } else { format!("m={}", more) };
+
+                        format!("\x1b_G{};{}\x1b\\", keys, String::from_utf8_lossy(chunk))
+                    })
+                    .collect()
+            },
+            InlineImage::ITerm2{..} => {
+                format!("\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07", png.len(), data)
+            },
+        })
+    }
+}
+
+impl Generator for InlineImage {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        InlineImage::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::ean13::*;
+    use ::sym::code39::*;
+    use ::generators::inline_image::*;
+
+    #[test]
+    fn ean_13_as_kitty() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let generated = InlineImage::kitty().generate(&ean13.encode()).unwrap();
+
+        // Base64 PNG data always begins with the encoded signature.
+        assert!(generated.starts_with("\x1b_Ga=T,f=100,m=0;iVBORw0KGgo"));
+        assert!(generated.ends_with("\x1b\\"));
+        assert_eq!(generated.matches("\x1b_G").count(), 1);
+    }
+
+    #[test]
+    fn kitty_chunks() {
+        let code39 = Code39::new("1ISTHELONELIESTNUMBER".to_string()).unwrap();
+        let kitty = InlineImage::Kitty{height: 400, xdim: 5, margin: 0};
+        let generated = kitty.generate(&code39.encode()).unwrap();
+        let chunks: Vec<&str> = generated.split_terminator("\x1b\\").collect();
+
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].starts_with("\x1b_Ga=T,f=100,m=1;"));
+        assert_eq!(chunks[0].len(), "\x1b_Ga=T,f=100,m=1;".len() + 4096);
+        assert!(chunks[chunks.len() - 1].starts_with("\x1b_Gm=0;"));
+    }
+
+    #[test]
+    fn ean_13_as_iterm2() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let generated = InlineImage::iterm2().generate(&ean13.encode()).unwrap();
+
+        assert!(generated.starts_with("\x1b]1337;File=inline=1;size="));
+        assert!(generated.contains(";preserveAspectRatio=1:iVBORw0KGgo"));
+        assert!(generated.ends_with("\x07"));
+    }
+}
@@ -1,2 +1,352 @@
+pub mod ansi;
 pub mod ascii;
+pub mod epl2;
+pub mod eps;
+pub mod escpos;
+pub mod framebuffer;
+pub mod hri;
+pub mod html;
+#[cfg(feature = "image")]
 pub mod image;
+#[cfg(feature = "image")]
+pub mod inline_image;
+pub mod netpbm;
+pub mod options;
+pub mod pdf;
+pub mod raw;
+pub mod sheet;
+pub mod sixel;
+pub mod svg;
+pub mod tiff;
+pub mod tspl;
+pub mod xbm;
+pub mod xpm;
+pub mod zpl;
+
+use ::sym::EncodedBarcode;
+use ::sym::itf14::BearerBars;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Add, Mul, Sub};
+
+/// The error returned when a generator cannot generate a barcode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorError {
+    message: String,
+}
+
+impl GeneratorError {
+    /// Returns a new error with the given message.
+    pub fn new(message: &str) -> GeneratorError {
+        GeneratorError{message: message.to_string()}
+    }
+
+    /// Returns the message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for GeneratorError {}
+
+impl<'a> From<&'a str> for GeneratorError {
+    fn from(message: &'a str) -> GeneratorError {
+        GeneratorError::new(message)
+    }
+}
+
+/// An output format for barcodes. Every generator in this module implements it, so code can
+/// be generic over output formats, and other crates can add formats of their own.
+pub trait Generator {
+    /// The generated barcode, such as a String of text or the bytes of a file.
+    type Output;
+
+    /// Generates the given EncodedBarcode.
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Self::Output, GeneratorError>;
+
+    /// Generates the given EncodedBarcode, writing it to `writer`, such as a file, a socket or
+    /// the body of an HTTP response. Returns the number of bytes written. Generators that can
+    /// write their output as they build it do so, rather than holding all of it in memory.
+    fn generate_to<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, GeneratorError>
+        where Self::Output: AsRef<[u8]> {
+        let output = self.generate(barcode)?;
+
+        writer.write_all(output.as_ref()).map_err(|_| GeneratorError::new("Could not write barcode."))?;
+        Ok(output.as_ref().len())
+    }
+}
+
+// Passes writes through to another writer, counting the bytes written.
+struct Counter<'a, W: 'a + Write> {
+    writer: &'a mut W,
+    count: usize,
+}
+
+impl<'a, W: Write> Counter<'a, W> {
+    fn new(writer: &'a mut W) -> Counter<'a, W> {
+        Counter{writer, count: 0}
+    }
+}
+
+impl<'a, W: Write> Write for Counter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A color, as red, green and blue components and an alpha (opacity) component.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    /// The red, green and blue components.
+    pub rgb: [u8; 3],
+    /// The opacity, from 0 (transparent) to 255 (opaque). Formats without transparency ignore
+    /// it.
+    pub alpha: u8,
+}
+
+impl Color {
+    /// Black, the usual color of bars.
+    pub const BLACK: Color = Color{rgb: [0, 0, 0], alpha: 255};
+    /// White, the usual color of spaces.
+    pub const WHITE: Color = Color{rgb: [255, 255, 255], alpha: 255};
+    /// A fully transparent background, for compositing barcodes onto other images. It is
+    /// white where transparency is not supported.
+    pub const TRANSPARENT: Color = Color{rgb: [255, 255, 255], alpha: 0};
+
+    /// Returns a new opaque color from its red, green and blue components.
+    pub fn new(r: u8, g: u8, b: u8) -> Color {
+        Color{rgb: [r, g, b], alpha: 255}
+    }
+
+    /// Returns a new color from its red, green, blue and alpha components.
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color{rgb: [r, g, b], alpha: a}
+    }
+
+    /// Returns whether the color is fully opaque.
+    pub fn is_opaque(&self) -> bool {
+        self.alpha == 255
+    }
+
+    /// Returns the color in hexadecimal notation, such as "#ff8000". The alpha component is
+    /// not included.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.rgb[0], self.rgb[1], self.rgb[2])
+    }
+}
+
+// The characters of standard base64, for embedding binary output in text.
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Encodes bytes as standard base64, with padding.
+fn base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let bits = (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_CHARS[(bits >> (18 - 6 * i)) & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+// Returns the start and length of each run of dark modules.
+fn runs(modules: &[u8]) -> Vec<(u32, u32)> {
+    let mut runs = vec![];
+    let mut start = None;
+
+    for (i, &m) in modules.iter().chain([0].iter()).enumerate() {
+        match (m, start) {
+            (1, None) => start = Some(i),
+            (0, Some(s)) => {
+                runs.push((s as u32, (i - s) as u32));
+                start = None;
+            },
+            _ => (),
+        }
+    }
+
+    runs
+}
+
+// Rectangles of any unit: x, y, width and height.
+type Rects<T> = Vec<(T, T, T, T)>;
+
+// Lays out bearer bars around a symbol of the given size, in the units of `xdim`. Returns the
+// rectangles of the bars, the position of the symbol inside them and
+// the total size.
+fn bearer_layout<T>(bearer_bars: &BearerBars, xdim: T, width: T, height: T) -> (Rects<T>, (T, T), (T, T))
+    where T: Copy + Default + From<u32> + Add<Output = T> + Sub<Output = T> + Mul<Output = T> {
+    let zero = T::default();
+    let thickness = T::from(bearer_bars.thickness) * xdim;
+    let side = if bearer_bars.frame { thickness } else { zero };
+    let left = side + T::from(bearer_bars.quiet_zone) * xdim;
+    let (total_width, total_height) = (left + width + left, thickness + height + thickness);
+    let mut rects = vec![(zero, zero, total_width, thickness), (zero, thickness + height, total_width, thickness)];
+
+    if bearer_bars.frame {
+        rects.push((zero, thickness, thickness, height));
+        rects.push((total_width - thickness, thickness, thickness, height));
+    }
+
+    (rects, (left, thickness), (total_width, total_height))
+}
+
+// Formats a number, such as a PDF or PostScript coordinate, with at most three decimal places.
+fn number(n: f64) -> String {
+    if n.abs() < 0.0005 {
+        return "0".to_string();
+    }
+
+    let s = format!("{:.3}", n);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Writes a PDF document of the given objects, numbered from 1 with the catalog first, and its
+// cross-reference table. Returns the number of bytes written.
+fn write_pdf<W: Write>(objects: &[String], writer: &mut W) -> io::Result<usize> {
+    let mut pdf = Counter::new(writer);
+    let mut offsets = vec![];
+
+    pdf.write_all(b"%PDF-1.4\n")?;
+
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.count);
+        write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object)?;
+    }
+
+    let xref = pdf.count;
+    write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1)?;
+
+    for offset in offsets {
+        writeln!(pdf, "{:010} 00000 n ", offset)?;
+    }
+
+    write!(pdf, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref)?;
+    Ok(pdf.count)
+}
+
+/// Returns a `data:` URI of the given data, such as "data:image/png;base64,iVBO...", for use
+/// as the `src` of an `<img>` element.
+pub fn data_uri(mime_type: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", mime_type, base64(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::generators::*;
+
+    #[test]
+    fn base64_encoding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xFB, 0xFF]), "+/8=");
+    }
+
+    #[test]
+    fn color_alpha() {
+        assert!(Color::new(255, 0, 0).is_opaque());
+        assert!(!Color::TRANSPARENT.is_opaque());
+        assert_eq!(Color::rgba(255, 128, 0, 64).to_hex(), "#ff8000");
+        assert_eq!(Color::rgba(0, 0, 0, 255), Color::BLACK);
+    }
+
+    #[test]
+    fn data_uri_encoding() {
+        assert_eq!(data_uri("text/plain", b"foo"), "data:text/plain;base64,Zm9v");
+    }
+
+    // Generates a barcode with any generator, counting its dark modules.
+    struct Count;
+
+    impl Generator for Count {
+        type Output = usize;
+
+        fn generate(&self, barcode: &EncodedBarcode) -> Result<usize, GeneratorError> {
+            match barcode.len() {
+                0 => Err(GeneratorError::new("Barcode is empty")),
+                _ => Ok(barcode.iter().filter(|&&m| m == 1).count()),
+            }
+        }
+    }
+
+    fn generate_all<G: Generator>(generator: &G, barcodes: &[EncodedBarcode]) -> Result<Vec<G::Output>, GeneratorError> {
+        barcodes.iter().map(|b| generator.generate(b)).collect()
+    }
+
+    #[test]
+    fn generic_generators() {
+        let barcodes = vec![vec![1, 0, 1], vec![1, 1, 0, 1]];
+
+        assert_eq!(generate_all(&Count, &barcodes), Ok(vec![2, 3]));
+        assert_eq!(generate_all(&Count, &[vec![]]).unwrap_err().to_string(), "Barcode is empty");
+        assert_eq!(generate_all(&svg::SVG::new(), &barcodes).unwrap().len(), 2);
+        assert_eq!(generate_all(&pdf::PDF::new(), &barcodes).unwrap().len(), 2);
+    }
+
+    // Writes the output of a generator, checking it matches the generated output.
+    fn assert_writes<G: Generator>(generator: &G, barcode: &EncodedBarcode) where G::Output: AsRef<[u8]> {
+        let mut written = vec![];
+        let count = generator.generate_to(barcode, &mut written).unwrap();
+
+        assert_eq!(count, written.len());
+        assert_eq!(&written[..], generator.generate(barcode).unwrap().as_ref());
+    }
+
+    // A writer that always fails, such as a closed socket.
+    struct Closed;
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn generate_to_writer() {
+        let barcode = vec![1, 0, 1, 1, 0, 0, 1];
+
+        assert_writes(&svg::SVG{single_path: true, ..svg::SVG::new()}, &barcode);
+        assert_writes(&pdf::PDF::new(), &barcode);
+        assert_writes(&eps::EPS::new(), &barcode);
+        assert_writes(&zpl::ZPL::new(), &barcode);
+        assert_writes(&raw::Raw::mono(), &barcode);
+        assert_writes(&framebuffer::Framebuffer::new(), &barcode);
+        #[cfg(feature = "image")]
+        assert_writes(&image::Image::png(), &barcode);
+
+        let error = svg::SVG::new().generate_to(&barcode, &mut Closed).unwrap_err();
+
+        assert_eq!(error.message(), "Could not write barcode.");
+        assert!(tiff::TIFF::new().generate_to(&barcode, &mut Closed).is_err());
+    }
+}
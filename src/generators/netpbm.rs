@@ -0,0 +1,175 @@
+//! This module provides types for generating Netpbm (PBM and PGM) images of barcodes. The
+//! formats need no encoder, and tools such as ImageMagick read them from a pipe.
+//!
+//! Images are written in the binary ("raw") variants: P4 for PBM and P5 for PGM.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The Netpbm image formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NetpbmFormat {
+    /// Portable bitmap: one bit per pixel, with 1 for black.
+    PBM,
+    /// Portable graymap: one byte per pixel, from 0 for black to 255 for white.
+    PGM,
+}
+
+/// The Netpbm barcode generator type.
+pub struct Netpbm {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For Netpbm, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The image format.
+    pub format: NetpbmFormat,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for Netpbm {
+    fn default() -> Netpbm {
+        Netpbm::pbm()
+    }
+}
+
+impl Netpbm {
+    /// Returns a new PBM with default values.
+    pub fn pbm() -> Netpbm {
+        Netpbm{height: 80, xdim: 1, quiet_zone: 10, format: NetpbmFormat::PBM, rotation: Rotation::Normal}
+    }
+
+    /// Returns a new PGM with default values.
+    pub fn pgm() -> Netpbm {
+        Netpbm{format: NetpbmFormat::PGM, ..Netpbm::pbm()}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> Netpbm {
+        Netpbm{height: options.height_pixels(), xdim: options.xdim_pixels(),
+               quiet_zone: options.quiet_zone_modules(), rotation: options.rotation, ..self}
+    }
+
+    // Writes the header and pixels of one bit per pixel in the chosen format.
+    fn image(&self, pixels: Pixels) -> Vec<u8> {
+        let (width, height) = (pixels.width as usize, pixels.height as usize);
+
+        match self.format {
+            NetpbmFormat::PBM => {
+                let mut image = format!("P4\n{} {}\n", width, height).into_bytes();
+                image.extend(pixels.data);
+                image
+            },
+            NetpbmFormat::PGM => {
+                let mut image = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+
+                for y in 0..height {
+                    image.extend((0..width).map(|x| {
+                        match (pixels.data[y * pixels.stride() + x / 8] >> (7 - x % 8)) & 1 {
+                            1 => 0,
+                            _ => 255,
+                        }
+                    }));
+                }
+
+                image
+            },
+        }
+    }
+
+    // Returns a raw pixel generator with the same dimensions.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode. Returns the bytes of the image.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        self.raw().generate(barcode).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns the bytes of the image.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        self.raw().generate_bars(bars).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns the bytes of the image.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        self.raw().generate_rows(rows).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns the
+    /// bytes of the image.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.image(p))
+    }
+}
+
+impl Generator for Netpbm {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        Netpbm::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::netpbm::*;
+
+    #[test]
+    fn code_39_as_pbm() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pbm = Netpbm{height: 1, quiet_zone: 0, ..Netpbm::pbm()};
+        let generated = pbm.generate(&code39.encode()).unwrap();
+
+        let mut expected = b"P4\n38 1\n".to_vec();
+        expected.extend(&[0x96, 0xD6, 0xA5, 0xA5, 0xB4]);
+        assert_eq!(generated, expected);
+    }
+
+    #[test]
+    fn code_39_as_pgm() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pgm = Netpbm{height: 2, quiet_zone: 1, ..Netpbm::pgm()};
+        let generated = pgm.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with(b"P5\n40 2\n255\n"));
+        assert_eq!(generated.len(), 12 + 40 * 2);
+        assert_eq!(&generated[12..17], &[255, 0, 255, 255, 0]);
+    }
+
+    #[test]
+    fn bars_as_pgm() {
+        let pgm = Netpbm{height: 3, quiet_zone: 0, ..Netpbm::pgm()};
+        let generated = pgm.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        // Bars are separated by a space, and the descender covers the lower two of three rows.
+        assert!(generated.starts_with(b"P5\n3 3\n255\n"));
+        assert_eq!(&generated[11..], &[0, 255, 255, 0, 255, 0, 0, 255, 0]);
+    }
+
+    #[test]
+    fn qr_as_pbm() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = Netpbm{quiet_zone: 4, ..Netpbm::pbm()}.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.starts_with(b"P4\n29 29\n"));
+        assert_eq!(generated.len(), 9 + 4 * 29);
+    }
+}
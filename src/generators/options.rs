@@ -0,0 +1,589 @@
+//! This module provides the options shared by all generators. Each generator has a
+//! `with_options` method that applies them in its own units, such as pixels, printer dots or
+//! millimeters, so sizes can be chosen once for every output format.
+//!
+//! Lengths in millimeters or inches are converted to whole pixels at `Options::dpi`, and
+//! lengths in pixels are converted to millimeters the same way. Modules that are not a whole
+//! number of printer dots print unevenly, so `Options::snap` rounds the sizes to whole dots
+//! for every generator, and `Options::sized` fits a barcode into a physical size with modules
+//! of whole dots.
+//!
+//! Quiet zones are at least as wide as the minimum of the symbology, which constrained
+//! layouts can opt out of by setting `Options::min_quiet_zone` to zero.
+//!
+//! Heights may depend on the width of the barcode. `Options::fit` resolves them for a given
+//! barcode, and `Options::for_symbology` starts from the minimum height in the specification
+//! of a symbology.
+//!
+//! `Options::bwr` narrows every bar around its center while keeping the module pitch, to
+//! compensate for ink that spreads when printed, for the generators of print formats: raw
+//! pixels, framebuffers, SVG, PDF and EPS.
+//!
+//! `Options::rotation` turns the whole barcode, including its quiet zones, for the generators
+//! that draw rectangles or pixels: raw pixels and the formats built on them, images, SVG, PDF
+//! and EPS.
+
+use std::ops::Sub;
+use ::sym::EncodedBarcode;
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::upce::UPCE;
+use ::sym::code39::Code39;
+use ::sym::code93::Code93;
+use ::sym::code128::Code128;
+use ::sym::gs1_128::GS1_128;
+use ::sym::codabar::Codabar;
+use ::sym::two_of_five::I2of5;
+use ::sym::itf14::ITF14;
+use ::generators::Color;
+
+/// The number of millimeters in an inch.
+pub const MM_PER_INCH: f64 = 25.4;
+
+/// A length, in pixels (or printer dots), millimeters or inches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    /// A number of pixels, or dots for printers.
+    Pixels(u32),
+    /// A number of millimeters.
+    Millimeters(f64),
+    /// A number of inches.
+    Inches(f64),
+}
+
+impl Length {
+    /// Returns the length in pixels at the given resolution, rounded to the nearest pixel.
+    pub fn pixels(&self, dpi: u32) -> u32 {
+        match *self {
+            Length::Pixels(p) => p,
+            Length::Millimeters(mm) => (mm * dpi as f64 / MM_PER_INCH).round() as u32,
+            Length::Inches(inches) => (inches * dpi as f64).round() as u32,
+        }
+    }
+
+    /// Returns the length in millimeters at the given resolution.
+    pub fn millimeters(&self, dpi: u32) -> f64 {
+        match *self {
+            Length::Pixels(p) => p as f64 * MM_PER_INCH / dpi as f64,
+            Length::Millimeters(mm) => mm,
+            Length::Inches(inches) => inches * MM_PER_INCH,
+        }
+    }
+}
+
+/// The height of the bars.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Height {
+    /// A fixed height.
+    Absolute(Length),
+    /// A multiple of the width of the barcode, without its quiet zones.
+    Ratio(f64),
+    /// The larger of a fixed height and a multiple of the width of the barcode, as most
+    /// specifications give their minimum heights.
+    AtLeast(Length, f64),
+}
+
+/// The bar width reduction (BWR): how much narrower than their modules bars are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarWidthReduction {
+    /// Bars are as wide as their modules.
+    None,
+    /// A fixed length, such as the measured ink spread of a printer.
+    Absolute(Length),
+    /// A fraction of the X dimension.
+    Modules(f64),
+}
+
+/// A clockwise rotation of the whole barcode, including its quiet zones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    /// No rotation, with vertical bars.
+    Normal,
+    /// A quarter turn clockwise, with horizontal bars ("ladder" orientation).
+    Clockwise90,
+    /// A half turn.
+    UpsideDown,
+    /// Three quarter turns clockwise, or a quarter turn counterclockwise.
+    Clockwise270,
+}
+
+impl Rotation {
+    /// Returns the angle of the rotation in degrees, clockwise.
+    pub fn degrees(&self) -> u32 {
+        match *self {
+            Rotation::Normal => 0,
+            Rotation::Clockwise90 => 90,
+            Rotation::UpsideDown => 180,
+            Rotation::Clockwise270 => 270,
+        }
+    }
+
+    /// Returns the width and height of an image of the given size, after rotation.
+    pub fn size<T>(&self, width: T, height: T) -> (T, T) {
+        match *self {
+            Rotation::Normal | Rotation::UpsideDown => (width, height),
+            Rotation::Clockwise90 | Rotation::Clockwise270 => (height, width),
+        }
+    }
+
+    /// Returns a rectangle (x, y, width and height from the top left) of an image of the given
+    /// size, after rotation.
+    pub fn rect<T: Copy + Sub<Output = T>>(&self, rect: (T, T, T, T), width: T, height: T) -> (T, T, T, T) {
+        let (x, y, w, h) = rect;
+
+        match *self {
+            Rotation::Normal => rect,
+            Rotation::Clockwise90 => (height - y - h, x, h, w),
+            Rotation::UpsideDown => (width - x - w, height - y - h, w, h),
+            Rotation::Clockwise270 => (y, width - x - w, h, w),
+        }
+    }
+}
+
+/// The sizes given by the specification of a symbology.
+pub trait Specification {
+    /// Returns the minimum height of the bars.
+    fn min_height() -> Height;
+
+    /// Returns the minimum width of the quiet zone on each side of the barcode, in modules.
+    fn quiet_zone() -> u32;
+}
+
+/// The options shared by all generators.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The X dimension: the width of the "narrow" bars and of each module. Widths in
+    /// millimeters are at least one pixel wide.
+    pub xdim: Length,
+    /// The height of the bars. Heights that depend on the width of the barcode must be
+    /// resolved with `fit`; until then, only their fixed part is used.
+    pub height: Height,
+    /// The width of the quiet zone on each side of the barcode, in modules. Generators with a
+    /// margin in pixels use this many modules.
+    pub quiet_zone: u32,
+    /// The minimum width of the quiet zone, in modules. Narrower quiet zones are widened to
+    /// it; set it to zero to opt out.
+    pub min_quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone. Formats that support it, such as PNG and
+    /// SVG, may leave a transparent background undrawn.
+    pub background: Color,
+    /// The resolution used to convert between pixels and millimeters, in pixels (or printer
+    /// dots) per inch.
+    pub dpi: u32,
+    /// The rotation of the barcode. Generators that cannot rotate their output ignore it.
+    pub rotation: Rotation,
+    /// The bar width reduction. Spaces widen by as much, so modules keep their pitch.
+    /// Only raw pixels, framebuffers, SVG, PDF and EPS apply it.
+    pub bwr: BarWidthReduction,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options::new()
+    }
+}
+
+impl Options {
+    /// Returns new Options with default values: black modules two pixels wide, bars 80 pixels
+    /// high and white quiet zones of 10 modules, at 96 dpi, without rotation or bar width
+    /// reduction.
+    pub fn new() -> Options {
+        Options{xdim: Length::Pixels(2), height: Height::Absolute(Length::Pixels(80)), quiet_zone: 10,
+                min_quiet_zone: 0, foreground: Color::BLACK, background: Color::WHITE, dpi: 96,
+                rotation: Rotation::Normal, bwr: BarWidthReduction::None}
+    }
+
+    /// Returns new Options with the minimum height and quiet zone of the given symbology,
+    /// such as `Options::for_symbology::<EAN13>()`.
+    pub fn for_symbology<S: Specification>() -> Options {
+        Options{height: S::min_height(), quiet_zone: S::quiet_zone(), min_quiet_zone: S::quiet_zone(),
+                ..Options::new()}
+    }
+
+    /// Returns the width of the quiet zone in modules, widened to the minimum.
+    pub fn quiet_zone_modules(&self) -> u32 {
+        self.quiet_zone.max(self.min_quiet_zone)
+    }
+
+    /// Returns the width of the quiet zone in whole pixels, for generators with a margin.
+    pub fn quiet_zone_pixels(&self) -> u32 {
+        self.quiet_zone_modules() * self.xdim_pixels()
+    }
+
+    /// Returns these options with the height resolved for the given barcode, in pixels if
+    /// the X dimension is in pixels and in millimeters otherwise.
+    pub fn fit(&self, barcode: &EncodedBarcode) -> Options {
+        let modules = barcode.len() as u32;
+        let height = match self.xdim {
+            Length::Pixels(_) => Length::Pixels(self.height_for(modules)),
+            _ => Length::Millimeters(self.height_millimeters_for(modules)),
+        };
+
+        Options{height: Height::Absolute(height), ..self.clone()}
+    }
+
+    /// Returns these options with the X dimension and the fixed part of the height rounded
+    /// to whole dots at `self.dpi`, so generators in millimeters draw modules that print
+    /// evenly.
+    pub fn snap(&self) -> Options {
+        let snap = |length: Length| Length::Pixels(length.pixels(self.dpi));
+        let height = match self.height {
+            Height::Absolute(length) => Height::Absolute(snap(length)),
+            Height::Ratio(ratio) => Height::Ratio(ratio),
+            Height::AtLeast(length, ratio) => Height::AtLeast(snap(length), ratio),
+        };
+
+        Options{xdim: Length::Pixels(self.xdim_pixels()), height, ..self.clone()}
+    }
+
+    /// Returns these options sized for the given barcode and its quiet zones to fit into a
+    /// physical width and height at `self.dpi`. The X dimension is the widest whole number of
+    /// dots that fits, at least one, and the height is rounded to whole dots.
+    pub fn sized(&self, barcode: &EncodedBarcode, width: Length, height: Length) -> Options {
+        let modules = barcode.len() as u32 + 2 * self.quiet_zone_modules();
+        let xdim = (width.pixels(self.dpi) / modules.max(1)).max(1);
+
+        Options{xdim: Length::Pixels(xdim), height: Height::Absolute(Length::Pixels(height.pixels(self.dpi))),
+                ..self.clone()}
+    }
+
+    // Returns the height in pixels for a barcode the given number of modules wide.
+    fn height_for(&self, modules: u32) -> u32 {
+        let width = (modules * self.xdim_pixels()) as f64;
+
+        match self.height {
+            Height::Absolute(length) => length.pixels(self.dpi),
+            Height::Ratio(ratio) => (ratio * width).round() as u32,
+            Height::AtLeast(length, ratio) => length.pixels(self.dpi).max((ratio * width).round() as u32),
+        }
+    }
+
+    // Returns the height in millimeters for a barcode the given number of modules wide.
+    fn height_millimeters_for(&self, modules: u32) -> f64 {
+        let width = modules as f64 * self.xdim_millimeters();
+
+        match self.height {
+            Height::Absolute(length) => length.millimeters(self.dpi),
+            Height::Ratio(ratio) => ratio * width,
+            Height::AtLeast(length, ratio) => length.millimeters(self.dpi).max(ratio * width),
+        }
+    }
+
+    /// Returns the height in whole pixels, at least one.
+    pub fn height_pixels(&self) -> u32 {
+        self.height_for(0).max(1)
+    }
+
+    /// Returns the height in millimeters. Heights in pixels are converted at `self.dpi`.
+    pub fn height_millimeters(&self) -> f64 {
+        self.height_millimeters_for(0)
+    }
+
+    /// Returns the X dimension in whole pixels, at least one.
+    pub fn xdim_pixels(&self) -> u32 {
+        self.xdim.pixels(self.dpi).max(1)
+    }
+
+    /// Returns the X dimension in millimeters. Widths in pixels are converted at `self.dpi`.
+    pub fn xdim_millimeters(&self) -> f64 {
+        self.xdim.millimeters(self.dpi)
+    }
+
+    /// Returns the bar width reduction in whole pixels, rounded to the nearest pixel.
+    pub fn bwr_pixels(&self) -> u32 {
+        match self.bwr {
+            BarWidthReduction::None => 0,
+            BarWidthReduction::Absolute(length) => length.pixels(self.dpi),
+            BarWidthReduction::Modules(fraction) => (fraction * self.xdim_pixels() as f64).round() as u32,
+        }
+    }
+
+    /// Returns the bar width reduction in millimeters.
+    pub fn bwr_millimeters(&self) -> f64 {
+        match self.bwr {
+            BarWidthReduction::None => 0.0,
+            BarWidthReduction::Absolute(length) => length.millimeters(self.dpi),
+            BarWidthReduction::Modules(fraction) => fraction * self.xdim_millimeters(),
+        }
+    }
+}
+
+// EAN and UPC symbols are 22.85mm high at the nominal X dimension of 0.33mm, and scale with
+// it. Their widths are 95 (EAN-13 and UPC-A), 67 (EAN-8) and 51 (UPC-E) modules. The quiet
+// zones are those on the left, which are the wider.
+impl Specification for EAN13 {
+    fn min_height() -> Height {
+        Height::Ratio(22.85 / (0.33 * 95.0))
+    }
+
+    fn quiet_zone() -> u32 {
+        11
+    }
+}
+
+impl Specification for UPCA {
+    fn min_height() -> Height {
+        Height::Ratio(22.85 / (0.33 * 95.0))
+    }
+
+    fn quiet_zone() -> u32 {
+        9
+    }
+}
+
+impl Specification for EAN8 {
+    fn min_height() -> Height {
+        Height::Ratio(18.23 / (0.33 * 67.0))
+    }
+
+    fn quiet_zone() -> u32 {
+        7
+    }
+}
+
+impl Specification for UPCE {
+    fn min_height() -> Height {
+        Height::Ratio(22.85 / (0.33 * 51.0))
+    }
+
+    fn quiet_zone() -> u32 {
+        9
+    }
+}
+
+// The ISO specifications of these symbologies require at least 5mm, or 15% of the width, and
+// quiet zones of 10 modules.
+impl Specification for Code39 {
+    fn min_height() -> Height {
+        Height::AtLeast(Length::Millimeters(5.0), 0.15)
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+impl Specification for Code93 {
+    fn min_height() -> Height {
+        Height::AtLeast(Length::Millimeters(5.0), 0.15)
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+impl Specification for Code128 {
+    fn min_height() -> Height {
+        Height::AtLeast(Length::Millimeters(5.0), 0.15)
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+impl Specification for Codabar {
+    fn min_height() -> Height {
+        Height::AtLeast(Length::Millimeters(5.0), 0.15)
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+impl Specification for I2of5 {
+    fn min_height() -> Height {
+        Height::AtLeast(Length::Millimeters(5.0), 0.15)
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+// GS1 logistic labels need 31.75mm (1.25in) for scanning in general distribution.
+impl Specification for GS1_128 {
+    fn min_height() -> Height {
+        Height::Absolute(Length::Millimeters(31.75))
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+impl Specification for ITF14 {
+    fn min_height() -> Height {
+        Height::Absolute(Length::Millimeters(31.75))
+    }
+
+    fn quiet_zone() -> u32 {
+        10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::generators::options::*;
+    use ::generators::svg::SVG;
+    use ::generators::pdf::PDF;
+    use ::generators::framebuffer::Framebuffer;
+    use ::sym::ean13::EAN13;
+    use ::sym::code39::Code39;
+
+    #[test]
+    fn length_conversion() {
+        assert_eq!(Length::Pixels(3).pixels(300), 3);
+        assert_eq!(Length::Millimeters(0.33).pixels(300), 4);
+        assert_eq!(Length::Millimeters(25.4).pixels(203), 203);
+        assert_eq!(Length::Pixels(150).millimeters(300), 12.7);
+        assert_eq!(Length::Millimeters(0.5).millimeters(300), 0.5);
+        assert_eq!(Length::Inches(0.5).pixels(203), 102);
+        assert_eq!(Length::Inches(0.5).millimeters(203), 12.7);
+    }
+
+    #[test]
+    fn options_xdim() {
+        let options = Options{xdim: Length::Millimeters(0.05), ..Options::new()};
+
+        assert_eq!(options.xdim_pixels(), 1);
+        assert_eq!(options.xdim_millimeters(), 0.05);
+        assert_eq!(Options::new().xdim_pixels(), 2);
+    }
+
+    #[test]
+    fn generators_with_options() {
+        let options = Options{xdim: Length::Millimeters(0.33), dpi: 300, ..Options::new()};
+
+        assert_eq!(SVG::new().with_options(&options).xdim, 4);
+        assert_eq!(PDF::new().with_options(&options).xdim, 0.33);
+        assert_eq!(Framebuffer::new().with_options(&options).module_dots(), 4);
+
+        let options = Options{xdim: Length::Pixels(3), dpi: 254, ..Options::new()};
+
+        assert_eq!(SVG::new().with_options(&options).xdim, 3);
+        assert!((PDF::new().with_options(&options).xdim - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn options_height() {
+        let options = Options{height: Height::Absolute(Length::Millimeters(10.0)), dpi: 254, ..Options::new()};
+
+        assert_eq!(options.height_pixels(), 100);
+        assert_eq!(options.height_millimeters(), 10.0);
+        assert_eq!(SVG::new().with_options(&options).height, 100);
+        assert_eq!(PDF::new().with_options(&options).height, 10.0);
+    }
+
+    #[test]
+    fn options_fit() {
+        let barcode = vec![1; 100];
+        let options = Options{height: Height::Ratio(0.5), ..Options::new()};
+
+        // Relative heights are only resolved for a barcode.
+        assert_eq!(options.height_pixels(), 1);
+        assert_eq!(options.fit(&barcode).height, Height::Absolute(Length::Pixels(100)));
+
+        let options = Options{xdim: Length::Millimeters(0.5), height: Height::AtLeast(Length::Millimeters(5.0), 0.15),
+                              ..Options::new()};
+
+        assert_eq!(options.height_millimeters(), 5.0);
+        assert_eq!(options.fit(&vec![1; 40]).height, Height::Absolute(Length::Millimeters(5.0)));
+        assert_eq!(options.fit(&barcode).height, Height::Absolute(Length::Millimeters(7.5)));
+    }
+
+    #[test]
+    fn options_for_symbology() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let options = Options{xdim: Length::Millimeters(0.33), dpi: 300, ..Options::for_symbology::<EAN13>()};
+        let fitted = options.fit(&ean13.encode());
+
+        match fitted.height {
+            Height::Absolute(Length::Millimeters(mm)) => assert!((mm - 22.85).abs() < 1e-9),
+            _ => panic!("Height was not resolved"),
+        }
+
+        assert_eq!(fitted.height_pixels(), 270);
+        assert_eq!(Options::for_symbology::<Code39>().height, Height::AtLeast(Length::Millimeters(5.0), 0.15));
+    }
+
+    #[test]
+    fn options_quiet_zone() {
+        let options = Options::for_symbology::<EAN13>();
+
+        assert_eq!(options.quiet_zone_modules(), 11);
+        assert_eq!(Options{quiet_zone: 4, ..options.clone()}.quiet_zone_modules(), 11);
+        assert_eq!(Options{quiet_zone: 4, min_quiet_zone: 0, ..options.clone()}.quiet_zone_modules(), 4);
+        assert_eq!(Options{quiet_zone: 20, ..options.clone()}.quiet_zone_pixels(), 40);
+        assert_eq!(SVG::new().with_options(&options).quiet_zone, 11);
+        assert_eq!(Options::new().quiet_zone_modules(), 10);
+    }
+
+    #[test]
+    fn options_colors() {
+        let options = Options{foreground: Color::new(0, 0, 128), background: Color::TRANSPARENT, ..Options::new()};
+        let svg = SVG::new().with_options(&options);
+
+        assert_eq!((svg.foreground, svg.background), (Color::new(0, 0, 128), Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn options_rotation() {
+        assert_eq!(Rotation::Clockwise90.size(40, 10), (10, 40));
+        assert_eq!(Rotation::UpsideDown.size(40, 10), (40, 10));
+        assert_eq!(Rotation::Normal.rect((1, 2, 3, 4), 40, 10), (1, 2, 3, 4));
+        assert_eq!(Rotation::Clockwise90.rect((1, 2, 3, 4), 40, 10), (4, 1, 4, 3));
+        assert_eq!(Rotation::UpsideDown.rect((1, 2, 3, 4), 40, 10), (36, 4, 3, 4));
+        assert_eq!(Rotation::Clockwise270.rect((1, 2, 3, 4), 40, 10), (2, 36, 4, 3));
+
+        let options = Options{rotation: Rotation::Clockwise90, ..Options::new()};
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let bitmap = Framebuffer::new().with_options(&options).generate(&code39.encode()).unwrap();
+
+        assert_eq!((bitmap.width, bitmap.height), (80, 116));
+    }
+
+    #[test]
+    fn options_bwr() {
+        let options = Options{xdim: Length::Millimeters(0.5), bwr: BarWidthReduction::Absolute(Length::Millimeters(0.1)),
+                              dpi: 254, ..Options::new()};
+
+        assert_eq!(options.bwr_pixels(), 1);
+        assert_eq!(options.bwr_millimeters(), 0.1);
+        assert_eq!(Framebuffer::new().with_options(&options).bwr, 0.1);
+
+        let options = Options{bwr: BarWidthReduction::Modules(0.2), ..options};
+
+        assert_eq!(options.bwr_pixels(), 1);
+        assert!((PDF::new().with_options(&options).bwr - 0.1).abs() < 1e-9);
+        assert_eq!(SVG::new().with_options(&Options::new()).bwr, 0);
+    }
+
+    #[test]
+    fn options_snap() {
+        let options = Options{xdim: Length::Millimeters(0.33), height: Height::AtLeast(Length::Millimeters(10.0), 0.15),
+                              dpi: 300, ..Options::new()}.snap();
+
+        // 3.9 dots wide, so every module is four dots.
+        assert_eq!(options.xdim, Length::Pixels(4));
+        assert_eq!(options.height, Height::AtLeast(Length::Pixels(118), 0.15));
+        assert!((PDF::new().with_options(&options).xdim - 4.0 * 25.4 / 300.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn options_sized() {
+        let barcode = vec![1; 95];
+        let options = Options{quiet_zone: 10, dpi: 203, ..Options::new()};
+        let sized = options.sized(&barcode, Length::Inches(2.0), Length::Millimeters(25.4));
+
+        // 406 dots for 115 modules, so three dots each.
+        assert_eq!(sized.xdim, Length::Pixels(3));
+        assert_eq!(sized.height_pixels(), 203);
+        assert_eq!(options.sized(&barcode, Length::Millimeters(1.0), Length::Millimeters(1.0)).xdim_pixels(), 1);
+    }
+}
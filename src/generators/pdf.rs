@@ -0,0 +1,323 @@
+//! This module provides types for generating PDF representations of barcodes. The barcode is
+//! drawn with vector rectangles on a single page the exact size of the symbol and its quiet
+//! zone, so it can be placed or printed at 100% scale.
+//!
+//! All dimensions are in millimeters.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::{Generator, GeneratorError, bearer_layout, number, write_pdf};
+use ::sym::itf14::BearerBars;
+use std::io::{self, Write};
+use ::generators::options::{Options, Rotation};
+
+/// The number of PDF points (1/72 inch) in a millimeter.
+pub const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+/// The PDF barcode generator type.
+pub struct PDF {
+    /// The height of the barcode in millimeters.
+    pub height: f64,
+    /// The X dimension. Specifies the width of the "narrow" bars, in millimeters.
+    pub xdim: f64,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The clockwise rotation of the whole page, including the quiet zone.
+    pub rotation: Rotation,
+    /// The bar width reduction in millimeters. Bars are drawn this much narrower around
+    /// their centers, by at most half the X dimension. Modules of 2D barcodes are not reduced.
+    pub bwr: f64,
+}
+
+impl Default for PDF {
+    fn default() -> PDF {
+        PDF::new()
+    }
+}
+
+// A rectangle in millimeters from the top left: x, y, width and height.
+type Rect = (f64, f64, f64, f64);
+
+// Formats a color for the `rg` operator.
+fn color(c: Color) -> String {
+    c.rgb.iter().map(|&v| number(v as f64 / 255.0)).collect::<Vec<String>>().join(" ")
+}
+
+impl PDF {
+    /// Returns a new PDF with default values.
+    pub fn new() -> PDF {
+        PDF{height: 20.0, xdim: 0.33, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+            rotation: Rotation::Normal, bwr: 0.0}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in millimeters.
+    pub fn with_options(self, options: &Options) -> PDF {
+        PDF{height: options.height_millimeters(), xdim: options.xdim_millimeters(),
+            quiet_zone: options.quiet_zone_modules(),
+            foreground: options.foreground, background: options.background, rotation: options.rotation,
+            bwr: options.bwr_millimeters()}
+    }
+
+    // Builds the document from the dark rectangles of a symbol of the given size in
+    // millimeters, not counting the quiet zone.
+    fn document(&self, width: f64, height: f64, rects: &[Rect], vertical_quiet_zone: bool) -> Vec<u8> {
+        let mut pdf = vec![];
+
+        self.write_document(width, height, rects, vertical_quiet_zone, &mut pdf).expect("Writing to a Vec cannot fail");
+        pdf
+    }
+
+    // Writes the document to `writer`. Only the page content is built in memory, as its
+    // length comes before it. Returns the number of bytes written.
+    fn write_document<W: Write>(&self, width: f64, height: f64, rects: &[Rect], vertical_quiet_zone: bool,
+                                writer: &mut W) -> io::Result<usize> {
+        let margin = self.quiet_zone as f64 * self.xdim;
+        let top = if vertical_quiet_zone { margin } else { 0.0 };
+        let (total_width, total_height) = (width + 2.0 * margin, height + 2.0 * top);
+        let (image_width, image_height) = self.rotation.size(total_width, total_height);
+        let (page_width, page_height) = (image_width * POINTS_PER_MM, image_height * POINTS_PER_MM);
+
+        let mut content = format!("{} rg\n0 0 {} {} re f\n{} rg\n",
+                                  color(self.background), number(page_width), number(page_height),
+                                  color(self.foreground));
+
+        for &(x, y, w, h) in rects {
+            let (x, y, w, h) = self.rotation.rect((x + margin, y + top, w, h), total_width, total_height);
+            // PDF coordinates start at the bottom left.
+            let bottom = (image_height - y - h) * POINTS_PER_MM;
+
+            content.push_str(&format!("{} {} {} {} re\n", number(x * POINTS_PER_MM),
+                                      number(bottom), number(w * POINTS_PER_MM),
+                                      number(h * POINTS_PER_MM)));
+        }
+
+        content.push_str("f\n");
+
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R >>",
+                    number(page_width), number(page_height)),
+            format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+        ];
+
+        write_pdf(&objects, writer)
+    }
+
+    // The rectangles of the runs of dark modules of one row, at the given y position and
+    // height.
+    fn row_rects(&self, modules: &[u8], y: f64, height: f64) -> Vec<Rect> {
+        let mut rects = vec![];
+        let mut start = None;
+
+        for (i, &m) in modules.iter().chain([0].iter()).enumerate() {
+            match (m, start) {
+                (1, None) => start = Some(i),
+                (0, Some(s)) => {
+                    rects.push((s as f64 * self.xdim, y, (i - s) as f64 * self.xdim, height));
+                    start = None;
+                },
+                _ => (),
+            }
+        }
+
+        rects
+    }
+
+    // The rectangles of one row of bars, narrowed by the bar width reduction.
+    fn bar_rects(&self, modules: &[u8], y: f64, height: f64) -> Vec<Rect> {
+        let reduction = self.bwr.max(0.0).min(self.xdim / 2.0);
+
+        self.row_rects(modules, y, height)
+            .into_iter()
+            .map(|(x, y, w, h)| (x + reduction / 2.0, y, w - reduction, h))
+            .collect()
+    }
+
+    /// Generates the given EncodedBarcode. Returns the bytes of the PDF file.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        let rects = self.bar_rects(barcode, 0.0, self.height);
+
+        Ok(self.document(barcode.len() as f64 * self.xdim, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedBarcode of an ITF-14 symbol, surrounded by the given bearer
+    /// bars. The quiet zone of the bearer bars replaces `self.quiet_zone`, and the bearer bars
+    /// are not narrowed by the bar width reduction. Returns a Vec<u8>.
+    pub fn generate_with_bearer_bars(&self, barcode: &EncodedBarcode, bearer_bars: &BearerBars) -> Result<Vec<u8>, &str> {
+        let (mut rects, (left, top), (width, height)) =
+            bearer_layout(bearer_bars, self.xdim, barcode.len() as f64 * self.xdim, self.height);
+        rects.extend(self.bar_rects(barcode, top, self.height).into_iter().map(|(x, y, w, h)| (x + left, y, w, h)));
+
+        Ok(PDF{quiet_zone: 0, ..*self}.document(width, height, &rects, false))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns the bytes of the PDF file.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        let section = self.height / 3.0;
+        let rects: Vec<Rect> = bars.iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                let (y, h) = match b {
+                    BarState::Full => (0.0, self.height),
+                    BarState::Ascender => (0.0, self.height - section),
+                    BarState::Descender => (section, self.height - section),
+                    BarState::Tracker => (section, self.height - 2.0 * section),
+                };
+
+                (2.0 * i as f64 * self.xdim, y, self.xdim, h)
+            })
+            .collect();
+        let width = (2 * bars.len()).saturating_sub(1) as f64 * self.xdim;
+
+        Ok(self.document(width, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns the bytes of the PDF file.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        let mut rects = vec![];
+        let mut y = 0.0;
+
+        for row in rows {
+            let height = row.height as f64 * self.xdim;
+
+            rects.extend(self.bar_rects(&row.modules, y, height));
+            y += height;
+        }
+
+        let width = rows.iter().map(|r| r.modules.len()).max().unwrap_or(0) as f64 * self.xdim;
+
+        Ok(self.document(width, y, &rects, false))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns the
+    /// bytes of the PDF file.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        let rects: Vec<Rect> = matrix.rows()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| self.row_rects(row, y as f64 * self.xdim, self.xdim))
+            .collect();
+
+        Ok(self.document(matrix.width as f64 * self.xdim, matrix.height as f64 * self.xdim, &rects, true))
+    }
+}
+
+impl Generator for PDF {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        PDF::generate(self, barcode).map_err(GeneratorError::from)
+    }
+
+    fn generate_to<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, GeneratorError> {
+        let rects = self.bar_rects(barcode, 0.0, self.height);
+
+        self.write_document(barcode.len() as f64 * self.xdim, self.height, &rects, false, writer)
+            .map_err(|_| GeneratorError::new("Could not write barcode."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::pdf::*;
+
+    fn as_string(pdf: Vec<u8>) -> String {
+        String::from_utf8(pdf).unwrap()
+    }
+
+    #[test]
+    fn code_39_as_pdf() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pdf = PDF{height: 25.4, xdim: 25.4 / 72.0, quiet_zone: 0, ..PDF::new()};
+        let generated = as_string(pdf.generate(&code39.encode()).unwrap());
+
+        assert!(generated.starts_with("%PDF-1.4\n1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n"));
+        assert!(generated.contains("/MediaBox [0 0 38 72]"));
+        assert!(generated.contains("1 1 1 rg\n0 0 38 72 re f\n0 0 0 rg\n0 0 1 72 re\n3 0 1 72 re\n5 0 2 72 re\n"));
+        assert!(generated.ends_with("%%EOF\n"));
+    }
+
+    #[test]
+    fn code_39_as_rotated_pdf() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pdf = PDF{height: 25.4, xdim: 25.4 / 72.0, quiet_zone: 0, rotation: Rotation::Clockwise90, ..PDF::new()};
+        let generated = as_string(pdf.generate(&code39.encode()).unwrap());
+
+        // The guard is at the top of the page.
+        assert!(generated.contains("/MediaBox [0 0 72 38]"));
+        assert!(generated.contains("0 0 0 rg\n0 37 72 1 re\n0 34 72 1 re\n0 31 72 2 re\n"));
+    }
+
+    #[test]
+    fn code_39_as_pdf_with_bwr() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pdf = PDF{height: 25.4, xdim: 25.4 / 72.0, quiet_zone: 0, bwr: 25.4 / 360.0, ..PDF::new()};
+        let generated = as_string(pdf.generate(&code39.encode()).unwrap());
+
+        // Each bar loses a tenth of a point on either side, but keeps its position.
+        assert!(generated.contains("/MediaBox [0 0 38 72]"));
+        assert!(generated.contains("0 0 0 rg\n0.1 0 0.8 72 re\n3.1 0 0.8 72 re\n5.1 0 1.8 72 re\n"));
+    }
+
+    #[test]
+    fn code_39_as_pdf_with_bearer_bars() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let pdf = PDF{height: 25.4, xdim: 25.4 / 72.0, ..PDF::new()};
+        let bearer_bars = BearerBars{thickness: 4, quiet_zone: 2, frame: false};
+        let generated = as_string(pdf.generate_with_bearer_bars(&code39.encode(), &bearer_bars).unwrap());
+
+        // Horizontal bars across the quiet zones, and no vertical bars.
+        assert!(generated.contains("/MediaBox [0 0 42 80]"));
+        assert!(generated.contains("0 0 0 rg\n0 76 42 4 re\n0 0 42 4 re\n2 4 1 72 re\n"));
+    }
+
+    #[test]
+    fn pdf_cross_reference_table() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let generated = as_string(PDF::new().generate(&code39.encode()).unwrap());
+
+        let xref = generated.find("xref\n").unwrap();
+        assert!(generated.ends_with(&format!("startxref\n{}\n%%EOF\n", xref)));
+
+        for i in 1..5 {
+            let offset = generated.find(&format!("\n{} 0 obj\n", i)).unwrap() + 1;
+            assert!(generated[xref..].contains(&format!("{:010} 00000 n \n", offset)));
+        }
+    }
+
+    #[test]
+    fn bars_as_pdf() {
+        let pdf = PDF{height: 3.0 * 25.4 / 72.0, xdim: 25.4 / 72.0, quiet_zone: 1, ..PDF::new()};
+        let generated = as_string(pdf.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap());
+
+        assert!(generated.contains("/MediaBox [0 0 5 3]"));
+        assert!(generated.contains("1 0 1 3 re\n3 0 1 2 re\n"));
+    }
+
+    #[test]
+    fn qr_as_pdf() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let pdf = PDF{xdim: 0.5, quiet_zone: 4, foreground: Color::new(255, 0, 51), ..PDF::new()};
+        let generated = as_string(pdf.generate_matrix(&qr.encode()).unwrap());
+
+        // 29 modules of 0.5mm.
+        assert!(generated.contains("/MediaBox [0 0 41.102 41.102]"));
+        assert!(generated.contains("1 0 0.2 rg\n"));
+    }
+}
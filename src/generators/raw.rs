@@ -0,0 +1,429 @@
+//! This module provides types for generating raw pixel buffers of barcodes, without any file
+//! format encoding. This is useful for drawing barcodes into your own framebuffer or texture.
+//!
+//! Pixels are stored row by row from the top left, either as RGBA or packed one bit per pixel.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::{Generator, GeneratorError, bearer_layout, runs};
+use ::generators::options::{Options, Rotation};
+use ::generators::hri::{HRI, TextPosition};
+use ::sym::itf14::BearerBars;
+
+/// The layout of the pixels in a buffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PixelFormat {
+    /// Four bytes per pixel: red, green, blue and alpha.
+    RGBA,
+    /// One bit per pixel, most significant bit first, with 1 for dark pixels. Each row starts
+    /// on a new byte.
+    Mono,
+}
+
+/// A buffer of raw pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pixels {
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The layout of the pixels in `data`.
+    pub format: PixelFormat,
+    /// The pixels, row by row from the top left.
+    pub data: Vec<u8>,
+}
+
+impl Pixels {
+    /// Returns the number of bytes in each row of pixels.
+    pub fn stride(&self) -> usize {
+        match self.format {
+            PixelFormat::RGBA => self.width as usize * 4,
+            PixelFormat::Mono => (self.width as usize).div_ceil(8),
+        }
+    }
+}
+
+// The pixels are written as they are laid out, such as into a framebuffer device.
+impl AsRef<[u8]> for Pixels {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// The raw pixel barcode generator type.
+pub struct Raw {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For raw pixels, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars. Not used for `PixelFormat::Mono`.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone. Not used for `PixelFormat::Mono`.
+    pub background: Color,
+    /// The layout of the generated pixels.
+    pub format: PixelFormat,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+    /// The bar width reduction in pixels. Bars are drawn this much narrower around their
+    /// centers, but at least one pixel wide. Modules of 2D barcodes are not reduced.
+    pub bwr: u32,
+}
+
+impl Default for Raw {
+    fn default() -> Raw {
+        Raw::new()
+    }
+}
+
+// A rectangle in pixels: x, y, width and height.
+type Rect = (u32, u32, u32, u32);
+
+impl Raw {
+    /// Returns a new Raw with default values.
+    pub fn new() -> Raw {
+        Raw{height: 80, xdim: 1, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+            format: PixelFormat::RGBA, rotation: Rotation::Normal, bwr: 0}
+    }
+
+    /// Returns a new Raw with default values that generates one bit per pixel.
+    pub fn mono() -> Raw {
+        Raw{format: PixelFormat::Mono, ..Raw::new()}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> Raw {
+        Raw{height: options.height_pixels(), xdim: options.xdim_pixels(),
+            quiet_zone: options.quiet_zone_modules(),
+            foreground: options.foreground, background: options.background,
+            rotation: options.rotation, bwr: options.bwr_pixels(), ..self}
+    }
+
+    // Draws the dark rectangles of a symbol of the given size in pixels, not counting the
+    // quiet zone.
+    fn pixels(&self, width: u32, height: u32, rects: &[Rect], vertical_quiet_zone: bool) -> Pixels {
+        let margin = self.quiet_zone * self.xdim;
+        let top = if vertical_quiet_zone { margin } else { 0 };
+        let rects: Vec<Rect> = rects.iter().map(|&(x, y, w, h)| (x + margin, y + top, w, h)).collect();
+
+        self.draw(width + 2 * margin, height + 2 * top, &rects)
+    }
+
+    // Draws the dark rectangles of an image of the given size in pixels, including the quiet
+    // zone, then rotates it.
+    fn draw(&self, total_width: u32, total_height: u32, rects: &[Rect]) -> Pixels {
+        let (image_width, image_height) = self.rotation.size(total_width, total_height);
+        let mut dark = vec![false; (image_width * image_height) as usize];
+
+        for &rect in rects {
+            let (x, y, w, h) = self.rotation.rect(rect, total_width, total_height);
+
+            for row in y..(y + h) {
+                let start = (row * image_width + x) as usize;
+
+                for pixel in &mut dark[start..start + w as usize] {
+                    *pixel = true;
+                }
+            }
+        }
+
+        let mut pixels = Pixels{width: image_width, height: image_height, format: self.format, data: vec![]};
+
+        pixels.data = match self.format {
+            PixelFormat::RGBA => dark.iter()
+                .flat_map(|&d| {
+                    let color = if d { self.foreground } else { self.background };
+                    vec![color.rgb[0], color.rgb[1], color.rgb[2], color.alpha]
+                })
+                .collect(),
+            PixelFormat::Mono => {
+                let stride = pixels.stride();
+                let mut data = vec![0; stride * image_height as usize];
+
+                for (i, _) in dark.iter().enumerate().filter(|&(_, &d)| d) {
+                    let (x, y) = (i % image_width as usize, i / image_width as usize);
+                    data[y * stride + x / 8] |= 0x80 >> (x % 8);
+                }
+
+                data
+            },
+        };
+
+        pixels
+    }
+
+    // The rectangles of the dark modules of one row, at the given y position and height.
+    fn row_rects(&self, modules: &[u8], y: u32, height: u32) -> Vec<Rect> {
+        modules.iter()
+            .enumerate()
+            .filter(|&(_, &m)| m == 1)
+            .map(|(x, _)| (x as u32 * self.xdim, y, self.xdim, height))
+            .collect()
+    }
+
+    // The rectangles of the runs of dark modules of one row of bars, narrowed by the bar
+    // width reduction.
+    fn bar_rects(&self, modules: &[u8], y: u32, height: u32) -> Vec<Rect> {
+        let reduction = self.bwr.min(self.xdim.saturating_sub(1));
+
+        runs(modules).iter()
+            .map(|&(x, len)| (x * self.xdim + reduction / 2, y, len * self.xdim - reduction, height))
+            .collect()
+    }
+
+    /// Generates the given EncodedBarcode. Returns Pixels.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Pixels, &'static str> {
+        let rects = self.bar_rects(barcode, 0, self.height);
+
+        Ok(self.pixels(barcode.len() as u32 * self.xdim, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedBarcode with its human-readable text beneath or above the
+    /// bars, and its guard bars extended into the text. The text may extend into the quiet
+    /// zone. Returns Pixels.
+    pub fn generate_with_text(&self, barcode: &EncodedBarcode, text: &HRI) -> Result<Pixels, &'static str> {
+        let margin = self.quiet_zone * self.xdim;
+        let width = barcode.len() as u32 * self.xdim + 2 * margin;
+        let (bars_top, text_top) = match text.position {
+            TextPosition::Below => (0, self.height),
+            TextPosition::Above => (text.line_height(), 0),
+        };
+        let (bars, guards) = text.split_guards(barcode);
+        let extension = text.guard_extension(self.xdim);
+        let guards_top = if text.position == TextPosition::Above { bars_top - extension } else { bars_top };
+        let mut rects: Vec<Rect> = self.bar_rects(&bars, bars_top, self.height)
+            .into_iter()
+            .chain(self.bar_rects(&guards, guards_top, self.height + extension))
+            .map(|(x, y, w, h)| (x + margin, y, w, h))
+            .collect();
+
+        for (i, _) in text.bitmap(width, margin, self.xdim).iter().enumerate().filter(|&(_, &d)| d) {
+            rects.push((i as u32 % width, text_top + i as u32 / width, 1, 1));
+        }
+
+        Ok(self.draw(width, self.height + text.line_height(), &rects))
+    }
+
+    /// Generates the given EncodedBarcode of an ITF-14 symbol, surrounded by the given bearer
+    /// bars. The quiet zone of the bearer bars replaces `self.quiet_zone`, and the bearer bars
+    /// are not narrowed by the bar width reduction. Returns Pixels.
+    pub fn generate_with_bearer_bars(&self, barcode: &EncodedBarcode, bearer_bars: &BearerBars) -> Result<Pixels, &'static str> {
+        let (mut rects, (left, top), (width, height)) =
+            bearer_layout(bearer_bars, self.xdim, barcode.len() as u32 * self.xdim, self.height);
+        rects.extend(self.bar_rects(barcode, top, self.height).into_iter().map(|(x, y, w, h)| (x + left, y, w, h)));
+
+        Ok(self.draw(width, height, &rects))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns Pixels.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Pixels, &'static str> {
+        let section = self.height / 3;
+        let rects: Vec<Rect> = bars.iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                let (y, h) = match b {
+                    BarState::Full => (0, self.height),
+                    BarState::Ascender => (0, self.height - section),
+                    BarState::Descender => (section, self.height - section),
+                    BarState::Tracker => (section, self.height - 2 * section),
+                };
+
+                (2 * i as u32 * self.xdim, y, self.xdim, h)
+            })
+            .collect();
+        let width = (2 * bars.len() as u32).saturating_sub(1) * self.xdim;
+
+        Ok(self.pixels(width, self.height, &rects, false))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns Pixels.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Pixels, &'static str> {
+        let mut rects = vec![];
+        let mut y = 0;
+
+        for row in rows {
+            let height = row.height * self.xdim;
+
+            rects.extend(self.bar_rects(&row.modules, y, height));
+            y += height;
+        }
+
+        let width = rows.iter().map(|r| r.modules.len()).max().unwrap_or(0) as u32 * self.xdim;
+
+        Ok(self.pixels(width, y, &rects, false))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns Pixels.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Pixels, &'static str> {
+        let rects: Vec<Rect> = matrix.rows()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| self.row_rects(row, y as u32 * self.xdim, self.xdim))
+            .collect();
+
+        Ok(self.pixels(matrix.width as u32 * self.xdim, matrix.height as u32 * self.xdim, &rects, true))
+    }
+}
+
+impl Generator for Raw {
+    type Output = Pixels;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Pixels, GeneratorError> {
+        Raw::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::raw::*;
+
+    #[test]
+    fn code_39_as_rgba() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let raw = Raw{height: 2, xdim: 2, quiet_zone: 1, foreground: Color::new(1, 2, 3), ..Raw::new()};
+        let generated = raw.generate(&code39.encode()).unwrap();
+
+        assert_eq!((generated.width, generated.height), (80, 2));
+        assert_eq!(generated.stride(), 320);
+        assert_eq!(generated.data.len(), 320 * 2);
+        assert_eq!(&generated.data[0..4], &[255, 255, 255, 255]);
+        assert_eq!(&generated.data[8..16], &[1, 2, 3, 255, 1, 2, 3, 255]);
+        assert_eq!(&generated.data[320 + 8..320 + 12], &[1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn code_39_as_mono() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let raw = Raw{height: 3, quiet_zone: 0, ..Raw::mono()};
+        let generated = raw.generate(&code39.encode()).unwrap();
+
+        assert_eq!((generated.width, generated.height), (38, 3));
+        assert_eq!(generated.stride(), 5);
+        // The guard, a narrow space, then the start of 'A'.
+        assert_eq!(&generated.data[0..2], &[0b10010110, 0b11010110]);
+        assert_eq!(&generated.data[5..7], &[0b10010110, 0b11010110]);
+        // Each row ends with two bits of padding.
+        assert_eq!(generated.data[14] & 0b00000011, 0);
+    }
+
+    #[test]
+    fn bars_as_mono() {
+        let raw = Raw{height: 3, quiet_zone: 0, ..Raw::mono()};
+        let generated = raw.generate_bars(&vec![BarState::Full, BarState::Descender, BarState::Ascender]).unwrap();
+
+        assert_eq!((generated.width, generated.height), (5, 3));
+        assert_eq!(generated.data, vec![0b10001000, 0b10101000, 0b10100000]);
+    }
+
+    #[test]
+    fn code_39_with_bwr() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let raw = Raw{height: 1, xdim: 2, quiet_zone: 0, bwr: 1, ..Raw::mono()};
+        let generated = raw.generate(&code39.encode()).unwrap();
+
+        // Each bar loses its last pixel, so the pitch of the modules is unchanged.
+        assert_eq!((generated.width, generated.height), (76, 1));
+        assert_eq!(&generated.data[0..2], &[0b10000010, 0b00111000]);
+    }
+
+    #[test]
+    fn code_39_with_bearer_bars() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let raw = Raw{height: 2, quiet_zone: 10, ..Raw::mono()};
+        let bearer_bars = BearerBars{thickness: 2, quiet_zone: 1, frame: true};
+        let generated = raw.generate_with_bearer_bars(&code39.encode(), &bearer_bars).unwrap();
+
+        assert_eq!((generated.width, generated.height), (44, 6));
+        assert_eq!(&generated.data[0..6], &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF0]);
+        // The frame, the quiet zone of the bearer bars, then the guard.
+        assert_eq!(generated.data[2 * 6], 0b11010010);
+        assert_eq!(generated.data[2 * 6 + 5] & 0b00110000, 0b00110000);
+        assert_eq!(&generated.data[5 * 6..6 * 6], &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF0]);
+    }
+
+    #[test]
+    fn code_39_rotated() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let raw = Raw{height: 3, quiet_zone: 0, rotation: Rotation::Clockwise90, ..Raw::mono()};
+        let generated = raw.generate(&code39.encode()).unwrap();
+
+        // The bars run across the rows, from the top.
+        assert_eq!((generated.width, generated.height), (3, 38));
+        assert_eq!(generated.data[0..8], [0b11100000, 0, 0, 0b11100000, 0, 0b11100000, 0b11100000, 0]);
+
+        let raw = Raw{rotation: Rotation::UpsideDown, ..raw};
+        let generated = raw.generate(&code39.encode()).unwrap();
+
+        // The guard ends the first row, backwards.
+        assert_eq!((generated.width, generated.height), (38, 3));
+        assert_eq!(generated.data[4], 0b10100100);
+    }
+
+    #[test]
+    fn ean13_with_text() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let hri = HRI::ean13(&ean13);
+        let raw = Raw{height: 20, quiet_zone: 11, ..Raw::mono()};
+        let generated = raw.generate_with_text(&ean13.encode(), &hri).unwrap();
+
+        assert_eq!((generated.width, generated.height), (117, 20 + 12));
+        // The text starts after a gap of one pixel and the extra three pixels of the font size,
+        // with only the six guard bars extending into it.
+        let dark = |x: usize, y: usize| (generated.data[y * generated.stride() + x / 8] >> (7 - x % 8)) & 1 == 1;
+        assert!((0..117).filter(|&x| dark(x, 20)).count() == 6 && (0..117).filter(|&x| dark(x, 23)).count() == 6);
+        // The top of the '7' in the quiet zone.
+        assert_eq!((4..11).filter(|&x| dark(x, 24)).count(), 5);
+    }
+
+    #[test]
+    fn ean8_with_guards() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let raw = Raw{height: 10, xdim: 1, quiet_zone: 0, ..Raw::mono()};
+        let generated = raw.generate_with_text(&ean8.encode(), &HRI::ean8(&ean8)).unwrap();
+        let dark = |x: usize, y: usize| (generated.data[y * generated.stride() + x / 8] >> (7 - x % 8)) & 1 == 1;
+
+        // The guards extend five modules into the text, the other bars stop above it.
+        assert_eq!((generated.width, generated.height), (67, 22));
+        assert!(dark(0, 14) && !dark(0, 15));
+        assert!(dark(32, 14) && dark(66, 14));
+        assert!(dark(5, 9) && !dark(5, 10));
+    }
+
+    #[test]
+    fn code_39_with_text_above() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let hri = HRI{position: TextPosition::Above, ..HRI::new("A", 38)};
+        let raw = Raw{height: 3, quiet_zone: 0, ..Raw::mono()};
+        let generated = raw.generate_with_text(&code39.encode(), &hri).unwrap();
+
+        assert_eq!((generated.width, generated.height), (38, 15));
+        assert_eq!(&generated.data[12 * 5..12 * 5 + 2], &[0b10010110, 0b11010110]);
+    }
+
+    #[test]
+    fn qr_as_mono() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let raw = Raw{quiet_zone: 4, ..Raw::mono()};
+        let generated = raw.generate_matrix(&qr.encode()).unwrap();
+
+        assert_eq!((generated.width, generated.height), (29, 29));
+        assert!(generated.data[0..16].iter().all(|&b| b == 0));
+        // The top left finder pattern begins on the fifth row, after four modules.
+        assert_eq!(generated.data[4 * 4], 0b00001111);
+    }
+}
@@ -0,0 +1,419 @@
+//! This module provides a generator for sheets of labels, such as Avery 5160 address labels:
+//! many barcodes, each with an optional caption, tiled in a grid on as many pages as they need.
+//!
+//! Each barcode is centered on its label at the X dimension of the sheet, with bars as high as
+//! the label allows. Sheets are generated as SVG documents and PNG images with one per page,
+//! or as a single PDF with a page per sheet. PNG output is only available with the `image`
+//! feature.
+//!
+//! All dimensions are in millimeters.
+
+use ::sym::EncodedBarcode;
+use ::generators::svg::SVG;
+use ::generators::pdf::POINTS_PER_MM;
+use ::generators::hri::{HRI, TextPosition};
+use ::generators::{number, runs, write_pdf};
+#[cfg(feature = "image")]
+use ::generators::raw::Raw;
+#[cfg(feature = "image")]
+use ::generators::options::MM_PER_INCH;
+
+/// The grid of labels on a page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout {
+    /// The width of the page.
+    pub page_width: f64,
+    /// The height of the page.
+    pub page_height: f64,
+    /// The number of labels across the page.
+    pub columns: u32,
+    /// The number of labels down the page.
+    pub rows: u32,
+    /// The width of each label.
+    pub label_width: f64,
+    /// The height of each label.
+    pub label_height: f64,
+    /// The distance from the left of the page to the first column of labels.
+    pub left: f64,
+    /// The distance from the top of the page to the first row of labels.
+    pub top: f64,
+    /// The distance between the left edges of neighbouring columns.
+    pub column_pitch: f64,
+    /// The distance between the top edges of neighbouring rows.
+    pub row_pitch: f64,
+}
+
+/// Avery 5160 address labels, and their compatibles: 30 labels of 2⅝ by 1 inch in three
+/// columns on US Letter paper.
+pub const AVERY_5160: Layout = Layout{page_width: 215.9, page_height: 279.4, columns: 3, rows: 10,
+                                      label_width: 66.675, label_height: 25.4, left: 4.7625, top: 12.7,
+                                      column_pitch: 69.85, row_pitch: 25.4};
+
+impl Layout {
+    /// Returns the number of labels on each page.
+    pub fn labels_per_page(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    /// Returns the position of the top left corner of a label on its page. Labels fill each
+    /// row from the left before the next row.
+    ///
+    /// Panics if the layout has no columns or no rows.
+    pub fn position(&self, index: usize) -> (f64, f64) {
+        let index = (index % self.labels_per_page()) as u32;
+
+        (self.left + (index % self.columns) as f64 * self.column_pitch,
+         self.top + (index / self.columns) as f64 * self.row_pitch)
+    }
+}
+
+/// A barcode to put on a label, with its human-readable text as a caption.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    /// The barcode.
+    pub barcode: EncodedBarcode,
+    /// The caption, if any. Its font size is replaced by that of the sheet.
+    pub text: Option<HRI>,
+}
+
+impl Label {
+    /// Returns a new Label without a caption.
+    pub fn new(barcode: EncodedBarcode) -> Label {
+        Label{barcode, text: None}
+    }
+
+    /// Returns a new Label with the given caption centered beneath the barcode.
+    pub fn with_caption(barcode: EncodedBarcode, caption: &str) -> Label {
+        let text = HRI::new(caption, barcode.len() as u32);
+
+        Label{barcode, text: Some(text)}
+    }
+}
+
+/// The label sheet generator type.
+pub struct Sheet {
+    /// The grid of labels on each page.
+    pub layout: Layout,
+    /// The X dimension. Specifies the width of the "narrow" bars, in millimeters.
+    pub xdim: f64,
+    /// The margin inside each label, which the barcode and its caption must fit within.
+    pub padding: f64,
+    /// The height of the captions, rounded to whole modules.
+    pub font_size: f64,
+    /// The resolution of PNG output, in pixels per inch.
+    pub dpi: u32,
+}
+
+impl Default for Sheet {
+    fn default() -> Sheet {
+        Sheet::new()
+    }
+}
+
+// A label laid out on a page, in modules from the top left of its barcode.
+struct Placed<'a> {
+    barcode: &'a EncodedBarcode,
+    text: Option<HRI>,
+    // The page, and the position of the top left of the barcode and its text on it.
+    page: usize,
+    x: f64,
+    y: f64,
+    // The height of the bars and the total height with the text.
+    height: u32,
+    total_height: u32,
+}
+
+impl<'a> Placed<'a> {
+    // Returns the top of the bars and the top of the text.
+    fn tops(&self) -> (u32, u32) {
+        match self.text {
+            Some(ref text) if text.position == TextPosition::Above => (text.line_height(), 0),
+            _ => (0, self.height),
+        }
+    }
+}
+
+// Escapes text for a PDF string, with characters outside of ASCII as '?'.
+fn pdf_string(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+impl Sheet {
+    /// Returns a new Sheet of Avery 5160 labels with default values.
+    pub fn new() -> Sheet {
+        Sheet{layout: AVERY_5160, xdim: 0.25, padding: 2.0, font_size: 2.5, dpi: 300}
+    }
+
+    /// Returns the number of pages the given labels need. Fails if the layout has no labels.
+    pub fn pages(&self, labels: &[Label]) -> Result<usize, &str> {
+        match self.layout.labels_per_page() {
+            0 => Err("Layout has no labels"),
+            n => Ok(labels.len().div_ceil(n)),
+        }
+    }
+
+    // Lays out the labels, centering each barcode and its text on its label.
+    fn place<'a>(&self, labels: &'a [Label]) -> Result<Vec<Placed<'a>>, &str> {
+        let font_size = ((self.font_size / self.xdim).round() as u32).max(1);
+        let height = ((self.layout.label_height - 2.0 * self.padding) / self.xdim).floor().max(0.0) as u32;
+        let mut placed = vec![];
+
+        if self.layout.labels_per_page() == 0 {
+            return Err("Layout has no labels");
+        }
+
+        for (i, label) in labels.iter().enumerate() {
+            let width = label.barcode.len() as f64 * self.xdim;
+            let text = label.text.clone().map(|t| HRI{font_size, ..t});
+            let line_height = text.as_ref().map_or(0, |t| t.line_height());
+
+            if width > self.layout.label_width - 2.0 * self.padding {
+                return Err("Barcode is wider than the label");
+            }
+
+            if height <= line_height {
+                return Err("Label is too short for the barcode");
+            }
+
+            let (left, top) = self.layout.position(i);
+
+            placed.push(Placed{barcode: &label.barcode, text, page: i / self.layout.labels_per_page(),
+                               x: left + (self.layout.label_width - width) / 2.0,
+                               y: top + (self.layout.label_height - height as f64 * self.xdim) / 2.0,
+                               height: height - line_height, total_height: height});
+        }
+
+        Ok(placed)
+    }
+
+    // Returns the rectangles of the bars of a label, in modules: x, y, width and height.
+    fn rects(&self, placed: &Placed) -> Vec<(u32, u32, u32, u32)> {
+        let (bars_top, _) = placed.tops();
+        let (bars, guards, extension) = match placed.text {
+            Some(ref text) => {
+                let (bars, guards) = text.split_guards(placed.barcode);
+                (bars, guards, text.guard_extension(1))
+            },
+            None => (placed.barcode.clone(), vec![], 0),
+        };
+        let guards_top = if placed.tops().1 == 0 { bars_top - extension } else { bars_top };
+
+        runs(&bars).into_iter()
+            .map(|(x, w)| (x, bars_top, w, placed.height))
+            .chain(runs(&guards).into_iter().map(|(x, w)| (x, guards_top, w, placed.height + extension)))
+            .collect()
+    }
+
+    /// Generates the given labels as SVG, sized in millimeters. Returns a String for each
+    /// page.
+    pub fn generate_svg(&self, labels: &[Label]) -> Result<Vec<String>, &str> {
+        let layout = &self.layout;
+        let header = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+            w = number(layout.page_width), h = number(layout.page_height));
+        let mut pages = vec![header; self.pages(labels)?];
+
+        for placed in self.place(labels)? {
+            // Each label is drawn by the SVG generator in modules, scaled into place.
+            let svg = SVG{height: placed.height, xdim: 1, quiet_zone: 0, ..SVG::new()};
+            let document = match placed.text {
+                Some(ref text) => svg.generate_with_text(placed.barcode, text),
+                None => svg.generate(placed.barcode),
+            }.map_err(|_| "Could not draw barcode.")?;
+            let start = document.find('\n').map_or(0, |i| i + 1);
+            let body = &document[start..document.len() - "</svg>".len()];
+            let (width, height) = (placed.barcode.len() as u32, placed.total_height);
+
+            pages[placed.page].push_str(&format!(
+                "<svg x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n{}</svg>\n",
+                number(placed.x), number(placed.y), number(width as f64 * self.xdim),
+                number(height as f64 * self.xdim), width, height, body));
+        }
+
+        Ok(pages.into_iter().map(|page| page + "</svg>").collect())
+    }
+
+    /// Generates the given labels as a PDF, with a page for each sheet and captions in
+    /// Courier. Returns a Vec<u8>.
+    pub fn generate_pdf(&self, labels: &[Label]) -> Result<Vec<u8>, &str> {
+        let (page_width, page_height) = (self.layout.page_width, self.layout.page_height);
+        let mut contents = vec!["0 0 0 rg\n".to_string(); self.pages(labels)?];
+
+        for placed in self.place(labels)? {
+            let content = &mut contents[placed.page];
+
+            for (x, y, w, h) in self.rects(&placed) {
+                let left = placed.x + x as f64 * self.xdim;
+                // PDF coordinates start at the bottom left.
+                let bottom = page_height - placed.y - (y + h) as f64 * self.xdim;
+
+                content.push_str(&format!("{} {} {} {} re\n", number(left * POINTS_PER_MM),
+                                          number(bottom * POINTS_PER_MM), number(w as f64 * self.xdim * POINTS_PER_MM),
+                                          number(h as f64 * self.xdim * POINTS_PER_MM)));
+            }
+
+            content.push_str("f\n");
+
+            if let Some(ref text) = placed.text {
+                let size = text.font_size as f64 * self.xdim * POINTS_PER_MM;
+                let baseline = placed.y + (placed.tops().1 + text.gap() + text.font_size) as f64 * self.xdim;
+
+                for group in &text.groups {
+                    let center = placed.x + (group.start as f64 + group.width as f64 / 2.0) * self.xdim;
+                    // Every character of Courier is 0.6 em wide.
+                    let width = 0.6 * size * group.text.chars().count() as f64;
+
+                    content.push_str(&format!("BT /F1 {} Tf {} {} Td ({}) Tj ET\n", number(size),
+                                              number(center * POINTS_PER_MM - width / 2.0),
+                                              number((page_height - baseline) * POINTS_PER_MM),
+                                              pdf_string(&group.text)));
+                }
+            }
+        }
+
+        // The catalog, the page tree and the font come first, then each page and its content.
+        let kids: Vec<String> = (0..contents.len()).map(|i| format!("{} 0 R", 4 + 2 * i)).collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), contents.len()),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+        ];
+
+        for (i, content) in contents.iter().enumerate() {
+            objects.push(format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                                 number(page_width * POINTS_PER_MM), number(page_height * POINTS_PER_MM), 5 + 2 * i));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+        }
+
+        let mut pdf = vec![];
+
+        write_pdf(&objects, &mut pdf).map_err(|_| "Could not write PDF.")?;
+        Ok(pdf)
+    }
+
+    /// Generates the given labels as grayscale PNG images at `self.dpi`, with each module a
+    /// whole number of pixels. Returns the bytes of an image for each page.
+    #[cfg(feature = "image")]
+    pub fn generate_png(&self, labels: &[Label]) -> Result<Vec<Vec<u8>>, &str> {
+        let pixels = |mm: f64| (mm * self.dpi as f64 / MM_PER_INCH).round() as u32;
+        let (width, height) = (pixels(self.layout.page_width), pixels(self.layout.page_height));
+        let xdim = pixels(self.xdim).max(1);
+        let mut pages = vec![vec![255u8; (width * height) as usize]; self.pages(labels)?];
+
+        for placed in self.place(labels)? {
+            let raw = Raw{height: placed.height * xdim, xdim, quiet_zone: 0, ..Raw::mono()};
+            let drawn = match placed.text {
+                Some(ref text) => raw.generate_with_text(placed.barcode, &HRI{font_size: text.font_size * xdim, ..text.clone()}),
+                None => raw.generate(placed.barcode),
+            }.map_err(|_| "Could not draw barcode.")?;
+            let (left, top) = (pixels(placed.x), pixels(placed.y));
+            let stride = drawn.stride();
+
+            for y in 0..drawn.height.min(height.saturating_sub(top)) {
+                for x in 0..drawn.width.min(width.saturating_sub(left)) {
+                    if drawn.data[y as usize * stride + x as usize / 8] >> (7 - x % 8) & 1 == 1 {
+                        pages[placed.page][((top + y) * width + left + x) as usize] = 0;
+                    }
+                }
+            }
+        }
+
+        pages.into_iter()
+            .map(|page| {
+                let buffer = ::image::ImageBuffer::from_raw(width, height, page).ok_or("Could not draw page.")?;
+                let mut png = vec![];
+
+                match ::image::ImageLuma8(buffer).save(&mut png, ::image::PNG) {
+                    Ok(_) => Ok(png),
+                    Err(_) => Err("Could not encode image."),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::ean13::*;
+    use ::generators::sheet::*;
+
+    fn labels(count: usize) -> Vec<Label> {
+        let code39 = Code39::new("A1".to_string()).unwrap();
+
+        (0..count).map(|i| Label::with_caption(code39.encode(), &format!("Box ({})", i))).collect()
+    }
+
+    #[test]
+    fn avery_5160_positions() {
+        assert_eq!(AVERY_5160.labels_per_page(), 30);
+        assert_eq!(AVERY_5160.position(0), (4.7625, 12.7));
+        assert_eq!(AVERY_5160.position(4), (4.7625 + 69.85, 12.7 + 25.4));
+        assert_eq!(AVERY_5160.position(30), AVERY_5160.position(0));
+        assert_eq!(Sheet::new().pages(&labels(31)), Ok(2));
+    }
+
+    #[test]
+    fn sheet_as_svg() {
+        let pages = Sheet::new().generate_svg(&labels(31)).unwrap();
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"215.9mm\" height=\"279.4mm\" viewBox=\"0 0 215.9 279.4\">\n"));
+        assert_eq!(pages[0].matches("<svg x=").count(), 30);
+        assert_eq!(pages[1].matches("<svg x=").count(), 1);
+        assert!(pages[1].contains(">Box (30)</text>"));
+        // 51 modules of 0.25mm, centered on the first label.
+        assert!(pages[1].contains("<svg x=\"31.725\" y=\"14.775\" width=\"12.75\" height=\"21.25\" viewBox=\"0 0 51 85\">\n"));
+        assert!(pages[1].ends_with("</svg>\n</svg>"));
+    }
+
+    #[test]
+    fn sheet_as_pdf() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let mut labels = labels(40);
+        labels.push(Label{text: Some(HRI::ean13(&ean13)), ..Label::new(ean13.encode())});
+        let pdf = String::from_utf8(Sheet::new().generate_pdf(&labels).unwrap()).unwrap();
+
+        assert!(pdf.contains("/Kids [4 0 R 6 0 R] /Count 2"));
+        assert!(pdf.contains("/MediaBox [0 0 612 792]"));
+        assert!(pdf.contains("/BaseFont /Courier"));
+        assert!(pdf.contains("(Box \\(0\\)) Tj ET"));
+        assert!(pdf.contains("(501031) Tj ET"));
+        assert!(pdf.ends_with("%%EOF\n"));
+    }
+
+    #[test]
+    fn sheet_errors() {
+        let code39 = Code39::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_string()).unwrap();
+        let labels = vec![Label::new(code39.encode())];
+
+        assert_eq!(Sheet::new().generate_svg(&labels).unwrap_err(), "Barcode is wider than the label");
+        assert_eq!(Sheet{xdim: 0.05, font_size: 30.0, ..Sheet::new()}.generate_pdf(&self::labels(1)).unwrap_err(),
+                   "Label is too short for the barcode");
+        assert_eq!(Sheet::new().generate_svg(&[]).unwrap(), Vec::<String>::new());
+
+        let sheet1 = Sheet{layout: Layout{columns: 0, ..AVERY_5160}, ..Sheet::new()};
+        let sheet2 = Sheet{layout: Layout{rows: 0, ..AVERY_5160}, ..Sheet::new()};
+
+        assert_eq!(sheet1.pages(&self::labels(1)), Err("Layout has no labels"));
+        assert_eq!(sheet1.generate_svg(&self::labels(1)).unwrap_err(), "Layout has no labels");
+        assert_eq!(sheet2.generate_pdf(&self::labels(1)).unwrap_err(), "Layout has no labels");
+        assert_eq!(sheet2.generate_svg(&[]).unwrap_err(), "Layout has no labels");
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn sheet_as_png() {
+        let sheet = Sheet{dpi: 100, ..Sheet::new()};
+        let pages = sheet.generate_png(&labels(3)).unwrap();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(&pages[0][1..4], b"PNG");
+    }
+}
@@ -0,0 +1,201 @@
+//! This module provides types for generating Sixel representations of barcodes, to display
+//! them inline in terminals that support Sixel graphics, such as xterm, mlterm and WezTerm.
+//!
+//! The image is drawn in bands six pixels high, with the background and the bars as two color
+//! registers. Repeated characters are run-length encoded.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The Sixel barcode generator type.
+pub struct Sixel {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For Sixel, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for Sixel {
+    fn default() -> Sixel {
+        Sixel::new()
+    }
+}
+
+// The number of pixel rows in each band.
+const SIXEL_BAND: usize = 6;
+
+// Defines a color register, with components in percent.
+fn register(n: u8, color: Color) -> String {
+    let percent = |c: u8| (c as u32 * 100 + 127) / 255;
+
+    format!("#{};2;{};{};{}", n, percent(color.rgb[0]), percent(color.rgb[1]), percent(color.rgb[2]))
+}
+
+// Run-length encodes sixel characters: runs of four or more become "!<count><char>".
+fn run_length(sixels: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut start = 0;
+
+    for i in 1..=sixels.len() {
+        if i == sixels.len() || sixels[i] != sixels[start] {
+            let (count, c) = (i - start, sixels[start] as char);
+
+            if count >= 4 {
+                encoded.push_str(&format!("!{}{}", count, c));
+            } else {
+                encoded.extend(std::iter::repeat_n(c, count));
+            }
+
+            start = i;
+        }
+    }
+
+    encoded
+}
+
+impl Sixel {
+    /// Returns a new Sixel with default values.
+    pub fn new() -> Sixel {
+        Sixel{height: 80, xdim: 2, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+              rotation: Rotation::Normal}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> Sixel {
+        Sixel{height: options.height_pixels(), xdim: options.xdim_pixels(),
+              quiet_zone: options.quiet_zone_modules(),
+              foreground: options.foreground, background: options.background, rotation: options.rotation}
+    }
+
+    // Encodes one bit per pixel as a Sixel image, painting each band in the background color
+    // (register 0) and then the foreground color (register 1).
+    fn image(&self, pixels: Pixels) -> String {
+        let (width, height) = (pixels.width as usize, pixels.height as usize);
+        let mut sixel = format!("\x1bPq\"1;1;{};{}{}{}", width, height, register(0, self.background),
+                                register(1, self.foreground));
+        let mut bands = vec![];
+
+        for top in (0..height).step_by(SIXEL_BAND) {
+            let mut dark = vec![0u8; width];
+            let mut light = vec![0u8; width];
+
+            for y in top..height.min(top + SIXEL_BAND) {
+                for (x, (d, l)) in dark.iter_mut().zip(light.iter_mut()).enumerate() {
+                    match (pixels.data[y * pixels.stride() + x / 8] >> (7 - x % 8)) & 1 {
+                        1 => *d |= 1 << (y - top),
+                        _ => *l |= 1 << (y - top),
+                    }
+                }
+            }
+
+            let to_sixels = |bits: Vec<u8>| -> Vec<u8> { bits.iter().map(|b| b + 63).collect() };
+            bands.push(format!("#0{}$#1{}", run_length(&to_sixels(light)), run_length(&to_sixels(dark))));
+        }
+
+        sixel.push_str(&bands.join("-"));
+        sixel.push_str("\x1b\\");
+        sixel
+    }
+
+    // Returns a raw pixel generator with the same dimensions.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.raw().generate(barcode).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        self.raw().generate_bars(bars).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        self.raw().generate_rows(rows).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.image(p))
+    }
+}
+
+impl Generator for Sixel {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        Sixel::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::sixel::*;
+
+    #[test]
+    fn code_39_as_sixel() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let sixel = Sixel{height: 6, xdim: 1, quiet_zone: 4, ..Sixel::new()};
+        let generated = sixel.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("\x1bPq\"1;1;46;6#0;2;100;100;100#1;2;0;0;0#0!4~?~~?~?"));
+        // The dark modules, starting with the guard, after the quiet zone.
+        assert!(generated.contains("$#1!4?~??~?~~?~~?~"));
+        assert!(generated.ends_with("!4?\x1b\\"));
+        assert!(!generated.contains('-'));
+    }
+
+    #[test]
+    fn sixel_bands() {
+        let sixel = Sixel{height: 8, xdim: 1, quiet_zone: 0, foreground: Color::new(255, 0, 128), ..Sixel::new()};
+        let generated = sixel.generate(&vec![1, 0, 0, 0, 0, 1]).unwrap();
+
+        assert_eq!(generated, "\x1bPq\"1;1;6;8#0;2;100;100;100#1;2;100;0;50#0?!4~?$#1~!4?~-#0?!4B?$#1B!4?B\x1b\\");
+    }
+
+    #[test]
+    fn bars_as_sixel() {
+        let sixel = Sixel{height: 6, xdim: 1, quiet_zone: 0, ..Sixel::new()};
+        let generated = sixel.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        // The descender covers the lower four of six rows.
+        assert!(generated.ends_with("#0?~B$#1~?{\x1b\\"));
+    }
+
+    #[test]
+    fn qr_as_sixel() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = Sixel::new().generate_matrix(&qr.encode()).unwrap();
+
+        // 82 pixel rows make 14 bands.
+        assert!(generated.starts_with("\x1bPq\"1;1;82;82"));
+        assert_eq!(generated.matches('-').count(), 13);
+    }
+}
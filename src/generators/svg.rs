@@ -0,0 +1,574 @@
+//! This module provides types for generating SVG representations of barcodes. SVG output is
+//! resolution independent and can be embedded directly into web pages.
+//!
+//! Each run of dark modules is drawn as a single rectangle over a background rectangle, and
+//! the symbol is surrounded by a quiet zone of light modules. Runs of the same width in
+//! consecutive rows, such as the columns of 2D barcodes, are merged into one rectangle. For
+//! documents that embed many barcodes, `SVG::single_path` draws all the rectangles as a single
+//! compact `<path>` instead.
+//!
+//! For web pages, the root element can be given an `id`, CSS classes and an accessible label,
+//! and the document a `<title>` and `<desc>` that screen readers announce.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::{bearer_layout, data_uri, runs, Counter};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+use ::generators::hri::{HRI, TextPosition};
+use ::sym::itf14::BearerBars;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// The SVG barcode generator type.
+#[derive(Clone, Debug)]
+pub struct SVG {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For SVG, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone. Transparent backgrounds are not drawn.
+    pub background: Color,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+    /// The bar width reduction in pixels. Bars are drawn this much narrower around their
+    /// centers, but at least one pixel wide. Modules of 2D barcodes are not reduced.
+    pub bwr: u32,
+    /// Whether to draw all the bars as one `<path>` element rather than a `<rect>` each, for
+    /// smaller documents.
+    pub single_path: bool,
+    /// The `id` attribute of the root element.
+    pub id: Option<String>,
+    /// The CSS class names of the root element, separated by spaces.
+    pub class: Option<String>,
+    /// The accessible name of the image, such as the encoded data. The root element is
+    /// given `role="img"` and an `aria-label` attribute.
+    pub aria_label: Option<String>,
+    /// The title of the image, as a `<title>` element.
+    pub title: Option<String>,
+    /// A longer description of the image, as a `<desc>` element.
+    pub description: Option<String>,
+}
+
+impl Default for SVG {
+    fn default() -> SVG {
+        SVG::new()
+    }
+}
+
+// A rectangle in pixels: x, y, width and height.
+type Rect = (u32, u32, u32, u32);
+
+// Returns the fill attributes of a color, with its opacity unless it is opaque.
+fn fill(color: Color) -> String {
+    if color.is_opaque() {
+        return format!("fill=\"{}\"", color.to_hex());
+    }
+
+    let opacity = format!("{:.3}", color.alpha as f64 / 255.0);
+    format!("fill=\"{}\" fill-opacity=\"{}\"", color.to_hex(), opacity.trim_end_matches('0').trim_end_matches('.'))
+}
+
+// Merges each rectangle into a rectangle of the same width that ends directly above it.
+fn merge(rects: &[Rect]) -> Vec<Rect> {
+    let mut merged: Vec<Rect> = vec![];
+    // The index of each merged rectangle by its x position, width and bottom.
+    let mut bottoms: HashMap<(u32, u32, u32), usize> = HashMap::new();
+
+    for &(x, y, w, h) in rects {
+        let i = match bottoms.remove(&(x, w, y)) {
+            Some(i) => {
+                merged[i].3 += h;
+                i
+            },
+            None => {
+                merged.push((x, y, w, h));
+                merged.len() - 1
+            },
+        };
+
+        bottoms.insert((x, w, y + h), i);
+    }
+
+    merged
+}
+
+// Escapes the characters of text and attribute values that are special in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl SVG {
+    /// Returns a new SVG with default values.
+    pub fn new() -> SVG {
+        SVG{height: 80, xdim: 1, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+            rotation: Rotation::Normal, bwr: 0, single_path: false, id: None, class: None, aria_label: None,
+            title: None, description: None}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> SVG {
+        SVG{height: options.height_pixels(), xdim: options.xdim_pixels(),
+            quiet_zone: options.quiet_zone_modules(),
+            foreground: options.foreground, background: options.background, rotation: options.rotation,
+            bwr: options.bwr_pixels(), ..self}
+    }
+
+    // Builds the document from the dark rectangles of a symbol of the given size in pixels,
+    // not counting the quiet zone, and the human-readable text with the top of its line.
+    fn document(&self, width: u32, height: u32, rects: &[Rect], vertical_quiet_zone: bool,
+                text: Option<(&HRI, u32)>) -> String {
+        let mut svg = vec![];
+
+        self.write_document(width, height, rects, vertical_quiet_zone, text, &mut svg)
+            .expect("Writing to a Vec cannot fail");
+        String::from_utf8(svg).expect("SVG documents are UTF-8")
+    }
+
+    // Writes the document to `writer` as it is built. Returns the number of bytes written.
+    fn write_document<W: Write>(&self, width: u32, height: u32, rects: &[Rect], vertical_quiet_zone: bool,
+                                text: Option<(&HRI, u32)>, writer: &mut W) -> io::Result<usize> {
+        let mut svg = Counter::new(writer);
+        let margin = self.quiet_zone * self.xdim;
+        let top = if vertical_quiet_zone { margin } else { 0 };
+        let (total_width, total_height) = (width + 2 * margin, height + 2 * top);
+        let (image_width, image_height) = self.rotation.size(total_width, total_height);
+        writeln!(svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\"{a}>",
+            w = image_width, h = image_height, a = self.attributes())?;
+
+        if let Some(ref title) = self.title {
+            writeln!(svg, "<title>{}</title>", escape(title))?;
+        }
+
+        if let Some(ref description) = self.description {
+            writeln!(svg, "<desc>{}</desc>", escape(description))?;
+        }
+
+        if self.background.alpha > 0 {
+            writeln!(svg, "<rect width=\"{}\" height=\"{}\" {}/>",
+                            image_width, image_height, fill(self.background))?;
+        }
+
+        let rects: Vec<Rect> = merge(rects).iter()
+            .map(|&(x, y, w, h)| self.rotation.rect((x + margin, y + top, w, h), total_width, total_height))
+            .collect();
+
+        if self.single_path {
+            if !rects.is_empty() {
+                svg.write_all(b"<path d=\"")?;
+
+                for &(x, y, w, h) in &rects {
+                    write!(svg, "M{} {}h{}v{}h-{}z", x, y, w, h, w)?;
+                }
+
+                writeln!(svg, "\" {}/>", fill(self.foreground))?;
+            }
+        } else {
+            for &(x, y, w, h) in &rects {
+                writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+                                x, y, w, h, fill(self.foreground))?;
+            }
+        }
+
+        if let Some((hri, text_top)) = text {
+            let baseline = top + text_top + hri.gap() + hri.font_size;
+
+            for group in &hri.groups {
+                let center = margin as i64 + (2 * group.start as i64 + group.width as i64) * self.xdim as i64 / 2;
+
+                #[cfg(feature = "font")]
+                {
+                    if let Some(ref font) = hri.font {
+                        let path = font.path(&group.text, center as f32, (top + text_top + hri.gap()) as f32, hri.font_size);
+
+                        writeln!(svg, "<path d=\"{}\" {}{}/>", path, fill(self.foreground),
+                                        self.transform(total_width, total_height))?;
+                        continue;
+                    }
+                }
+
+                let (x, y, _, _) = self.rotation.rect((center.max(0) as u32, baseline, 0, 0), total_width, total_height);
+                let transform = match self.rotation {
+                    Rotation::Normal => String::new(),
+                    r => format!(" transform=\"rotate({} {} {})\"", r.degrees(), x, y),
+                };
+
+                writeln!(svg, "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" {}{}>{}</text>",
+                                x, y, hri.font_size, fill(self.foreground), transform, escape(&group.text))?;
+            }
+        }
+
+        svg.write_all(b"</svg>")?;
+        Ok(svg.count)
+    }
+
+    // Returns the identifying and accessibility attributes of the root element, each with a
+    // leading space.
+    fn attributes(&self) -> String {
+        let mut attributes = String::new();
+
+        if let Some(ref id) = self.id {
+            attributes.push_str(&format!(" id=\"{}\"", escape(id)));
+        }
+
+        if let Some(ref class) = self.class {
+            attributes.push_str(&format!(" class=\"{}\"", escape(class)));
+        }
+
+        if let Some(ref label) = self.aria_label {
+            attributes.push_str(&format!(" role=\"img\" aria-label=\"{}\"", escape(label)));
+        }
+
+        attributes
+    }
+
+    // Returns the transform attribute that rotates shapes drawn on an image of the given size.
+    #[cfg(feature = "font")]
+    fn transform(&self, width: u32, height: u32) -> String {
+        let (x, y) = match self.rotation {
+            Rotation::Normal => return String::new(),
+            Rotation::Clockwise90 => (height, 0),
+            Rotation::UpsideDown => (width, height),
+            Rotation::Clockwise270 => (0, width),
+        };
+
+        format!(" transform=\"translate({} {}) rotate({})\"", x, y, self.rotation.degrees())
+    }
+
+    // The rectangles of one row of modules, at the given y position and height.
+    fn row_rects(&self, modules: &[u8], y: u32, height: u32) -> Vec<Rect> {
+        runs(modules).iter().map(|&(x, len)| (x * self.xdim, y, len * self.xdim, height)).collect()
+    }
+
+    // The rectangles of one row of bars, narrowed by the bar width reduction.
+    fn bar_rects(&self, modules: &[u8], y: u32, height: u32) -> Vec<Rect> {
+        let reduction = self.bwr.min(self.xdim.saturating_sub(1));
+
+        self.row_rects(modules, y, height)
+            .into_iter()
+            .map(|(x, y, w, h)| (x + reduction / 2, y, w - reduction, h))
+            .collect()
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        let rects = self.bar_rects(barcode, 0, self.height);
+
+        Ok(self.document(barcode.len() as u32 * self.xdim, self.height, &rects, false, None))
+    }
+
+    /// Generates the given EncodedBarcode with its human-readable text beneath or above the
+    /// bars, in a monospace font, and its guard bars extended into the text. The text may
+    /// extend into the quiet zone. Returns a String.
+    pub fn generate_with_text(&self, barcode: &EncodedBarcode, text: &HRI) -> Result<String, &str> {
+        let (bars_top, text_top) = match text.position {
+            TextPosition::Below => (0, self.height),
+            TextPosition::Above => (text.line_height(), 0),
+        };
+        let (bars, guards) = text.split_guards(barcode);
+        let extension = text.guard_extension(self.xdim);
+        let guards_top = if text.position == TextPosition::Above { bars_top - extension } else { bars_top };
+        let mut rects = self.bar_rects(&bars, bars_top, self.height);
+        rects.extend(self.bar_rects(&guards, guards_top, self.height + extension));
+
+        Ok(self.document(barcode.len() as u32 * self.xdim, self.height + text.line_height(), &rects, false,
+                         Some((text, text_top))))
+    }
+
+    /// Generates the given EncodedBarcode of an ITF-14 symbol, surrounded by the given bearer
+    /// bars. The quiet zone of the bearer bars replaces `self.quiet_zone`, and the bearer bars
+    /// are not narrowed by the bar width reduction. Returns a String.
+    pub fn generate_with_bearer_bars(&self, barcode: &EncodedBarcode, bearer_bars: &BearerBars) -> Result<String, &str> {
+        let (mut rects, (left, top), (width, height)) =
+            bearer_layout(bearer_bars, self.xdim, barcode.len() as u32 * self.xdim, self.height);
+        rects.extend(self.bar_rects(barcode, top, self.height).into_iter().map(|(x, y, w, h)| (x + left, y, w, h)));
+
+        Ok(SVG{quiet_zone: 0, ..self.clone()}.document(width, height, &rects, false, None))
+    }
+
+    /// Generates the given EncodedBarcode as a base64 `data:` URI, ready to use as the `src`
+    /// of an `<img>` element. Returns a String.
+    pub fn generate_data_uri(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.generate(barcode).map(|svg| data_uri("image/svg+xml", svg.as_bytes()))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        let section = self.height / 3;
+        let rects: Vec<Rect> = bars.iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                let (y, h) = match b {
+                    BarState::Full => (0, self.height),
+                    BarState::Ascender => (0, self.height - section),
+                    BarState::Descender => (section, self.height - section),
+                    BarState::Tracker => (section, self.height - 2 * section),
+                };
+
+                (2 * i as u32 * self.xdim, y, self.xdim, h)
+            })
+            .collect();
+        let width = (2 * bars.len() as u32).saturating_sub(1) * self.xdim;
+
+        Ok(self.document(width, self.height, &rects, false, None))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        let mut rects = vec![];
+        let mut y = 0;
+
+        for row in rows {
+            let height = row.height * self.xdim;
+
+            rects.extend(self.bar_rects(&row.modules, y, height));
+            y += height;
+        }
+
+        let width = rows.iter().map(|r| r.modules.len()).max().unwrap_or(0) as u32 * self.xdim;
+
+        Ok(self.document(width, y, &rects, false, None))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        let rects: Vec<Rect> = matrix.rows()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| self.row_rects(row, y as u32 * self.xdim, self.xdim))
+            .collect();
+
+        Ok(self.document(matrix.width as u32 * self.xdim, matrix.height as u32 * self.xdim, &rects, true, None))
+    }
+}
+
+impl Generator for SVG {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        SVG::generate(self, barcode).map_err(GeneratorError::from)
+    }
+
+    fn generate_to<W: Write>(&self, barcode: &EncodedBarcode, writer: &mut W) -> Result<usize, GeneratorError> {
+        let rects = self.bar_rects(barcode, 0, self.height);
+
+        self.write_document(barcode.len() as u32 * self.xdim, self.height, &rects, false, None, writer)
+            .map_err(|_| GeneratorError::new("Could not write barcode."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::ean13::*;
+    use ::sym::BarState;
+    use ::sym::databar::*;
+    use ::sym::itf14::*;
+    use ::sym2d::qr::*;
+    use ::generators::svg::*;
+
+    #[test]
+    fn code_39_as_svg() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{height: 20, xdim: 2, quiet_zone: 0, ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"76\" height=\"20\" viewBox=\"0 0 76 20\">\n"));
+        assert!(generated.contains("<rect width=\"76\" height=\"20\" fill=\"#ffffff\"/>\n"));
+        // The guard begins with a narrow bar, then a wide bar after a wide space.
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"2\" height=\"20\" fill=\"#000000\"/>\n<rect x=\"6\" y=\"0\" width=\"2\" height=\"20\" fill=\"#000000\"/>\n<rect x=\"10\" y=\"0\" width=\"4\" height=\"20\" fill=\"#000000\"/>"));
+        assert!(generated.ends_with("</svg>"));
+        assert_eq!(generated.matches("<rect").count(), 1 + 15);
+    }
+
+    #[test]
+    fn code_39_as_svg_with_quiet_zone_and_colors() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{foreground: Color::new(0x11, 0x22, 0x33), background: Color::new(0xff, 0xee, 0xdd), ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(generated.contains("width=\"58\" height=\"80\""));
+        assert!(generated.contains("<rect width=\"58\" height=\"80\" fill=\"#ffeedd\"/>"));
+        assert!(generated.contains("<rect x=\"10\" y=\"0\" width=\"1\" height=\"80\" fill=\"#112233\"/>"));
+    }
+
+    #[test]
+    fn code_39_as_accessible_svg() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{id: Some("code".to_string()), class: Some("barcode large".to_string()),
+                      aria_label: Some("Code 39: \"A\"".to_string()), title: Some("Part A".to_string()),
+                      description: Some("Scan <A> & B".to_string()), ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"58\" height=\"80\" viewBox=\"0 0 58 80\" id=\"code\" class=\"barcode large\" role=\"img\" aria-label=\"Code 39: &quot;A&quot;\">\n<title>Part A</title>\n<desc>Scan &lt;A&gt; &amp; B</desc>\n<rect width="));
+        assert!(!SVG::new().generate(&code39.encode()).unwrap().contains("<title>"));
+    }
+
+    #[test]
+    fn code_39_as_svg_with_transparency() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{foreground: Color::rgba(0, 0, 0, 128), background: Color::TRANSPARENT, ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(!generated.contains("<rect width="));
+        assert!(generated.contains("<rect x=\"10\" y=\"0\" width=\"1\" height=\"80\" fill=\"#000000\" fill-opacity=\"0.502\"/>"));
+        assert_eq!(generated.matches("<rect").count(), 15);
+    }
+
+    #[test]
+    fn code_39_as_svg_with_bwr() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{height: 20, xdim: 4, quiet_zone: 0, bwr: 2, ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(generated.contains("<rect x=\"1\" y=\"0\" width=\"2\" height=\"20\" fill=\"#000000\"/>\n<rect x=\"13\" y=\"0\" width=\"2\" height=\"20\" fill=\"#000000\"/>\n<rect x=\"21\" y=\"0\" width=\"6\" height=\"20\" fill=\"#000000\"/>"));
+
+        // Narrow bars keep at least one pixel.
+        let generated = SVG{bwr: 9, ..svg}.generate(&code39.encode()).unwrap();
+
+        assert!(generated.contains("<rect x=\"1\" y=\"0\" width=\"1\" height=\"20\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn itf14_as_svg_with_bearer_bars() {
+        let itf14 = ITF14::new("15400141288763".to_string()).unwrap();
+        let svg = SVG{height: 100, xdim: 2, ..SVG::new()};
+        let generated = svg.generate_with_bearer_bars(&itf14.encode(), &itf14.bearer_bars()).unwrap();
+
+        // 5 modules thick, around quiet zones of 10 modules.
+        assert!(generated.contains("width=\"330\" height=\"120\""));
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"330\" height=\"10\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"320\" y=\"10\" width=\"10\" height=\"100\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"30\" y=\"10\" width=\"2\" height=\"100\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn code_39_as_rotated_svg() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let svg = SVG{height: 20, quiet_zone: 1, rotation: Rotation::Clockwise270, ..SVG::new()};
+        let generated = svg.generate(&code39.encode()).unwrap();
+
+        assert!(generated.contains("width=\"20\" height=\"40\" viewBox=\"0 0 20 40\""));
+        assert!(generated.contains("<rect width=\"20\" height=\"40\" fill=\"#ffffff\"/>"));
+        // The guard is at the bottom, after the quiet zone.
+        assert!(generated.contains("<rect x=\"0\" y=\"38\" width=\"20\" height=\"1\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn ean13_as_svg_with_text() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let svg = SVG{height: 50, xdim: 2, quiet_zone: 11, ..SVG::new()};
+        let generated = svg.generate_with_text(&ean13.encode(), &HRI::ean13(&ean13)).unwrap();
+
+        assert!(generated.contains("width=\"234\" height=\"62\""));
+        assert!(generated.contains("<text x=\"15\" y=\"61\" font-family=\"monospace\" font-size=\"10\" text-anchor=\"middle\" fill=\"#000000\">7</text>\n"));
+        assert!(generated.contains(">501031</text>\n"));
+        // The guards extend five modules into the text.
+        assert!(generated.contains("<rect x=\"22\" y=\"0\" width=\"2\" height=\"60\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"30\" y=\"0\" width=\"4\" height=\"50\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<text x=\"164\" y=\"61\" font-family=\"monospace\" font-size=\"10\" text-anchor=\"middle\" fill=\"#000000\">311309</text>\n"));
+    }
+
+    #[test]
+    fn code_39_as_rotated_svg_with_text_above() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let hri = HRI{position: TextPosition::Above, ..HRI::new("A&B", 38)};
+        let svg = SVG{height: 20, quiet_zone: 0, rotation: Rotation::Clockwise90, ..SVG::new()};
+        let generated = svg.generate_with_text(&code39.encode(), &hri).unwrap();
+
+        assert!(generated.contains("width=\"32\" height=\"38\""));
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"20\" height=\"1\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<text x=\"21\" y=\"19\" font-family=\"monospace\" font-size=\"10\" text-anchor=\"middle\" fill=\"#000000\" transform=\"rotate(90 21 19)\">A&amp;B</text>"));
+    }
+
+    #[test]
+    #[cfg(feature = "font")]
+    fn code_39_as_svg_with_font() {
+        use ::generators::hri::Font;
+        use std::fs;
+
+        // DejaVu Sans is not on every system, so this passes without it.
+        let font = match fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") {
+            Ok(data) => Font::from_bytes(data).unwrap(),
+            Err(_) => return,
+        };
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let hri = HRI{font: Some(font), ..HRI::new("A", 38)};
+        let svg = SVG{height: 20, quiet_zone: 0, rotation: Rotation::UpsideDown, ..SVG::new()};
+        let generated = svg.generate_with_text(&code39.encode(), &hri).unwrap();
+
+        assert!(!generated.contains("<text"));
+        assert!(generated.contains("<path d=\"M"));
+        assert!(generated.contains("Z\" fill=\"#000000\" transform=\"translate(38 32) rotate(180)\"/>"));
+    }
+
+    #[test]
+    fn code_39_as_svg_data_uri() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let generated = SVG::new().generate_data_uri(&code39.encode()).unwrap();
+
+        // "<svg xmln" in base64.
+        assert!(generated.starts_with("data:image/svg+xml;base64,PHN2ZyB4bWxu"));
+    }
+
+    #[test]
+    fn bars_as_svg() {
+        let svg = SVG{height: 30, quiet_zone: 0, ..SVG::new()};
+        let generated = svg.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        assert!(generated.contains("width=\"3\" height=\"30\""));
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"1\" height=\"30\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"2\" y=\"10\" width=\"1\" height=\"20\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn databar_stacked_as_svg() {
+        let databar = DataBarStacked::new("2001234567890".to_string()).unwrap();
+        let svg = SVG{quiet_zone: 1, ..SVG::new()};
+        let generated = svg.generate_rows(&databar.encode()).unwrap();
+
+        assert!(generated.contains("width=\"52\" height=\"13\""));
+        assert!(generated.contains("<rect x=\"2\" y=\"0\" width=\"1\" height=\"5\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"5\" y=\"5\" width=\"3\" height=\"1\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn qr_as_svg() {
+        let qr = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::Q).unwrap();
+        let svg = SVG{xdim: 4, quiet_zone: 4, ..SVG::new()};
+        let generated = svg.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.contains("width=\"116\" height=\"116\""));
+        assert!(generated.contains("<rect x=\"16\" y=\"16\" width=\"28\" height=\"4\" fill=\"#000000\"/>"));
+        // The sides of the finder pattern are merged down its rows.
+        assert!(generated.contains("<rect x=\"16\" y=\"20\" width=\"4\" height=\"20\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn qr_as_svg_single_path() {
+        let qr = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::Q).unwrap();
+        let svg = SVG{xdim: 4, quiet_zone: 4, single_path: true, ..SVG::new()};
+        let generated = svg.generate_matrix(&qr.encode()).unwrap();
+
+        assert_eq!(generated.matches("<path").count(), 1);
+        assert!(!generated.contains("<rect x="));
+        assert!(generated.contains("<path d=\"M16 16h28v4h-28zM"));
+        assert!(generated.contains("zM16 20h4v20h-4zM"));
+        assert!(generated.len() < SVG{single_path: false, ..svg}.generate_matrix(&qr.encode()).unwrap().len() / 2);
+    }
+}
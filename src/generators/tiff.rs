@@ -0,0 +1,200 @@
+//! This module provides types for generating TIFF images of barcodes. The resolution is
+//! written to the X and Y resolution tags, so prepress software places the barcode at its
+//! physical size.
+//!
+//! Images are uncompressed bilevel TIFFs, with one bit per pixel and 1 for black.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The TIFF barcode generator type.
+pub struct TIFF {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For TIFF, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The resolution of the image, in pixels per inch.
+    pub dpi: u32,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for TIFF {
+    fn default() -> TIFF {
+        TIFF::new()
+    }
+}
+
+// The field types of IFD entries.
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
+const RATIONAL: u16 = 5;
+
+// The number of entries in the image file directory.
+const IFD_ENTRIES: u32 = 12;
+
+// Writes one IFD entry. Values of SHORT fields are left-justified in the value field.
+fn entry(tiff: &mut Vec<u8>, tag: u16, field_type: u16, value: u32) {
+    tiff.extend(&tag.to_le_bytes());
+    tiff.extend(&field_type.to_le_bytes());
+    tiff.extend(&1u32.to_le_bytes());
+
+    match field_type {
+        SHORT => {
+            tiff.extend(&(value as u16).to_le_bytes());
+            tiff.extend(&[0, 0]);
+        },
+        _ => tiff.extend(&value.to_le_bytes()),
+    }
+}
+
+impl TIFF {
+    /// Returns a new TIFF with default values, for 300 dpi.
+    pub fn new() -> TIFF {
+        TIFF{height: 300, xdim: 4, quiet_zone: 10, dpi: 300, rotation: Rotation::Normal}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels at the
+    /// resolution of the options.
+    pub fn with_options(self, options: &Options) -> TIFF {
+        TIFF{height: options.height_pixels(), xdim: options.xdim_pixels(),
+             quiet_zone: options.quiet_zone_modules(), dpi: options.dpi, rotation: options.rotation}
+    }
+
+    // Writes a little-endian TIFF with a single strip: the header, the image file directory,
+    // the two resolutions and then the pixels.
+    fn image(&self, pixels: Pixels) -> Vec<u8> {
+        let ifd_end = 8 + 2 + IFD_ENTRIES * 12 + 4;
+        let (x_resolution, y_resolution, strip) = (ifd_end, ifd_end + 8, ifd_end + 16);
+        let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0];
+
+        tiff.extend(&(IFD_ENTRIES as u16).to_le_bytes());
+        entry(&mut tiff, 256, LONG, pixels.width);
+        entry(&mut tiff, 257, LONG, pixels.height);
+        entry(&mut tiff, 258, SHORT, 1);
+        entry(&mut tiff, 259, SHORT, 1);
+        entry(&mut tiff, 262, SHORT, 0);
+        entry(&mut tiff, 273, LONG, strip);
+        entry(&mut tiff, 277, SHORT, 1);
+        entry(&mut tiff, 278, LONG, pixels.height);
+        entry(&mut tiff, 279, LONG, pixels.data.len() as u32);
+        entry(&mut tiff, 282, RATIONAL, x_resolution);
+        entry(&mut tiff, 283, RATIONAL, y_resolution);
+        entry(&mut tiff, 296, SHORT, 2);
+        tiff.extend(&0u32.to_le_bytes());
+
+        for _ in 0..2 {
+            tiff.extend(&self.dpi.to_le_bytes());
+            tiff.extend(&1u32.to_le_bytes());
+        }
+
+        tiff.extend(pixels.data);
+        tiff
+    }
+
+    // Returns a raw pixel generator with the same dimensions.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode. Returns the bytes of the image.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        self.raw().generate(barcode).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns the bytes of the image.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        self.raw().generate_bars(bars).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns the bytes of the image.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        self.raw().generate_rows(rows).map(|p| self.image(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns the
+    /// bytes of the image.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.image(p))
+    }
+}
+
+impl Generator for TIFF {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        TIFF::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym2d::qr::*;
+    use ::generators::tiff::*;
+
+    // Reads the value of an IFD entry with the given tag.
+    fn tag(tiff: &[u8], tag: u16) -> Option<u32> {
+        let count = u16::from_le_bytes([tiff[8], tiff[9]]) as usize;
+
+        tiff[10..10 + count * 12].chunks(12)
+            .find(|e| u16::from_le_bytes([e[0], e[1]]) == tag)
+            .map(|e| match u16::from_le_bytes([e[2], e[3]]) {
+                3 => u16::from_le_bytes([e[8], e[9]]) as u32,
+                _ => u32::from_le_bytes([e[8], e[9], e[10], e[11]]),
+            })
+    }
+
+    #[test]
+    fn code_39_as_tiff() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let tiff = TIFF{height: 2, xdim: 1, quiet_zone: 0, ..TIFF::new()};
+        let generated = tiff.generate(&code39.encode()).unwrap();
+
+        assert!(generated.starts_with(b"II*\0"));
+        assert_eq!(tag(&generated, 256), Some(38));
+        assert_eq!(tag(&generated, 257), Some(2));
+        assert_eq!(tag(&generated, 262), Some(0));
+        assert_eq!(tag(&generated, 279), Some(10));
+
+        let strip = tag(&generated, 273).unwrap() as usize;
+        assert_eq!(generated.len(), strip + 10);
+        assert_eq!(&generated[strip..strip + 5], &[0x96, 0xD6, 0xA5, 0xA5, 0xB4]);
+    }
+
+    #[test]
+    fn tiff_resolution() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let generated = TIFF{dpi: 600, ..TIFF::new()}.generate(&code39.encode()).unwrap();
+
+        for resolution_tag in &[282, 283] {
+            let offset = tag(&generated, *resolution_tag).unwrap() as usize;
+            assert_eq!(&generated[offset..offset + 8], &[0x58, 0x02, 0, 0, 1, 0, 0, 0]);
+        }
+
+        assert_eq!(tag(&generated, 296), Some(2));
+    }
+
+    #[test]
+    fn qr_as_tiff() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = TIFF{xdim: 2, quiet_zone: 4, ..TIFF::new()}.generate_matrix(&qr.encode()).unwrap();
+
+        assert_eq!(tag(&generated, 256), Some(58));
+        assert_eq!(tag(&generated, 257), Some(58));
+        assert_eq!(tag(&generated, 279), Some(8 * 58));
+    }
+}
@@ -0,0 +1,244 @@
+//! This module provides types for generating TSPL (TSC Printer Language) labels, to drive TSC
+//! label printers.
+//!
+//! The symbologies that TSPL printers support natively are sent as `BARCODE` commands. Any
+//! other encoded barcode can be sent as a `BITMAP` of its modules.
+//!
+//! All dimensions are in printer dots.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym::code39::Code39;
+use ::sym::code128::{Code128, CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C, FNC1};
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::two_of_five::I2of5;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
+
+/// The TSPL barcode generator type.
+pub struct TSPL {
+    /// The height of the barcode in dots.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For TSPL, each will be ```self.xdim``` dots wide.
+    pub xdim: u32,
+    /// The position of the top left of the barcode on the label.
+    pub origin: (u32, u32),
+    /// Whether the printer should print the human-readable text below native barcodes.
+    /// Bitmaps never include it.
+    pub human_readable: bool,
+}
+
+impl Default for TSPL {
+    fn default() -> TSPL {
+        TSPL::new()
+    }
+}
+
+// Formats digits as command data.
+fn digits(data: &[u8]) -> String {
+    data.iter().map(|d| d.to_string()).collect()
+}
+
+impl TSPL {
+    /// Returns a new TSPL with default values.
+    pub fn new() -> TSPL {
+        TSPL{height: 100, xdim: 2, origin: (50, 50), human_readable: false}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in printer dots.
+    pub fn with_options(self, options: &Options) -> TSPL {
+        TSPL{height: options.height_pixels(), xdim: options.xdim_pixels(), ..self}
+    }
+
+    // Wraps a command in a label that clears the image buffer first and prints one copy.
+    fn label(&self, command: &[u8]) -> Vec<u8> {
+        let mut label = b"CLS\r\n".to_vec();
+        label.extend(command);
+        label.extend(b"\r\nPRINT 1\r\n");
+        label
+    }
+
+    // Builds a native barcode command with the given code type and content. The wide bars
+    // of Code39 and Interleaved 2 of 5 are twice the width of the narrow ones, as encoded.
+    fn native(&self, code_type: &str, content: &str) -> String {
+        let command = format!("BARCODE {},{},\"{}\",{},{},0,{},{},\"{}\"", self.origin.0,
+                              self.origin.1, code_type, self.height, self.human_readable as u8,
+                              self.xdim, 2 * self.xdim, content.replace('"', "\\[\"]"));
+
+        String::from_utf8(self.label(command.as_bytes())).unwrap()
+    }
+
+    // Builds a bitmap from one bit per pixel. TSPL prints black for 0 bits.
+    fn bitmap(&self, pixels: Pixels) -> Vec<u8> {
+        let mut command = format!("BITMAP {},{},{},{},0,", self.origin.0, self.origin.1,
+                                  pixels.stride(), pixels.height).into_bytes();
+        command.extend(pixels.data.iter().map(|b| !b));
+
+        self.label(&command)
+    }
+
+    // Returns a raw pixel generator with the same dimensions and no quiet zone, which the
+    // label layout provides.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: 0, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode as a bitmap. Returns the bytes of the label.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, &str> {
+        self.raw().generate(barcode).map(|p| self.bitmap(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars) as a bitmap. Returns the bytes
+    /// of the label.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<Vec<u8>, &str> {
+        self.raw().generate_bars(bars).map(|p| self.bitmap(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode) as a bitmap. Each row is
+    /// ```row.height``` modules high, so `self.height` is not used. Returns the bytes of the
+    /// label.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<Vec<u8>, &str> {
+        self.raw().generate_rows(rows).map(|p| self.bitmap(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode) as a bitmap. Each module is square,
+    /// so `self.height` is not used. Returns the bytes of the label.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<Vec<u8>, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.bitmap(p))
+    }
+
+    /// Generates the given Code39 barcode as a native `BARCODE` command, with any check
+    /// character included in the content. Returns a String.
+    pub fn generate_code39(&self, barcode: &Code39) -> String {
+        let mut content: String = barcode.raw_data().iter().collect();
+
+        if barcode.checksum_required() {
+            content.extend(barcode.checksum_char());
+        }
+
+        self.native("39S", &content)
+    }
+
+    /// Generates the given Code128 barcode as a native `BARCODE` command.
+    ///
+    /// Data with character set selectors is sent with the matching manual subset codes, and
+    /// plain data is left to the printer's automatic mode. Returns Result<String, &str>, as
+    /// the automatic mode cannot encode FNC1.
+    pub fn generate_code128(&self, barcode: &Code128) -> Result<String, &str> {
+        let data = barcode.raw_data();
+        let manual = data.first().is_some_and(|&c| [CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C].contains(&c));
+
+        if !manual {
+            if data.contains(&FNC1) {
+                return Err("TSPL cannot encode FNC1 in automatic Code128 data");
+            }
+
+            return Ok(self.native("128", &data.iter().collect::<String>()));
+        }
+
+        let mut content = String::new();
+
+        for (i, &c) in data.iter().enumerate() {
+            match c {
+                CHARACTER_SET_A => content.push_str(if i == 0 { "!103" } else { "!101" }),
+                CHARACTER_SET_B => content.push_str(if i == 0 { "!104" } else { "!100" }),
+                CHARACTER_SET_C => content.push_str(if i == 0 { "!105" } else { "!099" }),
+                FNC1 => content.push_str("!102"),
+                _ => content.push(c),
+            }
+        }
+
+        Ok(self.native("128M", &content))
+    }
+
+    /// Generates the given EAN13 barcode as a native `BARCODE` command. The printer
+    /// calculates the check digit. Returns a String.
+    pub fn generate_ean13(&self, barcode: &EAN13) -> String {
+        self.native("EAN13", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given EAN8 barcode as a native `BARCODE` command. The printer
+    /// calculates the check digit. Returns a String.
+    pub fn generate_ean8(&self, barcode: &EAN8) -> String {
+        self.native("EAN8", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given UPCA barcode as a native `BARCODE` command. The printer
+    /// calculates the check digit. Returns a String.
+    pub fn generate_upca(&self, barcode: &UPCA) -> String {
+        self.native("UPCA", &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given I2of5 barcode as a native `BARCODE` command. Returns a String.
+    pub fn generate_i2of5(&self, barcode: &I2of5) -> String {
+        self.native("25", &digits(barcode.raw_data()))
+    }
+}
+
+impl Generator for TSPL {
+    type Output = Vec<u8>;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<Vec<u8>, GeneratorError> {
+        TSPL::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::code128::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::upca::*;
+    use ::sym::two_of_five::*;
+    use ::generators::tspl::*;
+
+    #[test]
+    fn code_39_as_tspl_bitmap() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let tspl = TSPL{height: 1, xdim: 1, ..TSPL::new()};
+        let generated = tspl.generate(&code39.encode()).unwrap();
+
+        let mut expected = b"CLS\r\nBITMAP 50,50,5,1,0,".to_vec();
+        expected.extend(&[!0x96, !0xD6, !0xA5, !0xA5, !0xB4]);
+        expected.extend(b"\r\nPRINT 1\r\n");
+        assert_eq!(generated, expected);
+    }
+
+    #[test]
+    fn code_39_as_tspl() {
+        let tspl = TSPL{human_readable: true, ..TSPL::new()};
+
+        assert_eq!(tspl.generate_code39(&Code39::new("TEST8052".to_string()).unwrap()),
+                   "CLS\r\nBARCODE 50,50,\"39S\",100,1,0,2,4,\"TEST8052\"\r\nPRINT 1\r\n");
+        assert!(tspl.generate_code39(&Code39::with_checksum("TEST".to_string()).unwrap())
+                    .contains(",\"TESTE\""));
+    }
+
+    #[test]
+    fn code_128_as_tspl() {
+        let tspl = TSPL{origin: (0, 10), xdim: 3, height: 60, ..TSPL::new()};
+
+        assert!(tspl.generate_code128(&Code128::new("ÀHELLOĆ1234ƒ".to_string()).unwrap()).unwrap()
+                    .contains("BARCODE 0,10,\"128M\",60,0,0,3,6,\"!103HELLO!0991234!102\""));
+        assert!(tspl.generate_code128(&Code128::auto("a\"b".to_string()).unwrap()).unwrap()
+                    .contains(",\"128\",60,0,0,3,6,\"a\\[\"]b\""));
+        assert!(tspl.generate_code128(&Code128::auto("ƒ1234".to_string()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn ean_upc_and_i2of5_as_tspl() {
+        let tspl = TSPL::new();
+
+        assert!(tspl.generate_ean13(&EAN13::new("750103131130".to_string()).unwrap()).contains(",\"EAN13\",100,0,0,2,4,\"750103131130\""));
+        assert!(tspl.generate_ean8(&EAN8::new("5512345".to_string()).unwrap()).contains(",\"EAN8\",100,0,0,2,4,\"5512345\""));
+        assert!(tspl.generate_upca(&UPCA::new("12345678901".to_string()).unwrap()).contains(",\"UPCA\",100,0,0,2,4,\"12345678901\""));
+        assert!(tspl.generate_i2of5(&I2of5::new("1234".to_string()).unwrap()).contains(",\"25\",100,0,0,2,4,\"1234\""));
+    }
+}
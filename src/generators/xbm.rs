@@ -0,0 +1,163 @@
+//! This module provides types for generating XBM representations of barcodes. An XBM image is
+//! C source code: two `#define`s for the dimensions and an array of the pixels, ready to be
+//! pasted into firmware.
+//!
+//! Each row is padded to a whole number of bytes, with the leftmost pixel in the least
+//! significant bit and 1 for black.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The XBM barcode generator type.
+pub struct XBM {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For XBM, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The prefix of the C identifiers, such as "barcode" for `barcode_width` and
+    /// `barcode_bits`.
+    pub name: String,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for XBM {
+    fn default() -> XBM {
+        XBM::new()
+    }
+}
+
+// The number of bytes on each line of the array.
+const BYTES_PER_LINE: usize = 12;
+
+/// Returns whether the given name is a valid C identifier.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') &&
+        chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl XBM {
+    /// Returns a new XBM with default values.
+    pub fn new() -> XBM {
+        XBM{height: 80, xdim: 1, quiet_zone: 10, name: "barcode".to_string(), rotation: Rotation::Normal}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> XBM {
+        XBM{height: options.height_pixels(), xdim: options.xdim_pixels(),
+            quiet_zone: options.quiet_zone_modules(), rotation: options.rotation, ..self}
+    }
+
+    // Writes the dimensions and the pixels, reversing the bits of each byte so that the
+    // leftmost pixel is the least significant.
+    fn source(&self, pixels: Pixels) -> String {
+        let lines: Vec<String> = pixels.data.chunks(BYTES_PER_LINE)
+            .map(|line| {
+                let bytes: Vec<String> = line.iter().map(|b| format!("0x{:02x}", b.reverse_bits())).collect();
+                format!("   {}", bytes.join(", "))
+            })
+            .collect();
+
+        format!("#define {name}_width {}\n#define {name}_height {}\nstatic unsigned char {name}_bits[] = {{\n{} }};\n",
+                pixels.width, pixels.height, lines.join(",\n"), name = self.name)
+    }
+
+    // Returns a raw pixel generator with the same dimensions, if the name can be used.
+    fn raw(&self) -> Result<Raw, &str> {
+        if !is_identifier(&self.name) {
+            return Err("Name is not a valid C identifier");
+        }
+
+        Ok(Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()})
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String of C source code.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.raw()?.generate(barcode).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String of C source code.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        self.raw()?.generate_bars(bars).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String of C source code.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        self.raw()?.generate_rows(rows).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String of C source code.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        self.raw()?.generate_matrix(matrix).map(|p| self.source(p))
+    }
+}
+
+impl Generator for XBM {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        XBM::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym2d::qr::*;
+    use ::generators::xbm::*;
+
+    #[test]
+    fn code_39_as_xbm() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let xbm = XBM{height: 1, quiet_zone: 0, name: "code39".to_string(), ..XBM::new()};
+        let generated = xbm.generate(&code39.encode()).unwrap();
+
+        assert_eq!(generated, "#define code39_width 38\n#define code39_height 1\n\
+                               static unsigned char code39_bits[] = {\n   0x69, 0x6b, 0xa5, 0xa5, 0x2d };\n");
+    }
+
+    #[test]
+    fn xbm_lines() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let xbm = XBM{height: 3, quiet_zone: 0, ..XBM::new()};
+        let generated = xbm.generate(&code39.encode()).unwrap();
+
+        // Fifteen bytes make two lines.
+        assert!(generated.contains("0x2d, 0x69, 0x6b,\n   0xa5, 0xa5, 0x2d };"));
+    }
+
+    #[test]
+    fn xbm_identifiers() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+
+        assert!(is_identifier("_barcode_1"));
+        assert!(!is_identifier("1barcode"));
+        assert!(!is_identifier(""));
+        assert!(XBM{name: "my-barcode".to_string(), ..XBM::new()}.generate(&code39.encode()).is_err());
+    }
+
+    #[test]
+    fn qr_as_xbm() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = XBM{quiet_zone: 4, ..XBM::new()}.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.starts_with("#define barcode_width 29\n#define barcode_height 29\n"));
+        assert_eq!(generated.matches("0x").count(), 4 * 29);
+    }
+}
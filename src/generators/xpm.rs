@@ -0,0 +1,163 @@
+//! This module provides types for generating XPM representations of barcodes. An XPM image is
+//! C source code: an array of strings holding the dimensions, a palette and one string for each
+//! row of pixels.
+//!
+//! Spaces are drawn with ' ' and bars with '#'.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym2d::EncodedMatrix;
+use ::generators::Color;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::xbm::is_identifier;
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::{Options, Rotation};
+
+/// The XPM barcode generator type.
+pub struct XPM {
+    /// The height of the barcode in pixels.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For XPM, each will be ```self.xdim``` pixels wide.
+    pub xdim: u32,
+    /// The width of the quiet zone on each side of the barcode, in modules.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: Color,
+    /// The color of the spaces and the quiet zone.
+    pub background: Color,
+    /// The C identifier of the array.
+    pub name: String,
+    /// The clockwise rotation of the whole image, including the quiet zone.
+    pub rotation: Rotation,
+}
+
+impl Default for XPM {
+    fn default() -> XPM {
+        XPM::new()
+    }
+}
+
+impl XPM {
+    /// Returns a new XPM with default values.
+    pub fn new() -> XPM {
+        XPM{height: 80, xdim: 1, quiet_zone: 10, foreground: Color::BLACK, background: Color::WHITE,
+            name: "barcode".to_string(), rotation: Rotation::Normal}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in pixels.
+    pub fn with_options(self, options: &Options) -> XPM {
+        XPM{height: options.height_pixels(), xdim: options.xdim_pixels(),
+            quiet_zone: options.quiet_zone_modules(),
+            foreground: options.foreground, background: options.background,
+            rotation: options.rotation, ..self}
+    }
+
+    // Writes the values, the palette and a string for each row of pixels.
+    fn source(&self, pixels: Pixels) -> String {
+        let mut strings = vec![
+            format!("\"{} {} 2 1\"", pixels.width, pixels.height),
+            format!("\"  c {}\"", self.background.to_hex()),
+            format!("\"# c {}\"", self.foreground.to_hex()),
+        ];
+
+        for y in 0..pixels.height as usize {
+            let row: String = (0..pixels.width as usize)
+                .map(|x| match (pixels.data[y * pixels.stride() + x / 8] >> (7 - x % 8)) & 1 {
+                    1 => '#',
+                    _ => ' ',
+                })
+                .collect();
+
+            strings.push(format!("\"{}\"", row));
+        }
+
+        format!("/* XPM */\nstatic char *{}[] = {{\n{}\n}};\n", self.name, strings.join(",\n"))
+    }
+
+    // Returns a raw pixel generator with the same dimensions, if the name can be used.
+    fn raw(&self) -> Result<Raw, &str> {
+        if !is_identifier(&self.name) {
+            return Err("Name is not a valid C identifier");
+        }
+
+        Ok(Raw{height: self.height, xdim: self.xdim, quiet_zone: self.quiet_zone,
+            rotation: self.rotation, ..Raw::mono()})
+    }
+
+    /// Generates the given EncodedBarcode. Returns a String of C source code.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.raw()?.generate(barcode).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars). The bars are split into
+    /// thirds: the upper (ascender), middle (tracker) and lower (descender) sections.
+    /// Returns a String of C source code.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        self.raw()?.generate_bars(bars).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode). Each row is ```row.height```
+    /// modules high, so `self.height` is not used. Returns a String of C source code.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        self.raw()?.generate_rows(rows).map(|p| self.source(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode). Each module is square, so
+    /// `self.height` is not used, and the quiet zone surrounds all four sides. Returns a
+    /// String of C source code.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        self.raw()?.generate_matrix(matrix).map(|p| self.source(p))
+    }
+}
+
+impl Generator for XPM {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        XPM::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::BarState;
+    use ::sym2d::qr::*;
+    use ::generators::xpm::*;
+
+    #[test]
+    fn code_39_as_xpm() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let xpm = XPM{height: 1, quiet_zone: 1, foreground: Color::new(0, 0, 128), ..XPM::new()};
+        let generated = xpm.generate(&code39.encode()).unwrap();
+
+        assert_eq!(generated, "/* XPM */\nstatic char *barcode[] = {\n\"40 1 2 1\",\n\"  c #ffffff\",\n\
+                               \"# c #000080\",\n\" #  # ## ## # ## # #  # ## #  # ## ## # \"\n};\n");
+    }
+
+    #[test]
+    fn bars_as_xpm() {
+        let xpm = XPM{height: 3, quiet_zone: 0, ..XPM::new()};
+        let generated = xpm.generate_bars(&vec![BarState::Full, BarState::Descender]).unwrap();
+
+        assert!(generated.ends_with("\"#  \",\n\"# #\",\n\"# #\"\n};\n"));
+    }
+
+    #[test]
+    fn xpm_name() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+
+        assert!(XPM{name: "".to_string(), ..XPM::new()}.generate(&code39.encode()).is_err());
+    }
+
+    #[test]
+    fn qr_as_xpm() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let generated = XPM{quiet_zone: 4, ..XPM::new()}.generate_matrix(&qr.encode()).unwrap();
+
+        assert!(generated.contains("\"29 29 2 1\""));
+        assert_eq!(generated.lines().count(), 3 + 3 + 29);
+    }
+}
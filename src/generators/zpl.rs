@@ -0,0 +1,259 @@
+//! This module provides types for generating ZPL (Zebra Programming Language) labels, to drive
+//! Zebra printers directly.
+//!
+//! Any encoded barcode can be sent as a `^GF` graphic field of its modules. The symbologies
+//! that Zebra printers support natively can instead be sent as their own barcode commands
+//! (`^B3`, `^BC`, `^BE`, ...), which lets the printer render them at its best quality.
+//!
+//! All dimensions are in printer dots.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::EncodedRows;
+use ::sym::code39::Code39;
+use ::sym::code128::{Code128, CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C, FNC1};
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::two_of_five::I2of5;
+use ::sym2d::EncodedMatrix;
+use ::generators::raw::{Raw, Pixels};
+use ::generators::{Generator, GeneratorError};
+use ::generators::options::Options;
+
+/// The ZPL barcode generator type.
+pub struct ZPL {
+    /// The height of the barcode in dots.
+    pub height: u32,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    /// For ZPL, each will be ```self.xdim``` dots wide.
+    pub xdim: u32,
+    /// The field origin: the position of the top left of the barcode on the label.
+    pub origin: (u32, u32),
+    /// Whether the printer should print the human-readable text below native barcodes.
+    /// Graphic fields never include it.
+    pub human_readable: bool,
+}
+
+impl Default for ZPL {
+    fn default() -> ZPL {
+        ZPL::new()
+    }
+}
+
+// Formats a yes or no parameter.
+fn yes_no(b: bool) -> char {
+    if b { 'Y' } else { 'N' }
+}
+
+// Formats digits as field data.
+fn digits(data: &[u8]) -> String {
+    data.iter().map(|d| d.to_string()).collect()
+}
+
+impl ZPL {
+    /// Returns a new ZPL with default values.
+    pub fn new() -> ZPL {
+        ZPL{height: 100, xdim: 2, origin: (50, 50), human_readable: false}
+    }
+
+    /// Returns this generator with the given options applied, with lengths in printer dots.
+    pub fn with_options(self, options: &Options) -> ZPL {
+        ZPL{height: options.height_pixels(), xdim: options.xdim_pixels(), ..self}
+    }
+
+    // Wraps a field in a label format, at the field origin.
+    fn label(&self, field: &str) -> String {
+        format!("^XA\n^FO{},{}\n{}\n^XZ", self.origin.0, self.origin.1, field)
+    }
+
+    // Wraps a native barcode command and its field data in a label format. The wide bars of
+    // Code39 and Interleaved 2 of 5 are twice the width of the narrow ones, as encoded.
+    fn native(&self, command: &str, data: &str) -> String {
+        self.label(&format!("^BY{},2\n{}^FD{}^FS", self.xdim, command, data))
+    }
+
+    // Builds a graphic field from one bit per pixel, with 1 for black.
+    fn graphic_field(&self, pixels: Pixels) -> String {
+        let hex: String = pixels.data.iter().map(|b| format!("{:02X}", b)).collect();
+
+        self.label(&format!("^GFA,{len},{len},{},{}^FS", pixels.stride(), hex, len = pixels.data.len()))
+    }
+
+    // Returns a raw pixel generator with the same dimensions and no quiet zone, which the
+    // label layout provides.
+    fn raw(&self) -> Raw {
+        Raw{height: self.height, xdim: self.xdim, quiet_zone: 0, ..Raw::mono()}
+    }
+
+    /// Generates the given EncodedBarcode as a graphic field. Returns a String.
+    pub fn generate(&self, barcode: &EncodedBarcode) -> Result<String, &str> {
+        self.raw().generate(barcode).map(|p| self.graphic_field(p))
+    }
+
+    /// Generates the given EncodedBars (height-modulated bars) as a graphic field. Returns a
+    /// String.
+    pub fn generate_bars(&self, bars: &EncodedBars) -> Result<String, &str> {
+        self.raw().generate_bars(bars).map(|p| self.graphic_field(p))
+    }
+
+    /// Generates the given EncodedRows (a multi-row barcode) as a graphic field. Each row is
+    /// ```row.height``` modules high, so `self.height` is not used. Returns a String.
+    pub fn generate_rows(&self, rows: &EncodedRows) -> Result<String, &str> {
+        self.raw().generate_rows(rows).map(|p| self.graphic_field(p))
+    }
+
+    /// Generates the given EncodedMatrix (a 2D barcode) as a graphic field. Each module is
+    /// square, so `self.height` is not used. Returns a String.
+    pub fn generate_matrix(&self, matrix: &EncodedMatrix) -> Result<String, &str> {
+        self.raw().generate_matrix(matrix).map(|p| self.graphic_field(p))
+    }
+
+    /// Generates the given Code39 barcode as a native `^B3` command. Returns a String.
+    pub fn generate_code39(&self, barcode: &Code39) -> String {
+        let data: String = barcode.raw_data().iter().collect();
+        let command = format!("^B3N,{},{},{},N", yes_no(barcode.checksum_required()), self.height,
+                              yes_no(self.human_readable));
+
+        self.native(&command, &data)
+    }
+
+    /// Generates the given Code128 barcode as a native `^BC` command. Returns a String.
+    ///
+    /// Data with character set selectors is sent with the matching subset invocation codes,
+    /// and plain data is left to the printer's automatic mode.
+    pub fn generate_code128(&self, barcode: &Code128) -> String {
+        let data = barcode.raw_data();
+        let manual = data.first().is_some_and(|&c| [CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C].contains(&c));
+        let mut field = String::new();
+
+        for (i, &c) in data.iter().enumerate() {
+            let code = match c {
+                CHARACTER_SET_A => if i == 0 { ">9" } else { ">7" },
+                CHARACTER_SET_B => if i == 0 { ">:" } else { ">6" },
+                CHARACTER_SET_C => if i == 0 { ">;" } else { ">5" },
+                FNC1 => ">8",
+                '>' => "><",
+                _ => {
+                    field.push(c);
+                    continue;
+                },
+            };
+
+            field.push_str(code);
+        }
+
+        let command = format!("^BCN,{},{},N,N,{}", self.height, yes_no(self.human_readable),
+                              if manual { 'N' } else { 'A' });
+
+        self.native(&command, &field)
+    }
+
+    /// Generates the given EAN13 barcode as a native `^BE` command. The printer calculates
+    /// the check digit. Returns a String.
+    pub fn generate_ean13(&self, barcode: &EAN13) -> String {
+        let command = format!("^BEN,{},{},N", self.height, yes_no(self.human_readable));
+
+        self.native(&command, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given EAN8 barcode as a native `^B8` command. The printer calculates
+    /// the check digit. Returns a String.
+    pub fn generate_ean8(&self, barcode: &EAN8) -> String {
+        let command = format!("^B8N,{},{},N", self.height, yes_no(self.human_readable));
+
+        self.native(&command, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given UPCA barcode as a native `^BU` command. The printer calculates
+    /// the check digit. Returns a String.
+    pub fn generate_upca(&self, barcode: &UPCA) -> String {
+        let command = format!("^BUN,{},{},N,Y", self.height, yes_no(self.human_readable));
+
+        self.native(&command, &digits(barcode.raw_data()))
+    }
+
+    /// Generates the given I2of5 barcode as a native `^B2` command. Returns a String.
+    pub fn generate_i2of5(&self, barcode: &I2of5) -> String {
+        let command = format!("^B2N,{},{},N,N", self.height, yes_no(self.human_readable));
+
+        self.native(&command, &digits(barcode.raw_data()))
+    }
+}
+
+impl Generator for ZPL {
+    type Output = String;
+
+    fn generate(&self, barcode: &EncodedBarcode) -> Result<String, GeneratorError> {
+        ZPL::generate(self, barcode).map_err(GeneratorError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code39::*;
+    use ::sym::code128::*;
+    use ::sym::ean13::*;
+    use ::sym::ean8::*;
+    use ::sym::upca::*;
+    use ::sym::two_of_five::*;
+    use ::sym2d::qr::*;
+    use ::generators::zpl::*;
+
+    #[test]
+    fn code_39_as_zpl_graphic_field() {
+        let code39 = Code39::new("A".to_string()).unwrap();
+        let zpl = ZPL{height: 2, xdim: 1, ..ZPL::new()};
+        let generated = zpl.generate(&code39.encode()).unwrap();
+
+        assert_eq!(generated, "^XA\n^FO50,50\n^GFA,10,10,5,96D6A5A5B496D6A5A5B4^FS\n^XZ");
+    }
+
+    #[test]
+    fn qr_as_zpl_graphic_field() {
+        let qr = QRCode::new("01234567".to_string()).unwrap();
+        let zpl = ZPL{xdim: 4, ..ZPL::new()};
+        let generated = zpl.generate_matrix(&qr.encode()).unwrap();
+
+        // 21 modules of 4 dots are 84 dots, or 11 bytes, wide.
+        assert!(generated.starts_with("^XA\n^FO50,50\n^GFA,924,924,11,FFFFFFF"));
+    }
+
+    #[test]
+    fn code_39_as_zpl() {
+        let code39 = Code39::new("TEST8052".to_string()).unwrap();
+        let zpl = ZPL{human_readable: true, ..ZPL::new()};
+
+        assert_eq!(zpl.generate_code39(&code39), "^XA\n^FO50,50\n^BY2,2\n^B3N,N,100,Y,N^FDTEST8052^FS\n^XZ");
+        assert!(zpl.generate_code39(&Code39::with_checksum("TEST".to_string()).unwrap()).contains("^B3N,Y,100,Y,N^FDTEST^FS"));
+    }
+
+    #[test]
+    fn code_128_as_zpl() {
+        let manual = Code128::new("ÀHELLOĆ1234ƒ".to_string()).unwrap();
+        let auto = Code128::auto("a>b".to_string()).unwrap();
+        let zpl = ZPL::new();
+
+        assert!(zpl.generate_code128(&manual).contains("^BCN,100,N,N,N,N^FD>9HELLO>51234>8^FS"));
+        assert!(zpl.generate_code128(&auto).contains("^BCN,100,N,N,N,A^FDa><b^FS"));
+    }
+
+    #[test]
+    fn ean_and_upc_as_zpl() {
+        let zpl = ZPL{origin: (10, 20), height: 50, ..ZPL::new()};
+
+        assert_eq!(zpl.generate_ean13(&EAN13::new("750103131130".to_string()).unwrap()),
+                   "^XA\n^FO10,20\n^BY2,2\n^BEN,50,N,N^FD750103131130^FS\n^XZ");
+        assert!(zpl.generate_ean13(&EAN13::new("7501031311309".to_string()).unwrap()).contains("^FD750103131130^FS"));
+        assert!(zpl.generate_ean8(&EAN8::new("5512345".to_string()).unwrap()).contains("^B8N,50,N,N^FD5512345^FS"));
+        assert!(zpl.generate_ean8(&EAN8::new("55123457".to_string()).unwrap()).contains("^FD5512345^FS"));
+        assert!(zpl.generate_upca(&UPCA::new("12345678901".to_string()).unwrap()).contains("^BUN,50,N,N,Y^FD12345678901^FS"));
+    }
+
+    #[test]
+    fn i2of5_as_zpl() {
+        let i2of5 = I2of5::with_padding("12345".to_string()).unwrap();
+
+        assert!(ZPL::new().generate_i2of5(&i2of5).contains("^B2N,100,N,N,N^FD012345^FS"));
+    }
+}
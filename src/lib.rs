@@ -1,13 +1,27 @@
+#[cfg(feature = "image")]
 extern crate image;
+#[cfg(feature = "webp")]
+extern crate image_webp;
+#[cfg(feature = "font")]
+extern crate ab_glyph;
 
 pub mod sym;
+pub mod sym2d;
 pub mod generators;
+pub mod decode;
 
 #[cfg(test)]
 mod tests {
     use sym::ean13::*;
     use sym::ean8::*;
+    use sym::upca::*;
     use sym::code39::*;
+    use sym::code128::*;
+    use sym::code93::*;
+    use sym::codabar::*;
+    use sym::two_of_five::*;
+    use sym::postnet::*;
+    use sym2d::qr::*;
     use generators::ascii::*;
 
     #[test]
@@ -38,7 +52,7 @@ mod tests {
 
     #[test]
     fn upca_usage() {
-        let upca = UPCA::new("012345123456".to_string());
+        let upca = UPCA::new("01234512345".to_string());
 
         assert!(upca.is_ok());
 
@@ -62,4 +76,81 @@ mod tests {
         assert!(ascii.generate(&encoded).is_ok());
     }
 
+    #[test]
+    fn code128_usage() {
+        let code128 = Code128::new("ƁHello123".to_string());
+
+        assert!(code128.is_ok());
+
+        let code128 = code128.unwrap();
+        let encoded = code128.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate(&encoded).is_ok());
+    }
+
+    #[test]
+    fn code93_usage() {
+        let code93 = Code93::new("TEST93".to_string());
+
+        assert!(code93.is_ok());
+
+        let code93 = code93.unwrap();
+        let encoded = code93.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate(&encoded).is_ok());
+    }
+
+    #[test]
+    fn codabar_usage() {
+        let codabar = Codabar::new("A40156B".to_string());
+
+        assert!(codabar.is_ok());
+
+        let codabar = codabar.unwrap();
+        let encoded = codabar.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate(&encoded).is_ok());
+    }
+
+    #[test]
+    fn i2of5_usage() {
+        let i2of5 = I2of5::new("12345670".to_string());
+
+        assert!(i2of5.is_ok());
+
+        let i2of5 = i2of5.unwrap();
+        let encoded = i2of5.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate(&encoded).is_ok());
+    }
+
+    #[test]
+    fn postnet_usage() {
+        let postnet = Postnet::new("12345".to_string());
+
+        assert!(postnet.is_ok());
+
+        let postnet = postnet.unwrap();
+        let encoded = postnet.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate_bars(&encoded).is_ok());
+    }
+
+    #[test]
+    fn qr_usage() {
+        let qr = QRCode::new("https://github.com/buntine/barcoders".to_string());
+
+        assert!(qr.is_ok());
+
+        let qr = qr.unwrap();
+        let encoded = qr.encode();
+        let ascii = ASCII::new();
+
+        assert!(ascii.generate_matrix(&encoded).is_ok());
+    }
 }
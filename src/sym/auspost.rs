@@ -0,0 +1,259 @@
+//! This module provides types for encoding Australia Post 4-state customer barcodes.
+//!
+//! The barcode encodes a 2-digit Format Control Code (FCC), an 8-digit Delivery Point Identifier
+//! (DPID) and, in the larger formats, customer information. The FCC determines the format:
+//!   * 11, 45, 87 and 92: Standard Customer Barcode (37 bars), without customer information
+//!   * 59: Customer Barcode 2 (52 bars), with up to 16 bars of customer information
+//!   * 62: Customer Barcode 3 (67 bars), with up to 31 bars of customer information
+//!
+//! Digits are encoded as two bars each. Customer information consisting only of digits is
+//! encoded the same way, otherwise each character is encoded as three bars. Four Reed-Solomon
+//! error correction symbols over GF(64) are appended before the stop bars.
+
+use ::sym::Parse;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+
+/// The characters that can be encoded in the customer information, in order of their values.
+pub const AUSPOST_CHARS: [char; 64] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H',
+    'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+    's', 't', 'u', 'v', 'w', 'x', 'y', 'z', ' ', '#',
+];
+
+/// Australia Post barcodes begin and end with an ascender bar followed by a tracker bar.
+pub const AUSPOST_GUARD: [BarState; 2] = [BarState::Ascender, BarState::Tracker];
+
+/// The bar states for each of the bar values 0-3.
+const AUSPOST_BARS: [BarState; 4] = [
+    BarState::Full, BarState::Ascender, BarState::Descender, BarState::Tracker,
+];
+
+/// The value of the filler bar used to pad unused space.
+const AUSPOST_FILLER: u8 = 3;
+
+/// Bar values for the digits 0-9 (N encoding table).
+const AUSPOST_N_TABLE: [[u8; 2]; 10] = [
+    [0,0], [0,1], [0,2], [1,0], [1,1], [1,2], [2,0], [2,1], [2,2], [3,0],
+];
+
+/// Bar values for each of the customer information characters (C encoding table).
+const AUSPOST_C_TABLE: [[u8; 3]; 64] = [
+    [2,2,2], [3,0,0], [3,0,1], [3,0,2], [3,1,0], [3,1,1], [3,1,2], [3,2,0], [3,2,1], [3,2,2],
+    [0,0,0], [0,0,1], [0,0,2], [0,1,0], [0,1,1], [0,1,2], [0,2,0], [0,2,1], [0,2,2], [1,0,0],
+    [1,0,1], [1,0,2], [1,1,0], [1,1,1], [1,1,2], [1,2,0], [1,2,1], [1,2,2], [2,0,0], [2,0,1],
+    [2,0,2], [2,1,0], [2,1,1], [2,1,2], [2,2,0], [2,2,1], [0,2,3], [0,3,0], [0,3,1], [0,3,2],
+    [0,3,3], [1,0,3], [1,1,3], [1,2,3], [1,3,0], [1,3,1], [1,3,2], [1,3,3], [2,0,3], [2,1,3],
+    [2,2,3], [2,3,0], [2,3,1], [2,3,2], [2,3,3], [3,0,3], [3,1,3], [3,2,3], [3,3,0], [3,3,1],
+    [3,3,2], [3,3,3], [0,0,3], [0,1,3],
+];
+
+/// The primitive polynomial of GF(64) used for error correction.
+const AUSPOST_RS_POLY: u32 = 0x43;
+
+/// The number of Reed-Solomon error correction symbols.
+const AUSPOST_RS_SYMBOLS: usize = 4;
+
+/// The Australia Post barcode type.
+pub struct AustraliaPost {
+    data: String,
+}
+
+impl AustraliaPost {
+    /// Creates a new barcode. The data must be a 2-digit FCC followed by an 8-digit DPID and,
+    /// for FCC 59 and 62, optional customer information.
+    /// Returns Result<AustraliaPost, String> indicating parse success.
+    pub fn new(data: String) -> Result<AustraliaPost, String> {
+        let data = AustraliaPost::parse(data)?;
+
+        if let Some(c) = data.chars().take(10).find(|c| !c.is_ascii_digit()) {
+            return Err(format!("Invalid character in FCC or DPID: {}", c));
+        }
+
+        let ausp = AustraliaPost{data};
+
+        let capacity = match ausp.format_control_code() {
+            "11" | "45" | "87" | "92" => 0,
+            "59" => 16,
+            "62" => 31,
+            fcc => return Err(format!("Unknown format control code: {}", fcc)),
+        };
+
+        if ausp.customer_bars().len() > capacity {
+            return Err(format!("Customer information exceeds {} bars", capacity));
+        }
+
+        Ok(ausp)
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the 2-digit Format Control Code.
+    pub fn format_control_code(&self) -> &str {
+        &self.data[..2]
+    }
+
+    /// Returns the 8-digit Delivery Point Identifier.
+    pub fn dpid(&self) -> &str {
+        &self.data[2..10]
+    }
+
+    /// Returns the customer information, which may be empty.
+    pub fn customer_info(&self) -> &str {
+        &self.data[10..]
+    }
+
+    fn n_encoding(&self, data: &str) -> Vec<u8> {
+        data.chars()
+            .flat_map(|c| AUSPOST_N_TABLE[c.to_digit(10).expect("Unknown character") as usize].to_vec())
+            .collect()
+    }
+
+    fn customer_bars(&self) -> Vec<u8> {
+        let info = self.customer_info();
+
+        if info.chars().all(|c| c.is_ascii_digit()) {
+            return self.n_encoding(info);
+        }
+
+        info.chars()
+            .flat_map(|c| {
+                let i = AUSPOST_CHARS.iter().position(|&ch| ch == c).expect("Unknown character");
+                AUSPOST_C_TABLE[i].to_vec()
+            })
+            .collect()
+    }
+
+    // Returns the bar values between the start bars and the error correction symbols, padded
+    // with filler bars to the size of the format.
+    fn data_bars(&self) -> Vec<u8> {
+        let len = match self.format_control_code() {
+            "59" => 36,
+            "62" => 51,
+            _ => 21,
+        };
+
+        let mut bars = self.n_encoding(&self.data[..10]);
+        bars.extend(self.customer_bars());
+        bars.resize(len, AUSPOST_FILLER);
+        bars
+    }
+
+    /// Calculates the Reed-Solomon error correction symbols. Each symbol is the value of three
+    /// consecutive bars.
+    pub fn checksum_symbols(&self) -> Vec<u8> {
+        let symbols: Vec<u16> = self.data_bars()
+            .chunks(3)
+            .map(|b| (b[0] * 16 + b[1] * 4 + b[2]) as u16)
+            .collect();
+
+        helpers::reed_solomon(&symbols, AUSPOST_RS_SYMBOLS, AUSPOST_RS_POLY, 1).iter()
+            .map(|&s| s as u8)
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full, Ascender, Descender and Tracker bars.
+    pub fn encode(&self) -> EncodedBars {
+        let checksum = self.checksum_symbols().iter()
+            .flat_map(|&s| vec![s / 16, (s / 4) % 4, s % 4])
+            .collect::<Vec<u8>>();
+
+        let mut bars = AUSPOST_GUARD.to_vec();
+        bars.extend(self.data_bars().iter().chain(checksum.iter()).map(|&b| AUSPOST_BARS[b as usize]));
+        bars.extend(AUSPOST_GUARD.iter());
+        bars
+    }
+}
+
+impl Parse for AustraliaPost {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        10..=25
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        AUSPOST_CHARS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::auspost::*;
+
+    fn collapse_bars(v: Vec<BarState>) -> String {
+        v.iter().map(|&b| match b {
+            BarState::Full => 'F',
+            BarState::Ascender => 'A',
+            BarState::Descender => 'D',
+            BarState::Tracker => 'T',
+        }).collect()
+    }
+
+    #[test]
+    fn new_auspost() {
+        let auspost1 = AustraliaPost::new("1139987520".to_string());
+        let auspost2 = AustraliaPost::new("593998752012345".to_string());
+        let auspost3 = AustraliaPost::new("6239987520ABCDE12345".to_string());
+
+        assert!(auspost1.is_ok());
+        assert!(auspost2.is_ok());
+        assert!(auspost3.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_auspost() {
+        let auspost1 = AustraliaPost::new("11399875A0".to_string());
+        let auspost2 = AustraliaPost::new("1039987520".to_string());
+        let auspost3 = AustraliaPost::new("5939987520ab-c".to_string());
+
+        assert!(auspost1.is_err());
+        assert!(auspost2.is_err());
+        assert!(auspost3.is_err());
+    }
+
+    #[test]
+    fn invalid_len_auspost() {
+        let auspost1 = AustraliaPost::new("113998752".to_string());
+        let auspost2 = AustraliaPost::new("113998752012".to_string());
+        let auspost3 = AustraliaPost::new("5939987520123456789".to_string());
+        let auspost4 = AustraliaPost::new("5939987520ABCDEF".to_string());
+
+        assert!(auspost1.is_err());
+        assert!(auspost2.is_err());
+        assert!(auspost3.is_err());
+        assert!(auspost4.is_err());
+    }
+
+    #[test]
+    fn auspost_raw_data() {
+        let auspost = AustraliaPost::new("593998752012345".to_string()).unwrap();
+
+        assert_eq!(auspost.raw_data(), "593998752012345");
+        assert_eq!(auspost.format_control_code(), "59");
+        assert_eq!(auspost.dpid(), "39987520");
+        assert_eq!(auspost.customer_info(), "12345");
+    }
+
+    #[test]
+    fn auspost_encode() {
+        let auspost1 = AustraliaPost::new("1139987520".to_string()).unwrap();
+        let auspost2 = AustraliaPost::new("593998752012345".to_string()).unwrap();
+        let auspost3 = AustraliaPost::new("5932211324Ab1 9".to_string()).unwrap();
+        let auspost4 = AustraliaPost::new("6239987520123456789012345".to_string()).unwrap();
+        let auspost5 = AustraliaPost::new("6239987520ABCDE12345".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(auspost1.encode()), "ATFAFAAFTFTFDDDAADFDFFTTFDADATAFTFDAT".to_string());
+        assert_eq!(collapse_bars(auspost2.encode()), "ATADTFAFTFTFDDDAADFDFFFAFDAFAAADTTTTTTADTAFTATFAAAAT".to_string());
+        assert_eq!(collapse_bars(auspost3.encode()), "ATADTFAFFDFDFAFAAFFDAAFFFFTFTFFFFTTDDTTFFAFTDDAAAAAT".to_string());
+        assert_eq!(collapse_bars(auspost4.encode()), "ATDFFDAFTFTFDDDAADFDFFFAFDAFAAADDFDADDTFFFFAFDAFAAADTADTAATAFTDFTAT".to_string());
+        assert_eq!(collapse_bars(auspost5.encode()), "ATDFFDAFTFTFDDDAADFDFFFFFFFAFFDFAFFAATFFTFATFDTAFTAATFTTFFAFDDATTAT".to_string());
+    }
+}
@@ -0,0 +1,233 @@
+//! This module provides types for encoding Channel Code barcodes. Channel Code encodes a small
+//! number in a very compact symbol, and is used to mark electronic components and other small
+//! items.
+//!
+//! A symbol of channel n (3 to 8) has a finder pattern of five narrow bars followed by n spaces
+//! and n bars, whose widths each sum to 2n - 1 modules. The values are numbered in the order of
+//! the width patterns, which are enumerated with wider elements later and narrow bars forbidden
+//! after too narrow a run of elements.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use std::ops::RangeInclusive;
+use std::iter::repeat_n;
+use std::char;
+
+/// Channel Code barcodes begin with five narrow bars.
+pub const CHANNEL_FINDER: [u8; 9] = [1,0,1,0,1,0,1,0,1];
+
+/// The largest value of each channel from 3 to 8.
+pub const CHANNEL_MAX_VALUES: [u32; 6] = [26, 292, 3493, 44072, 576688, 7742862];
+
+/// The smallest number of channels.
+pub const CHANNEL_MIN_CHANNELS: usize = 3;
+
+/// The largest number of channels.
+pub const CHANNEL_MAX_CHANNELS: usize = 8;
+
+// Enumerates the width patterns of a channel in order until the target value is reached. The
+// spaces and bars are stored from index 2, after the last narrow space and bars of the finder.
+struct Patterns {
+    spaces: [u8; 10],
+    bars: [u8; 10],
+    last: usize,
+    remaining: u32,
+}
+
+impl Patterns {
+    // Chooses the width of each space in turn, leaving `max_s` for it and the rest.
+    fn next_space(&mut self, i: usize, max_b: u8, max_s: u8) -> bool {
+        let first = if i < self.last { 1 } else { max_s };
+
+        for s in first..=max_s {
+            self.spaces[i] = s;
+
+            if self.next_bar(i, max_b, max_s + 1 - s) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // Chooses the width of each bar in turn. A bar may only be narrow if the preceding four
+    // elements are more than four modules wide.
+    fn next_bar(&mut self, i: usize, max_b: u8, max_s: u8) -> bool {
+        let run = self.spaces[i] + self.bars[i - 1] + self.spaces[i - 1] + self.bars[i - 2];
+        let first = if run > 4 { 1 } else { 2 };
+
+        if i < self.last {
+            for b in first..=max_b {
+                self.bars[i] = b;
+
+                if self.next_space(i + 1, max_b + 1 - b, max_s) {
+                    return true;
+                }
+            }
+
+            false
+        } else if first <= max_b {
+            self.bars[i] = max_b;
+
+            if self.remaining == 0 {
+                return true;
+            }
+
+            self.remaining -= 1;
+            false
+        } else {
+            false
+        }
+    }
+}
+
+/// The Channel Code barcode type.
+pub struct ChannelCode {
+    data: Vec<u8>,
+    value: u32,
+    channels: usize,
+}
+
+impl ChannelCode {
+    /// Creates a new barcode in the smallest channel that holds the value.
+    /// Returns Result<ChannelCode, String> indicating parse success.
+    pub fn new(data: String) -> Result<ChannelCode, String> {
+        let (data, value) = ChannelCode::parse_value(data)?;
+
+        match CHANNEL_MAX_VALUES.iter().position(|&max| value <= max) {
+            Some(i) => Ok(ChannelCode{data, value, channels: i + CHANNEL_MIN_CHANNELS}),
+            None => Err(format!("Value {} is too large for Channel Code", value)),
+        }
+    }
+
+    /// Creates a new barcode in the given channel (3-8).
+    /// Returns Result<ChannelCode, String> indicating parse success.
+    pub fn with_channels(data: String, channels: usize) -> Result<ChannelCode, String> {
+        if !(CHANNEL_MIN_CHANNELS..=CHANNEL_MAX_CHANNELS).contains(&channels) {
+            return Err(format!("Invalid number of channels {}", channels));
+        }
+
+        let (data, value) = ChannelCode::parse_value(data)?;
+
+        if value > CHANNEL_MAX_VALUES[channels - CHANNEL_MIN_CHANNELS] {
+            return Err(format!("Value {} is too large for channel {}", value, channels));
+        }
+
+        Ok(ChannelCode{data, value, channels})
+    }
+
+    fn parse_value(data: String) -> Result<(Vec<u8>, u32), String> {
+        let digits: Vec<u8> = ChannelCode::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+        let value = digits.iter().fold(0, |acc, &d| acc * 10 + d as u32);
+
+        Ok((digits, value))
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Returns the value encoded in the barcode.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns the channel of the barcode.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Returns the widths of the spaces and bars that follow the finder pattern, in pairs.
+    pub fn widths(&self) -> Vec<(u8, u8)> {
+        let n = self.channels as u8;
+        let mut patterns = Patterns{
+            spaces: [1; 10],
+            bars: [1; 10],
+            last: self.channels + 1,
+            remaining: self.value,
+        };
+
+        patterns.next_space(2, n, n);
+
+        (2..(self.channels + 2)).map(|i| (patterns.spaces[i], patterns.bars[i])).collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        let mut enc = CHANNEL_FINDER.to_vec();
+
+        for (s, b) in self.widths() {
+            enc.extend(repeat_n(0, s as usize));
+            enc.extend(repeat_n(1, b as usize));
+        }
+
+        enc
+    }
+}
+
+impl Parse for ChannelCode {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=7
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::channel::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_channel() {
+        let channel = ChannelCode::new("1234".to_string());
+
+        assert!(channel.is_ok());
+        assert_eq!(channel.unwrap().channels(), 5);
+    }
+
+    #[test]
+    fn invalid_data_channel() {
+        let channel = ChannelCode::new("12A".to_string());
+
+        assert!(channel.is_err());
+    }
+
+    #[test]
+    fn invalid_len_channel() {
+        assert!(ChannelCode::new("".to_string()).is_err());
+        assert!(ChannelCode::new("7742863".to_string()).is_err());
+        assert!(ChannelCode::with_channels("27".to_string(), 3).is_err());
+        assert!(ChannelCode::with_channels("1".to_string(), 9).is_err());
+    }
+
+    #[test]
+    fn channel_widths() {
+        assert_eq!(ChannelCode::new("0".to_string()).unwrap().widths(), vec![(1, 2), (1, 1), (3, 2)]);
+        assert_eq!(ChannelCode::new("1".to_string()).unwrap().widths(), vec![(1, 2), (1, 2), (3, 1)]);
+        assert_eq!(ChannelCode::new("26".to_string()).unwrap().widths(), vec![(3, 3), (1, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn channel_encode() {
+        let channel1 = ChannelCode::new("0".to_string()).unwrap();
+        let channel2 = ChannelCode::with_channels("44072".to_string(), 6).unwrap();
+
+        assert_eq!(collapse_vec(channel1.encode()), "1010101010110100011".to_string());
+        assert_eq!(channel2.encode().len(), 9 + 2 * (2 * 6 - 1));
+    }
+}
@@ -0,0 +1,165 @@
+//! This module provides types for encoding Codabar barcodes. Codabar is a simple, self-checking
+//! symbology still widely used by libraries, blood banks and courier services.
+//!
+//! Codabar data must begin and end with one of the start/stop characters A, B, C or D, which
+//! are chosen by the caller. For example: "A40156B".
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+
+/// Character -> Binary mappings for each of the 16 allowable data characters.
+pub const CODABAR_CHARS: [(char, &[u8]); 16] = [
+    ('0', &[1,0,1,0,1,0,0,1,1]), ('1', &[1,0,1,0,1,1,0,0,1]), ('2', &[1,0,1,0,0,1,0,1,1]),
+    ('3', &[1,1,0,0,1,0,1,0,1]), ('4', &[1,0,1,1,0,1,0,0,1]), ('5', &[1,1,0,1,0,1,0,0,1]),
+    ('6', &[1,0,0,1,0,1,0,1,1]), ('7', &[1,0,0,1,0,1,1,0,1]), ('8', &[1,0,0,1,1,0,1,0,1]),
+    ('9', &[1,1,0,1,0,0,1,0,1]), ('-', &[1,0,1,0,0,1,1,0,1]), ('$', &[1,0,1,1,0,0,1,0,1]),
+    (':', &[1,1,0,1,0,1,1,0,1,1]), ('/', &[1,1,0,1,1,0,1,0,1,1]), ('.', &[1,1,0,1,1,0,1,1,0,1]),
+    ('+', &[1,0,1,1,0,1,1,0,1,1]),
+];
+
+/// Character -> Binary mappings for the four start/stop characters.
+pub const CODABAR_GUARDS: [(char, [u8; 10]); 4] = [
+    ('A', [1,0,1,1,0,0,1,0,0,1]), ('B', [1,0,0,1,0,0,1,0,1,1]),
+    ('C', [1,0,1,0,0,1,0,0,1,1]), ('D', [1,0,1,0,0,1,1,0,0,1]),
+];
+
+/// The Codabar barcode type.
+pub struct Codabar {
+    data: Vec<char>,
+}
+
+impl Codabar {
+    /// Creates a new barcode. The first and last characters of `data` must be start/stop
+    /// characters (A, B, C or D).
+    /// Returns Result<Codabar, String> indicating parse success.
+    pub fn new(data: String) -> Result<Codabar, String> {
+        let d: Vec<char> = Codabar::parse(data)?.chars().collect();
+        let is_guard = |c: &char| CODABAR_GUARDS.iter().any(|g| g.0 == *c);
+
+        if !is_guard(&d[0]) || !is_guard(&d[d.len() - 1]) {
+            return Err("Data must begin and end with a start/stop character (A-D)".to_string());
+        }
+
+        if let Some(c) = d[1..d.len() - 1].iter().find(|c| is_guard(c)) {
+            return Err(format!("Start/stop character inside data: {}", c));
+        }
+
+        Ok(Codabar{data: d})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the start character.
+    pub fn start_char(&self) -> char {
+        self.data[0]
+    }
+
+    /// Returns the stop character.
+    pub fn stop_char(&self) -> char {
+        self.data[self.data.len() - 1]
+    }
+
+    fn char_encoding(&self, c: &char) -> Vec<u8> {
+        match CODABAR_CHARS.iter().find(|ch| ch.0 == *c) {
+            Some(&(_, enc)) => enc.to_vec(),
+            None => match CODABAR_GUARDS.iter().find(|ch| ch.0 == *c) {
+                Some(&(_, enc)) => enc.to_vec(),
+                None => panic!("Unknown char: {}", c),
+            },
+        }
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    /// Characters are separated by a single narrow space.
+    pub fn encode(&self) -> EncodedBarcode {
+        let mut encodings = vec![];
+
+        for (i, c) in self.data.iter().enumerate() {
+            if i > 0 {
+                encodings.push(vec![0]);
+            }
+
+            encodings.push(self.char_encoding(c));
+        }
+
+        helpers::join_vecs(&encodings[..])
+    }
+}
+
+impl Parse for Codabar {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Codabar is variable-length and includes the start/stop characters.
+    fn valid_len() -> RangeInclusive<u32> {
+        3..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        CODABAR_CHARS.iter().map(|c| c.0).chain(CODABAR_GUARDS.iter().map(|c| c.0)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::codabar::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_codabar() {
+        let codabar = Codabar::new("A12345B".to_string());
+
+        assert!(codabar.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_codabar() {
+        let codabar = Codabar::new("A12E45B".to_string());
+
+        assert!(codabar.is_err());
+    }
+
+    #[test]
+    fn invalid_guards_codabar() {
+        let codabar1 = Codabar::new("123456".to_string());
+        let codabar2 = Codabar::new("A12C45B".to_string());
+
+        assert!(codabar1.is_err());
+        assert!(codabar2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_codabar() {
+        let codabar = Codabar::new("AB".to_string());
+
+        assert!(codabar.is_err());
+    }
+
+    #[test]
+    fn codabar_raw_data() {
+        let codabar = Codabar::new("C1-2D".to_string()).unwrap();
+
+        assert_eq!(codabar.raw_data(), &['C', '1', '-', '2', 'D']);
+        assert_eq!(codabar.start_char(), 'C');
+        assert_eq!(codabar.stop_char(), 'D');
+    }
+
+    #[test]
+    fn codabar_encode() {
+        let codabar1 = Codabar::new("A1234B".to_string()).unwrap();
+        let codabar2 = Codabar::new("D$:/.+C".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(codabar1.encode()), "1011001001010101100101010010110110010101010110100101001001011".to_string());
+        assert_eq!(collapse_vec(codabar2.encode()), "101001100101011001010110101101101101101011011011011010101101101101010010011".to_string());
+    }
+}
@@ -0,0 +1,191 @@
+//! This module provides types for encoding Code11 barcodes. Code11 (also known as USD-8) is a
+//! numeric symbology used primarily for labelling telecommunications equipment.
+//!
+//! Each character is encoded as three bars and two spaces, separated from the next character
+//! by a narrow space. A "C" check digit is always appended, followed by a "K" check digit when
+//! the data is longer than 10 characters.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use std::ops::RangeInclusive;
+use std::iter::repeat_n;
+
+/// The 11 allowable characters, in order of their symbol value (0-10).
+pub const CODE11_CHARS: [char; 11] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '-'];
+
+/// Bar and space widths for each of the 11 symbol values. true = wide, false = narrow.
+const CODE11_WIDTHS: [[bool; 5]; 11] = [
+    [false, false, false, false, true], [true, false, false, false, true],
+    [false, true, false, false, true], [true, true, false, false, false],
+    [false, false, true, false, true], [true, false, true, false, false],
+    [false, true, true, false, false], [false, false, false, true, true],
+    [true, false, false, true, false], [true, false, false, false, false],
+    [false, false, true, false, false],
+];
+
+/// Bar and space widths of the start/stop character. true = wide, false = narrow.
+const CODE11_GUARD: [bool; 5] = [false, false, true, true, false];
+
+const NARROW: usize = 1;
+const WIDE: usize = 2;
+
+/// The length of data above which the "K" check digit is appended.
+const CODE11_K_THRESHOLD: usize = 10;
+
+/// The Code11 barcode type.
+pub struct Code11 {
+    data: Vec<char>,
+}
+
+impl Code11 {
+    /// Creates a new barcode.
+    /// Returns Result<Code11, String> indicating parse success.
+    pub fn new(data: String) -> Result<Code11, String> {
+        Code11::parse(data).map(|d| Code11{data: d.chars().collect()})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    fn char_value(&self, c: &char) -> u8 {
+        match CODE11_CHARS.iter().position(|ch| ch == c) {
+            Some(i) => i as u8,
+            None => panic!("Unknown char: {}", c),
+        }
+    }
+
+    fn values(&self) -> Vec<u8> {
+        self.data.iter().map(|c| self.char_value(c)).collect()
+    }
+
+    // Weights run from 1 at the rightmost character up to `max_weight`, then repeat.
+    fn checksum_value(values: &[u8], max_weight: usize) -> u8 {
+        let sum: usize = values.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &v)| (v as usize) * ((i % max_weight) + 1))
+            .sum();
+
+        (sum % 11) as u8
+    }
+
+    /// Calculates the "C" check digit value using a modulo-11 algorithm with weights from 1
+    /// to 10.
+    pub fn c_checksum_value(&self) -> u8 {
+        Code11::checksum_value(&self.values(), 10)
+    }
+
+    /// Calculates the "K" check digit value using a modulo-11 algorithm with weights from 1
+    /// to 9. The "C" check digit is included in the calculation. Returns None if the data is
+    /// too short to require a "K" check digit.
+    pub fn k_checksum_value(&self) -> Option<u8> {
+        if self.data.len() <= CODE11_K_THRESHOLD {
+            return None;
+        }
+
+        let mut values = self.values();
+        values.push(self.c_checksum_value());
+
+        Some(Code11::checksum_value(&values, 9))
+    }
+
+    fn char_encoding(&self, widths: &[bool; 5]) -> Vec<u8> {
+        widths.iter()
+            .enumerate()
+            .flat_map(|(i, &wide)| repeat_n(if i % 2 == 0 { 1 } else { 0 }, if wide { WIDE } else { NARROW }))
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        let mut values = self.values();
+        values.push(self.c_checksum_value());
+        values.extend(self.k_checksum_value());
+
+        let mut enc = self.char_encoding(&CODE11_GUARD);
+
+        for &v in &values {
+            enc.push(0);
+            enc.extend(self.char_encoding(&CODE11_WIDTHS[v as usize]));
+        }
+
+        enc.push(0);
+        enc.extend(self.char_encoding(&CODE11_GUARD));
+        enc
+    }
+}
+
+impl Parse for Code11 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Code11 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        CODE11_CHARS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code11::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_code11() {
+        let code11 = Code11::new("123-45".to_string());
+
+        assert!(code11.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_code11() {
+        let code11 = Code11::new("123A45".to_string());
+
+        assert!(code11.is_err());
+    }
+
+    #[test]
+    fn invalid_len_code11() {
+        let code11 = Code11::new("".to_string());
+
+        assert!(code11.is_err());
+    }
+
+    #[test]
+    fn code11_raw_data() {
+        let code11 = Code11::new("123-45".to_string()).unwrap();
+
+        assert_eq!(code11.raw_data(), &['1', '2', '3', '-', '4', '5']);
+    }
+
+    #[test]
+    fn code11_checksum_calculation() {
+        let code111 = Code11::new("123-45".to_string()).unwrap();
+        let code112 = Code11::new("0123456789-".to_string()).unwrap();
+
+        assert_eq!(code111.c_checksum_value(), 5);
+        assert_eq!(code111.k_checksum_value(), None);
+        assert_eq!(code112.c_checksum_value(), 0);
+        assert_eq!(code112.k_checksum_value(), Some(6));
+    }
+
+    #[test]
+    fn code11_encode() {
+        let code111 = Code11::new("123-45".to_string()).unwrap();
+        let code112 = Code11::new("0123456789-".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(code111.encode()), "1011001011010110100101101100101010110101011011011011010110110101011001".to_string());
+        assert_eq!(collapse_vec(code112.encode()), "1011001010101101101011010010110110010101011011011011010100110101010011011010010110101010110101010110100110101011001".to_string());
+    }
+}
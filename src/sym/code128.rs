@@ -0,0 +1,592 @@
+//! This module provides types for encoding Code128 barcodes. Code128 is a high-density
+//! symbology capable of encoding the full 128 character ASCII set and is the standard barcode
+//! used in shipping and logistics.
+//!
+//! Code128 has three character sets. The set to begin with, and any switches between sets, are
+//! given inline in the data using the following special characters:
+//!   * À - Character set A (upper-case letters, digits, punctuation and ASCII control characters).
+//!   * Ɓ - Character set B (upper and lower-case letters, digits and punctuation).
+//!   * Ć - Character set C (pairs of digits, 00-99).
+//!
+//! The FNC1 function character, used by GS1-128 and other application standards, is given as
+//! 'ƒ' and is available in every character set.
+//!
+//! For example, "ÀHELLO" is encoded entirely in set A, and "Ć1234ƁAB" encodes "1234" in set C
+//! before switching to set B for "AB".
+//!
+//! Alternatively, `Code128::auto` accepts plain ASCII data and chooses the character sets itself.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+
+/// Binary encodings for each of the 106 Code128 symbol values (0-105).
+/// The symbol value of a character depends on the active character set.
+pub const CODE128_CHARS: [[u8; 11]; 106] = [
+    [1,1,0,1,1,0,0,1,1,0,0], [1,1,0,0,1,1,0,1,1,0,0], [1,1,0,0,1,1,0,0,1,1,0],
+    [1,0,0,1,0,0,1,1,0,0,0], [1,0,0,1,0,0,0,1,1,0,0], [1,0,0,0,1,0,0,1,1,0,0],
+    [1,0,0,1,1,0,0,1,0,0,0], [1,0,0,1,1,0,0,0,1,0,0], [1,0,0,0,1,1,0,0,1,0,0],
+    [1,1,0,0,1,0,0,1,0,0,0], [1,1,0,0,1,0,0,0,1,0,0], [1,1,0,0,0,1,0,0,1,0,0],
+    [1,0,1,1,0,0,1,1,1,0,0], [1,0,0,1,1,0,1,1,1,0,0], [1,0,0,1,1,0,0,1,1,1,0],
+    [1,0,1,1,1,0,0,1,1,0,0], [1,0,0,1,1,1,0,1,1,0,0], [1,0,0,1,1,1,0,0,1,1,0],
+    [1,1,0,0,1,1,1,0,0,1,0], [1,1,0,0,1,0,1,1,1,0,0], [1,1,0,0,1,0,0,1,1,1,0],
+    [1,1,0,1,1,1,0,0,1,0,0], [1,1,0,0,1,1,1,0,1,0,0], [1,1,1,0,1,1,0,1,1,1,0],
+    [1,1,1,0,1,0,0,1,1,0,0], [1,1,1,0,0,1,0,1,1,0,0], [1,1,1,0,0,1,0,0,1,1,0],
+    [1,1,1,0,1,1,0,0,1,0,0], [1,1,1,0,0,1,1,0,1,0,0], [1,1,1,0,0,1,1,0,0,1,0],
+    [1,1,0,1,1,0,1,1,0,0,0], [1,1,0,1,1,0,0,0,1,1,0], [1,1,0,0,0,1,1,0,1,1,0],
+    [1,0,1,0,0,0,1,1,0,0,0], [1,0,0,0,1,0,1,1,0,0,0], [1,0,0,0,1,0,0,0,1,1,0],
+    [1,0,1,1,0,0,0,1,0,0,0], [1,0,0,0,1,1,0,1,0,0,0], [1,0,0,0,1,1,0,0,0,1,0],
+    [1,1,0,1,0,0,0,1,0,0,0], [1,1,0,0,0,1,0,1,0,0,0], [1,1,0,0,0,1,0,0,0,1,0],
+    [1,0,1,1,0,1,1,1,0,0,0], [1,0,1,1,0,0,0,1,1,1,0], [1,0,0,0,1,1,0,1,1,1,0],
+    [1,0,1,1,1,0,1,1,0,0,0], [1,0,1,1,1,0,0,0,1,1,0], [1,0,0,0,1,1,1,0,1,1,0],
+    [1,1,1,0,1,1,1,0,1,1,0], [1,1,0,1,0,0,0,1,1,1,0], [1,1,0,0,0,1,0,1,1,1,0],
+    [1,1,0,1,1,1,0,1,0,0,0], [1,1,0,1,1,1,0,0,0,1,0], [1,1,0,1,1,1,0,1,1,1,0],
+    [1,1,1,0,1,0,1,1,0,0,0], [1,1,1,0,1,0,0,0,1,1,0], [1,1,1,0,0,0,1,0,1,1,0],
+    [1,1,1,0,1,1,0,1,0,0,0], [1,1,1,0,1,1,0,0,0,1,0], [1,1,1,0,0,0,1,1,0,1,0],
+    [1,1,1,0,1,1,1,1,0,1,0], [1,1,0,0,1,0,0,0,0,1,0], [1,1,1,1,0,0,0,1,0,1,0],
+    [1,0,1,0,0,1,1,0,0,0,0], [1,0,1,0,0,0,0,1,1,0,0], [1,0,0,1,0,1,1,0,0,0,0],
+    [1,0,0,1,0,0,0,0,1,1,0], [1,0,0,0,0,1,0,1,1,0,0], [1,0,0,0,0,1,0,0,1,1,0],
+    [1,0,1,1,0,0,1,0,0,0,0], [1,0,1,1,0,0,0,0,1,0,0], [1,0,0,1,1,0,1,0,0,0,0],
+    [1,0,0,1,1,0,0,0,0,1,0], [1,0,0,0,0,1,1,0,1,0,0], [1,0,0,0,0,1,1,0,0,1,0],
+    [1,1,0,0,0,0,1,0,0,1,0], [1,1,0,0,1,0,1,0,0,0,0], [1,1,1,1,0,1,1,1,0,1,0],
+    [1,1,0,0,0,0,1,0,1,0,0], [1,0,0,0,1,1,1,1,0,1,0], [1,0,1,0,0,1,1,1,1,0,0],
+    [1,0,0,1,0,1,1,1,1,0,0], [1,0,0,1,0,0,1,1,1,1,0], [1,0,1,1,1,1,0,0,1,0,0],
+    [1,0,0,1,1,1,1,0,1,0,0], [1,0,0,1,1,1,1,0,0,1,0], [1,1,1,1,0,1,0,0,1,0,0],
+    [1,1,1,1,0,0,1,0,1,0,0], [1,1,1,1,0,0,1,0,0,1,0], [1,1,0,1,1,0,1,1,1,1,0],
+    [1,1,0,1,1,1,1,0,1,1,0], [1,1,1,1,0,1,1,0,1,1,0], [1,0,1,0,1,1,1,1,0,0,0],
+    [1,0,1,0,0,0,1,1,1,1,0], [1,0,0,0,1,0,1,1,1,1,0], [1,0,1,1,1,1,0,1,0,0,0],
+    [1,0,1,1,1,1,0,0,0,1,0], [1,1,1,1,0,1,0,1,0,0,0], [1,1,1,1,0,1,0,0,0,1,0],
+    [1,0,1,1,1,0,1,1,1,1,0], [1,0,1,1,1,1,0,1,1,1,0], [1,1,1,0,1,0,1,1,1,1,0],
+    [1,1,1,1,0,1,0,1,1,1,0], [1,1,0,1,0,0,0,0,1,0,0], [1,1,0,1,0,0,1,0,0,0,0],
+    [1,1,0,1,0,0,1,1,1,0,0],
+];
+
+/// Code128 barcodes must end with this stop pattern (including the final termination bar).
+pub const CODE128_STOP: [u8; 13] = [1,1,0,0,0,1,1,1,0,1,0,1,1];
+
+/// The special character used to select character set A.
+pub const CHARACTER_SET_A: char = 'À';
+/// The special character used to select character set B.
+pub const CHARACTER_SET_B: char = 'Ɓ';
+/// The special character used to select character set C.
+pub const CHARACTER_SET_C: char = 'Ć';
+/// The special character used to represent the FNC1 function character.
+pub const FNC1: char = 'ƒ';
+
+/// The symbol value of FNC1, which is the same in all three character sets.
+pub const FNC1_VALUE: u8 = 102;
+
+/// The three Code128 character sets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CharacterSet {
+    A,
+    B,
+    C,
+}
+
+/// The character sets in the order they are preferred when choosing a start character
+/// that results in equally short symbols.
+const START_PREFERENCE: [CharacterSet; 3] = [CharacterSet::B, CharacterSet::C, CharacterSet::A];
+
+/// The most characters of data accepted by `auto`, which needs no start character.
+const AUTO_MAX_LEN: usize = 255;
+
+/// The symbol value of the Shift character, which encodes the next character in the other
+/// of sets A and B.
+pub const SHIFT: u8 = 98;
+
+impl CharacterSet {
+    fn index(&self) -> usize {
+        match *self {
+            CharacterSet::A => 0,
+            CharacterSet::B => 1,
+            CharacterSet::C => 2,
+        }
+    }
+
+    fn from_char(c: char) -> Option<CharacterSet> {
+        match c {
+            CHARACTER_SET_A => Some(CharacterSet::A),
+            CHARACTER_SET_B => Some(CharacterSet::B),
+            CHARACTER_SET_C => Some(CharacterSet::C),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol value of the start character for this set.
+    pub fn start_value(&self) -> u8 {
+        match *self {
+            CharacterSet::A => 103,
+            CharacterSet::B => 104,
+            CharacterSet::C => 105,
+        }
+    }
+
+    /// Returns the symbol value used to switch to `to` while this set is active.
+    pub fn switch_value(&self, to: CharacterSet) -> Option<u8> {
+        match (*self, to) {
+            (CharacterSet::A, CharacterSet::B) => Some(100),
+            (CharacterSet::A, CharacterSet::C) => Some(99),
+            (CharacterSet::B, CharacterSet::A) => Some(101),
+            (CharacterSet::B, CharacterSet::C) => Some(99),
+            (CharacterSet::C, CharacterSet::A) => Some(101),
+            (CharacterSet::C, CharacterSet::B) => Some(100),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol value of the given character in this set, if it can be represented.
+    /// Character set C encodes pairs of digits and so only returns a value here for FNC1.
+    pub fn char_value(&self, c: char) -> Option<u8> {
+        let ascii = c as u32;
+
+        match *self {
+            _ if c == FNC1 => Some(FNC1_VALUE),
+            CharacterSet::A if ascii < 32 => Some((ascii + 64) as u8),
+            CharacterSet::A if ascii < 96 => Some((ascii - 32) as u8),
+            CharacterSet::B if (32..128).contains(&ascii) => Some((ascii - 32) as u8),
+            _ => None,
+        }
+    }
+}
+
+/// The Code128 barcode type.
+pub struct Code128 {
+    data: Vec<char>,
+    values: Vec<u8>,
+}
+
+impl Code128 {
+    /// Creates a new barcode.
+    /// The data must begin with one of the character set selectors (À, Ɓ or Ć).
+    /// Returns Result<Code128, String> indicating parse success.
+    pub fn new(data: String) -> Result<Code128, String> {
+        match Code128::parse(data) {
+            Ok(d) => {
+                let data: Vec<char> = d.chars().collect();
+                Code128::symbol_values(&data).map(|values| Code128{data, values})
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // Converts the given data into a sequence of symbol values, beginning with the
+    // start character.
+    fn symbol_values(data: &[char]) -> Result<Vec<u8>, String> {
+        let mut set = match data.first().and_then(|&c| CharacterSet::from_char(c)) {
+            Some(s) => s,
+            None => return Err("Data must begin with a character set (À, Ɓ or Ć)".to_string()),
+        };
+        let mut values = vec![set.start_value()];
+        let mut pending_digit: Option<u32> = None;
+
+        for &c in &data[1..] {
+            if let Some(to) = CharacterSet::from_char(c) {
+                if pending_digit.is_some() {
+                    return Err("Character set C requires pairs of digits".to_string());
+                }
+
+                match set.switch_value(to) {
+                    Some(v) => values.push(v),
+                    None => return Err(format!("Character set {:?} is already active", to)),
+                }
+
+                set = to;
+                continue;
+            }
+
+            match set {
+                CharacterSet::C if c == FNC1 && pending_digit.is_none() => values.push(FNC1_VALUE),
+                CharacterSet::C => {
+                    let digit = match c.to_digit(10) {
+                        Some(d) => d,
+                        None => return Err(format!("Invalid character for set C: {}", c)),
+                    };
+
+                    match pending_digit.take() {
+                        Some(tens) => values.push((tens * 10 + digit) as u8),
+                        None => pending_digit = Some(digit),
+                    }
+                },
+                _ => {
+                    match set.char_value(c) {
+                        Some(v) => values.push(v),
+                        None => return Err(format!("Invalid character for set {:?}: {}", set, c)),
+                    }
+                },
+            }
+        }
+
+        if pending_digit.is_some() {
+            return Err("Character set C requires pairs of digits".to_string());
+        }
+
+        if values.len() < 2 {
+            return Err("Data must contain at least one character".to_string());
+        }
+
+        Ok(values)
+    }
+
+    /// Creates a new barcode from plain ASCII data, automatically choosing the start character,
+    /// and any character set switches and shifts, so that the encoded symbol is as short as
+    /// possible.
+    /// Returns Result<Code128, String> indicating parse success.
+    pub fn auto(data: String) -> Result<Code128, String> {
+        let data: Vec<char> = data.chars().collect();
+
+        // Counted in characters, as FNC1 is not ASCII.
+        if data.is_empty() || data.len() > AUTO_MAX_LEN {
+            return Err(format!("Data does not fit within range of 1-{}", AUTO_MAX_LEN));
+        }
+
+        Code128::optimal_values(&data).map(|values| Code128{data, values})
+    }
+
+    /// Finds the shortest sequence of symbol values for the given data, beginning with the
+    /// start character.
+    //
+    // cost[i][s] holds the fewest symbols needed to encode data[i..] when set `s` is active
+    // and step[i][s] holds the move that achieves it. Switching twice in a row is never
+    // worthwhile, so each position only considers consuming data in the active set or
+    // switching once and then consuming.
+    pub fn optimal_values(data: &[char]) -> Result<Vec<u8>, String> {
+        #[derive(Clone, Copy)]
+        enum Step {
+            Consume,
+            Shift,
+            Switch(CharacterSet),
+        }
+
+        let sets = [CharacterSet::A, CharacterSet::B, CharacterSet::C];
+        let n = data.len();
+        let mut cost = vec![[0usize; 3]; n + 1];
+        let mut step = vec![[Step::Consume; 3]; n + 1];
+
+        for i in (0..n).rev() {
+            let mut consume = [None; 3];
+
+            for s in &sets {
+                consume[s.index()] = match *s {
+                    CharacterSet::C if data[i] == FNC1 => Some((1 + cost[i + 1][s.index()], Step::Consume)),
+                    CharacterSet::C => {
+                        let pair = i + 1 < n && data[i].is_ascii_digit() && data[i + 1].is_ascii_digit();
+                        if pair { Some((1 + cost[i + 2][s.index()], Step::Consume)) } else { None }
+                    },
+                    CharacterSet::A | CharacterSet::B => {
+                        let other = if *s == CharacterSet::A { CharacterSet::B } else { CharacterSet::A };
+
+                        if s.char_value(data[i]).is_some() {
+                            Some((1 + cost[i + 1][s.index()], Step::Consume))
+                        } else if other.char_value(data[i]).is_some() {
+                            Some((2 + cost[i + 1][s.index()], Step::Shift))
+                        } else {
+                            None
+                        }
+                    },
+                };
+            }
+
+            if consume.iter().all(|c| c.is_none()) {
+                return Err(format!("Invalid character: {}", data[i]));
+            }
+
+            for s in &sets {
+                let mut best = consume[s.index()];
+
+                for t in sets.iter().filter(|&t| t != s) {
+                    if let Some((c, _)) = consume[t.index()] {
+                        if best.is_none_or(|(b, _)| c + 1 < b) {
+                            best = Some((c + 1, Step::Switch(*t)));
+                        }
+                    }
+                }
+
+                let (c, st) = best.unwrap();
+                cost[i][s.index()] = c;
+                step[i][s.index()] = st;
+            }
+        }
+
+        let mut set = START_PREFERENCE.iter()
+            .cloned()
+            .min_by_key(|s| cost[0][s.index()])
+            .unwrap();
+        let mut values = vec![set.start_value()];
+        let mut i = 0;
+
+        while i < n {
+            match step[i][set.index()] {
+                Step::Switch(to) => {
+                    values.push(set.switch_value(to).unwrap());
+                    set = to;
+                },
+                Step::Consume if set == CharacterSet::C && data[i] == FNC1 => {
+                    values.push(FNC1_VALUE);
+                    i += 1;
+                },
+                Step::Consume if set == CharacterSet::C => {
+                    let tens = data[i].to_digit(10).unwrap();
+                    let units = data[i + 1].to_digit(10).unwrap();
+                    values.push((tens * 10 + units) as u8);
+                    i += 2;
+                },
+                Step::Consume => {
+                    values.push(set.char_value(data[i]).unwrap());
+                    i += 1;
+                },
+                Step::Shift => {
+                    let other = if set == CharacterSet::A { CharacterSet::B } else { CharacterSet::A };
+                    values.push(SHIFT);
+                    values.push(other.char_value(data[i]).unwrap());
+                    i += 1;
+                },
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the symbol values, beginning with the start character and without the check
+    /// character.
+    pub fn values(&self) -> &[u8] {
+        &self.values[..]
+    }
+
+    /// Returns the character set that is active at the end of the data.
+    pub fn final_set(&self) -> CharacterSet {
+        let mut set = match self.values[0] {
+            103 => CharacterSet::A,
+            104 => CharacterSet::B,
+            _ => CharacterSet::C,
+        };
+        let mut values = self.values[1..].iter();
+
+        while let Some(&v) = values.next() {
+            let to = [CharacterSet::A, CharacterSet::B, CharacterSet::C].iter()
+                .cloned()
+                .find(|&to| set.switch_value(to) == Some(v));
+
+            match to {
+                Some(to) => set = to,
+                // The shifted character is in the other set, and may look like a switch.
+                None if v == SHIFT && set != CharacterSet::C => { values.next(); },
+                None => {},
+            }
+        }
+
+        set
+    }
+
+    /// Appends the linkage character that signals a GS1 Composite component: a switch from
+    /// the final character set, A to B, B to C or C to A, before the check character.
+    pub fn with_linkage(mut self) -> Code128 {
+        let set = self.final_set();
+        let to = match set {
+            CharacterSet::A => CharacterSet::B,
+            CharacterSet::B => CharacterSet::C,
+            CharacterSet::C => CharacterSet::A,
+        };
+
+        self.values.push(set.switch_value(to).unwrap());
+        self
+    }
+
+    /// Calculates the check character value using a weighted modulo-103 algorithm.
+    pub fn checksum_value(&self) -> u8 {
+        let sum = self.values.iter()
+            .enumerate()
+            .fold(0, |acc, (i, &v)| acc + (v as usize) * i.max(1));
+
+        (sum % 103) as u8
+    }
+
+    fn char_encoding(&self, v: u8) -> [u8; 11] {
+        CODE128_CHARS[v as usize]
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        self.values.iter()
+            .chain([self.checksum_value()].iter())
+            .flat_map(|&v| self.char_encoding(v).to_vec())
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[self.payload(), CODE128_STOP.to_vec()][..])
+    }
+}
+
+impl Parse for Code128 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Code128 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        2..=256
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    /// This includes all 128 ASCII characters, the three character set selectors and FNC1.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = (0..128u8).map(|b| b as char).collect();
+        chars.extend([CHARACTER_SET_A, CHARACTER_SET_B, CHARACTER_SET_C, FNC1].iter().cloned());
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code128::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_code128() {
+        let code128_a = Code128::new("ÀHELLO".to_string());
+        let code128_b = Code128::new("ƁHello".to_string());
+        let code128_c = Code128::new("Ć123456".to_string());
+
+        assert!(code128_a.is_ok());
+        assert!(code128_b.is_ok());
+        assert!(code128_c.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_code128() {
+        let code128_a = Code128::new("ÀHello".to_string());
+        let code128_c = Code128::new("Ć12AB".to_string());
+
+        assert!(code128_a.is_err());
+        assert!(code128_c.is_err());
+    }
+
+    #[test]
+    fn missing_character_set_code128() {
+        let code128 = Code128::new("HELLO".to_string());
+
+        assert!(code128.is_err());
+    }
+
+    #[test]
+    fn odd_digits_in_set_c_code128() {
+        let code128 = Code128::new("Ć12345".to_string());
+
+        assert!(code128.is_err());
+    }
+
+    #[test]
+    fn invalid_len_code128() {
+        let code128 = Code128::new("".to_string());
+
+        assert!(code128.is_err());
+    }
+
+    #[test]
+    fn code128_raw_data() {
+        let code128 = Code128::new("ƁAb1".to_string()).unwrap();
+
+        assert_eq!(code128.raw_data(), &['Ɓ', 'A', 'b', '1']);
+    }
+
+    #[test]
+    fn code128_checksum_calculation() {
+        let code1281 = Code128::new("ƁPJJ123C".to_string()).unwrap(); // Check value: 55
+        let code1282 = Code128::new("Ć1234".to_string()).unwrap(); // Check value: 82
+
+        assert_eq!(code1281.checksum_value(), 55);
+        assert_eq!(code1282.checksum_value(), 82);
+    }
+
+    #[test]
+    fn code128_final_set() {
+        assert_eq!(Code128::new("ƁPJJ123C".to_string()).unwrap().final_set(), CharacterSet::B);
+        assert_eq!(Code128::new("ÀAĆ12".to_string()).unwrap().final_set(), CharacterSet::C);
+        assert_eq!(Code128::auto("aBc".to_string()).unwrap().final_set(), CharacterSet::B);
+
+        // The linkage character switches from the final set: B to C (99).
+        let linked = Code128::new("Ć1234Ɓa".to_string()).unwrap().with_linkage();
+        assert_eq!(linked.final_set(), CharacterSet::C);
+    }
+
+    #[test]
+    fn code128_encode() {
+        let code1281 = Code128::new("ÀHI".to_string()).unwrap();
+        let code1282 = Code128::new("Ć1234".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(code1281.encode()), "110100001001100010100011000100010110010111001100011101011".to_string());
+        assert_eq!(collapse_vec(code1282.encode()), "110100111001011001110010001011000100100111101100011101011".to_string());
+    }
+
+    #[test]
+    fn code128_auto() {
+        let code1281 = Code128::auto("HELLO".to_string()).unwrap();
+        let code1282 = Code128::auto("1234".to_string()).unwrap();
+        let code1283 = Code128::auto("ABC123456".to_string()).unwrap();
+        let code1284 = Code128::auto("A\tB".to_string()).unwrap();
+
+        assert_eq!(code1281.encode(), Code128::new("ƁHELLO".to_string()).unwrap().encode());
+        assert_eq!(code1282.encode(), Code128::new("Ć1234".to_string()).unwrap().encode());
+        assert_eq!(code1283.encode(), Code128::new("ƁABCĆ123456".to_string()).unwrap().encode());
+        assert_eq!(code1284.encode(), Code128::new("ÀA\tB".to_string()).unwrap().encode());
+    }
+
+    #[test]
+    fn code128_auto_with_shift() {
+        let code128 = Code128::auto("a\nb".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(code128.encode()), "1101001000010010110000111101000101000011001010010000110111011001001100011101011".to_string());
+    }
+
+    #[test]
+    fn code128_auto_odd_digits() {
+        let code128 = Code128::auto("12345".to_string()).unwrap();
+
+        assert_eq!(code128.encode().len(), 6 * 11 + 13);
+        assert_eq!(code128.raw_data(), &['1', '2', '3', '4', '5']);
+    }
+
+    #[test]
+    fn code128_auto_len() {
+        let code1281 = Code128::auto("A".to_string()).unwrap();
+        let code1282 = Code128::auto("ƒ".repeat(255)).unwrap();
+
+        assert_eq!(code1281.encode(), Code128::new("ƁA".to_string()).unwrap().encode());
+        assert_eq!(code1282.raw_data().len(), 255);
+        assert!(Code128::auto("".to_string()).is_err());
+        assert!(Code128::auto("a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn invalid_data_code128_auto() {
+        let code128 = Code128::auto("Hello é".to_string());
+
+        assert!(code128.is_err());
+    }
+
+    #[test]
+    fn code128_fnc1() {
+        let code1281 = Code128::new("ĆƒÀƒAƒ".to_string()).unwrap();
+        let code1282 = Code128::auto("ƒ1234".to_string()).unwrap();
+
+        assert_eq!(&code1281.values[..], &[105, 102, 101, 102, 33, 102]);
+        assert_eq!(&code1282.values[..], &[105, 102, 12, 34]);
+    }
+
+    #[test]
+    fn code128_encode_with_switch() {
+        let code128 = Code128::new("Ć12ƁA".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(code128.encode()), "11010011100101100111001011110111010100011000100100011001100011101011".to_string());
+    }
+}
@@ -0,0 +1,200 @@
+//! This module provides types for encoding Code 16K barcodes. Code 16K is a stacked symbology
+//! of 2 to 16 rows of five Code128 characters each, for more data than a single Code128 row
+//! holds in a form that linear scanners can still read row by row.
+//!
+//! The data is plain ASCII (and FNC1, given as 'ƒ'), and the character sets are chosen
+//! automatically as for `Code128::auto`. The rows are separated by 1X bars, and the first
+//! codeword gives the number of rows and the initial character set in place of a start
+//! character.
+
+use ::sym::Parse;
+use ::sym::{EncodedRow, EncodedRows};
+use ::sym::code128::{Code128, CODE128_CHARS, FNC1};
+use std::iter::repeat_n;
+use std::ops::RangeInclusive;
+
+/// The element widths of the eight start and stop patterns, which identify each row.
+const CODE16K_START_STOP: [[u8; 4]; 8] = [
+    [3, 2, 1, 1], [2, 2, 2, 1], [2, 1, 2, 2], [1, 4, 1, 1],
+    [1, 1, 3, 2], [1, 2, 3, 1], [1, 1, 1, 4], [3, 1, 1, 2],
+];
+
+/// The stop pattern of each row. Row `i` starts with start pattern `i % 8`.
+const CODE16K_STOP_PATTERNS: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 4, 5, 6, 7, 0, 1, 2, 3];
+
+/// The pattern of symbol value 106, which Code 16K adds to the Code128 patterns.
+const CODE16K_CHAR_106: [u8; 11] = [1,1,0,1,0,1,1,1,0,0,0];
+
+// The pad symbol value, and the modulus of the check characters.
+const CODE16K_PAD: u8 = 103;
+const CODE16K_MODULO: usize = 107;
+
+/// The largest number of rows in a symbol.
+pub const CODE16K_MAX_ROWS: usize = 16;
+
+/// The height of each row in multiples of the X dimension, not counting the separator bars.
+pub const CODE16K_ROW_HEIGHT: u32 = 8;
+
+/// The Code 16K barcode type.
+pub struct Code16K {
+    data: Vec<char>,
+    values: Vec<u8>,
+}
+
+impl Code16K {
+    /// Creates a new barcode.
+    /// Returns Result<Code16K, String> indicating parse success.
+    pub fn new(data: String) -> Result<Code16K, String> {
+        let data: Vec<char> = Code16K::parse(data)?.chars().collect();
+        let code128 = Code128::optimal_values(&data)?;
+
+        // The mode codeword takes the place of the start character, and the check characters
+        // take the last two places.
+        let rows = (code128.len() + 2).div_ceil(5).max(2);
+
+        if rows > CODE16K_MAX_ROWS {
+            return Err("Data too long to encode in a Code 16K symbol".to_string());
+        }
+
+        let mode = code128[0] - 103;
+        let mut values = vec![(7 * (rows - 2)) as u8 + mode];
+        values.extend(&code128[1..]);
+        values.resize(rows * 5 - 2, CODE16K_PAD);
+
+        Ok(Code16K{data, values})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        (self.values.len() + 2) / 5
+    }
+
+    /// Calculates the two modulo-107 check characters. The first weights each value by its
+    /// position plus two, and the second by its position plus one, including the first check
+    /// character.
+    pub fn checksum_values(&self) -> (u8, u8) {
+        let weighted = |offset: usize| self.values.iter()
+            .enumerate()
+            .fold(0, |acc, (i, &v)| acc + (i + offset) * v as usize);
+        let first = weighted(2) % CODE16K_MODULO;
+        let second = (weighted(1) + first * (self.values.len() + 1)) % CODE16K_MODULO;
+
+        (first as u8, second as u8)
+    }
+
+    // Converts element widths, beginning with a bar if `bar_first` is set, into binary digits.
+    fn pattern_modules(widths: &[u8], bar_first: bool) -> Vec<u8> {
+        widths.iter()
+            .enumerate()
+            .flat_map(|(i, &w)| repeat_n(((i % 2 == 1) != bar_first) as u8, w as usize))
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedRows (wrapper type of Vec<EncodedRow>), with a separator bar above, below
+    /// and between the rows. The characters follow a 1X bar after the start pattern, so each
+    /// begins with a space.
+    pub fn encode(&self) -> EncodedRows {
+        let (first, second) = self.checksum_values();
+        let values: Vec<u8> = self.values.iter().cloned().chain(vec![first, second]).collect();
+        let mut rows = vec![];
+
+        for (i, row) in values.chunks(5).enumerate() {
+            let mut modules = Code16K::pattern_modules(&CODE16K_START_STOP[i % 8], true);
+            modules.push(1);
+
+            for &v in row {
+                let pattern = if v == 106 { CODE16K_CHAR_106 } else { CODE128_CHARS[v as usize] };
+                modules.extend(pattern.iter().map(|m| 1 - m));
+            }
+
+            modules.extend(Code16K::pattern_modules(&CODE16K_START_STOP[CODE16K_STOP_PATTERNS[i]], false));
+
+            rows.push(EncodedRow{modules: vec![1; 70], height: 1});
+            rows.push(EncodedRow{modules, height: CODE16K_ROW_HEIGHT});
+        }
+
+        rows.push(EncodedRow{modules: vec![1; 70], height: 1});
+        rows
+    }
+}
+
+impl Parse for Code16K {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=154
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    /// This includes all 128 ASCII characters and FNC1.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = (0..128u8).map(|b| b as char).collect();
+        chars.push(FNC1);
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code16k::*;
+    use std::char;
+
+    fn collapse_vec(v: &[u8]) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_code16k() {
+        let code16k = Code16K::new("Code 16K".to_string());
+
+        assert!(code16k.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_code16k() {
+        let code16k = Code16K::new("Code 16K é".to_string());
+
+        assert!(code16k.is_err());
+    }
+
+    #[test]
+    fn invalid_len_code16k() {
+        assert!(Code16K::new("".to_string()).is_err());
+        assert!(Code16K::new("a".repeat(78)).is_err());
+        assert!(Code16K::new("a".repeat(77)).is_ok());
+    }
+
+    #[test]
+    fn code16k_rows() {
+        assert_eq!(Code16K::new("A".to_string()).unwrap().rows(), 2);
+        assert_eq!(Code16K::new("ABCDEFGHIJ".to_string()).unwrap().rows(), 3);
+        assert_eq!(Code16K::new("1".repeat(154)).unwrap().rows(), 16);
+    }
+
+    #[test]
+    fn code16k_checksum_calculation() {
+        let code16k = Code16K::new("AB".to_string()).unwrap(); // Values: 1, 33, 34, 103 x 5
+
+        assert_eq!(code16k.checksum_values(), (97, 66));
+    }
+
+    #[test]
+    fn code16k_encode() {
+        let rows = Code16K::new("AB".to_string()).unwrap().encode();
+
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|r| r.modules.len() == 70));
+        assert_eq!(collapse_vec(&rows[0].modules), "1".repeat(70));
+        // Start pattern 0, the 1X bar, then the mode codeword (1) inverted.
+        assert_eq!(collapse_vec(&rows[1].modules[..19]), "1110010100110010011");
+        // Stop pattern 1 of the second row ends with a bar.
+        assert_eq!(collapse_vec(&rows[3].modules[63..]), "0011001");
+        assert_eq!(rows[1].height, CODE16K_ROW_HEIGHT);
+    }
+}
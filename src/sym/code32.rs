@@ -0,0 +1,190 @@
+//! This module provides types for encoding Code32 barcodes. Code32 (also known as the Italian
+//! Pharmacode) identifies pharmaceutical products sold in Italy.
+//!
+//! The 8-digit product number and its check digit are converted into a 6-character base-32
+//! number, which is then encoded as a Code39 barcode. The human readable text is printed with a
+//! leading 'A'.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::code39::Code39;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The 32 characters used to represent base-32 digits. The vowels A, E, I and O are omitted.
+pub const CODE32_CHARS: [char; 32] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'B', 'C', 'D', 'F', 'G', 'H', 'J', 'K',
+    'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+/// The number of base-32 characters encoded.
+const CODE32_LEN: usize = 6;
+
+/// The Code32 barcode type.
+pub struct Code32 {
+    data: Vec<u8>,
+    code39: Code39,
+}
+
+impl Code32 {
+    /// Creates a new barcode from an 8-digit product number, or 9 digits whose final check
+    /// digit will be verified.
+    /// Returns Result<Code32, String> indicating parse success.
+    pub fn new(data: String) -> Result<Code32, String> {
+        let mut digits: Vec<u8> = Code32::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 9 {
+            let check = digits.pop().unwrap();
+
+            if Code32::checksum(&digits) != check {
+                return Err(format!("Invalid check digit: {}", check));
+            }
+        }
+
+        let code39 = Code39::new(Code32::base32(&digits))?;
+
+        Ok(Code32{data: digits, code39})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    // Digits in even positions (counting from 1) are doubled and the digits of the products
+    // summed, as in the Luhn algorithm.
+    fn checksum(digits: &[u8]) -> u8 {
+        let sum: u32 = digits.iter()
+            .enumerate()
+            .map(|(i, &d)| {
+                let d = d as u32;
+                if i % 2 == 1 { (d * 2) / 10 + (d * 2) % 10 } else { d }
+            })
+            .sum();
+
+        (sum % 10) as u8
+    }
+
+    // Converts the digits and their check digit into a 6-character base-32 string.
+    fn base32(digits: &[u8]) -> String {
+        let mut n = digits.iter()
+            .chain(Some(Code32::checksum(digits)).iter())
+            .fold(0u32, |acc, &d| acc * 10 + d as u32);
+        let mut chars = vec!['0'; CODE32_LEN];
+
+        for c in chars.iter_mut().rev() {
+            *c = CODE32_CHARS[(n % 32) as usize];
+            n /= 32;
+        }
+
+        chars.into_iter().collect()
+    }
+
+    /// Calculates the check digit using a modulo-10 algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        Code32::checksum(&self.data)
+    }
+
+    /// Returns the base-32 characters encoded in the Code39 barcode.
+    pub fn code39_data(&self) -> String {
+        self.code39.raw_data().iter().collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code39.encode()
+    }
+}
+
+impl Parse for Code32 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        8..=9
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code32::*;
+    use ::sym::code39::Code39;
+
+    #[test]
+    fn new_code32() {
+        let code321 = Code32::new("01234567".to_string());
+        let code322 = Code32::new("012345676".to_string());
+
+        assert!(code321.is_ok());
+        assert!(code322.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_code32() {
+        let code32 = Code32::new("0123456A".to_string());
+
+        assert!(code32.is_err());
+    }
+
+    #[test]
+    fn invalid_len_code32() {
+        let code321 = Code32::new("1234567".to_string());
+        let code322 = Code32::new("0123456760".to_string());
+
+        assert!(code321.is_err());
+        assert!(code322.is_err());
+    }
+
+    #[test]
+    fn invalid_checksum_code32() {
+        let code32 = Code32::new("012345677".to_string());
+
+        assert!(code32.is_err());
+    }
+
+    #[test]
+    fn code32_raw_data() {
+        let code32 = Code32::new("012345676".to_string()).unwrap();
+
+        assert_eq!(code32.raw_data(), &[0,1,2,3,4,5,6,7]);
+    }
+
+    #[test]
+    fn code32_checksum_calculation() {
+        let code321 = Code32::new("01234567".to_string()).unwrap();
+        let code322 = Code32::new("12345678".to_string()).unwrap();
+        let code323 = Code32::new("99999999".to_string()).unwrap();
+
+        assert_eq!(code321.checksum_digit(), 6);
+        assert_eq!(code322.checksum_digit(), 8);
+        assert_eq!(code323.checksum_digit(), 2);
+    }
+
+    #[test]
+    fn code32_base32_conversion() {
+        let code321 = Code32::new("01234567".to_string()).unwrap();
+        let code322 = Code32::new("12345678".to_string()).unwrap();
+        let code323 = Code32::new("00000000".to_string()).unwrap();
+        let code324 = Code32::new("99999999".to_string()).unwrap();
+
+        assert_eq!(code321.code39_data(), "0CSSBD".to_string());
+        assert_eq!(code322.code39_data(), "3PRM8N".to_string());
+        assert_eq!(code323.code39_data(), "000000".to_string());
+        assert_eq!(code324.code39_data(), "XTPLHS".to_string());
+    }
+
+    #[test]
+    fn code32_encode() {
+        let code32 = Code32::new("01234567".to_string()).unwrap();
+        let code39 = Code39::new("0CSSBD".to_string()).unwrap();
+
+        assert_eq!(code32.encode(), code39.encode());
+    }
+}
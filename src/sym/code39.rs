@@ -5,7 +5,7 @@
 use ::sym::Parse;
 use ::sym::EncodedBarcode;
 use ::sym::helpers;
-use std::ops::Range;
+use std::ops::RangeInclusive;
 
 /// Character -> Binary mappings for each of the 43 allowable character.
 pub const CODE39_CHARS: [(char, [u8; 12]); 43] = [
@@ -38,7 +38,7 @@ pub struct Code39 {
 impl Code39 {
    fn init(data: String, checksum_required: bool) -> Result<Code39, String> {
         match Code39::parse(data) {
-            Ok(d) => Ok(Code39{data: d.chars().collect(), checksum_required: checksum_required}),
+            Ok(d) => Ok(Code39{data: d.chars().collect(), checksum_required}),
             Err(e) => Err(e),
         }
     }
@@ -60,16 +60,18 @@ impl Code39 {
         &self.data[..]
     }
 
+    /// Returns true if the barcode has an appended check character.
+    pub fn checksum_required(&self) -> bool {
+        self.checksum_required
+    }
+
     /// Calculates the checksum character using a modulo-43 algorithm.
     pub fn checksum_char(&self) -> Option<char> {
         let get_char_pos = |&c| CODE39_CHARS.iter().position(|t| t.0 == c).unwrap();
         let indices = self.data.iter().map(&get_char_pos);
-        let index = indices.fold(0, |acc, i| acc + i) % CODE39_CHARS.len();
+        let index = indices.sum::<usize>() % CODE39_CHARS.len();
 
-        match CODE39_CHARS.get(index) {
-            Some(&(c, _)) => Some(c),
-            None => None,
-        }
+        CODE39_CHARS.get(index).map(|&(c, _)| c)
     }
 
     fn checksum_encoding(&self) -> [u8; 12] {
@@ -82,7 +84,7 @@ impl Code39 {
     fn char_encoding(&self, c: &char) -> [u8; 12] {
          match CODE39_CHARS.iter().find(|&ch| ch.0 == *c) {
             Some(&(_, enc)) => enc,
-            None => panic!(format!("Unknown char: {}", c)),
+            None => panic!("Unknown char: {}", c),
         }
     }
 
@@ -97,7 +99,7 @@ impl Code39 {
         let mut enc = vec![0];
 
         for c in &self.data {
-            self.push_encoding(&mut enc, self.char_encoding(&c));
+            self.push_encoding(&mut enc, self.char_encoding(c));
         }
 
         if self.checksum_required {
@@ -118,8 +120,8 @@ impl Code39 {
 impl Parse for Code39 {
     /// Returns the valid length of data acceptable in this type of barcode.
     /// Code-39 is variable-length.
-    fn valid_len() -> Range<u32> {
-        1..128
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
     }
 
     /// Returns the set of valid characters allowed in this type of barcode.
@@ -0,0 +1,186 @@
+//! This module provides types for encoding Code93 barcodes. Code93 was designed as a
+//! higher-density, more secure replacement for Code39 and is used by Canada Post and in many
+//! of the same non-retail environments as Code39.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+
+/// The 43 allowable characters, in order of their symbol value (0-42).
+pub const CODE93_CHARS: [char; 43] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '-', '.',
+    ' ', '$', '/', '+', '%',
+];
+
+/// Binary encodings for each of the 47 symbol values. Values 43-46 are the four shift
+/// characters, which may appear as check characters.
+pub const CODE93_ENCODINGS: [[u8; 9]; 47] = [
+    [1,0,0,0,1,0,1,0,0], [1,0,1,0,0,1,0,0,0], [1,0,1,0,0,0,1,0,0], [1,0,1,0,0,0,0,1,0],
+    [1,0,0,1,0,1,0,0,0], [1,0,0,1,0,0,1,0,0], [1,0,0,1,0,0,0,1,0], [1,0,1,0,1,0,0,0,0],
+    [1,0,0,0,1,0,0,1,0], [1,0,0,0,0,1,0,1,0], [1,1,0,1,0,1,0,0,0], [1,1,0,1,0,0,1,0,0],
+    [1,1,0,1,0,0,0,1,0], [1,1,0,0,1,0,1,0,0], [1,1,0,0,1,0,0,1,0], [1,1,0,0,0,1,0,1,0],
+    [1,0,1,1,0,1,0,0,0], [1,0,1,1,0,0,1,0,0], [1,0,1,1,0,0,0,1,0], [1,0,0,1,1,0,1,0,0],
+    [1,0,0,0,1,1,0,1,0], [1,0,1,0,1,1,0,0,0], [1,0,1,0,0,1,1,0,0], [1,0,1,0,0,0,1,1,0],
+    [1,0,0,1,0,1,1,0,0], [1,0,0,0,1,0,1,1,0], [1,1,0,1,1,0,1,0,0], [1,1,0,1,1,0,0,1,0],
+    [1,1,0,1,0,1,1,0,0], [1,1,0,1,0,0,1,1,0], [1,1,0,0,1,0,1,1,0], [1,1,0,0,1,1,0,1,0],
+    [1,0,1,1,0,1,1,0,0], [1,0,1,1,0,0,1,1,0], [1,0,0,1,1,0,1,1,0], [1,0,0,1,1,1,0,1,0],
+    [1,0,0,1,0,1,1,1,0], [1,1,1,0,1,0,1,0,0], [1,1,1,0,1,0,0,1,0], [1,1,1,0,0,1,0,1,0],
+    [1,0,1,1,0,1,1,1,0], [1,0,1,1,1,0,1,1,0], [1,1,0,1,0,1,1,1,0], [1,0,0,1,0,0,1,1,0],
+    [1,1,1,0,1,1,0,1,0], [1,1,1,0,1,0,1,1,0], [1,0,0,1,1,0,0,1,0],
+];
+
+/// Code93 barcodes must start and end with the '*' special character.
+pub const CODE93_GUARD: [u8; 9] = [1,0,1,0,1,1,1,1,0];
+
+/// The final termination bar following the stop character.
+pub const CODE93_TERMINATOR: [u8; 1] = [1];
+
+/// The Code93 barcode type.
+pub struct Code93 {
+    data: Vec<char>,
+}
+
+impl Code93 {
+    /// Creates a new barcode.
+    /// Returns Result<Code93, String> indicating parse success.
+    pub fn new(data: String) -> Result<Code93, String> {
+        match Code93::parse(data) {
+            Ok(d) => Ok(Code93{data: d.chars().collect()}),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    fn char_value(&self, c: &char) -> u8 {
+        match CODE93_CHARS.iter().position(|ch| ch == c) {
+            Some(i) => i as u8,
+            None => panic!("Unknown char: {}", c),
+        }
+    }
+
+    fn values(&self) -> Vec<u8> {
+        self.data.iter().map(|c| self.char_value(c)).collect()
+    }
+
+    // Weights run from 1 at the rightmost character up to `max_weight`, then repeat.
+    fn checksum_value(values: &[u8], max_weight: usize) -> u8 {
+        let sum: usize = values.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &v)| (v as usize) * ((i % max_weight) + 1))
+            .sum();
+
+        (sum % 47) as u8
+    }
+
+    /// Calculates the first ("C") check character value using a modulo-47 algorithm with
+    /// weights from 1 to 20.
+    pub fn c_checksum_value(&self) -> u8 {
+        Code93::checksum_value(&self.values(), 20)
+    }
+
+    /// Calculates the second ("K") check character value using a modulo-47 algorithm with
+    /// weights from 1 to 15. The "C" check character is included in the calculation.
+    pub fn k_checksum_value(&self) -> u8 {
+        let mut values = self.values();
+        values.push(self.c_checksum_value());
+
+        Code93::checksum_value(&values, 15)
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut values = self.values();
+        values.push(self.c_checksum_value());
+        values.push(self.k_checksum_value());
+
+        values.iter()
+            .flat_map(|&v| CODE93_ENCODINGS[v as usize].to_vec())
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            CODE93_GUARD.to_vec(), self.payload(), CODE93_GUARD.to_vec(),
+            CODE93_TERMINATOR.to_vec()][..])
+    }
+}
+
+impl Parse for Code93 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Code93 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        CODE93_CHARS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::code93::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_code93() {
+        let code93 = Code93::new("12345".to_string());
+
+        assert!(code93.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_code93() {
+        let code93 = Code93::new("1212s".to_string());
+
+        assert!(code93.is_err());
+    }
+
+    #[test]
+    fn invalid_len_code93() {
+        let code93 = Code93::new("".to_string());
+
+        assert!(code93.is_err());
+    }
+
+    #[test]
+    fn code93_raw_data() {
+        let code93 = Code93::new("12345".to_string()).unwrap();
+
+        assert_eq!(code93.raw_data(), &['1', '2', '3', '4', '5']);
+    }
+
+    #[test]
+    fn code93_checksum_calculation() {
+        let code931 = Code93::new("TEST93".to_string()).unwrap(); // Check chars: '+', '6'
+        let code932 = Code93::new("CODE93".to_string()).unwrap(); // Check chars: 'P', 'V'
+
+        assert_eq!(code931.c_checksum_value(), 41);
+        assert_eq!(code931.k_checksum_value(), 6);
+        assert_eq!(code932.c_checksum_value(), 25);
+        assert_eq!(code932.k_checksum_value(), 31);
+    }
+
+    #[test]
+    fn code93_encode() {
+        let code931 = Code93::new("TEST93".to_string()).unwrap();
+        let code932 = Code93::new("A".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(code931.encode()), "1010111101101001101100100101101011001101001101000010101010000101011101101001000101010111101".to_string());
+        assert_eq!(collapse_vec(code932.encode()), "1010111101101010001101010001100101101010111101".to_string());
+    }
+}
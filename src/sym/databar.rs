@@ -0,0 +1,1031 @@
+//! This module provides types for encoding GS1 DataBar (formerly RSS) barcodes. DataBar encodes
+//! a GTIN-14 in a smaller space than EAN-13 or ITF-14, and is used on fresh produce, coupons and
+//! small items.
+//!
+//! This module defines types for:
+//!   * GS1 DataBar Omnidirectional
+//!   * GS1 DataBar Stacked
+//!   * GS1 DataBar Stacked Omnidirectional
+//!   * GS1 DataBar Expanded
+//!
+//! The 13 digits of the GTIN (excluding its check digit) are split into four data characters,
+//! each encoded as four bars and four spaces whose widths are chosen from combinatorial tables.
+//! Two finder patterns, selected by a modulo-79 checksum, sit between the data characters.
+//!
+//! The stacked forms split the same four characters over two rows, separated by a pattern
+//! derived from the rows themselves: a single separator row for DataBar Stacked, or three for
+//! the taller DataBar Stacked Omnidirectional.
+//!
+//! DataBar Expanded encodes a string of GS1 Application Identifiers, given in the same
+//! parenthesised form as GS1-128, of up to 74 numeric or 41 alphanumeric characters. The data
+//! is compacted into a bit stream which is split into 12-bit data characters; pairs of
+//! characters are separated by finder patterns whose sequence depends on the number of
+//! characters in the symbol.
+//!
+//! Each type can also be created with a linkage flag, which signals that a GS1 Composite
+//! component is printed above it.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::{EncodedRow, EncodedRows};
+use ::sym::helpers;
+use ::sym::code128::FNC1;
+use ::sym::gs1_128::GS1_128;
+use std::ops::{Range, RangeInclusive};
+use std::iter::repeat_n;
+use std::char;
+
+/// The widths of the left and right guard patterns (a space followed by a bar).
+pub const DATABAR_GUARD: [u8; 2] = [1, 1];
+
+/// The widths of the nine finder patterns, beginning with a space.
+pub const DATABAR_FINDERS: [[u8; 5]; 9] = [
+    [3,8,2,1,1], [3,5,5,1,1], [3,3,7,1,1], [3,1,9,1,1], [2,7,4,1,1],
+    [2,5,6,1,1], [2,3,8,1,1], [1,5,7,1,1], [1,3,9,1,1],
+];
+
+/// The parameters of a group of character values.
+struct CharGroup {
+    /// The first value in the group.
+    start: u32,
+    /// The total width of the odd elements.
+    odd_modules: u32,
+    /// The total width of the even elements.
+    even_modules: u32,
+    /// The maximum width of an odd element.
+    odd_widest: u32,
+    /// The maximum width of an even element.
+    even_widest: u32,
+    /// The number of combinations of the elements whose widths are given by the remainder of
+    /// the value: odd elements for inside characters, even elements otherwise.
+    combinations: u32,
+}
+
+macro_rules! group {
+    ($start:expr, $odd:expr, $even:expr, $odd_widest:expr, $even_widest:expr, $combinations:expr) => (
+        CharGroup{
+            start: $start, odd_modules: $odd, even_modules: $even, odd_widest: $odd_widest,
+            even_widest: $even_widest, combinations: $combinations,
+        }
+    )
+}
+
+/// Groups of values for the outside (16-module) data characters.
+const DATABAR_OUTSIDE_GROUPS: [CharGroup; 5] = [
+    group!(0, 12, 4, 8, 1, 1), group!(161, 10, 6, 6, 3, 10), group!(961, 8, 8, 4, 5, 34),
+    group!(2015, 6, 10, 3, 6, 70), group!(2715, 4, 12, 1, 8, 126),
+];
+
+/// Groups of values for the inside (15-module) data characters.
+const DATABAR_INSIDE_GROUPS: [CharGroup; 4] = [
+    group!(0, 5, 10, 2, 7, 4), group!(336, 7, 8, 4, 5, 20), group!(1036, 9, 6, 6, 3, 48),
+    group!(1516, 11, 4, 8, 1, 81),
+];
+
+/// The widths of the six DataBar Expanded finder patterns (A-F), beginning with a space. The
+/// reversed patterns (A2-F2) are the mirror images of these.
+pub const DATABAR_EXPANDED_FINDERS: [[u8; 5]; 6] = [
+    [1,8,4,1,1], [3,6,4,1,1], [3,4,6,1,1], [3,2,8,1,1], [2,6,5,1,1], [2,2,9,1,1],
+];
+
+/// The sequences of DataBar Expanded finder patterns for symbols with 2 to 11 finders. Each
+/// finder is numbered in the order A1, A2, B1, B2, ... F2, starting from 1.
+const DATABAR_EXPANDED_SEQUENCES: [&[u8]; 10] = [
+    &[1, 2], &[1, 4, 3], &[1, 6, 3, 8], &[1, 10, 3, 8, 5], &[1, 10, 3, 8, 7, 12],
+    &[1, 10, 3, 8, 9, 12, 11], &[1, 2, 3, 4, 5, 6, 7, 8], &[1, 2, 3, 4, 5, 6, 7, 10, 9],
+    &[1, 2, 3, 4, 5, 6, 7, 10, 11, 12], &[1, 2, 3, 4, 5, 8, 7, 10, 9, 12, 11],
+];
+
+/// Groups of values for the DataBar Expanded (17-module) characters.
+const DATABAR_EXPANDED_GROUPS: [CharGroup; 5] = [
+    group!(0, 12, 5, 7, 2, 4), group!(348, 10, 7, 5, 4, 20), group!(1388, 8, 9, 4, 5, 52),
+    group!(2948, 6, 11, 3, 6, 104), group!(3988, 4, 13, 1, 8, 204),
+];
+
+/// The punctuation characters of the DataBar Expanded alphanumeric encodation, in order of
+/// their values from 58.
+const DATABAR_ALPHANUMERIC_PUNCTUATION: [char; 5] = ['*', ',', '-', '.', '/'];
+
+/// The punctuation characters of the DataBar Expanded ISO/IEC 646 encodation, in order of
+/// their values from 232.
+const DATABAR_ISO646_PUNCTUATION: [char; 21] = [
+    '!', '"', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', ':', ';', '<', '=', '>',
+    '?', '_', ' ',
+];
+
+/// The minimum and maximum number of bits in a DataBar Expanded bit stream.
+const DATABAR_EXPANDED_MIN_BITS: usize = 36;
+const DATABAR_EXPANDED_MAX_BITS: usize = 252;
+
+/// The value added to the data of a DataBar Omnidirectional barcode by the linkage flag.
+const DATABAR_LINKAGE: u64 = 10_000_000_000_000;
+
+/// The modulus of the DataBar checksum.
+const DATABAR_CHECKSUM_MODULUS: u32 = 79;
+
+/// The modulus of the DataBar Expanded checksum.
+const DATABAR_EXPANDED_CHECKSUM_MODULUS: u32 = 211;
+
+// The number of ways of choosing r items from n.
+fn combinations(n: i32, r: i32) -> i32 {
+    if r < 0 || r > n {
+        return 0;
+    }
+
+    let r = r.min(n - r);
+
+    (0..r).fold(1i64, |acc, i| acc * (n - i) as i64 / (i + 1) as i64) as i32
+}
+
+/// Calculates the widths of `elements` bars (or spaces) with a total width of `n` modules that
+/// represent `value`, with no element wider than `max_width`. Unless `no_narrow` is set, at
+/// least one element is a single module wide.
+fn element_widths(value: u32, n: u32, elements: usize, max_width: u32, no_narrow: bool) -> Vec<u8> {
+    let mut value = value as i32;
+    let mut n = n as i32;
+    let elements = elements as i32;
+    let max_width = max_width as i32;
+    let mut widths = vec![];
+    let mut narrow_mask = 0;
+
+    for bar in 0..(elements - 1) {
+        let mut width = 1;
+        let mut sub_value;
+        narrow_mask |= 1 << bar;
+
+        loop {
+            sub_value = combinations(n - width - 1, elements - bar - 2);
+
+            if !no_narrow && narrow_mask == 0 && n - width - (elements - bar - 1) >= elements - bar - 1 {
+                sub_value -= combinations(n - width - (elements - bar), elements - bar - 2);
+            }
+
+            if elements - bar - 1 > 1 {
+                let less_value: i32 = ((max_width + 1)..=(n - width - (elements - bar - 2)))
+                    .map(|widest| combinations(n - width - widest - 1, elements - bar - 3))
+                    .sum();
+
+                sub_value -= less_value * (elements - 1 - bar);
+            } else if n - width > max_width {
+                sub_value -= 1;
+            }
+
+            value -= sub_value;
+
+            if value < 0 {
+                break;
+            }
+
+            width += 1;
+            narrow_mask &= !(1 << bar);
+        }
+
+        value += sub_value;
+        n -= width;
+        widths.push(width as u8);
+    }
+
+    widths.push(n as u8);
+    widths
+}
+
+/// Calculates the widths of the eight elements of a data character, beginning with the first
+/// odd element.
+fn char_widths(value: u32, outside: bool) -> Vec<u8> {
+    let groups: &[CharGroup] = if outside { &DATABAR_OUTSIDE_GROUPS } else { &DATABAR_INSIDE_GROUPS };
+    let group = groups.iter().rev().find(|g| value >= g.start).unwrap();
+    let value = value - group.start;
+
+    let (odd, even) = if outside {
+        (element_widths(value / group.combinations, group.odd_modules, 4, group.odd_widest, true),
+         element_widths(value % group.combinations, group.even_modules, 4, group.even_widest, false))
+    } else {
+        (element_widths(value % group.combinations, group.odd_modules, 4, group.odd_widest, false),
+         element_widths(value / group.combinations, group.even_modules, 4, group.even_widest, true))
+    };
+
+    odd.iter().zip(even.iter()).flat_map(|(&o, &e)| vec![o, e]).collect()
+}
+
+/// Calculates the widths of the 2 * `elements` elements of a character, beginning with the
+/// first odd element. The remainder of the value gives the widths of the even elements.
+fn grouped_char_widths(value: u32, groups: &[CharGroup], elements: usize, odd_no_narrow: bool) -> Vec<u8> {
+    let group = groups.iter().rev().find(|g| value >= g.start).unwrap();
+    let value = value - group.start;
+
+    let odd = element_widths(value / group.combinations, group.odd_modules, elements, group.odd_widest, odd_no_narrow);
+    let even = element_widths(value % group.combinations, group.even_modules, elements, group.even_widest, !odd_no_narrow);
+
+    odd.iter().zip(even.iter()).flat_map(|(&o, &e)| vec![o, e]).collect()
+}
+
+/// Calculates the widths of the eight elements of a DataBar Expanded character, beginning
+/// with the first odd element.
+fn expanded_char_widths(value: u32) -> Vec<u8> {
+    grouped_char_widths(value, &DATABAR_EXPANDED_GROUPS, 4, false)
+}
+
+// Converts a sequence of element widths, beginning with a space, into binary digits.
+fn widths_to_modules(widths: &[u8]) -> EncodedBarcode {
+    widths_to_row(widths, false)
+}
+
+// Converts a sequence of element widths, beginning with a bar if `bar_first` is set, into
+// binary digits.
+fn widths_to_row(widths: &[u8], bar_first: bool) -> EncodedBarcode {
+    widths.iter()
+        .enumerate()
+        .flat_map(|(i, &w)| repeat_n(((i % 2 == 1) != bar_first) as u8, w as usize))
+        .collect()
+}
+
+/// The number of elements of a DataBar Omnidirectional barcode in the top row of the stacked
+/// forms.
+const DATABAR_STACKED_SPLIT: usize = 23;
+
+/// The modules of the top and bottom rows of the stacked forms around the finder patterns.
+const DATABAR_TOP_FINDER_MODULES: Range<usize> = 17..33;
+const DATABAR_BOTTOM_FINDER_MODULES: Range<usize> = 16..32;
+
+/// The number of modules at each end of a separator that are always spaces.
+const DATABAR_SEPARATOR_MARGIN: usize = 4;
+
+// Splits the elements of a DataBar Omnidirectional barcode into two rows, each with its own
+// guard patterns. The bottom row begins with a bar.
+fn stacked_rows(widths: &[u8]) -> (EncodedBarcode, EncodedBarcode) {
+    let (left, right) = widths.split_at(DATABAR_STACKED_SPLIT);
+    let top: Vec<u8> = left.iter().chain(DATABAR_GUARD.iter()).cloned().collect();
+    let bottom: Vec<u8> = DATABAR_GUARD.iter().chain(right.iter()).cloned().collect();
+
+    (widths_to_row(&top, false), widths_to_row(&bottom, true))
+}
+
+// Returns the range of modules of a separator that may contain bars.
+fn separator_range(len: usize) -> Range<usize> {
+    DATABAR_SEPARATOR_MARGIN..(len - DATABAR_SEPARATOR_MARGIN)
+}
+
+// The DataBar Stacked separator is the complement of the modules the two rows share. Where
+// the rows differ, it alternates between bars and spaces.
+fn stacked_separator(top: &[u8], bottom: &[u8]) -> EncodedBarcode {
+    let mut separator = vec![0; top.len()];
+
+    for i in separator_range(top.len()) {
+        separator[i] = if top[i] == bottom[i] { 1 - top[i] } else { 1 - separator[i - 1] };
+    }
+
+    separator
+}
+
+// A DataBar Stacked Omnidirectional separator next to a row is the complement of the row,
+// except that the spaces of the finder pattern are complemented by alternating bars and spaces.
+fn finder_separator(row: &[u8], finder: Range<usize>) -> EncodedBarcode {
+    let mut separator = vec![0; row.len()];
+    let mut bar = true;
+
+    for i in separator_range(row.len()) {
+        separator[i] = 1 - row[i];
+    }
+
+    for i in finder {
+        separator[i] = (row[i] == 0 && bar) as u8;
+        bar = row[i] == 1 || !bar;
+    }
+
+    separator
+}
+
+// Parses a GTIN of 13 digits, or 14 digits whose final check digit is verified.
+fn gtin_digits(data: String) -> Result<Vec<u8>, String> {
+    let mut digits: Vec<u8> = data.chars()
+        .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+        .collect();
+
+    if digits.len() == 14 {
+        let check = digits.pop().unwrap();
+
+        if helpers::modulo_10_checksum(&digits) != check {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+    }
+
+    Ok(digits)
+}
+
+/// The GS1 DataBar Omnidirectional barcode type.
+pub struct DataBar {
+    data: Vec<u8>,
+    linked: bool,
+}
+
+impl DataBar {
+    /// Creates a new barcode from a 13-digit GTIN (without check digit) or a 14-digit GTIN
+    /// (whose check digit will be verified).
+    /// Returns Result<DataBar, String> indicating parse success.
+    pub fn new(data: String) -> Result<DataBar, String> {
+        gtin_digits(DataBar::parse(data)?).map(|d| DataBar{data: d, linked: false})
+    }
+
+    /// Creates a new barcode, as `new`, with the linkage flag set for a composite component.
+    /// Returns Result<DataBar, String> indicating parse success.
+    pub fn with_linkage(data: String) -> Result<DataBar, String> {
+        DataBar::new(data).map(|databar| DataBar{linked: true, ..databar})
+    }
+
+    /// Returns true if the linkage flag is set.
+    pub fn is_linked(&self) -> bool {
+        self.linked
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the GTIN check digit using a modulo-10 weighting algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        helpers::modulo_10_checksum(&self.data)
+    }
+
+    /// Returns the values of the four data characters: left outside, left inside, right
+    /// outside and right inside.
+    pub fn data_characters(&self) -> [u32; 4] {
+        let linkage = if self.linked { DATABAR_LINKAGE } else { 0 };
+        let value = self.data.iter().fold(0u64, |acc, &d| acc * 10 + d as u64) + linkage;
+        let left = (value / 4_537_077) as u32;
+        let right = (value % 4_537_077) as u32;
+
+        [left / 1597, left % 1597, right / 1597, right % 1597]
+    }
+
+    fn data_widths(&self) -> Vec<Vec<u8>> {
+        self.data_characters().iter()
+            .enumerate()
+            .map(|(i, &v)| char_widths(v, i % 2 == 0))
+            .collect()
+    }
+
+    /// Calculates the modulo-79 checksum, which selects the two finder patterns.
+    pub fn checksum_value(&self) -> u32 {
+        let mut weight = 1;
+        let mut sum = 0;
+
+        for widths in self.data_widths() {
+            for &w in &widths {
+                sum += w as u32 * weight;
+                weight = (weight * 3) % DATABAR_CHECKSUM_MODULUS;
+            }
+        }
+
+        sum % DATABAR_CHECKSUM_MODULUS
+    }
+
+    /// Returns the widths of the 46 elements of the barcode, beginning with a space.
+    pub fn widths(&self) -> Vec<u8> {
+        let data = self.data_widths();
+        let mut checksum = self.checksum_value();
+
+        // Finder combinations (0,8) and (8,0) are not used.
+        if checksum >= 8 {
+            checksum += 1;
+        }
+
+        if checksum >= 72 {
+            checksum += 1;
+        }
+
+        let left_finder = DATABAR_FINDERS[(checksum / 9) as usize];
+        let right_finder = DATABAR_FINDERS[(checksum % 9) as usize];
+
+        let mut widths = DATABAR_GUARD.to_vec();
+        widths.extend(data[0].iter());
+        widths.extend(left_finder.iter());
+        widths.extend(data[1].iter().rev());
+        widths.extend(data[3].iter());
+        widths.extend(right_finder.iter().rev());
+        widths.extend(data[2].iter().rev());
+        widths.extend(DATABAR_GUARD.iter());
+        widths
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        widths_to_modules(&self.widths())
+    }
+}
+
+impl Parse for DataBar {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        13..=14
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+/// The GS1 DataBar Stacked barcode type.
+pub struct DataBarStacked {
+    databar: DataBar,
+}
+
+impl DataBarStacked {
+    /// Creates a new barcode from a 13-digit GTIN (without check digit) or a 14-digit GTIN
+    /// (whose check digit will be verified).
+    /// Returns Result<DataBarStacked, String> indicating parse success.
+    pub fn new(data: String) -> Result<DataBarStacked, String> {
+        DataBar::new(data).map(|databar| DataBarStacked{databar})
+    }
+
+    /// Creates a new barcode, as `new`, with the linkage flag set for a composite component.
+    /// Returns Result<DataBarStacked, String> indicating parse success.
+    pub fn with_linkage(data: String) -> Result<DataBarStacked, String> {
+        DataBar::with_linkage(data).map(|databar| DataBarStacked{databar})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        self.databar.raw_data()
+    }
+
+    /// Calculates the GTIN check digit using a modulo-10 weighting algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        self.databar.checksum_digit()
+    }
+
+    /// Encodes the barcode as a 5X high top row, a 1X separator and a 7X high bottom row.
+    /// Returns EncodedRows (wrapper type of Vec<EncodedRow>).
+    pub fn encode(&self) -> EncodedRows {
+        let (top, bottom) = stacked_rows(&self.databar.widths());
+        let separator = stacked_separator(&top, &bottom);
+
+        vec![
+            EncodedRow{modules: top, height: 5},
+            EncodedRow{modules: separator, height: 1},
+            EncodedRow{modules: bottom, height: 7},
+        ]
+    }
+}
+
+/// The GS1 DataBar Stacked Omnidirectional barcode type.
+pub struct DataBarStackedOmni {
+    databar: DataBar,
+}
+
+impl DataBarStackedOmni {
+    /// Creates a new barcode from a 13-digit GTIN (without check digit) or a 14-digit GTIN
+    /// (whose check digit will be verified).
+    /// Returns Result<DataBarStackedOmni, String> indicating parse success.
+    pub fn new(data: String) -> Result<DataBarStackedOmni, String> {
+        DataBar::new(data).map(|databar| DataBarStackedOmni{databar})
+    }
+
+    /// Creates a new barcode, as `new`, with the linkage flag set for a composite component.
+    /// Returns Result<DataBarStackedOmni, String> indicating parse success.
+    pub fn with_linkage(data: String) -> Result<DataBarStackedOmni, String> {
+        DataBar::with_linkage(data).map(|databar| DataBarStackedOmni{databar})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        self.databar.raw_data()
+    }
+
+    /// Calculates the GTIN check digit using a modulo-10 weighting algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        self.databar.checksum_digit()
+    }
+
+    /// Encodes the barcode as two 33X high rows separated by three 1X separator rows.
+    /// Returns EncodedRows (wrapper type of Vec<EncodedRow>).
+    pub fn encode(&self) -> EncodedRows {
+        let (top, bottom) = stacked_rows(&self.databar.widths());
+        let mut middle = vec![0; top.len()];
+
+        for i in separator_range(top.len()).skip(1).step_by(2) {
+            middle[i] = 1;
+        }
+
+        let top_separator = finder_separator(&top, DATABAR_TOP_FINDER_MODULES);
+        let bottom_separator = finder_separator(&bottom, DATABAR_BOTTOM_FINDER_MODULES);
+
+        vec![
+            EncodedRow{modules: top, height: 33},
+            EncodedRow{modules: top_separator, height: 1},
+            EncodedRow{modules: middle, height: 1},
+            EncodedRow{modules: bottom_separator, height: 1},
+            EncodedRow{modules: bottom, height: 33},
+        ]
+    }
+}
+
+/// The encodation modes of the DataBar Expanded general purpose data field.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Encodation {
+    Numeric,
+    Alphanumeric,
+    Iso646,
+}
+
+// Returns the number of bits needed to complete the final 12-bit data character.
+fn bits_remaining(len: usize) -> usize {
+    if len < DATABAR_EXPANDED_MIN_BITS {
+        DATABAR_EXPANDED_MIN_BITS - len
+    } else {
+        (12 - len % 12) % 12
+    }
+}
+
+fn is_numeric(c: char) -> bool {
+    c.is_ascii_digit() || c == FNC1
+}
+
+fn is_alphanumeric(c: char) -> bool {
+    is_numeric(c) || c.is_ascii_uppercase() || DATABAR_ALPHANUMERIC_PUNCTUATION.contains(&c)
+}
+
+fn numeric_value(c: char) -> u32 {
+    if c == FNC1 { 10 } else { c.to_digit(10).unwrap() }
+}
+
+// Returns the characters of the general purpose data field: each AI and its value, with an
+// FNC1 after every variable-length element except the last.
+fn general_data(elements: &[(String, String)]) -> Vec<char> {
+    let mut general = vec![];
+
+    for (i, (ai, value)) in elements.iter().enumerate() {
+        general.extend(ai.chars().chain(value.chars()));
+
+        if !GS1_128::has_predefined_length(ai) && i < elements.len() - 1 {
+            general.push(FNC1);
+        }
+    }
+
+    general
+}
+
+// Encodes the general purpose data field. Encodation begins in numeric mode and switches to
+// alphanumeric or ISO/IEC 646 mode as the data requires. A single digit left over at the end
+// of numeric data is returned separately, as its encoding depends on the size of the symbol.
+fn general_field(data: &[char]) -> (Vec<u8>, Encodation, Option<u8>) {
+    let mut bits = vec![];
+    let mut mode = Encodation::Numeric;
+    let mut i = 0;
+
+    while i < data.len() {
+        let c = data[i];
+        let rest = &data[i..];
+
+        if mode == Encodation::Numeric {
+            if rest.len() > 1 && is_numeric(c) && is_numeric(rest[1]) {
+                helpers::push_bits(&mut bits, numeric_value(c) * 11 + numeric_value(rest[1]) + 8, 7);
+                i += 2;
+            } else if rest.len() == 1 && c.is_ascii_digit() {
+                return (bits, mode, Some(numeric_value(c) as u8));
+            } else {
+                helpers::push_bits(&mut bits, 0b0000, 4);
+                mode = Encodation::Alphanumeric;
+            }
+
+            continue;
+        }
+
+        if c == FNC1 {
+            // FNC1 also latches back to numeric mode.
+            helpers::push_bits(&mut bits, 0b01111, 5);
+            mode = Encodation::Numeric;
+            i += 1;
+        } else if rest.len() >= 4 && rest.iter().take(6).all(|&c| is_numeric(c)) {
+            helpers::push_bits(&mut bits, 0b000, 3);
+            mode = Encodation::Numeric;
+        } else if mode == Encodation::Alphanumeric && !is_alphanumeric(c) {
+            helpers::push_bits(&mut bits, 0b00100, 5);
+            mode = Encodation::Iso646;
+        } else if mode == Encodation::Iso646 && rest.iter().take(10).all(|&c| is_alphanumeric(c)) {
+            helpers::push_bits(&mut bits, 0b00100, 5);
+            mode = Encodation::Alphanumeric;
+        } else {
+            if c.is_ascii_digit() {
+                helpers::push_bits(&mut bits, numeric_value(c) + 5, 5);
+            } else if c.is_ascii_uppercase() && mode == Encodation::Alphanumeric {
+                helpers::push_bits(&mut bits, c as u32 - 33, 6);
+            } else if mode == Encodation::Alphanumeric {
+                let p = DATABAR_ALPHANUMERIC_PUNCTUATION.iter().position(|&p| p == c).unwrap();
+                helpers::push_bits(&mut bits, 58 + p as u32, 6);
+            } else if c.is_ascii_uppercase() {
+                helpers::push_bits(&mut bits, c as u32 - 1, 7);
+            } else if c.is_ascii_lowercase() {
+                helpers::push_bits(&mut bits, c as u32 - 7, 7);
+            } else {
+                let p = DATABAR_ISO646_PUNCTUATION.iter().position(|&p| p == c).unwrap();
+                helpers::push_bits(&mut bits, 232 + p as u32, 8);
+            }
+
+            i += 1;
+        }
+    }
+
+    (bits, mode, None)
+}
+
+/// The GS1 DataBar Expanded barcode type.
+pub struct DataBarExpanded {
+    data: Vec<char>,
+    elements: Vec<(String, String)>,
+    bits: Vec<u8>,
+}
+
+impl DataBarExpanded {
+    /// Creates a new barcode from a string of parenthesised AIs and their values.
+    /// Returns Result<DataBarExpanded, String> indicating parse success.
+    pub fn new(data: String) -> Result<DataBarExpanded, String> {
+        let d = DataBarExpanded::parse(data)?;
+        let elements = GS1_128::elements_from(&d)?;
+        let bits = DataBarExpanded::bit_stream(&elements)?;
+
+        Ok(DataBarExpanded{data: d.chars().collect(), elements, bits})
+    }
+
+    /// Creates a new barcode, as `new`, with the linkage flag set for a composite component.
+    /// Returns Result<DataBarExpanded, String> indicating parse success.
+    pub fn with_linkage(data: String) -> Result<DataBarExpanded, String> {
+        let mut databar = DataBarExpanded::new(data)?;
+        databar.bits[0] = 1;
+
+        Ok(databar)
+    }
+
+    /// Returns true if the linkage flag is set.
+    pub fn is_linked(&self) -> bool {
+        self.bits[0] == 1
+    }
+
+    // Builds the bit stream: a linkage flag, the encodation method, the variable length symbol
+    // field, a compressed GTIN if the data begins with AI (01), then the general purpose data
+    // field and padding.
+    fn bit_stream(elements: &[(String, String)]) -> Result<Vec<u8>, String> {
+        let mut bits = vec![0];
+        let mut elements = elements;
+
+        match elements.first() {
+            Some((ai, gtin)) if ai == "01" => {
+                let digits: Vec<u32> = gtin.chars().map(|c| c.to_digit(10).unwrap()).collect();
+
+                bits.extend(&[1, 0, 0]);
+                helpers::push_bits(&mut bits, digits[0], 4);
+
+                for chunk in digits[1..13].chunks(3) {
+                    helpers::push_bits(&mut bits, chunk[0] * 100 + chunk[1] * 10 + chunk[2], 10);
+                }
+
+                elements = &elements[1..];
+            },
+            _ => bits.extend(&[0, 0, 0, 0]),
+        }
+
+        // The variable length symbol field follows the encodation method.
+        let vls = if bits[1] == 1 { 2 } else { 3 };
+
+        let general = general_data(elements);
+        let (field, mode, last_digit) = general_field(&general);
+        bits.extend(field);
+
+        // A final digit is encoded in four bits if that fills the symbol, otherwise together
+        // with an FNC1.
+        if let Some(d) = last_digit {
+            match bits_remaining(bits.len()) {
+                4..=6 => helpers::push_bits(&mut bits, d as u32 + 1, 4),
+                _ => helpers::push_bits(&mut bits, d as u32 * 11 + 10 + 8, 7),
+            }
+        }
+
+        let mut padding = vec![];
+
+        if !general.is_empty() && mode == Encodation::Numeric {
+            helpers::push_bits(&mut padding, 0b0000, 4);
+        }
+
+        while padding.len() < DATABAR_EXPANDED_MAX_BITS {
+            helpers::push_bits(&mut padding, 0b00100, 5);
+        }
+
+        let remaining = bits_remaining(bits.len());
+        bits.extend(&padding[..remaining]);
+
+        if bits.len() > DATABAR_EXPANDED_MAX_BITS {
+            return Err("Data too long to encode in a DataBar Expanded barcode".to_string());
+        }
+
+        let symbol_chars = bits.len() / 12 + 1;
+        bits[vls] = (symbol_chars % 2) as u8;
+        bits[vls + 1] = if symbol_chars > 14 { 1 } else { 0 };
+
+        Ok(bits)
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the (AI, value) pairs encoded in the barcode.
+    pub fn elements(&self) -> &[(String, String)] {
+        &self.elements[..]
+    }
+
+    /// Returns the values of the data characters, each holding 12 bits of the bit stream.
+    pub fn data_characters(&self) -> Vec<u32> {
+        self.bits.chunks(12)
+            .map(|chunk| chunk.iter().fold(0, |acc, &b| (acc << 1) | b as u32))
+            .collect()
+    }
+
+    // Returns the finder sequence for the symbol, which has one finder for each pair of
+    // characters.
+    fn finder_sequence(&self) -> &'static [u8] {
+        let symbol_chars = self.data_characters().len() + 1;
+
+        DATABAR_EXPANDED_SEQUENCES[symbol_chars.div_ceil(2) - 2]
+    }
+
+    /// Calculates the modulo-211 checksum. The weights of each data character's elements
+    /// depend on the finder pattern it adjoins and on which side of it it lies.
+    pub fn checksum_value(&self) -> u32 {
+        let sequence = self.finder_sequence();
+        let mut sum = 0;
+
+        // Symbol character 0 is the check character, to the left of finder A1. Positions are
+        // numbered from the right of A1, through the left and right of A2, B1, and so on.
+        for (i, &v) in self.data_characters().iter().enumerate() {
+            let k = i + 1;
+            let position = 2 * sequence[k / 2] as usize + k % 2 - 3;
+            let mut weight = (0..position * 8).fold(1, |w, _| (w * 3) % DATABAR_EXPANDED_CHECKSUM_MODULUS);
+
+            for w in expanded_char_widths(v) {
+                sum += w as u32 * weight;
+                weight = (weight * 3) % DATABAR_EXPANDED_CHECKSUM_MODULUS;
+            }
+        }
+
+        sum % DATABAR_EXPANDED_CHECKSUM_MODULUS
+    }
+
+    /// Calculates the value of the check character, which combines the checksum with the
+    /// number of characters in the symbol.
+    pub fn check_character_value(&self) -> u32 {
+        let symbol_chars = self.data_characters().len() as u32 + 1;
+
+        DATABAR_EXPANDED_CHECKSUM_MODULUS * (symbol_chars - 4) + self.checksum_value()
+    }
+
+    /// Returns the widths of the elements of the barcode, beginning with a space.
+    pub fn widths(&self) -> Vec<u8> {
+        let mut chars = vec![expanded_char_widths(self.check_character_value())];
+        chars.extend(self.data_characters().into_iter().map(expanded_char_widths));
+
+        let mut widths = DATABAR_GUARD.to_vec();
+
+        for (pair, &finder) in chars.chunks(2).zip(self.finder_sequence().iter()) {
+            let pattern = DATABAR_EXPANDED_FINDERS[(finder as usize - 1) / 2];
+
+            widths.extend(pair[0].iter());
+
+            if finder % 2 == 1 {
+                widths.extend(pattern.iter());
+            } else {
+                widths.extend(pattern.iter().rev());
+            }
+
+            if let Some(right) = pair.get(1) {
+                widths.extend(right.iter().rev());
+            }
+        }
+
+        widths.extend(DATABAR_GUARD.iter());
+        widths
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        widths_to_modules(&self.widths())
+    }
+}
+
+impl Parse for DataBarExpanded {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        4..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        GS1_128::valid_chars()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::databar::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_databar() {
+        let databar1 = DataBar::new("2001234567890".to_string());
+        let databar2 = DataBar::new("20012345678909".to_string());
+
+        assert!(databar1.is_ok());
+        assert!(databar2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_databar() {
+        let databar = DataBar::new("200123456789A".to_string());
+
+        assert!(databar.is_err());
+    }
+
+    #[test]
+    fn invalid_len_databar() {
+        let databar1 = DataBar::new("200123456789".to_string());
+        let databar2 = DataBar::new("200123456789090".to_string());
+
+        assert!(databar1.is_err());
+        assert!(databar2.is_err());
+    }
+
+    #[test]
+    fn invalid_checksum_databar() {
+        let databar = DataBar::new("20012345678908".to_string());
+
+        assert!(databar.is_err());
+    }
+
+    #[test]
+    fn databar_raw_data() {
+        let databar = DataBar::new("20012345678909".to_string()).unwrap();
+
+        assert_eq!(databar.raw_data(), &[2,0,0,1,2,3,4,5,6,7,8,9,0]);
+    }
+
+    #[test]
+    fn databar_element_widths() {
+        assert_eq!(element_widths(0, 12, 4, 8, true), vec![1, 1, 2, 8]);
+        assert_eq!(element_widths(160, 12, 4, 8, true), vec![8, 2, 1, 1]);
+        assert_eq!(element_widths(0, 4, 4, 1, false), vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn databar_data_characters() {
+        let databar1 = DataBar::new("2001234567890".to_string()).unwrap();
+        let databar2 = DataBar::new("9999999999999".to_string()).unwrap();
+
+        assert_eq!(databar1.data_characters(), [276, 312, 1563, 311]);
+        assert_eq!(databar2.data_characters(), [1380, 202, 621, 1488]);
+    }
+
+    #[test]
+    fn databar_linkage() {
+        let databar = DataBar::with_linkage("2001234567890".to_string()).unwrap();
+        let expanded = DataBarExpanded::with_linkage("(01)98898765432106".to_string()).unwrap();
+
+        assert!(databar.is_linked() && expanded.is_linked());
+        assert!(!DataBar::new("2001234567890".to_string()).unwrap().is_linked());
+        assert_eq!(databar.data_characters(), [1656, 514, 2185, 203]);
+        assert_eq!(expanded.data_characters(), vec![3741, 3702, 3207, 3282]);
+        assert_eq!(DataBarStacked::with_linkage("2001234567890".to_string()).unwrap().encode().len(), 3);
+    }
+
+    #[test]
+    fn databar_checksum_calculation() {
+        let databar1 = DataBar::new("2001234567890".to_string()).unwrap();
+        let databar2 = DataBar::new("0401234567890".to_string()).unwrap();
+
+        assert_eq!(databar1.checksum_digit(), 9);
+        assert_eq!(databar1.checksum_value(), 35);
+        assert_eq!(databar2.checksum_value(), 24);
+    }
+
+    #[test]
+    fn databar_encode() {
+        let databar1 = DataBar::new("2001234567890".to_string()).unwrap();
+        let databar2 = DataBar::new("0000000000000".to_string()).unwrap();
+        let databar3 = DataBar::new("0401234567890".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(databar1.encode()), "010100011101000001001111111000010100110110111110110000010010100101100000000111000110110110001101".to_string());
+        assert_eq!(collapse_vec(databar2.encode()), "010101001000000001000111111110010111111100101010101010110000000101111111110111011111111011010101".to_string());
+        assert_eq!(collapse_vec(databar3.encode()), "010010010000001001000111000000010101100001111010101111101011110101111111000001000010101111001101".to_string());
+    }
+
+    #[test]
+    fn new_databar_expanded() {
+        let databar1 = DataBarExpanded::new("(01)98898765432106".to_string());
+        let databar2 = DataBarExpanded::new("(01)98898765432106(3202)012345(15)991231".to_string());
+        let databar3 = DataBarExpanded::new("(10)ABC-123(21)xyz!".to_string());
+
+        assert!(databar1.is_ok());
+        assert!(databar2.is_ok());
+        assert!(databar3.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_databar_expanded() {
+        let databar1 = DataBarExpanded::new("(01)98898765432107".to_string());
+        let databar2 = DataBarExpanded::new("(99)ABC#".to_string());
+        let databar3 = DataBarExpanded::new("0198898765432106".to_string());
+
+        assert!(databar1.is_err());
+        assert!(databar2.is_err());
+        assert!(databar3.is_err());
+    }
+
+    #[test]
+    fn invalid_len_databar_expanded() {
+        let databar1 = DataBarExpanded::new("(1)".to_string());
+        let databar2 = DataBarExpanded::new("(01)98898765432106(10)ABCDEFGHIJKLMNOPQRST(21)abcdefghij".to_string());
+
+        assert!(databar1.is_err());
+        assert!(databar2.is_err());
+    }
+
+    #[test]
+    fn databar_expanded_elements() {
+        let databar = DataBarExpanded::new("(01)98898765432106(15)991231".to_string()).unwrap();
+
+        assert_eq!(databar.elements(), &[("01".to_string(), "98898765432106".to_string()),
+                                         ("15".to_string(), "991231".to_string())]);
+    }
+
+    #[test]
+    fn databar_expanded_data_characters() {
+        let databar1 = DataBarExpanded::new("(01)98898765432106".to_string()).unwrap();
+        let databar2 = DataBarExpanded::new("(10)ABC-123(21)xyz!".to_string()).unwrap();
+
+        assert_eq!(databar1.data_characters(), vec![1693, 3702, 3207, 3282]);
+        assert_eq!(databar2.data_characters(), vec![19, 130, 395, 3077, 1433, 3842, 1822, 1487, 2568]);
+    }
+
+    #[test]
+    fn databar_expanded_checksum_calculation() {
+        let databar1 = DataBarExpanded::new("(01)98898765432106".to_string()).unwrap();
+        let databar2 = DataBarExpanded::new("(01)98898765432106(3202)012345(15)991231".to_string()).unwrap();
+
+        assert_eq!(databar1.checksum_value(), 156);
+        assert_eq!(databar1.check_character_value(), 367);
+        assert_eq!(databar2.checksum_value(), 42);
+        assert_eq!(databar2.check_character_value(), 1519);
+    }
+
+    #[test]
+    fn databar_expanded_encode() {
+        let databar1 = DataBarExpanded::new("(01)98898765432106".to_string()).unwrap();
+        let databar2 = DataBarExpanded::new("(01)00012345678905(10)ABC123".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(databar1.encode()), "01011110100010000010111111110000101001110001001111011010000001100010101111000000111010011100000010010100111110111001100011111100001010".to_string());
+        assert_eq!(collapse_vec(databar2.encode()), "0100011000001011011011111111000010110011000010111101011110011011111010111110000001100010110000110111000111101101011110001111110000101110001100100001010011101111110110101111111100111001011011111101110011011100101111100011110000001010".to_string());
+    }
+    #[test]
+    fn new_databar_stacked() {
+        let databar1 = DataBarStacked::new("2001234567890".to_string());
+        let databar2 = DataBarStackedOmni::new("20012345678909".to_string());
+        let databar3 = DataBarStacked::new("20012345678908".to_string());
+        let databar4 = DataBarStackedOmni::new("200123456789A".to_string());
+
+        assert!(databar1.is_ok());
+        assert!(databar2.is_ok());
+        assert!(databar3.is_err());
+        assert!(databar4.is_err());
+    }
+
+    #[test]
+    fn databar_stacked_encode() {
+        let databar = DataBarStacked::new("2001234567890".to_string()).unwrap();
+        let rows = databar.encode();
+
+        assert_eq!(rows.iter().map(|r| r.height).collect::<Vec<u32>>(), vec![5, 1, 7]);
+        assert_eq!(collapse_vec(rows[0].modules.clone()), "01010001110100000100111111100001010011011011111010".to_string());
+        assert_eq!(collapse_vec(rows[1].modules.clone()), "00001110101011011010010101011010101001001001010000".to_string());
+        assert_eq!(collapse_vec(rows[2].modules.clone()), "10110000010010100101100000000111000110110110001101".to_string());
+    }
+
+    #[test]
+    fn databar_stacked_omni_encode() {
+        let databar = DataBarStackedOmni::new("0401234567890".to_string()).unwrap();
+        let rows = databar.encode();
+
+        assert_eq!(rows.iter().map(|r| r.height).collect::<Vec<u32>>(), vec![33, 1, 1, 1, 33]);
+        assert_eq!(collapse_vec(rows[0].modules.clone()), "01001001000000100100011100000001010110000111101010".to_string());
+        assert_eq!(collapse_vec(rows[1].modules.clone()), "00000110111111011010100010101010101001111000010000".to_string());
+        assert_eq!(collapse_vec(rows[2].modules.clone()), "00000101010101010101010101010101010101010101010000".to_string());
+        assert_eq!(collapse_vec(rows[3].modules.clone()), "00000000010100001010000000101010111101010000110000".to_string());
+        assert_eq!(collapse_vec(rows[4].modules.clone()), "10101111101011110101111111000001000010101111001101".to_string());
+    }
+}
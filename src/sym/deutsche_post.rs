@@ -0,0 +1,226 @@
+//! This module provides types for encoding the parcel routing barcodes of Deutsche Post.
+//!
+//! This module defines types for:
+//!   * Identcode, which identifies a parcel by the mail centre, customer and delivery number
+//!     (11 digits and a check digit)
+//!   * Leitcode, which routes a parcel by postcode, street, house number and product code
+//!     (13 digits and a check digit)
+//!
+//! The digits and their check digit are encoded as an Interleaved 2 of 5 barcode. The check
+//! digit weights the digits 4 and 9 alternately, beginning with 4 at the leftmost digit.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::two_of_five::I2of5;
+use std::ops::RangeInclusive;
+use std::char;
+
+// Parses the digits of a code with `len` digits before the check digit. If the check digit is
+// present it is verified.
+fn digits(data: String, len: usize) -> Result<Vec<u8>, String> {
+    let mut digits: Vec<u8> = data.chars()
+        .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+        .collect();
+
+    if digits.len() == len + 1 {
+        let check = digits.pop().unwrap();
+
+        if checksum(&digits) != check {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+    }
+
+    Ok(digits)
+}
+
+fn checksum(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 4 } else { 9 })
+        .sum();
+
+    ((10 - (sum % 10)) % 10) as u8
+}
+
+fn i2of5(digits: &[u8]) -> Result<I2of5, String> {
+    let data = digits.iter()
+        .chain([checksum(digits)].iter())
+        .map(|&d| char::from_digit(d as u32, 10).unwrap())
+        .collect();
+
+    I2of5::new(data)
+}
+
+// Formats the digits in groups of the given lengths, separated by '.' or, before the groups
+// whose indices are in `spaces`, by a space. The check digit follows after a space.
+fn human_readable(digits: &[u8], groups: &[usize], spaces: &[usize]) -> String {
+    let mut text = String::new();
+    let mut start = 0;
+
+    for (i, &len) in groups.iter().enumerate() {
+        if i > 0 {
+            text.push(if spaces.contains(&i) { ' ' } else { '.' });
+        }
+
+        text.extend(digits[start..(start + len)].iter().map(|&d| char::from_digit(d as u32, 10).unwrap()));
+        start += len;
+    }
+
+    text.push(' ');
+    text.push(char::from_digit(checksum(digits) as u32, 10).unwrap());
+    text
+}
+
+/// The Identcode barcode type.
+pub struct Identcode {
+    data: Vec<u8>,
+    i2of5: I2of5,
+}
+
+impl Identcode {
+    /// Creates a new barcode from 11 digits, or 12 digits whose final check digit will be
+    /// verified.
+    /// Returns Result<Identcode, String> indicating parse success.
+    pub fn new(data: String) -> Result<Identcode, String> {
+        let digits = digits(Identcode::parse(data)?, 11)?;
+        let i2of5 = i2of5(&digits)?;
+
+        Ok(Identcode{data: digits, i2of5})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit using a modulo-10 algorithm with weights of 4 and 9.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum(&self.data)
+    }
+
+    /// Returns the human readable text, grouped as "56.310 243.031 3".
+    pub fn human_readable(&self) -> String {
+        human_readable(&self.data, &[2, 3, 3, 3], &[2])
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.i2of5.encode()
+    }
+}
+
+/// The Leitcode barcode type.
+pub struct Leitcode {
+    data: Vec<u8>,
+    i2of5: I2of5,
+}
+
+impl Leitcode {
+    /// Creates a new barcode from 13 digits, or 14 digits whose final check digit will be
+    /// verified.
+    /// Returns Result<Leitcode, String> indicating parse success.
+    pub fn new(data: String) -> Result<Leitcode, String> {
+        let digits = digits(Leitcode::parse(data)?, 13)?;
+        let i2of5 = i2of5(&digits)?;
+
+        Ok(Leitcode{data: digits, i2of5})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit using a modulo-10 algorithm with weights of 4 and 9.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum(&self.data)
+    }
+
+    /// Returns the human readable text, grouped as "21348.075.016.40 1".
+    pub fn human_readable(&self) -> String {
+        human_readable(&self.data, &[5, 3, 3, 2], &[])
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.i2of5.encode()
+    }
+}
+
+impl Parse for Identcode {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        11..=12
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+impl Parse for Leitcode {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        13..=14
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::deutsche_post::*;
+
+    #[test]
+    fn new_deutsche_post() {
+        assert!(Identcode::new("56310243031".to_string()).is_ok());
+        assert!(Identcode::new("563102430313".to_string()).is_ok());
+        assert!(Leitcode::new("2134807501640".to_string()).is_ok());
+        assert!(Leitcode::new("21348075016401".to_string()).is_ok());
+    }
+
+    #[test]
+    fn invalid_data_deutsche_post() {
+        assert!(Identcode::new("5631024303A".to_string()).is_err());
+        assert!(Leitcode::new("21348.075.016".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_len_deutsche_post() {
+        assert!(Identcode::new("5631024303".to_string()).is_err());
+        assert!(Leitcode::new("213480750164012".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_checksum_deutsche_post() {
+        assert!(Identcode::new("563102430314".to_string()).is_err());
+        assert!(Leitcode::new("21348075016402".to_string()).is_err());
+    }
+
+    #[test]
+    fn deutsche_post_checksum_calculation() {
+        assert_eq!(Identcode::new("56310243031".to_string()).unwrap().checksum_digit(), 3);
+        assert_eq!(Leitcode::new("2134807501640".to_string()).unwrap().checksum_digit(), 1);
+    }
+
+    #[test]
+    fn deutsche_post_human_readable() {
+        assert_eq!(Identcode::new("56310243031".to_string()).unwrap().human_readable(), "56.310 243.031 3");
+        assert_eq!(Leitcode::new("2134807501640".to_string()).unwrap().human_readable(), "21348.075.016.40 1");
+    }
+
+    #[test]
+    fn deutsche_post_encode() {
+        let identcode = Identcode::new("56310243031".to_string()).unwrap();
+        let leitcode = Leitcode::new("2134807501640".to_string()).unwrap();
+
+        assert_eq!(identcode.encode(), I2of5::new("563102430313".to_string()).unwrap().encode());
+        assert_eq!(leitcode.encode(), I2of5::new("21348075016401".to_string()).unwrap().encode());
+    }
+}
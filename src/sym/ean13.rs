@@ -1,17 +1,17 @@
-//! This module provides types for encoding UPC and EAN barcodes.
+//! This module provides types for encoding EAN-13 barcodes and their variants.
 //! EAN13 barcodes are very common in retail. 90% of the products you purchase from a supermarket
 //! will use EAN13.
 //!
 //! This module defines types for:
-//!   * UPC-A
 //!   * EAN-13
 //!   * Bookland
 //!   * JAN
 
 use ::sym::Parse;
 use ::sym::EncodedBarcode;
+use ::sym::EncodedGuards;
 use ::sym::helpers;
-use std::ops::Range;
+use std::ops::RangeInclusive;
 use std::char;
 
 /// Encoding mappings for EAN barcodes.
@@ -32,7 +32,7 @@ pub const EAN_ENCODINGS: [[[u8; 7]; 10]; 3] = [
 
 /// Maps parity (odd/even) for the left-side digits based on the first digit in
 /// the number system portion of the barcode data.
-const PARITY: [[usize; 5]; 10] = [
+pub const EAN_PARITY: [[usize; 5]; 10] = [
     [0,0,0,0,0],
     [0,1,0,1,1],
     [0,1,1,0,1],
@@ -60,28 +60,32 @@ pub struct EAN13 {
 /// Bookland are EAN-13 that use number system 978.
 pub type Bookland = EAN13;
 
-/// The UPC-A barcode type.
-/// UPC-A are EAN-13 that start with a 0.
-pub type UPCA = EAN13;
-
 /// The JAN barcode type.
 /// JAN are EAN-13 that use number system of 49.
 pub type JAN = EAN13;
 
 impl EAN13 {
-    /// Creates a new barcode.
+    /// Creates a new barcode from 12 data digits, or 13 digits whose final check digit will be
+    /// verified.
     /// Returns Result<EAN13, String> indicating parse success.
     pub fn new(data: String) -> Result<EAN13, String> {
-        match EAN13::parse(data) {
-            Ok(d) => {
-                let digits = d.chars().map(|c| c.to_digit(10).expect("Unknown character") as u8).collect();
-                Ok(EAN13{data: digits})
+        let mut digits: Vec<u8> = EAN13::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 13 {
+            let check = digits.pop().unwrap();
+
+            if helpers::modulo_10_checksum(&digits) != check {
+                return Err(format!("Invalid check digit: {}", check));
             }
-            Err(e) => Err(e),
         }
+
+        Ok(EAN13{data: digits})
     }
 
-    /// Returns the data as was passed into the constructor.
+    /// Returns the data as was passed into the constructor, without the check digit.
     pub fn raw_data(&self) -> &[u8] {
         &self.data[..]
     }
@@ -100,7 +104,7 @@ impl EAN13 {
 
         match 10 - (((odds * 3) + evens) % 10) {
             10    => 0,
-            n @ _ => n,
+            n => n,
         }
     }
 
@@ -129,14 +133,14 @@ impl EAN13 {
     }
 
     fn parity_mapping(&self) -> [usize; 5] {
-        PARITY[self.data[0] as usize]
+        EAN_PARITY[self.data[0] as usize]
     }
 
     fn left_payload(&self) -> Vec<u8> {
         let slices: Vec<[u8; 7]> = self.left_digits()
             .iter()
             .zip(self.parity_mapping().iter())
-            .map(|(d, s)| self.char_encoding(*s, &d))
+            .map(|(d, s)| self.char_encoding(*s, d))
             .collect();
 
         slices.iter().flat_map(|e| e.iter()).cloned().collect()
@@ -145,7 +149,7 @@ impl EAN13 {
     fn right_payload(&self) -> Vec<u8> {
         let slices: Vec<[u8; 7]> = self.right_digits()
             .iter()
-            .map(|d| self.char_encoding(2, &d))
+            .map(|d| self.char_encoding(2, d))
             .collect();
 
         slices.iter().flat_map(|e| e.iter()).cloned().collect()
@@ -159,17 +163,22 @@ impl EAN13 {
             self.left_payload(), EAN_MIDDLE_GUARD.to_vec(), self.right_payload(), 
             self.checksum_encoding(), EAN_RIGHT_GUARD.to_vec()][..])
     }
+
+    /// Returns the modules whose bars extend below the human-readable text: the left, middle and right guards.
+    pub fn encode_guards(&self) -> EncodedGuards {
+        helpers::join_vecs(&[vec![1; 3], vec![0; 42], vec![1; 5], vec![0; 42], vec![1; 3]][..])
+    }
 }
 
 impl Parse for EAN13 {
     /// Returns the valid length of data acceptable in this type of barcode.
-    fn valid_len() -> Range<u32> {
-        12..13
+    fn valid_len() -> RangeInclusive<u32> {
+        12..=13
     }
 
     /// Returns the set of valid characters allowed in this type of barcode.
     fn valid_chars() -> Vec<char> {
-        (0..10).into_iter().map(|i| char::from_digit(i, 10).unwrap()).collect()
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
     }
 }
 
@@ -220,8 +229,8 @@ mod tests {
 
     #[test]
     fn ean13_encode_as_upca() {
-        let ean131 = UPCA::new("012345612345".to_string()).unwrap(); // Check digit: 8
-        let ean132 = UPCA::new("000118999561".to_string()).unwrap(); // Check digit: 3
+        let ean131 = EAN13::new("012345612345".to_string()).unwrap(); // Check digit: 8
+        let ean132 = EAN13::new("000118999561".to_string()).unwrap(); // Check digit: 3
 
         assert_eq!(collapse_vec(ean131.encode()), "10100110010010011011110101000110110001010111101010110011011011001000010101110010011101001000101".to_string());
         assert_eq!(collapse_vec(ean132.encode()), "10100011010001101001100100110010110111000101101010111010011101001001110101000011001101000010101".to_string());
@@ -245,10 +254,29 @@ mod tests {
         assert_eq!(collapse_vec(ean132.encode()), "10101101110100001001110101011110111001001100101010110110010000101011100111010011101001000010101".to_string());
     }
 
+    #[test]
+    fn ean13_with_check_digit() {
+        let ean13 = EAN13::new("7501031311309".to_string()).unwrap();
+
+        assert_eq!(ean13.raw_data(), &[7,5,0,1,0,3,1,3,1,1,3,0]);
+        assert_eq!(ean13.encode(), EAN13::new("750103131130".to_string()).unwrap().encode());
+        assert_eq!(ean13.encode().len(), ean13.encode_guards().len());
+        assert_eq!(EAN13::new("7501031311308".to_string()).err(), Some("Invalid check digit: 8".to_string()));
+    }
+
+    #[test]
+    fn ean13_encode_guards() {
+        let ean13 = EAN13::new("750103131130".to_string()).unwrap();
+        let guards = ean13.encode_guards();
+
+        assert_eq!(guards.len(), ean13.encode().len());
+        assert_eq!(&guards[44..51], &[0, 1, 1, 1, 1, 1, 0]);
+    }
+
     #[test]
     fn ean13_as_upca_checksum_calculation() {
-        let ean131 = UPCA::new("003600029145".to_string()).unwrap(); // Check digit: 2
-        let ean132 = UPCA::new("012345612345".to_string()).unwrap(); // Check digit: 8
+        let ean131 = EAN13::new("003600029145".to_string()).unwrap(); // Check digit: 2
+        let ean132 = EAN13::new("012345612345".to_string()).unwrap(); // Check digit: 8
 
         assert_eq!(ean131.checksum_digit(), 2);
         assert_eq!(ean132.checksum_digit(), 8);
@@ -3,12 +3,13 @@
 
 use ::sym::Parse;
 use ::sym::EncodedBarcode;
+use ::sym::EncodedGuards;
 use ::sym::helpers;
 use ::sym::ean13::EAN_ENCODINGS;
 use ::sym::ean13::EAN_LEFT_GUARD;
 use ::sym::ean13::EAN_MIDDLE_GUARD;
 use ::sym::ean13::EAN_RIGHT_GUARD;
-use std::ops::Range;
+use std::ops::RangeInclusive;
 use std::char;
 
 /// The EAN-8 barcode type.
@@ -17,19 +18,27 @@ pub struct EAN8 {
 }
 
 impl EAN8 {
-    /// Creates a new barcode.
+    /// Creates a new barcode from 7 data digits, or 8 digits whose final check digit will be
+    /// verified.
     /// Returns Result<EAN8, String> indicating parse success.
     pub fn new(data: String) -> Result<EAN8, String> {
-        match EAN8::parse(data) {
-            Ok(d) => {
-                let digits = d.chars().map(|c| c.to_digit(10).expect("Unknown character") as u8).collect();
-                Ok(EAN8{data: digits})
+        let mut digits: Vec<u8> = EAN8::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 8 {
+            let check = digits.pop().unwrap();
+
+            if helpers::modulo_10_checksum(&digits) != check {
+                return Err(format!("Invalid check digit: {}", check));
             }
-            Err(e) => Err(e),
         }
+
+        Ok(EAN8{data: digits})
     }
 
-    /// Returns the data as was passed into the constructor.
+    /// Returns the data as was passed into the constructor, without the check digit.
     pub fn raw_data(&self) -> &[u8] {
         &self.data[..]
     }
@@ -48,7 +57,7 @@ impl EAN8 {
 
         match 10 - (((odds * 3) + evens) % 10) {
             10    => 0,
-            n @ _ => n,
+            n => n,
         }
     }
 
@@ -60,7 +69,7 @@ impl EAN8 {
         let mut ns = vec![];
 
         for d in self.number_system_digits() {
-            ns.extend(self.char_encoding(0, &d).iter().cloned());
+            ns.extend(self.char_encoding(0, d).iter().cloned());
         }
 
         ns
@@ -85,7 +94,7 @@ impl EAN8 {
     fn left_payload(&self) -> Vec<u8> {
         let slices: Vec<[u8; 7]> = self.left_digits()
             .iter()
-            .map(|d| self.char_encoding(0, &d))
+            .map(|d| self.char_encoding(0, d))
             .collect();
 
         slices.iter().flat_map(|e| e.iter()).cloned().collect()
@@ -94,7 +103,7 @@ impl EAN8 {
     fn right_payload(&self) -> Vec<u8> {
         let slices: Vec<[u8; 7]> = self.right_digits()
             .iter()
-            .map(|d| self.char_encoding(2, &d))
+            .map(|d| self.char_encoding(2, d))
             .collect();
 
         slices.iter().flat_map(|e| e.iter()).cloned().collect()
@@ -108,17 +117,22 @@ impl EAN8 {
             self.left_payload(), EAN_MIDDLE_GUARD.to_vec(), self.right_payload(),
             self.checksum_encoding(), EAN_RIGHT_GUARD.to_vec()][..])
     }
+
+    /// Returns the modules whose bars extend below the human-readable text: the left, middle and right guards.
+    pub fn encode_guards(&self) -> EncodedGuards {
+        helpers::join_vecs(&[vec![1; 3], vec![0; 28], vec![1; 5], vec![0; 28], vec![1; 3]][..])
+    }
 }
 
 impl Parse for EAN8 {
     /// Returns the valid length of data acceptable in this type of barcode.
-    fn valid_len() -> Range<u32> {
-        7..8
+    fn valid_len() -> RangeInclusive<u32> {
+        7..=8
     }
 
     /// Returns the set of valid characters allowed in this type of barcode.
     fn valid_chars() -> Vec<char> {
-        (0..10).into_iter().map(|i| char::from_digit(i, 10).unwrap()).collect()
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
     }
 }
 
@@ -153,6 +167,15 @@ mod tests {
         assert!(ean8.is_err());
     }
 
+    #[test]
+    fn ean8_with_check_digit() {
+        let ean8 = EAN8::new("55123457".to_string()).unwrap();
+
+        assert_eq!(ean8.raw_data(), &[5,5,1,2,3,4,5]);
+        assert_eq!(ean8.encode().len(), ean8.encode_guards().len());
+        assert!(EAN8::new("55123458".to_string()).is_err());
+    }
+
     #[test]
     fn ean8_raw_data() {
         let ean8 = EAN8::new("1234567".to_string()).unwrap();
@@ -169,6 +192,15 @@ mod tests {
         assert_eq!(collapse_vec(ean82.encode()), "1010001011011011101111010100011010101010000100111011001101010000101".to_string());
     }
 
+    #[test]
+    fn ean8_encode_guards() {
+        let ean8 = EAN8::new("5512345".to_string()).unwrap();
+        let guards = ean8.encode_guards();
+
+        assert_eq!(guards.len(), ean8.encode().len());
+        assert_eq!(&guards[30..37], &[0, 1, 1, 1, 1, 1, 0]);
+    }
+
     #[test]
     fn ean8_checksum_calculation() {
         let ean81 = EAN8::new("4575678".to_string()).unwrap(); // Check digit: 8
@@ -1,16 +1,23 @@
 //! This module provides types for encoding supplemental 2-digit and 5-digit EAN barcodes.
 //! Supplemental EAN-2 barcodes are used in magazines and newspapers to indicate issue number and
 //! EAN-5 barcodes are often used to indicate the suggested retail price of books.
+//!
+//! A supplement follows its main symbol after a gap of light modules. `WithSupplement` composes
+//! the two into one barcode, such as an `EAN13WithSupplement` or a `UPCAWithSupplement`.
 
 use ::sym::Parse;
-use ::sym::EncodedBarcode;
-use ::sym::ean13::EAN_ENCODINGS;
+use ::sym::{EncodedBarcode, EncodedGuards};
+use ::sym::ean13::{EAN13, EAN_ENCODINGS};
+use ::sym::upca::UPCA;
 use ::sym::helpers;
-use std::ops::Range;
+use std::ops::RangeInclusive;
 use std::char;
 
 pub const EANSUPP_LEFT_GUARD: [u8; 4] = [1,0,1,1];
 
+/// The number of light modules between a main symbol and its supplement.
+pub const EANSUPP_GAP: usize = 9;
+
 /// Maps parity (odd/even) for the EAN-5 barcodes based on the check digit.
 const EAN5_PARITY: [[usize; 5]; 10] = [
     [0,0,1,1,1],
@@ -56,7 +63,7 @@ impl EANSUPP {
                 match digits.len() {
                     2 => Ok(EANSUPP::EAN2{data: digits}),
                     5 => Ok(EANSUPP::EAN5{data: digits}),
-                    n @ _ => Err(format!("Invalid supplemental length: {}", n)),
+                    n => Err(format!("Invalid supplemental length: {}", n)),
                 }
             }
             Err(e) => Err(e),
@@ -91,7 +98,7 @@ impl EANSUPP {
 
         match ((odds * 3) + (evens * 9)) % 10 {
             10    => 0,
-            n @ _ => n,
+            n => n,
         }
     }
 
@@ -113,7 +120,7 @@ impl EANSUPP {
         let slices: Vec<[u8; 7]> = self.raw_data()
             .iter()
             .zip(self.parity().iter())
-            .map(|(d, s)| self.char_encoding(*s, &d))
+            .map(|(d, s)| self.char_encoding(*s, d))
             .collect();
 
         for (i, d) in slices.iter().enumerate() {
@@ -136,15 +143,115 @@ impl EANSUPP {
     }
 }
 
+/// Joins a main symbol and its supplement, with the gap between them.
+pub fn join_supplement(main: EncodedBarcode, supplement: &EANSUPP) -> EncodedBarcode {
+    helpers::join_vecs(&[main, vec![0; EANSUPP_GAP], supplement.encode()][..])
+}
+
+/// A symbol that can be followed by a supplement.
+pub trait Supplementable: Sized {
+    /// Creates the symbol from its data, as its own constructor does.
+    fn from_data(data: String) -> Result<Self, String>;
+
+    /// Encodes the symbol.
+    fn encode_main(&self) -> EncodedBarcode;
+
+    /// Encodes the guard bars of the symbol, which extend into its human-readable text.
+    fn encode_main_guards(&self) -> EncodedGuards;
+}
+
+impl Supplementable for EAN13 {
+    fn from_data(data: String) -> Result<EAN13, String> {
+        EAN13::new(data)
+    }
+
+    fn encode_main(&self) -> EncodedBarcode {
+        self.encode()
+    }
+
+    fn encode_main_guards(&self) -> EncodedGuards {
+        self.encode_guards()
+    }
+}
+
+impl Supplementable for UPCA {
+    fn from_data(data: String) -> Result<UPCA, String> {
+        UPCA::new(data)
+    }
+
+    fn encode_main(&self) -> EncodedBarcode {
+        self.encode()
+    }
+
+    fn encode_main_guards(&self) -> EncodedGuards {
+        self.encode_guards()
+    }
+}
+
+/// A main symbol followed by an EAN-2 or EAN-5 supplement, encoded as one barcode.
+pub struct WithSupplement<T: Supplementable> {
+    main: T,
+    supplement: EANSUPP,
+}
+
+/// An EAN-13 barcode with an EAN-2 or EAN-5 supplement, as on books and periodicals.
+pub type EAN13WithSupplement = WithSupplement<EAN13>;
+
+/// A UPC-A barcode with an EAN-2 or EAN-5 supplement, as on magazines.
+pub type UPCAWithSupplement = WithSupplement<UPCA>;
+
+impl<T: Supplementable> WithSupplement<T> {
+    /// Creates a new barcode from the data of the main symbol and of a 2-digit or 5-digit
+    /// supplement.
+    /// Returns Result<WithSupplement, String> indicating parse success.
+    pub fn new(data: String, supplement: String) -> Result<WithSupplement<T>, String> {
+        Ok(WithSupplement{main: T::from_data(data)?, supplement: EANSUPP::new(supplement)?})
+    }
+
+    /// Creates a new barcode from a main symbol and its supplement.
+    pub fn from_parts(main: T, supplement: EANSUPP) -> WithSupplement<T> {
+        WithSupplement{main, supplement}
+    }
+
+    /// Returns the main symbol.
+    pub fn main(&self) -> &T {
+        &self.main
+    }
+
+    /// Returns the supplement.
+    pub fn supplement(&self) -> &EANSUPP {
+        &self.supplement
+    }
+
+    /// Returns the module where the supplement starts, after the main symbol and the gap.
+    pub fn supplement_start(&self) -> usize {
+        self.main.encode_main().len() + EANSUPP_GAP
+    }
+
+    /// Encodes the main symbol, the gap and the supplement.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        join_supplement(self.main.encode_main(), &self.supplement)
+    }
+
+    /// Encodes the guard bars of the main symbol. The bars of the supplement do not extend.
+    /// Returns EncodedGuards of the same length as the EncodedBarcode.
+    pub fn encode_guards(&self) -> EncodedGuards {
+        let mut guards = self.main.encode_main_guards();
+        guards.resize(self.encode().len(), 0);
+        guards
+    }
+}
+
 impl Parse for EANSUPP {
     /// Returns the valid length of data acceptable in this type of barcode.
-    fn valid_len() -> Range<u32> {
-        2..5
+    fn valid_len() -> RangeInclusive<u32> {
+        2..=5
     }
 
     /// Returns the set of valid characters allowed in this type of barcode.
     fn valid_chars() -> Vec<char> {
-        (0..10).into_iter().map(|i| char::from_digit(i, 10).unwrap()).collect()
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
     }
 }
 
@@ -214,4 +321,36 @@ mod tests {
         assert_eq!(collapse_vec(ean51.encode()), "10110110001010011001010011011010111101010011101".to_string());
     }
 
+    #[test]
+    fn ean13_with_supplement() {
+        let ean13 = EAN13WithSupplement::new("978185102353".to_string(), "51299".to_string()).unwrap();
+        let encoded = ean13.encode();
+
+        assert_eq!(encoded.len(), 95 + 9 + 47);
+        assert_eq!(ean13.supplement_start(), 104);
+        assert_eq!(&encoded[..95], &ean13.main().encode()[..]);
+        assert_eq!(&encoded[95..104], &[0; 9]);
+        assert_eq!(&encoded[104..], &ean13.supplement().encode()[..]);
+        assert_eq!(ean13.encode_guards().len(), encoded.len());
+        assert_eq!(ean13.encode_guards()[94..].iter().filter(|&&g| g == 1).count(), 1);
+    }
+
+    #[test]
+    fn upca_with_supplement() {
+        let upca = UPCAWithSupplement::new("07012345678".to_string(), "12".to_string()).unwrap();
+        let encoded = upca.encode();
+
+        assert_eq!(encoded.len(), 95 + 9 + 20);
+        assert_eq!(&encoded[..95], &upca.main().encode()[..]);
+        assert_eq!(&encoded[95..104], &[0; 9]);
+        assert_eq!(&encoded[104..], &upca.supplement().encode()[..]);
+        assert_eq!(&upca.encode_guards()[..95], &upca.main().encode_guards()[..]);
+    }
+
+    #[test]
+    fn invalid_ean13_with_supplement() {
+        assert!(EAN13WithSupplement::new("97818510235".to_string(), "51299".to_string()).is_err());
+        assert!(EAN13WithSupplement::new("978185102353".to_string(), "512".to_string()).is_err());
+    }
+
 }
@@ -0,0 +1,104 @@
+//! This module provides types for encoding FIMs (Facing Identification Marks). A FIM is printed
+//! near the top edge of United States reply-mail envelopes, left of the postage area, so that
+//! mail processing equipment can identify the type of mail and face it.
+//!
+//! Each FIM is nine positions, at 1/16 inch intervals, that either hold a bar or are empty:
+//!   * FIM A, for courtesy reply mail with a POSTNET or IMb barcode
+//!   * FIM B, for business reply mail without a barcode
+//!   * FIM C, for business reply mail with a barcode
+//!   * FIM D, for OCR-readable mail, such as information-based indicia
+
+use ::sym::EncodedBarcode;
+
+/// The bars of the nine positions of each FIM, in the order A-D.
+const FIM_PATTERNS: [[u8; 9]; 4] = [
+    [1, 1, 0, 0, 1, 0, 0, 1, 1],
+    [1, 0, 1, 1, 0, 1, 1, 0, 1],
+    [1, 1, 0, 1, 0, 1, 0, 1, 1],
+    [1, 1, 1, 0, 1, 0, 1, 1, 1],
+];
+
+/// The FIM barcode type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FIM {
+    /// FIM A.
+    A,
+    /// FIM B.
+    B,
+    /// FIM C.
+    C,
+    /// FIM D.
+    D,
+}
+
+impl FIM {
+    /// Creates a new barcode from the letter of the FIM ("A" to "D").
+    /// Returns Result<FIM, String> indicating parse success.
+    pub fn new(data: String) -> Result<FIM, String> {
+        match &data.to_uppercase()[..] {
+            "A" => Ok(FIM::A),
+            "B" => Ok(FIM::B),
+            "C" => Ok(FIM::C),
+            "D" => Ok(FIM::D),
+            _ => Err(format!("Invalid FIM: {}", data)),
+        }
+    }
+
+    /// Returns the nine positions of the FIM: 1 where a bar is printed and 0 where none is.
+    pub fn positions(self) -> [u8; 9] {
+        FIM_PATTERNS[self as usize]
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of 17 binary digits. The bars and
+    /// the spaces between the positions are each one module (1/32 inch) wide.
+    pub fn encode(self) -> EncodedBarcode {
+        let positions = self.positions();
+        let mut enc = vec![positions[0]];
+
+        for &p in &positions[1..] {
+            enc.extend(&[0, p]);
+        }
+
+        enc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::fim::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_fim() {
+        assert_eq!(FIM::new("A".to_string()), Ok(FIM::A));
+        assert_eq!(FIM::new("d".to_string()), Ok(FIM::D));
+    }
+
+    #[test]
+    fn invalid_data_fim() {
+        let fim = FIM::new("E".to_string());
+
+        assert!(fim.is_err());
+    }
+
+    #[test]
+    fn invalid_len_fim() {
+        let fim = FIM::new("AB".to_string());
+
+        assert!(fim.is_err());
+    }
+
+    #[test]
+    fn fim_encode() {
+        assert_eq!(collapse_vec(FIM::A.encode()), "10100000100000101");
+        assert_eq!(collapse_vec(FIM::B.encode()), "10001010001010001");
+        assert_eq!(collapse_vec(FIM::C.encode()), "10100010001000101");
+        assert_eq!(collapse_vec(FIM::D.encode()), "10101000100010101");
+    }
+}
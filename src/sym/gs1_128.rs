@@ -0,0 +1,338 @@
+//! This module provides types for encoding GS1-128 barcodes (formerly known as UCC/EAN-128).
+//! GS1-128 is an application of Code128 that carries one or more Application Identifier (AI)
+//! elements and is used on shipping labels, pallets and cartons throughout the supply chain.
+//!
+//! Data is given in the human-readable form, with each AI enclosed in parentheses. For example:
+//! "(01)09501101530003(17)140704(10)AB-123". The FNC1 characters that begin the symbol and
+//! separate variable-length elements are inserted automatically.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use ::sym::code128::{Code128, FNC1};
+use std::ops::RangeInclusive;
+
+/// The maximum number of data characters (AIs and their values) allowed in a GS1-128 symbol.
+pub const GS1_128_MAX_DATA_LEN: usize = 48;
+
+/// The format of an Application Identifier's value.
+pub struct AIFormat {
+    /// The AI itself, or the leading digits of a family of AIs (e.g. "31" for 3100-3699).
+    pub prefix: &'static str,
+    /// The total number of digits in the AI.
+    pub ai_len: usize,
+    /// The minimum length of the value.
+    pub min_len: usize,
+    /// The maximum length of the value.
+    pub max_len: usize,
+    /// Whether the value may only contain digits.
+    pub numeric: bool,
+    /// Whether the last digit of the value is a GS1 modulo-10 check digit.
+    pub check_digit: bool,
+}
+
+macro_rules! ai {
+    ($prefix:expr, $ai_len:expr, $min:expr, $max:expr, $numeric:expr, $check:expr) => {
+        AIFormat{prefix: $prefix, ai_len: $ai_len, min_len: $min, max_len: $max,
+                 numeric: $numeric, check_digit: $check}
+    }
+}
+
+/// The supported Application Identifiers. Entries are matched in order, so more specific
+/// prefixes must appear before the families they belong to.
+pub const GS1_AIS: [AIFormat; 70] = [
+    ai!("00", 2, 18, 18, true, true),     ai!("01", 2, 14, 14, true, true),
+    ai!("02", 2, 14, 14, true, true),     ai!("10", 2, 1, 20, false, false),
+    ai!("11", 2, 6, 6, true, false),      ai!("12", 2, 6, 6, true, false),
+    ai!("13", 2, 6, 6, true, false),      ai!("15", 2, 6, 6, true, false),
+    ai!("16", 2, 6, 6, true, false),      ai!("17", 2, 6, 6, true, false),
+    ai!("20", 2, 2, 2, true, false),      ai!("21", 2, 1, 20, false, false),
+    ai!("22", 2, 1, 20, false, false),    ai!("235", 3, 1, 28, false, false),
+    ai!("240", 3, 1, 30, false, false),   ai!("241", 3, 1, 30, false, false),
+    ai!("242", 3, 1, 6, true, false),     ai!("243", 3, 1, 20, false, false),
+    ai!("250", 3, 1, 30, false, false),   ai!("251", 3, 1, 30, false, false),
+    ai!("253", 3, 14, 30, false, false),  ai!("254", 3, 1, 20, false, false),
+    ai!("255", 3, 14, 25, true, false),   ai!("30", 2, 1, 8, true, false),
+    ai!("31", 4, 6, 6, true, false),      ai!("32", 4, 6, 6, true, false),
+    ai!("33", 4, 6, 6, true, false),      ai!("34", 4, 6, 6, true, false),
+    ai!("35", 4, 6, 6, true, false),      ai!("36", 4, 6, 6, true, false),
+    ai!("37", 2, 1, 8, true, false),      ai!("390", 4, 1, 15, true, false),
+    ai!("391", 4, 4, 18, true, false),    ai!("392", 4, 1, 15, true, false),
+    ai!("393", 4, 4, 18, true, false),    ai!("394", 4, 4, 4, true, false),
+    ai!("400", 3, 1, 30, false, false),   ai!("401", 3, 1, 30, false, false),
+    ai!("402", 3, 17, 17, true, true),    ai!("403", 3, 1, 30, false, false),
+    ai!("410", 3, 13, 13, true, true),    ai!("411", 3, 13, 13, true, true),
+    ai!("412", 3, 13, 13, true, true),    ai!("413", 3, 13, 13, true, true),
+    ai!("414", 3, 13, 13, true, true),    ai!("415", 3, 13, 13, true, true),
+    ai!("416", 3, 13, 13, true, true),    ai!("417", 3, 13, 13, true, true),
+    ai!("420", 3, 1, 20, false, false),   ai!("421", 3, 4, 12, false, false),
+    ai!("422", 3, 3, 3, true, false),     ai!("423", 3, 4, 15, true, false),
+    ai!("424", 3, 3, 3, true, false),     ai!("425", 3, 3, 15, true, false),
+    ai!("426", 3, 3, 3, true, false),     ai!("7001", 4, 13, 13, true, false),
+    ai!("7002", 4, 1, 30, false, false),  ai!("7003", 4, 10, 10, true, false),
+    ai!("8001", 4, 14, 14, true, false),  ai!("8002", 4, 1, 20, false, false),
+    ai!("8003", 4, 15, 30, false, false), ai!("8004", 4, 1, 30, false, false),
+    ai!("8005", 4, 6, 6, true, false),    ai!("8006", 4, 18, 18, true, false),
+    ai!("8007", 4, 1, 34, false, false),  ai!("8008", 4, 9, 12, true, false),
+    ai!("8018", 4, 18, 18, true, true),   ai!("8020", 4, 1, 25, false, false),
+    ai!("90", 2, 1, 30, false, false),    ai!("9", 2, 1, 90, false, false),
+];
+
+/// Elements whose AI begins with one of these prefixes have a predefined length and so are
+/// never followed by an FNC1 separator.
+const PREDEFINED_LENGTH_PREFIXES: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20",
+    "31", "32", "33", "34", "35", "36", "41",
+];
+
+/// The characters (other than digits and upper and lower-case letters) permitted in
+/// alphanumeric AI values. Parentheses are also permitted by GS1, but are reserved here for
+/// delimiting the AIs.
+const GS1_PUNCTUATION: [char; 18] = [
+    '!', '"', '%', '&', '\'', '*', '+', ',', '-', '.', '/', ':', ';', '<', '=', '>', '?', '_',
+];
+
+/// The GS1-128 barcode type.
+#[allow(non_camel_case_types)]
+pub struct GS1_128 {
+    data: Vec<char>,
+    elements: Vec<(String, String)>,
+    code128: Code128,
+}
+
+impl GS1_128 {
+    /// Creates a new barcode from a string of parenthesised AIs and their values.
+    /// Returns Result<GS1_128, String> indicating parse success.
+    pub fn new(data: String) -> Result<GS1_128, String> {
+        let d = GS1_128::parse(data)?;
+        let elements = GS1_128::elements_from(&d)?;
+        let data_len: usize = elements.iter().map(|(ai, v)| ai.len() + v.len()).sum();
+
+        if data_len > GS1_128_MAX_DATA_LEN {
+            return Err(format!("Data exceeds the maximum of {} characters", GS1_128_MAX_DATA_LEN));
+        }
+
+        match Code128::auto(GS1_128::fnc1_data(&elements)) {
+            Ok(code128) => Ok(GS1_128{data: d.chars().collect(), elements, code128}),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Creates a new barcode, as `new`, with the linkage character for a composite component.
+    /// Returns Result<GS1_128, String> indicating parse success.
+    pub fn with_linkage(data: String) -> Result<GS1_128, String> {
+        GS1_128::new(data).map(|gs1| GS1_128{code128: gs1.code128.with_linkage(), ..gs1})
+    }
+
+    /// Returns the AI format for the given AI, if it is supported.
+    pub fn ai_format(ai: &str) -> Option<&'static AIFormat> {
+        GS1_AIS.iter().find(|f| ai.starts_with(f.prefix) && ai.len() == f.ai_len)
+    }
+
+    /// Splits a string of parenthesised AIs into (AI, value) pairs, validating each value
+    /// against the format of its AI.
+    pub fn elements_from(data: &str) -> Result<Vec<(String, String)>, String> {
+        let elements = GS1_128::split_elements(data)?;
+
+        for (ai, value) in &elements {
+            GS1_128::validate_element(ai, value)?;
+        }
+
+        Ok(elements)
+    }
+
+    /// Returns true if the value of the given AI has a predefined length, and so is never
+    /// followed by an FNC1 separator.
+    pub fn has_predefined_length(ai: &str) -> bool {
+        PREDEFINED_LENGTH_PREFIXES.iter().any(|p| ai.starts_with(p))
+    }
+
+    // Splits "(01)123(10)ABC" into [("01", "123"), ("10", "ABC")].
+    fn split_elements(data: &str) -> Result<Vec<(String, String)>, String> {
+        if !data.starts_with('(') {
+            return Err("Data must begin with a parenthesised AI".to_string());
+        }
+
+        let mut elements = vec![];
+
+        for part in data[1..].split('(') {
+            let mut pieces = part.splitn(2, ')');
+            let ai = pieces.next().unwrap_or("");
+
+            match pieces.next() {
+                Some(value) if !ai.is_empty() && ai.chars().all(|c| c.is_ascii_digit()) => {
+                    elements.push((ai.to_string(), value.to_string()));
+                },
+                _ => return Err(format!("Invalid AI: ({}", part)),
+            }
+        }
+
+        Ok(elements)
+    }
+
+    fn validate_element(ai: &str, value: &str) -> Result<(), String> {
+        let format = match GS1_128::ai_format(ai) {
+            Some(f) => f,
+            None => return Err(format!("Unknown AI: ({})", ai)),
+        };
+
+        if value.len() < format.min_len || value.len() > format.max_len {
+            return Err(format!("Value for AI ({}) must be {}-{} characters", ai, format.min_len, format.max_len));
+        }
+
+        if format.numeric && !value.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Value for AI ({}) must be numeric", ai));
+        }
+
+        if format.check_digit {
+            let digits: Vec<u8> = value.chars().map(|c| c.to_digit(10).unwrap() as u8).collect();
+            let (data, check) = digits.split_at(digits.len() - 1);
+
+            if helpers::modulo_10_checksum(data) != check[0] {
+                return Err(format!("Invalid check digit for AI ({})", ai));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the data for the underlying symbology: a leading FNC1, followed by each AI and
+    /// value, with an FNC1 separator after every variable-length element except the last.
+    pub fn fnc1_data(elements: &[(String, String)]) -> String {
+        let mut data = FNC1.to_string();
+
+        for (i, (ai, value)) in elements.iter().enumerate() {
+            data.push_str(ai);
+            data.push_str(value);
+
+            if !GS1_128::has_predefined_length(ai) && i < elements.len() - 1 {
+                data.push(FNC1);
+            }
+        }
+
+        data
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the (AI, value) pairs encoded in the barcode.
+    pub fn elements(&self) -> &[(String, String)] {
+        &self.elements[..]
+    }
+
+    /// Calculates the symbol check character value using the Code128 modulo-103 algorithm.
+    pub fn checksum_value(&self) -> u8 {
+        self.code128.checksum_value()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code128.encode()
+    }
+}
+
+impl Parse for GS1_128 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        4..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = ('0'..='9').chain('A'..='Z').chain('a'..='z').collect();
+        chars.extend(GS1_PUNCTUATION.iter().cloned());
+        chars.extend(['(', ')'].iter().cloned());
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::gs1_128::*;
+    use ::sym::code128::Code128;
+
+    #[test]
+    fn new_gs1_128() {
+        let gs1 = GS1_128::new("(01)09501101530003(17)140704(10)AB-123".to_string());
+
+        assert!(gs1.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_gs1_128() {
+        let gs1 = GS1_128::new("01095011015300031714070410AB-123".to_string());
+
+        assert!(gs1.is_err());
+    }
+
+    #[test]
+    fn unknown_ai_gs1_128() {
+        let gs1 = GS1_128::new("(05)1234".to_string());
+
+        assert!(gs1.is_err());
+    }
+
+    #[test]
+    fn invalid_value_gs1_128() {
+        let gs1_len = GS1_128::new("(01)0950110153000".to_string());
+        let gs1_numeric = GS1_128::new("(17)14O704".to_string());
+
+        assert!(gs1_len.is_err());
+        assert!(gs1_numeric.is_err());
+    }
+
+    #[test]
+    fn invalid_check_digit_gs1_128() {
+        let gs1 = GS1_128::new("(00)106141411234567890".to_string());
+
+        assert!(gs1.is_err());
+    }
+
+    #[test]
+    fn invalid_len_gs1_128() {
+        let gs1 = GS1_128::new("(10)ABCDEFGHIJKLMNOPQRST(21)ABCDEFGHIJKLMNOPQRST(22)ABCD".to_string());
+
+        assert!(gs1.is_err());
+    }
+
+    #[test]
+    fn gs1_128_elements() {
+        let gs1 = GS1_128::new("(00)106141411234567897(3103)000750".to_string()).unwrap();
+
+        assert_eq!(gs1.elements(), &[("00".to_string(), "106141411234567897".to_string()),
+                                     ("3103".to_string(), "000750".to_string())]);
+    }
+
+    #[test]
+    fn gs1_128_fnc1_separators() {
+        let gs11 = GS1_128::new("(01)09501101530003(10)AB-123(17)140704".to_string()).unwrap();
+        let gs12 = GS1_128::new("(01)09501101530003(17)140704(10)AB-123".to_string()).unwrap();
+        let code1281 = Code128::auto("ƒ010950110153000310AB-123ƒ17140704".to_string()).unwrap();
+        let code1282 = Code128::auto("ƒ01095011015300031714070410AB-123".to_string()).unwrap();
+
+        assert_eq!(gs11.encode(), code1281.encode());
+        assert_eq!(gs12.encode(), code1282.encode());
+    }
+
+    #[test]
+    fn gs1_128_checksum_calculation() {
+        let gs1 = GS1_128::new("(00)106141411234567897".to_string()).unwrap();
+
+        // Start C (105), FNC1 (102), then the pairs 00 10 61 41 41 12 34 56 78 97.
+        assert_eq!(gs1.checksum_value(), 34);
+    }
+
+    #[test]
+    fn gs1_128_linkage() {
+        let gs1 = GS1_128::new("(00)106141411234567897".to_string()).unwrap();
+        let linked = GS1_128::with_linkage("(00)106141411234567897".to_string()).unwrap();
+
+        // The data ends in set C, so the linkage character is Code A (101).
+        assert_eq!(linked.checksum_value(), 10);
+        assert_eq!(linked.encode().len(), gs1.encode().len() + 11);
+    }
+}
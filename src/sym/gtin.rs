@@ -0,0 +1,242 @@
+//! This module provides utilities for working with GTINs (Global Trade Item Numbers), the
+//! numbers carried by EAN-8, UPC-A, EAN-13 and ITF-14 barcodes.
+//!
+//! GTIN-8, GTIN-12, GTIN-13 and GTIN-14 are all the same number with different numbers of
+//! leading zeros, so a GTIN is normalized to 14 digits and can be converted to any form that is
+//! long enough to hold it. The first of the 14 digits is the packaging indicator: 0 for a trade
+//! item itself, 1-8 for groupings of it, and 9 for variable measure items.
+
+use ::sym::helpers;
+use ::sym::ean13::EAN13;
+use ::sym::ean8::EAN8;
+use ::sym::upca::UPCA;
+use ::sym::itf14::ITF14;
+use std::char;
+
+/// The packaging indicator of variable measure trade items.
+pub const GTIN_VARIABLE_MEASURE: u8 = 9;
+
+// The number of digits of a normalized GTIN, without the check digit.
+const GTIN_LEN: usize = 13;
+
+/// Returns true if the digits are a GTIN-8, GTIN-12, GTIN-13 or GTIN-14 with a valid check
+/// digit.
+pub fn is_valid(data: &str) -> bool {
+    GTIN::new(data.to_string()).is_ok()
+}
+
+/// A GTIN, normalized to 14 digits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GTIN {
+    data: Vec<u8>,
+}
+
+impl GTIN {
+    /// Creates a new GTIN from a GTIN-8, GTIN-12, GTIN-13 or GTIN-14, whose final check digit
+    /// will be verified.
+    /// Returns Result<GTIN, String> indicating parse success.
+    pub fn new(data: String) -> Result<GTIN, String> {
+        match data.len() {
+            8 | 12 | 13 | 14 => (),
+            n => return Err(format!("GTINs have 8, 12, 13 or 14 digits, not {}", n)),
+        }
+
+        let mut digits: Vec<u8> = vec![];
+
+        for c in data.chars() {
+            match c.to_digit(10) {
+                Some(d) => digits.push(d as u8),
+                None => return Err(format!("Invalid character: {}", c)),
+            }
+        }
+
+        let check = digits.pop().unwrap();
+
+        if helpers::modulo_10_checksum(&digits) != check {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+
+        GTIN::from_digits(&digits)
+    }
+
+    /// Creates a new GTIN from the digits of a GTIN without its check digit, which will be
+    /// calculated. Up to 13 digits are accepted, and leading zeros may be omitted.
+    /// Returns Result<GTIN, String> indicating success.
+    pub fn from_digits(digits: &[u8]) -> Result<GTIN, String> {
+        if digits.len() > GTIN_LEN || digits.iter().any(|&d| d > 9) {
+            return Err("GTINs have at most 13 digits before the check digit".to_string());
+        }
+
+        let mut data = vec![0; GTIN_LEN - digits.len()];
+        data.extend(digits);
+
+        Ok(GTIN{data})
+    }
+
+    /// Returns the 13 digits of the normalized GTIN before the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the GTIN check digit using a modulo-10 weighting algorithm. The check digit
+    /// is the same for every length of the GTIN.
+    pub fn checksum_digit(&self) -> u8 {
+        helpers::modulo_10_checksum(&self.data)
+    }
+
+    /// Returns the packaging indicator (the first digit of the GTIN-14).
+    pub fn indicator_digit(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Returns true if the GTIN identifies a variable measure trade item.
+    pub fn is_variable_measure(&self) -> bool {
+        self.indicator_digit() == GTIN_VARIABLE_MEASURE
+    }
+
+    /// Returns the GTIN of a grouping of this trade item with the given packaging indicator
+    /// (1-8), or of the trade item itself for 0. The check digit is recalculated.
+    /// Returns Result<GTIN, String> indicating success.
+    pub fn with_indicator(&self, indicator: u8) -> Result<GTIN, String> {
+        if indicator >= GTIN_VARIABLE_MEASURE {
+            return Err(format!("Invalid packaging indicator: {}", indicator));
+        }
+
+        let mut data = self.data.clone();
+        data[0] = indicator;
+
+        Ok(GTIN{data})
+    }
+
+    // Returns the last `len` digits of the GTIN, including the check digit, if the digits
+    // before them are all zero.
+    fn digits(&self, len: usize) -> Option<String> {
+        let (zeros, digits) = self.data.split_at(GTIN_LEN + 1 - len);
+
+        if zeros.iter().any(|&d| d != 0) {
+            return None;
+        }
+
+        Some(digits.iter()
+             .chain([self.checksum_digit()].iter())
+             .map(|&d| char::from_digit(d as u32, 10).unwrap())
+             .collect())
+    }
+
+    /// Returns the GTIN-14, including the check digit.
+    pub fn gtin14(&self) -> String {
+        self.digits(14).unwrap()
+    }
+
+    /// Returns the GTIN-13, including the check digit, if the packaging indicator is 0.
+    pub fn gtin13(&self) -> Option<String> {
+        self.digits(13)
+    }
+
+    /// Returns the GTIN-12, including the check digit, if the GTIN has two leading zeros.
+    pub fn gtin12(&self) -> Option<String> {
+        self.digits(12)
+    }
+
+    /// Returns the GTIN-8, including the check digit, if the GTIN has six leading zeros.
+    pub fn gtin8(&self) -> Option<String> {
+        self.digits(8)
+    }
+
+    /// Creates an EAN-13 barcode of the GTIN-13.
+    /// Returns Result<EAN13, String> indicating success.
+    pub fn ean13(&self) -> Result<EAN13, String> {
+        match self.gtin13() {
+            Some(digits) => EAN13::new(digits[..12].to_string()),
+            None => Err("GTIN does not fit in 13 digits".to_string()),
+        }
+    }
+
+    /// Creates an EAN-8 barcode of the GTIN-8.
+    /// Returns Result<EAN8, String> indicating success.
+    pub fn ean8(&self) -> Result<EAN8, String> {
+        match self.gtin8() {
+            Some(digits) => EAN8::new(digits[..7].to_string()),
+            None => Err("GTIN does not fit in 8 digits".to_string()),
+        }
+    }
+
+    /// Creates a UPC-A barcode of the GTIN-12.
+    /// Returns Result<UPCA, String> indicating success.
+    pub fn upca(&self) -> Result<UPCA, String> {
+        match self.gtin12() {
+            Some(digits) => UPCA::new(digits),
+            None => Err("GTIN does not fit in 12 digits".to_string()),
+        }
+    }
+
+    /// Creates an ITF-14 barcode of the GTIN-14.
+    /// Returns Result<ITF14, String> indicating success.
+    pub fn itf14(&self) -> Result<ITF14, String> {
+        ITF14::new(self.gtin14())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::gtin::*;
+
+    #[test]
+    fn new_gtin() {
+        assert!(GTIN::new("96385074".to_string()).is_ok());
+        assert!(GTIN::new("036000291452".to_string()).is_ok());
+        assert!(GTIN::new("4006381333931".to_string()).is_ok());
+        assert!(GTIN::new("10012345000017".to_string()).is_ok());
+    }
+
+    #[test]
+    fn invalid_data_gtin() {
+        assert!(GTIN::new("4006381333932".to_string()).is_err());
+        assert!(GTIN::new("40063813339A1".to_string()).is_err());
+        assert!(!is_valid("036000291453"));
+        assert!(is_valid("036000291452"));
+    }
+
+    #[test]
+    fn invalid_len_gtin() {
+        assert!(GTIN::new("0360002914".to_string()).is_err());
+        assert!(GTIN::from_digits(&[1; 14]).is_err());
+    }
+
+    #[test]
+    fn gtin_normalization() {
+        let gtin = GTIN::new("036000291452".to_string()).unwrap();
+
+        assert_eq!(gtin, GTIN::new("00036000291452".to_string()).unwrap());
+        assert_eq!(gtin, GTIN::from_digits(&[3, 6, 0, 0, 0, 2, 9, 1, 4, 5]).unwrap());
+        assert_eq!(gtin.gtin14(), "00036000291452");
+        assert_eq!(gtin.gtin13(), Some("0036000291452".to_string()));
+        assert_eq!(gtin.gtin12(), Some("036000291452".to_string()));
+        assert_eq!(gtin.gtin8(), None);
+        assert_eq!(GTIN::new("96385074".to_string()).unwrap().gtin14(), "00000096385074");
+    }
+
+    #[test]
+    fn gtin_indicator() {
+        let gtin = GTIN::new("0012345000010".to_string()).unwrap();
+        let case = gtin.with_indicator(1).unwrap();
+
+        assert_eq!(case.gtin14(), "10012345000017");
+        assert_eq!(case.indicator_digit(), 1);
+        assert_eq!(case.gtin13(), None);
+        assert_eq!(case.with_indicator(0).unwrap(), gtin);
+        assert!(gtin.with_indicator(9).is_err());
+        assert!(GTIN::from_digits(&[9, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0, 0, 1]).unwrap().is_variable_measure());
+    }
+
+    #[test]
+    fn gtin_barcodes() {
+        let gtin = GTIN::new("036000291452".to_string()).unwrap();
+
+        assert_eq!(gtin.upca().unwrap().encode(), UPCA::new("03600029145".to_string()).unwrap().encode());
+        assert_eq!(gtin.ean13().unwrap().encode(), EAN13::new("003600029145".to_string()).unwrap().encode());
+        assert_eq!(gtin.itf14().unwrap().encode(), ITF14::new("0003600029145".to_string()).unwrap().encode());
+        assert!(gtin.ean8().is_err());
+        assert!(GTIN::new("96385074".to_string()).unwrap().ean8().is_ok());
+    }
+}
@@ -1,6 +1,80 @@
 pub fn join_vecs(vecs: &[Vec<u8>]) -> Vec<u8> {
     vecs.iter()
-        .flat_map(|b| b.into_iter())
+        .flat_map(|b| b.iter())
         .cloned()
         .collect()
 }
+
+/// Calculates the GS1 modulo-10 check digit for the given digits (excluding the check digit).
+/// Digits are weighted 3 and 1 alternately, beginning with 3 at the rightmost digit.
+pub fn modulo_10_checksum(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { d as u32 * 3 } else { d as u32 })
+        .sum();
+
+    ((10 - (sum % 10)) % 10) as u8
+}
+
+/// Appends the `len` least significant bits of `value` to `bits`, most significant first.
+pub fn push_bits(bits: &mut Vec<u8>, value: u32, len: usize) {
+    bits.extend((0..len).rev().map(|i| ((value >> i) & 1) as u8));
+}
+
+/// Calculates Reed-Solomon error correction codewords for the given data over the Galois field
+/// GF(2^m) defined by the primitive polynomial `poly` (of degree m). The generator polynomial
+/// has the roots a^first_root, a^(first_root + 1), ..., a^(first_root + ecc_len - 1).
+/// Returns the error correction codewords, highest degree first.
+pub fn reed_solomon(data: &[u16], ecc_len: usize, poly: u32, first_root: usize) -> Vec<u16> {
+    if ecc_len == 0 {
+        return vec![];
+    }
+
+    let size = 1usize << (31 - poly.leading_zeros());
+    let mut exp = vec![0u16; size * 2];
+    let mut log = vec![0usize; size];
+    let mut x = 1u32;
+
+    for i in 0..(size - 1) {
+        exp[i] = x as u16;
+        exp[i + size - 1] = x as u16;
+        log[x as usize] = i;
+        x <<= 1;
+
+        if x & size as u32 != 0 {
+            x ^= poly;
+        }
+    }
+
+    let mul = |a: u16, b: u16| if a == 0 || b == 0 { 0 } else { exp[log[a as usize] + log[b as usize]] };
+
+    // The generator polynomial, highest degree first.
+    let mut generator = vec![1u16];
+
+    for i in 0..ecc_len {
+        let root = exp[(first_root + i) % (size - 1)];
+        let mut next = generator.clone();
+        next.push(0);
+
+        for (j, &c) in generator.iter().enumerate() {
+            next[j + 1] ^= mul(c, root);
+        }
+
+        generator = next;
+    }
+
+    let mut remainder = vec![0u16; ecc_len];
+
+    for &d in data {
+        let factor = d ^ remainder[0];
+        remainder.remove(0);
+        remainder.push(0);
+
+        for (r, &g) in remainder.iter_mut().zip(generator[1..].iter()) {
+            *r ^= mul(factor, g);
+        }
+    }
+
+    remainder
+}
@@ -0,0 +1,183 @@
+//! This module provides types for encoding HIBC LIC (Health Industry Bar Code, Labeler
+//! Identification Code) barcodes, which identify medical devices and supplies.
+//!
+//! The primary data structure holds the labeler identification code, the product or catalogue
+//! number and the unit of measure. The secondary data structure holds the quantity, expiry date
+//! and lot or serial number in one of the HIBC formats (such as "$$3" followed by an expiry date
+//! and lot number), and is linked to its primary data structure by a link character. The two
+//! may also be concatenated into one symbol, separated by a '/'.
+//!
+//! Each data structure begins with the '+' flag character and ends with a modulo-43 check
+//! character, and is encoded as a Code39 or Code128 barcode.
+
+use ::sym::EncodedBarcode;
+use ::sym::code39::{Code39, CODE39_CHARS};
+use ::sym::code128::Code128;
+
+/// The flag character that begins every HIBC data structure.
+pub const HIBC_FLAG: char = '+';
+
+/// The character that separates the primary and secondary data of a concatenated data
+/// structure.
+pub const HIBC_SEPARATOR: char = '/';
+
+// Calculates the modulo-43 check character of the given data, using the values of the Code39
+// characters.
+fn check_char(data: &str) -> char {
+    let sum: usize = data.chars()
+        .map(|c| CODE39_CHARS.iter().position(|t| t.0 == c).unwrap())
+        .sum();
+
+    CODE39_CHARS[sum % CODE39_CHARS.len()].0
+}
+
+// Returns an error if the data includes characters that are not Code39 characters.
+fn validate_chars(data: &str, allowed: &dyn Fn(char) -> bool) -> Result<(), String> {
+    match data.chars().find(|&c| !allowed(c) || !CODE39_CHARS.iter().any(|t| t.0 == c)) {
+        Some(c) => Err(format!("Invalid character: {}", c)),
+        None => Ok(()),
+    }
+}
+
+/// The HIBC barcode type.
+pub struct HIBC {
+    data: String,
+}
+
+impl HIBC {
+    /// Creates a new barcode of a primary data structure from a labeler identification code
+    /// (4 characters, the first a letter), a product or catalogue number (1-18 letters and
+    /// digits) and a unit of measure digit (0 for the lowest level of packaging).
+    /// Returns Result<HIBC, String> indicating success.
+    pub fn primary(labeler: &str, product: &str, unit_of_measure: u8) -> Result<HIBC, String> {
+        if labeler.len() != 4 || !labeler.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return Err("Labeler identification code must be 4 characters beginning with a letter".to_string());
+        }
+
+        if product.is_empty() || product.len() > 18 {
+            return Err("Product or catalogue number must be 1-18 characters".to_string());
+        }
+
+        if unit_of_measure > 9 {
+            return Err(format!("Invalid unit of measure: {}", unit_of_measure));
+        }
+
+        let alphanumeric = |c: char| c.is_ascii_uppercase() || c.is_ascii_digit();
+        validate_chars(labeler, &alphanumeric)?;
+        validate_chars(product, &alphanumeric)?;
+
+        Ok(HIBC{data: format!("{}{}{}{}", HIBC_FLAG, labeler, product, unit_of_measure)})
+    }
+
+    /// Creates a new barcode of a secondary data structure, such as "$$3250701LOT123", linked
+    /// to the given primary data structure by its check character.
+    /// Returns Result<HIBC, String> indicating success.
+    pub fn secondary(data: &str, primary: &HIBC) -> Result<HIBC, String> {
+        HIBC::validate_secondary(data)?;
+
+        Ok(HIBC{data: format!("{}{}{}", HIBC_FLAG, data, primary.check_char())})
+    }
+
+    /// Creates a new barcode of a concatenated data structure, holding the primary data
+    /// structure and the given secondary data with a single check character.
+    /// Returns Result<HIBC, String> indicating success.
+    pub fn concatenated(primary: &HIBC, secondary: &str) -> Result<HIBC, String> {
+        HIBC::validate_secondary(secondary)?;
+
+        Ok(HIBC{data: format!("{}{}{}", primary.data, HIBC_SEPARATOR, secondary)})
+    }
+
+    fn validate_secondary(data: &str) -> Result<(), String> {
+        if data.is_empty() {
+            return Err("Secondary data must not be empty".to_string());
+        }
+
+        validate_chars(data, &|c| c != HIBC_FLAG)
+    }
+
+    /// Returns the data structure, beginning with the flag character, without the check
+    /// character.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Calculates the check character using a modulo-43 algorithm.
+    pub fn check_char(&self) -> char {
+        check_char(&self.data)
+    }
+
+    /// Returns the Code39 barcode of the data structure, with its check character.
+    pub fn code39(&self) -> Code39 {
+        Code39::with_checksum(self.data.clone()).unwrap()
+    }
+
+    /// Returns the Code128 barcode of the data structure, with its check character.
+    pub fn code128(&self) -> Code128 {
+        Code128::auto(format!("{}{}", self.data, self.check_char())).unwrap()
+    }
+
+    /// Encodes the barcode as Code39.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code39().encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::hibc::*;
+
+    #[test]
+    fn new_hibc() {
+        let primary = HIBC::primary("A123", "BJC5D6E7", 1);
+
+        assert!(primary.is_ok());
+        assert!(HIBC::secondary("$$3250701LOT123", &primary.unwrap()).is_ok());
+    }
+
+    #[test]
+    fn invalid_data_hibc() {
+        assert!(HIBC::primary("1123", "BJC5D6E7", 1).is_err());
+        assert!(HIBC::primary("A123", "BJC5-6E7", 1).is_err());
+        assert!(HIBC::primary("A123", "bjc5d6e7", 1).is_err());
+        assert!(HIBC::primary("A123", "BJC5D6E7", 10).is_err());
+
+        let primary = HIBC::primary("A123", "BJC5D6E7", 1).unwrap();
+
+        assert!(HIBC::secondary("$$3250701+LOT", &primary).is_err());
+        assert!(HIBC::concatenated(&primary, "$$3250701lot").is_err());
+    }
+
+    #[test]
+    fn invalid_len_hibc() {
+        assert!(HIBC::primary("A12", "BJC5D6E7", 1).is_err());
+        assert!(HIBC::primary("A123", "", 1).is_err());
+        assert!(HIBC::primary("A123", &"1".repeat(19), 1).is_err());
+    }
+
+    #[test]
+    fn hibc_check_char() {
+        let primary = HIBC::primary("A123", "BJC5D6E7", 1).unwrap();
+
+        assert_eq!(primary.raw_data(), "+A123BJC5D6E71");
+        assert_eq!(primary.check_char(), 'G');
+    }
+
+    #[test]
+    fn hibc_linked_structures() {
+        let primary = HIBC::primary("A123", "BJC5D6E7", 1).unwrap();
+        let secondary = HIBC::secondary("$$3250701LOT123", &primary).unwrap();
+        let concatenated = HIBC::concatenated(&primary, "$$3250701LOT123").unwrap();
+
+        assert_eq!(secondary.raw_data(), "+$$3250701LOT123G");
+        assert_eq!(concatenated.raw_data(), "+A123BJC5D6E71/$$3250701LOT123");
+    }
+
+    #[test]
+    fn hibc_encode() {
+        let primary = HIBC::primary("A123", "BJC5D6E7", 1).unwrap();
+
+        assert_eq!(primary.encode(), Code39::new("+A123BJC5D6E71G".to_string()).unwrap().encode());
+        assert_eq!(primary.code128().encode(), Code128::auto("+A123BJC5D6E71G".to_string()).unwrap().encode());
+    }
+}
@@ -0,0 +1,309 @@
+//! This module provides types for encoding Intelligent Mail (IMb) barcodes. The Intelligent Mail
+//! barcode is a 4-state barcode used by the United States Postal Service, replacing POSTNET and
+//! PLANET.
+//!
+//! The data consists of a 20-digit tracking code followed by an optional 5, 9 or 11-digit
+//! routing (ZIP) code. It is converted into a single binary value, protected by an 11-bit CRC
+//! and split into ten codewords. Each codeword is mapped to a 13-bit character and the bits of
+//! the characters are spread across the ascenders and descenders of 65 bars.
+
+use ::sym::Parse;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The number of bars in an Intelligent Mail barcode.
+pub const IMB_BARS: usize = 65;
+
+/// The length of the tracking code.
+const IMB_TRACKING_LEN: usize = 20;
+
+/// The valid lengths of the routing code.
+const IMB_ROUTING_LENGTHS: [usize; 4] = [0, 5, 9, 11];
+
+/// The generator polynomial of the 11-bit CRC.
+const IMB_CRC_POLYNOMIAL: u16 = 0x0F35;
+
+/// The bar-to-character mapping. For each character (A-J) and each of its 13 bits, gives the
+/// position (1-130) of that bit amongst the bars. Positions 1-65 are the descenders of bars
+/// 1-65 and positions 66-130 are their ascenders.
+const IMB_BAR_POSITIONS: [u8; 130] = [
+    67, 6, 78, 16, 86, 95, 34, 40, 45, 113, 117, 121, 62,
+    87, 18, 104, 41, 76, 57, 119, 115, 72, 97, 2, 127, 26,
+    105, 35, 122, 52, 114, 7, 24, 82, 68, 63, 94, 44, 77,
+    112, 70, 100, 39, 30, 107, 15, 125, 85, 10, 65, 54, 88,
+    20, 106, 46, 66, 8, 116, 29, 61, 99, 80, 90, 37, 123,
+    51, 25, 84, 129, 56, 4, 109, 96, 28, 36, 47, 11, 71,
+    33, 102, 21, 9, 17, 49, 124, 79, 64, 91, 42, 69, 53,
+    60, 14, 1, 27, 103, 126, 75, 89, 50, 120, 19, 32, 110,
+    92, 111, 130, 59, 31, 12, 81, 43, 55, 5, 74, 22, 101,
+    128, 58, 118, 48, 108, 38, 98, 93, 23, 83, 13, 73, 3,
+];
+
+/// The Intelligent Mail barcode type.
+pub struct IntelligentMail {
+    data: Vec<u8>,
+}
+
+impl IntelligentMail {
+    /// Creates a new barcode. The data must be a 20-digit tracking code optionally followed by
+    /// a 5, 9 or 11-digit routing code. The second digit of the tracking code must be 0-4.
+    /// Returns Result<IntelligentMail, String> indicating parse success.
+    pub fn new(data: String) -> Result<IntelligentMail, String> {
+        let digits: Vec<u8> = IntelligentMail::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        let routing_len = digits.len().saturating_sub(IMB_TRACKING_LEN);
+
+        if digits.len() < IMB_TRACKING_LEN || !IMB_ROUTING_LENGTHS.contains(&routing_len) {
+            return Err(format!("Data must be 20, 25, 29 or 31 digits long, not {}", digits.len()));
+        }
+
+        if digits[1] > 4 {
+            return Err(format!("Invalid barcode identifier: {}{}", digits[0], digits[1]));
+        }
+
+        Ok(IntelligentMail{data: digits})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Returns the 20-digit tracking code.
+    pub fn tracking_code(&self) -> &[u8] {
+        &self.data[..IMB_TRACKING_LEN]
+    }
+
+    /// Returns the routing code, which may be empty.
+    pub fn routing_code(&self) -> &[u8] {
+        &self.data[IMB_TRACKING_LEN..]
+    }
+
+    // Converts the routing and tracking codes into a single 102-bit binary value.
+    fn binary_value(&self) -> u128 {
+        let routing = self.routing_code().iter()
+            .fold(0u128, |acc, &d| acc * 10 + d as u128);
+
+        let mut value = match self.routing_code().len() {
+            0 => 0,
+            5 => routing + 1,
+            9 => routing + 100_001,
+            _ => routing + 1_000_100_001,
+        };
+
+        let tracking = self.tracking_code();
+        value = value * 10 + tracking[0] as u128;
+        value = value * 5 + tracking[1] as u128;
+
+        tracking[2..].iter().fold(value, |acc, &d| acc * 10 + d as u128)
+    }
+
+    /// Calculates the 11-bit CRC (frame check sequence) of the binary value.
+    pub fn checksum_value(&self) -> u16 {
+        let bytes = self.binary_value().to_be_bytes();
+        let mut fcs: u16 = 0x07FF;
+
+        // The binary value occupies 102 bits of 13 bytes, so the 2 most significant bits of the
+        // first byte are skipped.
+        for (i, &byte) in bytes[3..].iter().enumerate() {
+            let (mut data, bits) = if i == 0 { ((byte as u16) << 5, 6) } else { ((byte as u16) << 3, 8) };
+
+            for _ in 0..bits {
+                fcs = if (fcs ^ data) & 0x400 != 0 { (fcs << 1) ^ IMB_CRC_POLYNOMIAL } else { fcs << 1 };
+                fcs &= 0x7FF;
+                data <<= 1;
+            }
+        }
+
+        fcs
+    }
+
+    /// Returns the ten codewords (A-J) of the barcode.
+    pub fn codewords(&self) -> [u16; 10] {
+        let mut value = self.binary_value();
+        let mut codewords = [0u16; 10];
+
+        codewords[9] = (value % 636) as u16 * 2;
+        value /= 636;
+
+        for i in (1..9).rev() {
+            codewords[i] = (value % 1365) as u16;
+            value /= 1365;
+        }
+
+        codewords[0] = value as u16;
+
+        if self.checksum_value() & 0x400 != 0 {
+            codewords[0] += 659;
+        }
+
+        codewords
+    }
+
+    // Builds the table of 13-bit characters with n bits set. Each character is followed by its
+    // bit-reversal, with palindromic characters placed at the end of the table.
+    fn n_of_13_table(n: u32, len: usize) -> Vec<u16> {
+        let mut table = vec![0; len];
+        let mut lower = 0;
+        let mut upper = len;
+
+        for count in 0..8192u16 {
+            if count.count_ones() != n {
+                continue;
+            }
+
+            let reverse = count.reverse_bits() >> 3;
+
+            if reverse < count {
+                continue;
+            }
+
+            if reverse == count {
+                upper -= 1;
+                table[upper] = count;
+            } else {
+                table[lower] = count;
+                table[lower + 1] = reverse;
+                lower += 2;
+            }
+        }
+
+        table
+    }
+
+    fn characters(&self) -> [u16; 10] {
+        let five_of_13 = IntelligentMail::n_of_13_table(5, 1287);
+        let two_of_13 = IntelligentMail::n_of_13_table(2, 78);
+        let fcs = self.checksum_value();
+        let mut characters = [0u16; 10];
+
+        for (i, &cw) in self.codewords().iter().enumerate() {
+            let c = if cw < 1287 { five_of_13[cw as usize] } else { two_of_13[cw as usize - 1287] };
+            characters[i] = if fcs & (1 << i) != 0 { c ^ 0x1FFF } else { c };
+        }
+
+        characters
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full, Ascender, Descender and Tracker bars.
+    pub fn encode(&self) -> EncodedBars {
+        let mut bits = [false; IMB_BARS * 2];
+
+        for (i, &c) in self.characters().iter().enumerate() {
+            for bit in 0..13 {
+                if c & (1 << bit) != 0 {
+                    bits[IMB_BAR_POSITIONS[i * 13 + bit] as usize - 1] = true;
+                }
+            }
+        }
+
+        (0..IMB_BARS).map(|i| {
+            match (bits[IMB_BARS + i], bits[i]) {
+                (true, true) => BarState::Full,
+                (true, false) => BarState::Ascender,
+                (false, true) => BarState::Descender,
+                (false, false) => BarState::Tracker,
+            }
+        }).collect()
+    }
+}
+
+impl Parse for IntelligentMail {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        20..=31
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::intelligent_mail::*;
+
+    fn collapse_bars(v: Vec<BarState>) -> String {
+        v.iter().map(|&b| match b {
+            BarState::Full => 'F',
+            BarState::Ascender => 'A',
+            BarState::Descender => 'D',
+            BarState::Tracker => 'T',
+        }).collect()
+    }
+
+    #[test]
+    fn new_intelligent_mail() {
+        let imb1 = IntelligentMail::new("01234567094987654321".to_string());
+        let imb2 = IntelligentMail::new("0123456709498765432101234567891".to_string());
+
+        assert!(imb1.is_ok());
+        assert!(imb2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_intelligent_mail() {
+        let imb1 = IntelligentMail::new("0123456709498765432A".to_string());
+        let imb2 = IntelligentMail::new("05234567094987654321".to_string());
+
+        assert!(imb1.is_err());
+        assert!(imb2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_intelligent_mail() {
+        let imb1 = IntelligentMail::new("0123456709498765432".to_string());
+        let imb2 = IntelligentMail::new("012345670949876543210123".to_string());
+        let imb3 = IntelligentMail::new("01234567094987654321012345678912".to_string());
+
+        assert!(imb1.is_err());
+        assert!(imb2.is_err());
+        assert!(imb3.is_err());
+    }
+
+    #[test]
+    fn intelligent_mail_raw_data() {
+        let imb = IntelligentMail::new("0123456709498765432101234".to_string()).unwrap();
+
+        assert_eq!(imb.tracking_code(), &[0,1,2,3,4,5,6,7,0,9,4,9,8,7,6,5,4,3,2,1]);
+        assert_eq!(imb.routing_code(), &[0,1,2,3,4]);
+    }
+
+    #[test]
+    fn intelligent_mail_checksum_calculation() {
+        let imb1 = IntelligentMail::new("01234567094987654321".to_string()).unwrap();
+        let imb2 = IntelligentMail::new("0123456709498765432101234567891".to_string()).unwrap();
+
+        assert_eq!(imb1.checksum_value(), 0x051);
+        assert_eq!(imb2.checksum_value(), 0x751);
+    }
+
+    #[test]
+    fn intelligent_mail_codewords() {
+        let imb1 = IntelligentMail::new("01234567094987654321".to_string()).unwrap();
+        let imb2 = IntelligentMail::new("0123456709498765432101234567891".to_string()).unwrap();
+
+        assert_eq!(imb1.codewords(), [0, 0, 0, 0, 559, 202, 508, 451, 124, 34]);
+        assert_eq!(imb2.codewords(), [673, 787, 607, 1022, 861, 19, 816, 1294, 35, 602]);
+    }
+
+    #[test]
+    fn intelligent_mail_encode() {
+        let imb1 = IntelligentMail::new("01234567094987654321".to_string()).unwrap();
+        let imb2 = IntelligentMail::new("0123456709498765432101234".to_string()).unwrap();
+        let imb3 = IntelligentMail::new("01234567094987654321012345678".to_string()).unwrap();
+        let imb4 = IntelligentMail::new("0123456709498765432101234567891".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(imb1.encode()), "ATTFATTDTTADTAATTDTDTATTDAFDDFADFDFTFFFFFTATFAAAATDFFTDAADFTFDTDT".to_string());
+        assert_eq!(collapse_bars(imb2.encode()), "DTTAFADDTTFTDTFTFDTDDADADAFADFATDDFTAAAFDTTADFAAATDFDTDFADDDTDFFT".to_string());
+        assert_eq!(collapse_bars(imb3.encode()), "ADFTTAFDTTTTFATTADTAAATFTFTATDAAAFDDADATATDTDTTDFDTDATADADTDFFTFA".to_string());
+        assert_eq!(collapse_bars(imb4.encode()), "AADTFFDFTDADTAADAATFDTDDAAADDTDTTDAFADADDDTFFFDDTTTADFAAADFTDAADA".to_string());
+    }
+}
@@ -0,0 +1,210 @@
+//! This module provides types for encoding ISBNs (International Standard Book Numbers) as
+//! Bookland EAN-13 barcodes.
+//!
+//! Both 10-digit ISBNs (whose check character may be 'X') and 13-digit ISBNs are accepted, with
+//! or without hyphens or spaces between their parts. 10-digit ISBNs are converted to 13 digits
+//! by prefixing 978. An EAN-5 supplement giving the price, such as "51995" for USD 19.95, may be
+//! added to the right of the main symbol.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::ean13::EAN13;
+use ::sym::ean_supp::{EANSUPP, EANSUPP_GAP};
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The EAN-13 prefix of 10-digit ISBNs.
+pub const ISBN_PREFIX: &str = "978";
+
+/// The EAN-13 prefix of ISBNs issued since 10-digit ISBNs ran out.
+pub const ISBN_PREFIX_979: &str = "979";
+
+// Calculates the modulo-11 check value of the first 9 digits of an ISBN-10, weighted from 10
+// down to 2. A value of 10 is written as 'X'.
+fn isbn10_checksum(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * (10 - i as u32))
+        .sum();
+
+    ((11 - sum % 11) % 11) as u8
+}
+
+/// The ISBN barcode type.
+pub struct ISBN {
+    data: String,
+    ean13: EAN13,
+    price: Option<EANSUPP>,
+}
+
+impl ISBN {
+    /// Creates a new barcode from a 10- or 13-digit ISBN, whose check digit will be verified.
+    /// Returns Result<ISBN, String> indicating parse success.
+    pub fn new(data: String) -> Result<ISBN, String> {
+        let isbn = ISBN::parse(data)?;
+        let chars: Vec<char> = isbn.chars().filter(|&c| c != '-' && c != ' ').collect();
+        let digits: Vec<u8> = chars.iter().filter_map(|c| c.to_digit(10)).map(|d| d as u8).collect();
+
+        let ean13 = match chars.len() {
+            10 if chars[..9].iter().all(|c| c.is_ascii_digit()) => {
+                let check = isbn10_checksum(&digits[..9]);
+                let expected = if check == 10 { 'X' } else { char::from_digit(check as u32, 10).unwrap() };
+
+                if chars[9].to_ascii_uppercase() != expected {
+                    return Err(format!("Invalid check digit: {}", chars[9]));
+                }
+
+                let data: String = digits[..9].iter().map(|&d| char::from_digit(d as u32, 10).unwrap()).collect();
+                EAN13::new(format!("{}{}", ISBN_PREFIX, data))?
+            },
+            13 if digits.len() == 13 => {
+                let prefix: String = chars[..3].iter().collect();
+
+                if prefix != ISBN_PREFIX && prefix != ISBN_PREFIX_979 {
+                    return Err(format!("ISBN-13 must begin with {} or {}", ISBN_PREFIX, ISBN_PREFIX_979));
+                }
+
+                let ean13 = EAN13::new(chars[..12].iter().collect())?;
+
+                if ean13.checksum_digit() != digits[12] {
+                    return Err(format!("Invalid check digit: {}", digits[12]));
+                }
+
+                ean13
+            },
+            _ => return Err("ISBN must have 10 or 13 digits".to_string()),
+        };
+
+        Ok(ISBN{data: isbn, ean13, price: None})
+    }
+
+    /// Creates a new barcode from a 10- or 13-digit ISBN and a 5-digit price supplement.
+    /// Returns Result<ISBN, String> indicating parse success.
+    pub fn with_price(data: String, price: String) -> Result<ISBN, String> {
+        if price.len() != 5 {
+            return Err("Price supplement must have 5 digits".to_string());
+        }
+
+        let price = EANSUPP::new(price)?;
+
+        ISBN::new(data).map(|isbn| ISBN{price: Some(price), ..isbn})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the 13-digit ISBN, including its check digit.
+    pub fn isbn13(&self) -> String {
+        self.ean13.raw_data().iter()
+            .chain([self.ean13.checksum_digit()].iter())
+            .map(|&d| char::from_digit(d as u32, 10).unwrap())
+            .collect()
+    }
+
+    /// Returns the 10-digit ISBN, including its check character, for ISBNs beginning with
+    /// 978.
+    pub fn isbn10(&self) -> Option<String> {
+        let isbn13 = self.isbn13();
+
+        if !isbn13.starts_with(ISBN_PREFIX) {
+            return None;
+        }
+
+        let digits = &self.ean13.raw_data()[3..12];
+        let check = match isbn10_checksum(digits) {
+            10 => 'X',
+            n => char::from_digit(n as u32, 10).unwrap(),
+        };
+
+        Some(isbn13[3..12].chars().chain(Some(check)).collect())
+    }
+
+    /// Returns the EAN-13 barcode of the ISBN.
+    pub fn ean13(&self) -> &EAN13 {
+        &self.ean13
+    }
+
+    /// Returns the EAN-5 price supplement, if any.
+    pub fn price(&self) -> Option<&EANSUPP> {
+        self.price.as_ref()
+    }
+
+    /// Encodes the barcode, followed by the price supplement if there is one.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        match self.price {
+            Some(ref price) => helpers::join_vecs(&[self.ean13.encode(), vec![0; EANSUPP_GAP], price.encode()][..]),
+            None => self.ean13.encode(),
+        }
+    }
+}
+
+impl Parse for ISBN {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        10..=17
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect();
+        chars.extend(['X', 'x', '-', ' '].iter().cloned());
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::isbn::*;
+
+    #[test]
+    fn new_isbn() {
+        let isbn10 = ISBN::new("0-306-40615-2".to_string());
+        let isbn13 = ISBN::new("978-3-16-148410-0".to_string());
+        let isbn10_x = ISBN::new("0 8044 2957 X".to_string());
+
+        assert!(isbn10.is_ok());
+        assert!(isbn13.is_ok());
+        assert!(isbn10_x.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_isbn() {
+        assert!(ISBN::new("0-306-40615-3".to_string()).is_err());
+        assert!(ISBN::new("978-3-16-148410-1".to_string()).is_err());
+        assert!(ISBN::new("977-3-16-148410-0".to_string()).is_err());
+        assert!(ISBN::new("03064061X2".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_len_isbn() {
+        assert!(ISBN::new("0-306-4061".to_string()).is_err());
+        assert!(ISBN::with_price("0-306-40615-2".to_string(), "519".to_string()).is_err());
+    }
+
+    #[test]
+    fn isbn_conversion() {
+        let isbn = ISBN::new("0-306-40615-2".to_string()).unwrap();
+        let isbn_979 = ISBN::new("979-10-90636-07-1".to_string()).unwrap();
+
+        assert_eq!(isbn.isbn13(), "9780306406157");
+        assert_eq!(isbn.isbn10(), Some("0306406152".to_string()));
+        assert_eq!(ISBN::new("9780804429573".to_string()).unwrap().isbn10(), Some("080442957X".to_string()));
+        assert_eq!(isbn_979.isbn10(), None);
+    }
+
+    #[test]
+    fn isbn_encode() {
+        let isbn = ISBN::new("0-306-40615-2".to_string()).unwrap();
+        let priced = ISBN::with_price("0-306-40615-2".to_string(), "51995".to_string()).unwrap();
+        let ean13 = EAN13::new("978030640615".to_string()).unwrap();
+        let price = EANSUPP::new("51995".to_string()).unwrap();
+
+        assert_eq!(isbn.encode(), ean13.encode());
+        assert_eq!(priced.encode().len(), 95 + EANSUPP_GAP + 47);
+        assert_eq!(&priced.encode()[104..], &price.encode()[..]);
+    }
+}
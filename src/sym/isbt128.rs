@@ -0,0 +1,201 @@
+//! This module provides types for encoding ISBT 128 data structures, which identify blood,
+//! cell, tissue and organ products, as Code128 barcodes.
+//!
+//! This module supports:
+//!   * The donation identification number (DIN): the facility identification number, the year
+//!     and a sequence number, followed by two flag characters.
+//!   * The product code: a product description code, the type of donation and two division
+//!     characters.
+//!
+//! Each data structure begins with its data identifier characters, which are inserted
+//! automatically. The keyboard entry check character, printed beside the human readable text
+//! rather than encoded, is calculated with the ISO 7064 modulo 37-2 algorithm.
+
+use ::sym::EncodedBarcode;
+use ::sym::code128::Code128;
+
+/// The characters with values 0-36 in the ISO 7064 modulo 37-2 algorithm.
+pub const ISO7064_MOD37_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ*";
+
+/// The data identifier of the donation identification number. Its second character is the
+/// first character of the DIN itself.
+pub const ISBT128_DIN_IDENTIFIER: &str = "=";
+
+/// The data identifier of the product code.
+pub const ISBT128_PRODUCT_CODE_IDENTIFIER: &str = "=<";
+
+/// Flag values of 60-96 carry the value of the DIN's check character plus this offset.
+pub const ISBT128_CHECK_FLAG_OFFSET: u8 = 60;
+
+/// Calculates the value (0-36) of the ISO 7064 modulo 37-2 check character of the given
+/// digits and upper-case letters.
+pub fn check_value(data: &str) -> u8 {
+    let p = data.chars().fold(0, |p, c| {
+        ((p + ISO7064_MOD37_CHARS.find(c).expect("Unknown character")) * 2) % 37
+    });
+
+    ((38 - p) % 37) as u8
+}
+
+/// Calculates the ISO 7064 modulo 37-2 check character of the given digits and upper-case
+/// letters.
+pub fn check_char(data: &str) -> char {
+    ISO7064_MOD37_CHARS.as_bytes()[check_value(data) as usize] as char
+}
+
+fn is_alphanumeric(c: char) -> bool {
+    c.is_ascii_digit() || c.is_ascii_uppercase()
+}
+
+/// The ISBT 128 barcode type.
+pub struct ISBT128 {
+    data: String,
+    identifier: &'static str,
+    code128: Code128,
+}
+
+impl ISBT128 {
+    /// Creates a new barcode of a donation identification number from the 13 characters of
+    /// the DIN (a 5 character facility identification number, a 2 digit year and a 6 digit
+    /// sequence number) and the 2 digit flag value.
+    /// Returns Result<ISBT128, String> indicating success.
+    pub fn donation_identification(din: &str, flags: u8) -> Result<ISBT128, String> {
+        if din.len() != 13 {
+            return Err("Donation identification numbers must be 13 characters".to_string());
+        }
+
+        let (facility, number) = din.split_at(5);
+
+        if !facility.starts_with(|c: char| is_alphanumeric(c) && c != 'O' && c != '0') ||
+           !facility[1..].chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid facility identification number: {}", facility));
+        }
+
+        if !number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid year and sequence number: {}", number));
+        }
+
+        if flags > 99 {
+            return Err(format!("Invalid flags: {}", flags));
+        }
+
+        ISBT128::build(format!("{}{:02}", din, flags), ISBT128_DIN_IDENTIFIER)
+    }
+
+    /// Creates a new barcode of a donation identification number whose flags carry its check
+    /// character.
+    /// Returns Result<ISBT128, String> indicating success.
+    pub fn donation_identification_with_check(din: &str) -> Result<ISBT128, String> {
+        ISBT128::donation_identification(din, 0)?;
+
+        ISBT128::donation_identification(din, ISBT128_CHECK_FLAG_OFFSET + check_value(din))
+    }
+
+    /// Creates a new barcode of a product code from its 8 characters: the product description
+    /// code (a letter and 4 digits), the type of donation and the two division characters.
+    /// Returns Result<ISBT128, String> indicating success.
+    pub fn product_code(code: &str) -> Result<ISBT128, String> {
+        if code.len() != 8 {
+            return Err("Product codes must be 8 characters".to_string());
+        }
+
+        let (description, rest) = code.split_at(5);
+
+        if !description.starts_with(|c: char| c.is_ascii_uppercase()) ||
+           !description[1..].chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid product description code: {}", description));
+        }
+
+        if !rest.chars().all(is_alphanumeric) {
+            return Err(format!("Invalid type of donation or divisions: {}", rest));
+        }
+
+        ISBT128::build(code.to_string(), ISBT128_PRODUCT_CODE_IDENTIFIER)
+    }
+
+    fn build(data: String, identifier: &'static str) -> Result<ISBT128, String> {
+        let code128 = Code128::auto(format!("{}{}", identifier, data))?;
+
+        Ok(ISBT128{data, identifier, code128})
+    }
+
+    /// Returns the data of the data structure, without its data identifier.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the data identifier of the data structure.
+    pub fn identifier(&self) -> &str {
+        self.identifier
+    }
+
+    /// Calculates the keyboard entry check character, over the data without the flags of a
+    /// donation identification number.
+    pub fn keyboard_check_char(&self) -> char {
+        match self.identifier {
+            ISBT128_DIN_IDENTIFIER => check_char(&self.data[..13]),
+            _ => check_char(&self.data),
+        }
+    }
+
+    /// Returns the Code128 barcode of the data structure.
+    pub fn code128(&self) -> &Code128 {
+        &self.code128
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code128.encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::isbt128::*;
+
+    #[test]
+    fn new_isbt128() {
+        assert!(ISBT128::donation_identification("W000007123456", 0).is_ok());
+        assert!(ISBT128::product_code("E0001V00").is_ok());
+    }
+
+    #[test]
+    fn invalid_data_isbt128() {
+        assert!(ISBT128::donation_identification("O000007123456", 0).is_err());
+        assert!(ISBT128::donation_identification("W0000071234X6", 0).is_err());
+        assert!(ISBT128::donation_identification("W000007123456", 100).is_err());
+        assert!(ISBT128::product_code("00001V00").is_err());
+        assert!(ISBT128::product_code("E0001v00").is_err());
+    }
+
+    #[test]
+    fn invalid_len_isbt128() {
+        assert!(ISBT128::donation_identification("W00000712345", 0).is_err());
+        assert!(ISBT128::product_code("E0001V0").is_err());
+    }
+
+    #[test]
+    fn isbt128_check_char() {
+        assert_eq!(check_char("G123498654321"), 'H');
+        assert_eq!(check_char("W000007123456"), 'D');
+        assert_eq!(ISBT128::donation_identification("W000007123456", 0).unwrap().keyboard_check_char(), 'D');
+    }
+
+    #[test]
+    fn isbt128_check_flags() {
+        let din = ISBT128::donation_identification_with_check("W000007123456").unwrap();
+
+        assert_eq!(din.raw_data(), "W00000712345673");
+    }
+
+    #[test]
+    fn isbt128_encode() {
+        let din = ISBT128::donation_identification("W000007123456", 0).unwrap();
+        let product = ISBT128::product_code("E0001V00").unwrap();
+
+        assert_eq!(din.encode(), Code128::auto("=W00000712345600".to_string()).unwrap().encode());
+        assert_eq!(product.identifier(), "=<");
+        assert_eq!(product.encode(), Code128::auto("=<E0001V00".to_string()).unwrap().encode());
+    }
+}
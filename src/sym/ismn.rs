@@ -0,0 +1,124 @@
+//! This module provides types for encoding ISMNs (International Standard Music Numbers) of
+//! printed music as EAN-13 barcodes.
+//!
+//! Both 10-character ISMNs beginning with 'M' and 13-digit ISMNs beginning with 979-0 are
+//! accepted, with or without hyphens or spaces between their parts. The 'M' stands for 979-0,
+//! so both forms have the same EAN-13 check digit, which is verified.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::ean13::EAN13;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The EAN-13 prefix of ISMNs, for which the 'M' of 10-character ISMNs stands.
+pub const ISMN_PREFIX: &str = "9790";
+
+/// The ISMN barcode type.
+pub struct ISMN {
+    data: String,
+    ean13: EAN13,
+}
+
+impl ISMN {
+    /// Creates a new barcode from a 10-character or 13-digit ISMN, whose check digit will be
+    /// verified.
+    /// Returns Result<ISMN, String> indicating parse success.
+    pub fn new(data: String) -> Result<ISMN, String> {
+        let ismn = ISMN::parse(data)?;
+        let chars: String = ismn.chars().filter(|&c| c != '-' && c != ' ').collect::<String>().to_uppercase();
+
+        let digits = match chars.len() {
+            10 if chars.starts_with('M') => format!("{}{}", ISMN_PREFIX, &chars[1..]),
+            13 if chars.starts_with(ISMN_PREFIX) => chars.clone(),
+            _ => return Err(format!("ISMN must be 'M' and 9 digits, or {} and 9 digits", ISMN_PREFIX)),
+        };
+
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err("ISMN must have only digits after its prefix".to_string());
+        }
+
+        let ean13 = EAN13::new(digits[..12].to_string())?;
+        let check = digits[12..].parse::<u8>().unwrap();
+
+        if ean13.checksum_digit() != check {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+
+        Ok(ISMN{data: ismn, ean13})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the 13-digit ISMN, including its check digit.
+    pub fn ismn13(&self) -> String {
+        self.ean13.raw_data().iter()
+            .chain([self.ean13.checksum_digit()].iter())
+            .map(|&d| char::from_digit(d as u32, 10).unwrap())
+            .collect()
+    }
+
+    /// Returns the EAN-13 barcode of the ISMN.
+    pub fn ean13(&self) -> &EAN13 {
+        &self.ean13
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.ean13.encode()
+    }
+}
+
+impl Parse for ISMN {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        10..=17
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect();
+        chars.extend(['M', 'm', '-', ' '].iter().cloned());
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::ismn::*;
+
+    #[test]
+    fn new_ismn() {
+        let ismn10 = ISMN::new("M-2306-7118-7".to_string());
+        let ismn13 = ISMN::new("979-0-2306-7118-7".to_string());
+
+        assert!(ismn10.is_ok());
+        assert!(ismn13.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_ismn() {
+        assert!(ISMN::new("M-2306-7118-8".to_string()).is_err());
+        assert!(ISMN::new("979-1-2306-7118-7".to_string()).is_err());
+        assert!(ISMN::new("9-2306-7118-7M".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_len_ismn() {
+        let ismn = ISMN::new("M-2306-7118".to_string());
+
+        assert!(ismn.is_err());
+    }
+
+    #[test]
+    fn ismn_conversion() {
+        let ismn = ISMN::new("m 2306 7118 7".to_string()).unwrap();
+
+        assert_eq!(ismn.ismn13(), "9790230671187");
+        assert_eq!(ismn.encode(), EAN13::new("979023067118".to_string()).unwrap().encode());
+    }
+}
@@ -0,0 +1,164 @@
+//! This module provides types for encoding ISSNs (International Standard Serial Numbers) of
+//! periodicals as EAN-13 barcodes with an EAN-2 issue supplement.
+//!
+//! The ISSN is given as 8 characters, with or without a hyphen between its halves, and its
+//! check character (which may be 'X') is verified. The EAN-13 is 977, the first 7 digits of the
+//! ISSN, and two variant digits (usually 00, but used by some publishers for price or edition
+//! variants). The EAN-2 supplement gives the issue number, such as the week or month.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::ean13::EAN13;
+use ::sym::ean_supp::{EANSUPP, join_supplement};
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The EAN-13 prefix of ISSNs.
+pub const ISSN_PREFIX: &str = "977";
+
+// Calculates the modulo-11 check value of the first 7 digits of an ISSN, weighted from 8 down
+// to 2. A value of 10 is written as 'X'.
+fn issn_checksum(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * (8 - i as u32))
+        .sum();
+
+    ((11 - sum % 11) % 11) as u8
+}
+
+/// The ISSN barcode type.
+pub struct ISSN {
+    data: Vec<u8>,
+    ean13: EAN13,
+    issue: EANSUPP,
+}
+
+impl ISSN {
+    /// Creates a new barcode from an ISSN, whose check character will be verified, and a
+    /// 2-digit issue number, with variant digits 00.
+    /// Returns Result<ISSN, String> indicating parse success.
+    pub fn new(data: String, issue: String) -> Result<ISSN, String> {
+        ISSN::with_variant(data, "00".to_string(), issue)
+    }
+
+    /// Creates a new barcode from an ISSN, whose check character will be verified, 2 variant
+    /// digits and a 2-digit issue number.
+    /// Returns Result<ISSN, String> indicating parse success.
+    pub fn with_variant(data: String, variant: String, issue: String) -> Result<ISSN, String> {
+        let chars: Vec<char> = ISSN::parse(data)?.chars().filter(|&c| c != '-').collect();
+
+        if chars.len() != 8 || !chars[..7].iter().all(|c| c.is_ascii_digit()) {
+            return Err("ISSN must have 7 digits and a check character".to_string());
+        }
+
+        let digits: Vec<u8> = chars[..7].iter().map(|c| c.to_digit(10).unwrap() as u8).collect();
+        let expected = match issn_checksum(&digits) {
+            10 => 'X',
+            n => char::from_digit(n as u32, 10).unwrap(),
+        };
+
+        if chars[7].to_ascii_uppercase() != expected {
+            return Err(format!("Invalid check character: {}", chars[7]));
+        }
+
+        if variant.len() != 2 || issue.len() != 2 {
+            return Err("Variant and issue must have 2 digits".to_string());
+        }
+
+        let ean13 = EAN13::new(format!("{}{}{}", ISSN_PREFIX, chars[..7].iter().collect::<String>(), variant))?;
+        let issue = EANSUPP::new(issue)?;
+
+        Ok(ISSN{data: digits, ean13, issue})
+    }
+
+    /// Returns the first 7 digits of the ISSN, without the check character.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check character using a modulo-11 algorithm with weights from 8 to 2.
+    /// Returns 'X' for a check value of 10.
+    pub fn checksum_char(&self) -> char {
+        match issn_checksum(&self.data) {
+            10 => 'X',
+            n => char::from_digit(n as u32, 10).unwrap(),
+        }
+    }
+
+    /// Returns the EAN-13 barcode of the ISSN.
+    pub fn ean13(&self) -> &EAN13 {
+        &self.ean13
+    }
+
+    /// Returns the EAN-2 issue supplement.
+    pub fn issue(&self) -> &EANSUPP {
+        &self.issue
+    }
+
+    /// Encodes the barcode, followed by the issue supplement.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        join_supplement(self.ean13.encode(), &self.issue)
+    }
+}
+
+impl Parse for ISSN {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        8..=9
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect();
+        chars.extend(['X', 'x', '-'].iter().cloned());
+        chars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::issn::*;
+    use ::sym::ean_supp::EANSUPP_GAP;
+
+    #[test]
+    fn new_issn() {
+        let issn = ISSN::new("0317-8471".to_string(), "03".to_string());
+        let issn_x = ISSN::new("2434561X".to_string(), "12".to_string());
+
+        assert!(issn.is_ok());
+        assert!(issn_x.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_issn() {
+        assert!(ISSN::new("0317-8472".to_string(), "03".to_string()).is_err());
+        assert!(ISSN::new("0317-847X".to_string(), "03".to_string()).is_err());
+        assert!(ISSN::new("0317-8471".to_string(), "0A".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_len_issn() {
+        assert!(ISSN::new("0317-847".to_string(), "03".to_string()).is_err());
+        assert!(ISSN::new("0317-8471".to_string(), "003".to_string()).is_err());
+    }
+
+    #[test]
+    fn issn_checksum_calculation() {
+        let issn = ISSN::new("0317-8471".to_string(), "03".to_string()).unwrap();
+
+        assert_eq!(issn.checksum_char(), '1');
+        assert_eq!(ISSN::new("2434-561X".to_string(), "03".to_string()).unwrap().checksum_char(), 'X');
+    }
+
+    #[test]
+    fn issn_encode() {
+        let issn = ISSN::with_variant("0317-8471".to_string(), "01".to_string(), "03".to_string()).unwrap();
+        let ean13 = EAN13::new("977031784701".to_string()).unwrap();
+        let issue = EANSUPP::new("03".to_string()).unwrap();
+
+        assert_eq!(issn.ean13().checksum_digit(), ean13.checksum_digit());
+        assert_eq!(issn.encode(), [ean13.encode(), vec![0; EANSUPP_GAP], issue.encode()].concat());
+    }
+}
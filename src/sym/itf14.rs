@@ -0,0 +1,184 @@
+//! This module provides types for encoding ITF-14 barcodes. ITF-14 is the GS1 implementation
+//! of Interleaved 2 of 5 used to mark shipping cartons with a 14-digit GTIN. ITF-14 symbols are
+//! usually printed on corrugated board and surrounded by bearer bars, which protect the symbol
+//! from partial scans and even out printing plate pressure.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use ::sym::two_of_five::I2of5;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The geometry of the bearer bars surrounding an ITF-14 symbol. All measurements are given
+/// in modules (multiples of the narrow bar width).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BearerBars {
+    /// The thickness of the bearer bars. GS1 requires at least twice the narrow bar width.
+    pub thickness: u32,
+    /// The width of the quiet zones between the symbol and the vertical bearer bars.
+    pub quiet_zone: u32,
+    /// Whether the bearer bars form a full frame around the symbol. When false only the
+    /// horizontal bars above and below the symbol are drawn.
+    pub frame: bool,
+}
+
+/// The default bearer bar geometry recommended by GS1 for symbols printed directly onto
+/// corrugated board.
+pub const ITF14_BEARER_BARS: BearerBars = BearerBars{thickness: 5, quiet_zone: 10, frame: true};
+
+/// The ITF-14 barcode type.
+pub struct ITF14 {
+    data: Vec<u8>,
+    bearer_bars: BearerBars,
+}
+
+impl ITF14 {
+    /// Creates a new barcode from a 13-digit GTIN (without check digit) or a 14-digit GTIN
+    /// (whose check digit will be verified).
+    /// Returns Result<ITF14, String> indicating parse success.
+    pub fn new(data: String) -> Result<ITF14, String> {
+        let d = ITF14::parse(data)?;
+        let mut digits: Vec<u8> = d.chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 14 {
+            let check = digits.pop().unwrap();
+
+            if helpers::modulo_10_checksum(&digits) != check {
+                return Err(format!("Invalid check digit: {}", check));
+            }
+        }
+
+        Ok(ITF14{data: digits, bearer_bars: ITF14_BEARER_BARS})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the GTIN check digit using a modulo-10 weighting algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        helpers::modulo_10_checksum(&self.data)
+    }
+
+    /// Returns the packaging indicator (the first digit of the GTIN-14).
+    pub fn indicator_digit(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Returns the bearer bar geometry that generators should draw around the symbol.
+    pub fn bearer_bars(&self) -> BearerBars {
+        self.bearer_bars
+    }
+
+    /// Sets the bearer bar geometry. The thickness must be at least 2 modules.
+    pub fn set_bearer_bars(&mut self, bearer_bars: BearerBars) -> Result<(), String> {
+        if bearer_bars.thickness < 2 {
+            return Err("Bearer bars must be at least 2 modules thick".to_string());
+        }
+
+        self.bearer_bars = bearer_bars;
+        Ok(())
+    }
+
+    fn gtin(&self) -> String {
+        self.data.iter()
+            .chain([self.checksum_digit()].iter())
+            .map(|&d| char::from_digit(d as u32, 10).unwrap())
+            .collect()
+    }
+
+    /// Encodes the barcode, including the check digit.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        I2of5::new(self.gtin()).expect("GTIN-14 is always valid I2of5").encode()
+    }
+}
+
+impl Parse for ITF14 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        13..=14
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::itf14::*;
+    use ::sym::two_of_five::I2of5;
+
+    #[test]
+    fn new_itf14() {
+        let itf141 = ITF14::new("1540014128876".to_string());
+        let itf142 = ITF14::new("15400141288763".to_string());
+
+        assert!(itf141.is_ok());
+        assert!(itf142.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_itf14() {
+        let itf14 = ITF14::new("154001412887A".to_string());
+
+        assert!(itf14.is_err());
+    }
+
+    #[test]
+    fn invalid_len_itf14() {
+        let itf141 = ITF14::new("123456789012".to_string());
+        let itf142 = ITF14::new("154001412887631".to_string());
+
+        assert!(itf141.is_err());
+        assert!(itf142.is_err());
+    }
+
+    #[test]
+    fn invalid_check_digit_itf14() {
+        let itf14 = ITF14::new("15400141288764".to_string());
+
+        assert!(itf14.is_err());
+    }
+
+    #[test]
+    fn itf14_raw_data() {
+        let itf14 = ITF14::new("15400141288763".to_string()).unwrap();
+
+        assert_eq!(itf14.raw_data(), &[1,5,4,0,0,1,4,1,2,8,8,7,6]);
+        assert_eq!(itf14.indicator_digit(), 1);
+    }
+
+    #[test]
+    fn itf14_checksum_calculation() {
+        let itf141 = ITF14::new("1540014128876".to_string()).unwrap(); // Check digit: 3
+        let itf142 = ITF14::new("0001234560001".to_string()).unwrap(); // Check digit: 2
+
+        assert_eq!(itf141.checksum_digit(), 3);
+        assert_eq!(itf142.checksum_digit(), 2);
+    }
+
+    #[test]
+    fn itf14_encode() {
+        let itf14 = ITF14::new("1540014128876".to_string()).unwrap();
+        let i2of5 = I2of5::new("15400141288763".to_string()).unwrap();
+
+        assert_eq!(itf14.encode(), i2of5.encode());
+    }
+
+    #[test]
+    fn itf14_bearer_bars() {
+        let mut itf14 = ITF14::new("1540014128876".to_string()).unwrap();
+
+        assert_eq!(itf14.bearer_bars(), ITF14_BEARER_BARS);
+        assert!(itf14.set_bearer_bars(BearerBars{thickness: 1, quiet_zone: 10, frame: false}).is_err());
+        assert!(itf14.set_bearer_bars(BearerBars{thickness: 3, quiet_zone: 10, frame: false}).is_ok());
+        assert_eq!(itf14.bearer_bars().thickness, 3);
+    }
+}
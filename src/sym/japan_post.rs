@@ -0,0 +1,202 @@
+//! This module provides types for encoding Japan Post customer barcodes. The customer barcode
+//! is a 4-state barcode printed on mail in Japan, encoding the 7-digit postal code followed by
+//! the numeric part of the address (block, building and room numbers).
+//!
+//! The address must already be converted to the form used by the barcode: digits, hyphens and
+//! the Latin letters A-Z. Each letter is converted to a control code followed by a digit:
+//!   * A-J: CC1 followed by 0-9
+//!   * K-T: CC2 followed by 0-9
+//!   * U-Z: CC3 followed by 0-5
+//!
+//! The converted data is truncated or padded with CC4 to 20 characters, and a modulo-19 check
+//! character is appended. Each character is encoded as three bars.
+
+use ::sym::Parse;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use std::ops::RangeInclusive;
+
+/// Japan Post barcodes begin with a full bar and a descender bar.
+pub const JAPAN_POST_START: [BarState; 2] = [BarState::Full, BarState::Descender];
+/// Japan Post barcodes end with a descender bar and a full bar.
+pub const JAPAN_POST_STOP: [BarState; 2] = [BarState::Descender, BarState::Full];
+
+/// The number of characters encoded (excluding the check character).
+const JAPAN_POST_LEN: usize = 20;
+
+/// The value of the hyphen character.
+const JAPAN_POST_HYPHEN: u8 = 10;
+
+/// The value of control code CC1. CC2-CC8 follow consecutively.
+const JAPAN_POST_CC1: u8 = 11;
+
+/// The value of control code CC4, used as padding.
+const JAPAN_POST_PADDING: u8 = 14;
+
+/// Encodings for each of the character values: the digits 0-9, the hyphen and the control
+/// codes CC1-CC8.
+const JAPAN_POST_ENCODINGS: [[BarState; 3]; 19] = [
+    [BarState::Full, BarState::Tracker, BarState::Tracker],
+    [BarState::Full, BarState::Full, BarState::Tracker],
+    [BarState::Full, BarState::Descender, BarState::Ascender],
+    [BarState::Descender, BarState::Full, BarState::Ascender],
+    [BarState::Full, BarState::Ascender, BarState::Descender],
+    [BarState::Full, BarState::Tracker, BarState::Full],
+    [BarState::Descender, BarState::Ascender, BarState::Full],
+    [BarState::Ascender, BarState::Full, BarState::Descender],
+    [BarState::Ascender, BarState::Descender, BarState::Full],
+    [BarState::Tracker, BarState::Full, BarState::Full],
+    [BarState::Tracker, BarState::Full, BarState::Tracker],
+    [BarState::Descender, BarState::Ascender, BarState::Tracker],
+    [BarState::Descender, BarState::Tracker, BarState::Ascender],
+    [BarState::Ascender, BarState::Descender, BarState::Tracker],
+    [BarState::Tracker, BarState::Descender, BarState::Ascender],
+    [BarState::Ascender, BarState::Tracker, BarState::Descender],
+    [BarState::Tracker, BarState::Ascender, BarState::Descender],
+    [BarState::Tracker, BarState::Tracker, BarState::Full],
+    [BarState::Full, BarState::Full, BarState::Full],
+];
+
+/// The Japan Post barcode type.
+pub struct JapanPost {
+    data: String,
+}
+
+impl JapanPost {
+    /// Creates a new barcode. The data must begin with the 7-digit postal code.
+    /// Returns Result<JapanPost, String> indicating parse success.
+    pub fn new(data: String) -> Result<JapanPost, String> {
+        let data = JapanPost::parse(data)?;
+
+        if let Some(c) = data.chars().take(7).find(|c| !c.is_ascii_digit()) {
+            return Err(format!("Invalid character in postal code: {}", c));
+        }
+
+        Ok(JapanPost{data})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the values of the 20 characters encoded, after converting letters to control
+    /// codes and padding.
+    pub fn values(&self) -> Vec<u8> {
+        let mut values: Vec<u8> = self.data.chars()
+            .flat_map(|c| match c {
+                '0'..='9' => vec![c as u8 - b'0'],
+                '-' => vec![JAPAN_POST_HYPHEN],
+                'A'..='J' => vec![JAPAN_POST_CC1, c as u8 - b'A'],
+                'K'..='T' => vec![JAPAN_POST_CC1 + 1, c as u8 - b'K'],
+                _ => vec![JAPAN_POST_CC1 + 2, c as u8 - b'U'],
+            })
+            .collect();
+
+        values.resize(JAPAN_POST_LEN, JAPAN_POST_PADDING);
+        values
+    }
+
+    /// Calculates the check character value, which brings the sum of all values to a multiple
+    /// of 19.
+    pub fn checksum_value(&self) -> u8 {
+        let sum: u32 = self.values().iter().map(|&v| v as u32).sum();
+
+        ((19 - (sum % 19)) % 19) as u8
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full, Ascender, Descender and Tracker bars.
+    pub fn encode(&self) -> EncodedBars {
+        let mut bars = JAPAN_POST_START.to_vec();
+
+        for &v in self.values().iter().chain(Some(self.checksum_value()).iter()) {
+            bars.extend(JAPAN_POST_ENCODINGS[v as usize].iter());
+        }
+
+        bars.extend(JAPAN_POST_STOP.iter());
+        bars
+    }
+}
+
+impl Parse for JapanPost {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        7..=41
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        "0123456789-ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::japan_post::*;
+
+    fn collapse_bars(v: Vec<BarState>) -> String {
+        v.iter().map(|&b| match b {
+            BarState::Full => 'F',
+            BarState::Ascender => 'A',
+            BarState::Descender => 'D',
+            BarState::Tracker => 'T',
+        }).collect()
+    }
+
+    #[test]
+    fn new_japan_post() {
+        let japan_post1 = JapanPost::new("1540023".to_string());
+        let japan_post2 = JapanPost::new("15400233-16-4-205".to_string());
+
+        assert!(japan_post1.is_ok());
+        assert!(japan_post2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_japan_post() {
+        let japan_post1 = JapanPost::new("154002a3-16".to_string());
+        let japan_post2 = JapanPost::new("15400A33-16".to_string());
+
+        assert!(japan_post1.is_err());
+        assert!(japan_post2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_japan_post() {
+        let japan_post = JapanPost::new("154002".to_string());
+
+        assert!(japan_post.is_err());
+    }
+
+    #[test]
+    fn japan_post_values() {
+        let japan_post1 = JapanPost::new("15400233-16-4-205".to_string()).unwrap();
+        let japan_post2 = JapanPost::new("6540123789-A-K-Z".to_string()).unwrap();
+        let japan_post3 = JapanPost::new("1234567ABCDEFGHIJ".to_string()).unwrap();
+
+        assert_eq!(japan_post1.values(), vec![1,5,4,0,0,2,3,3,10,1,6,10,4,10,2,0,5,14,14,14]);
+        assert_eq!(japan_post2.values(), vec![6,5,4,0,1,2,3,7,8,9,10,11,0,10,12,0,10,13,5,14]);
+        assert_eq!(japan_post3.values(), vec![1,2,3,4,5,6,7,11,0,11,1,11,2,11,3,11,4,11,5,11]);
+    }
+
+    #[test]
+    fn japan_post_checksum_calculation() {
+        let japan_post1 = JapanPost::new("15400233-16-4-205".to_string()).unwrap();
+        let japan_post2 = JapanPost::new("6540123789-A-K-Z".to_string()).unwrap();
+        let japan_post3 = JapanPost::new("1234567ABCDEFGHIJ".to_string()).unwrap();
+
+        assert_eq!(japan_post1.checksum_value(), 6);
+        assert_eq!(japan_post2.checksum_value(), 3);
+        assert_eq!(japan_post3.checksum_value(), 13);
+    }
+
+    #[test]
+    fn japan_post_encode() {
+        let japan_post1 = JapanPost::new("15400233-16-4-205".to_string()).unwrap();
+        let japan_post2 = JapanPost::new("6540123789-A-K-Z".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(japan_post1.encode()), "FDFFTFTFFADFTTFTTFDADFADFATFTFFTDAFTFTFADTFTFDAFTTFTFTDATDATDADAFDF".to_string());
+        assert_eq!(collapse_bars(japan_post2.encode()), "FDDAFFTFFADFTTFFTFDADFAAFDADFTFFTFTDATFTTTFTDTAFTTTFTADTFTFTDADFADF".to_string());
+    }
+}
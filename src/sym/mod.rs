@@ -1,25 +1,94 @@
 pub mod ean13;
 pub mod ean8;
+pub mod upca;
+pub mod upce;
 pub mod ean_supp;
+pub mod isbn;
+pub mod ismn;
+pub mod issn;
 pub mod code39;
+pub mod code32;
+pub mod pzn;
+pub mod hibc;
+pub mod vin;
+pub mod code93;
+pub mod code11;
+pub mod code128;
+pub mod code16k;
+pub mod isbt128;
+pub mod codabar;
+pub mod msi;
+pub mod plessey;
+pub mod telepen;
+pub mod channel;
+pub mod pharmacode;
+pub mod postnet;
+pub mod fim;
+pub mod intelligent_mail;
+pub mod rm4scc;
+pub mod auspost;
+pub mod japan_post;
+pub mod two_of_five;
+pub mod itf14;
+pub mod deutsche_post;
+pub mod databar;
+pub mod gs1_128;
+pub mod sscc;
+pub mod gtin;
 pub mod helpers;
 
-use std::ops::Range;
+use std::ops::RangeInclusive;
 use std::iter::Iterator;
 
 pub type EncodedBarcode = Vec<u8>;
 
+/// Marks the modules of a barcode whose bars extend below the human-readable text, such as the
+/// guard bars of EAN and UPC symbols: 1 for modules that extend and 0 for the others, in the
+/// same order as the EncodedBarcode.
+pub type EncodedGuards = Vec<u8>;
+
+/// The state of a single bar in a height-modulated barcode, such as a two-track or postal
+/// barcode. Each bar spans one or more of three sections: the upper (ascender), middle
+/// (tracker) and lower (descender) sections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarState {
+    /// The bar spans all three sections.
+    Full,
+    /// The bar spans the upper and middle sections.
+    Ascender,
+    /// The bar spans the middle and lower sections.
+    Descender,
+    /// The bar spans only the middle section.
+    Tracker,
+}
+
+/// A sequence of height-modulated bars. Adjacent bars are separated by a space of the same
+/// width as a bar.
+pub type EncodedBars = Vec<BarState>;
+
+/// A single row of a multi-row (stacked) barcode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedRow {
+    /// The binary digits of the row.
+    pub modules: EncodedBarcode,
+    /// The height of the row, in multiples of the X dimension.
+    pub height: u32,
+}
+
+/// The rows of a multi-row (stacked) barcode, from top to bottom.
+pub type EncodedRows = Vec<EncodedRow>;
+
 pub trait Parse {
     fn valid_chars() -> Vec<char>;
-    fn valid_len() -> Range<u32>;
+    fn valid_len() -> RangeInclusive<u32>;
 
     fn parse(data: String) -> Result<String, String> {
         let valid_chars = Self::valid_chars();
         let valid_len = Self::valid_len();
         let data_len = data.len() as u32;
 
-        if data_len < valid_len.start || data_len > valid_len.end {
-            return Err(format!("Data does not fit within range of {}-{}", valid_len.start, valid_len.end - 1));
+        if !valid_len.contains(&data_len) {
+            return Err(format!("Data does not fit within range of {}-{}", valid_len.start(), valid_len.end()));
         }
 
         let bad_char = data.chars().find(|&c| valid_chars.iter().find(|&vc| *vc == c).is_none());
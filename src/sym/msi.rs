@@ -0,0 +1,216 @@
+//! This module provides types for encoding MSI (Modified Plessey) barcodes. MSI is a numeric-only
+//! symbology used mainly for inventory control and shelf labelling in retail stores.
+//!
+//! Each digit is encoded as four bits (binary-coded decimal), most significant bit first.
+//! One of several check digit schemes can be chosen when the barcode is created.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// MSI barcodes begin with a wide bar and a narrow space.
+pub const MSI_START: [u8; 3] = [1,1,0];
+/// MSI barcodes end with a narrow bar, a wide space and a narrow bar.
+pub const MSI_STOP: [u8; 4] = [1,0,0,1];
+
+/// Binary encodings for each of the two bit values.
+const MSI_BITS: [[u8; 3]; 2] = [[1,0,0], [1,1,0]];
+
+/// The check digit schemes available for MSI barcodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MSIChecksum {
+    /// No check digit.
+    None,
+    /// A single Luhn modulo-10 check digit.
+    Mod10,
+    /// A single modulo-11 check digit, using the IBM weights (2-7). A check value of 10 is
+    /// appended as the two digits "10".
+    Mod11,
+    /// Two modulo-10 check digits, the second calculated over the data and the first.
+    Mod1010,
+    /// A modulo-11 check digit followed by a modulo-10 check digit.
+    Mod1110,
+}
+
+/// The MSI barcode type.
+pub struct MSI {
+    data: Vec<u8>,
+    checksum: MSIChecksum,
+}
+
+impl MSI {
+    fn init(data: String, checksum: MSIChecksum) -> Result<MSI, String> {
+        let digits = MSI::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        Ok(MSI{data: digits, checksum})
+    }
+
+    /// Creates a new barcode without a check digit.
+    /// Returns Result<MSI, String> indicating parse success.
+    pub fn new(data: String) -> Result<MSI, String> {
+        MSI::init(data, MSIChecksum::None)
+    }
+
+    /// Creates a new barcode with check digits appended using the given scheme.
+    /// Returns Result<MSI, String> indicating parse success.
+    pub fn with_checksum(data: String, checksum: MSIChecksum) -> Result<MSI, String> {
+        MSI::init(data, checksum)
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    fn mod10(digits: &[u8]) -> Vec<u8> {
+        let sum: u32 = digits.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &d)| {
+                let d = d as u32;
+                if i % 2 == 0 { (d * 2) / 10 + (d * 2) % 10 } else { d }
+            })
+            .sum();
+
+        vec![((10 - (sum % 10)) % 10) as u8]
+    }
+
+    fn mod11(digits: &[u8]) -> Vec<u8> {
+        let sum: u32 = digits.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &d)| (d as u32) * ((i as u32 % 6) + 2))
+            .sum();
+
+        match (11 - (sum % 11)) % 11 {
+            10 => vec![1, 0],
+            n => vec![n as u8],
+        }
+    }
+
+    /// Calculates the check digits for the chosen scheme.
+    pub fn checksum_digits(&self) -> Vec<u8> {
+        let first = match self.checksum {
+            MSIChecksum::None => return vec![],
+            MSIChecksum::Mod10 | MSIChecksum::Mod1010 => MSI::mod10(&self.data),
+            MSIChecksum::Mod11 | MSIChecksum::Mod1110 => MSI::mod11(&self.data),
+        };
+
+        match self.checksum {
+            MSIChecksum::Mod1010 | MSIChecksum::Mod1110 => {
+                let with_first = helpers::join_vecs(&[self.data.clone(), first.clone()][..]);
+                helpers::join_vecs(&[first, MSI::mod10(&with_first)][..])
+            },
+            _ => first,
+        }
+    }
+
+    fn char_encoding(&self, d: u8) -> Vec<u8> {
+        (0..4).rev()
+            .flat_map(|bit| MSI_BITS[((d >> bit) & 1) as usize].to_vec())
+            .collect()
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        self.data.iter()
+            .chain(self.checksum_digits().iter())
+            .flat_map(|&d| self.char_encoding(d))
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            MSI_START.to_vec(), self.payload(), MSI_STOP.to_vec()][..])
+    }
+}
+
+impl Parse for MSI {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// MSI is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::msi::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_msi() {
+        let msi = MSI::new("1234567".to_string());
+
+        assert!(msi.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_msi() {
+        let msi = MSI::with_checksum("12A4".to_string(), MSIChecksum::Mod10);
+
+        assert!(msi.is_err());
+    }
+
+    #[test]
+    fn invalid_len_msi() {
+        let msi = MSI::new("".to_string());
+
+        assert!(msi.is_err());
+    }
+
+    #[test]
+    fn msi_raw_data() {
+        let msi = MSI::with_checksum("1234".to_string(), MSIChecksum::Mod11).unwrap();
+
+        assert_eq!(msi.raw_data(), &[1,2,3,4]);
+    }
+
+    #[test]
+    fn msi_checksum_calculation() {
+        let data = "1234567".to_string();
+        let none = MSI::new(data.clone()).unwrap();
+        let mod10 = MSI::with_checksum(data.clone(), MSIChecksum::Mod10).unwrap();
+        let mod11 = MSI::with_checksum(data.clone(), MSIChecksum::Mod11).unwrap();
+        let mod1010 = MSI::with_checksum(data.clone(), MSIChecksum::Mod1010).unwrap();
+        let mod1110 = MSI::with_checksum(data, MSIChecksum::Mod1110).unwrap();
+
+        assert_eq!(none.checksum_digits(), vec![]);
+        assert_eq!(mod10.checksum_digits(), vec![4]);
+        assert_eq!(mod11.checksum_digits(), vec![4]);
+        assert_eq!(mod1010.checksum_digits(), vec![4, 1]);
+        assert_eq!(mod1110.checksum_digits(), vec![4, 1]);
+    }
+
+    #[test]
+    fn msi_mod11_ten() {
+        let msi = MSI::with_checksum("6".to_string(), MSIChecksum::Mod11).unwrap();
+
+        assert_eq!(msi.checksum_digits(), vec![1, 0]);
+    }
+
+    #[test]
+    fn msi_encode() {
+        let msi1 = MSI::new("1".to_string()).unwrap();
+        let msi2 = MSI::with_checksum("29".to_string(), MSIChecksum::Mod10).unwrap();
+
+        assert_eq!(collapse_vec(msi1.encode()), "1101001001001101001".to_string());
+        assert_eq!(collapse_vec(msi2.encode()), "1101001001101001101001001101101001001101001".to_string());
+    }
+}
@@ -0,0 +1,223 @@
+//! This module provides types for encoding Pharmacode (Laetus) barcodes. Pharmacode is used on
+//! pharmaceutical packaging lines to verify that the correct packaging material is being used.
+//! It encodes a single integer rather than a string of characters.
+//!
+//! This module defines types for:
+//!   * One-track Pharmacode
+//!   * Two-track Pharmacode
+//!
+//! In one-track Pharmacode, narrow bars are one module wide, wide bars are three modules wide
+//! and the spaces between bars are two modules wide. Two-track Pharmacode instead varies the
+//! height of the bars, each of which occupies the upper track, the lower track or both.
+
+use ::sym::EncodedBarcode;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use std::iter::repeat_n;
+use std::ops::Range;
+
+/// The range of integers that can be encoded as a one-track Pharmacode.
+pub const PHARMACODE_RANGE: Range<u32> = 3..131071;
+
+/// The range of integers that can be encoded as a two-track Pharmacode.
+pub const PHARMACODE_TWO_TRACK_RANGE: Range<u32> = 4..64570081;
+
+const NARROW_BAR: usize = 1;
+const WIDE_BAR: usize = 3;
+const SPACE: usize = 2;
+
+/// The one-track Pharmacode barcode type.
+pub struct Pharmacode {
+    data: u32,
+}
+
+impl Pharmacode {
+    /// Creates a new barcode. The data must be between 3 and 131070 (inclusive).
+    /// Returns Result<Pharmacode, String> indicating success.
+    pub fn new(data: u32) -> Result<Pharmacode, String> {
+        if !PHARMACODE_RANGE.contains(&data) {
+            return Err(format!("Data does not fit within range of {}-{}",
+                               PHARMACODE_RANGE.start, PHARMACODE_RANGE.end - 1));
+        }
+
+        Ok(Pharmacode{data})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> u32 {
+        self.data
+    }
+
+    /// Returns the bars, from left to right. true = wide, false = narrow.
+    pub fn bars(&self) -> Vec<bool> {
+        let mut n = self.data;
+        let mut bars = vec![];
+
+        while n > 0 {
+            if n.is_multiple_of(2) {
+                bars.push(true);
+                n = (n - 2) / 2;
+            } else {
+                bars.push(false);
+                n = (n - 1) / 2;
+            }
+        }
+
+        bars.reverse();
+        bars
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        let mut enc = vec![];
+
+        for (i, &wide) in self.bars().iter().enumerate() {
+            if i > 0 {
+                enc.extend(repeat_n(0, SPACE));
+            }
+
+            enc.extend(repeat_n(1, if wide { WIDE_BAR } else { NARROW_BAR }));
+        }
+
+        enc
+    }
+}
+
+/// The two-track Pharmacode barcode type.
+pub struct PharmacodeTwoTrack {
+    data: u32,
+}
+
+impl PharmacodeTwoTrack {
+    /// Creates a new barcode. The data must be between 4 and 64570080 (inclusive).
+    /// Returns Result<PharmacodeTwoTrack, String> indicating success.
+    pub fn new(data: u32) -> Result<PharmacodeTwoTrack, String> {
+        if !PHARMACODE_TWO_TRACK_RANGE.contains(&data) {
+            return Err(format!("Data does not fit within range of {}-{}",
+                               PHARMACODE_TWO_TRACK_RANGE.start, PHARMACODE_TWO_TRACK_RANGE.end - 1));
+        }
+
+        Ok(PharmacodeTwoTrack{data})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> u32 {
+        self.data
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full (both tracks), Ascender (upper track only) and Descender
+    /// (lower track only) bars.
+    pub fn encode(&self) -> EncodedBars {
+        let mut n = self.data;
+        let mut bars = vec![];
+
+        while n > 0 {
+            match n % 3 {
+                0 => { bars.push(BarState::Full); n = (n - 3) / 3; },
+                1 => { bars.push(BarState::Descender); n = (n - 1) / 3; },
+                _ => { bars.push(BarState::Ascender); n = (n - 2) / 3; },
+            }
+        }
+
+        bars.reverse();
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::pharmacode::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_pharmacode() {
+        let pharmacode1 = Pharmacode::new(3);
+        let pharmacode2 = Pharmacode::new(131070);
+
+        assert!(pharmacode1.is_ok());
+        assert!(pharmacode2.is_ok());
+    }
+
+    #[test]
+    fn invalid_range_pharmacode() {
+        let pharmacode1 = Pharmacode::new(2);
+        let pharmacode2 = Pharmacode::new(131071);
+
+        assert!(pharmacode1.is_err());
+        assert!(pharmacode2.is_err());
+    }
+
+    #[test]
+    fn pharmacode_raw_data() {
+        let pharmacode = Pharmacode::new(1234).unwrap();
+
+        assert_eq!(pharmacode.raw_data(), 1234);
+    }
+
+    #[test]
+    fn pharmacode_bars() {
+        let pharmacode1 = Pharmacode::new(4).unwrap();
+        let pharmacode2 = Pharmacode::new(131070).unwrap();
+
+        assert_eq!(pharmacode1.bars(), vec![false, true]);
+        assert_eq!(pharmacode2.bars(), vec![true; 16]);
+    }
+
+    #[test]
+    fn pharmacode_encode() {
+        let pharmacode1 = Pharmacode::new(3).unwrap();
+        let pharmacode2 = Pharmacode::new(1234).unwrap();
+
+        assert_eq!(collapse_vec(pharmacode1.encode()), "1001".to_string());
+        assert_eq!(collapse_vec(pharmacode2.encode()), "10010011100111001001110010010011100111".to_string());
+    }
+
+    #[test]
+    fn new_pharmacode_two_track() {
+        let pharmacode1 = PharmacodeTwoTrack::new(4);
+        let pharmacode2 = PharmacodeTwoTrack::new(64570080);
+
+        assert!(pharmacode1.is_ok());
+        assert!(pharmacode2.is_ok());
+    }
+
+    #[test]
+    fn invalid_range_pharmacode_two_track() {
+        let pharmacode1 = PharmacodeTwoTrack::new(3);
+        let pharmacode2 = PharmacodeTwoTrack::new(64570081);
+
+        assert!(pharmacode1.is_err());
+        assert!(pharmacode2.is_err());
+    }
+
+    #[test]
+    fn pharmacode_two_track_raw_data() {
+        let pharmacode = PharmacodeTwoTrack::new(1234).unwrap();
+
+        assert_eq!(pharmacode.raw_data(), 1234);
+    }
+
+    #[test]
+    fn pharmacode_two_track_encode() {
+        let pharmacode1 = PharmacodeTwoTrack::new(4).unwrap();
+        let pharmacode2 = PharmacodeTwoTrack::new(1234).unwrap();
+        let pharmacode3 = PharmacodeTwoTrack::new(64570080).unwrap();
+
+        assert_eq!(pharmacode1.encode(), vec![BarState::Descender, BarState::Descender]);
+        assert_eq!(pharmacode2.encode(), vec![BarState::Descender,
+                                                   BarState::Descender,
+                                                   BarState::Ascender,
+                                                   BarState::Full,
+                                                   BarState::Descender,
+                                                   BarState::Full,
+                                                   BarState::Descender]);
+        assert_eq!(pharmacode3.encode(), vec![BarState::Full; 16]);
+    }
+}
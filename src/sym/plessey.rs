@@ -0,0 +1,152 @@
+//! This module provides types for encoding Plessey barcodes. Plessey (also known as Anker or
+//! UK Plessey) was used for library shelving and retail labelling in the United Kingdom, and is
+//! the ancestor of MSI.
+//!
+//! Each hexadecimal digit is encoded as four bits, least significant bit first, and an 8-bit
+//! CRC check value follows the data.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+
+/// The hexadecimal digits, in order of value.
+pub const PLESSEY_CHARS: &str = "0123456789ABCDEF";
+
+/// Plessey barcodes begin with the bits 1, 1, 0 and 1.
+pub const PLESSEY_START: [u8; 16] = [1,1,1,0,1,1,1,0,1,0,0,0,1,1,1,0];
+/// Plessey barcodes end with a wide bar, a wide space, two narrow bars and two wide bars.
+pub const PLESSEY_STOP: [u8; 19] = [1,1,1,0,0,0,1,0,0,0,1,0,1,1,1,0,1,1,1];
+
+/// Binary encodings for each of the two bit values.
+const PLESSEY_BITS: [[u8; 4]; 2] = [[1,0,0,0], [1,1,1,0]];
+
+/// The generator polynomial of the CRC, x^8 + x^7 + x^6 + x^5 + x^3 + 1, highest degree first.
+const PLESSEY_CRC_POLY: [u8; 9] = [1,1,1,1,0,1,0,0,1];
+
+/// The Plessey barcode type.
+pub struct Plessey {
+    data: Vec<u8>,
+}
+
+impl Plessey {
+    /// Creates a new barcode.
+    /// Returns Result<Plessey, String> indicating parse success.
+    pub fn new(data: String) -> Result<Plessey, String> {
+        let digits = Plessey::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(16).expect("Unknown character") as u8)
+            .collect();
+
+        Ok(Plessey{data: digits})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    fn data_bits(&self) -> Vec<u8> {
+        self.data.iter()
+            .flat_map(|&d| (0..4).map(move |bit| (d >> bit) & 1))
+            .collect()
+    }
+
+    /// Calculates the eight check bits: the remainder of the data bits, followed by eight
+    /// zeros, divided by the CRC polynomial.
+    pub fn checksum_bits(&self) -> Vec<u8> {
+        let mut bits = self.data_bits();
+        let len = bits.len();
+        bits.extend(&[0; 8]);
+
+        for i in 0..len {
+            if bits[i] == 1 {
+                for (j, &p) in PLESSEY_CRC_POLY.iter().enumerate() {
+                    bits[i + j] ^= p;
+                }
+            }
+        }
+
+        bits.split_off(len)
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        self.data_bits().iter()
+            .chain(self.checksum_bits().iter())
+            .flat_map(|&b| PLESSEY_BITS[b as usize].to_vec())
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            PLESSEY_START.to_vec(), self.payload(), PLESSEY_STOP.to_vec()][..])
+    }
+}
+
+impl Parse for Plessey {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Plessey is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=65
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        PLESSEY_CHARS.chars().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::plessey::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_plessey() {
+        let plessey = Plessey::new("12345ABCDEF".to_string());
+
+        assert!(plessey.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_plessey() {
+        let plessey = Plessey::new("12345G".to_string());
+
+        assert!(plessey.is_err());
+    }
+
+    #[test]
+    fn invalid_len_plessey() {
+        let plessey = Plessey::new("".to_string());
+
+        assert!(plessey.is_err());
+    }
+
+    #[test]
+    fn plessey_checksum_calculation() {
+        let plessey1 = Plessey::new("1".to_string()).unwrap();
+        let plessey2 = Plessey::new("8".to_string()).unwrap();
+
+        assert_eq!(plessey1.checksum_bits(), vec![1, 1, 1, 0, 1, 1, 0, 0]);
+        // The data bits 0001 leave the polynomial itself, less its leading term.
+        assert_eq!(plessey2.checksum_bits(), vec![1, 1, 1, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn plessey_encode() {
+        let plessey = Plessey::new("1".to_string()).unwrap();
+        let encoded = collapse_vec(plessey.encode());
+
+        assert_eq!(&encoded[..16], "1110111010001110");
+        assert_eq!(&encoded[16..32], "1110100010001000");
+        assert_eq!(&encoded[(encoded.len() - 19)..], "1110001000101110111");
+        assert_eq!(encoded.len(), 16 + 12 * 4 + 19);
+    }
+}
@@ -0,0 +1,268 @@
+//! This module provides types for encoding the height-modulated barcodes of the United States
+//! Postal Service.
+//!
+//! This module defines types for:
+//!   * POSTNET
+//!   * PLANET
+//!
+//! POSTNET (Postal Numeric Encoding Technique) encodes ZIP codes. Each digit is encoded as five
+//! bars, two of which are tall and three short. The barcode is framed by a tall bar at either
+//! end and a modulo-10 check digit is appended to the data. POSTNET supports 5-digit ZIP,
+//! 9-digit ZIP+4 and 11-digit Delivery Point (DPBC) codes.
+//!
+//! PLANET (Postal Alpha Numeric Encoding Technique) is used for tracking mail. It uses the
+//! inverse of the POSTNET scheme (two short and three tall bars per digit) and encodes 11 or 13
+//! digits.
+
+use ::sym::Parse;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// POSTNET and PLANET barcodes begin and end with a single tall frame bar.
+pub const POSTNET_FRAME: BarState = BarState::Full;
+
+/// POSTNET encodings for the digits 0-9. true = tall bar, false = short bar.
+/// PLANET uses the inverse of these encodings.
+const POSTNET_DIGITS: [[bool; 5]; 10] = [
+    [true, true, false, false, false], [false, false, false, true, true],
+    [false, false, true, false, true], [false, false, true, true, false],
+    [false, true, false, false, true], [false, true, false, true, false],
+    [false, true, true, false, false], [true, false, false, false, true],
+    [true, false, false, true, false], [true, false, true, false, false],
+];
+
+/// The valid lengths of POSTNET data: ZIP, ZIP+4 and Delivery Point.
+const POSTNET_LENGTHS: [usize; 3] = [5, 9, 11];
+
+/// The valid lengths of PLANET data.
+const PLANET_LENGTHS: [usize; 2] = [11, 13];
+
+fn digits(data: String, lengths: &[usize]) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = data.chars()
+        .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+        .collect();
+
+    if !lengths.contains(&digits.len()) {
+        let lengths: Vec<String> = lengths.iter().map(|l| l.to_string()).collect();
+        return Err(format!("Data must be {} digits long, not {}", lengths.join(" or "), digits.len()));
+    }
+
+    Ok(digits)
+}
+
+// Calculates the check digit, which brings the sum of all digits to a multiple of 10.
+fn checksum_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter().map(|&d| d as u32).sum();
+
+    ((10 - (sum % 10)) % 10) as u8
+}
+
+// Encodes the digits and their check digit between two frame bars. PLANET inverts the tall
+// and short bars of each digit.
+fn encode_bars(digits: &[u8], inverted: bool) -> EncodedBars {
+    let mut bars = vec![POSTNET_FRAME];
+
+    for &d in digits.iter().chain(Some(checksum_digit(digits)).iter()) {
+        bars.extend(POSTNET_DIGITS[d as usize].iter()
+            .map(|&tall| if tall != inverted { BarState::Full } else { BarState::Descender }));
+    }
+
+    bars.push(POSTNET_FRAME);
+    bars
+}
+
+/// The POSTNET barcode type.
+pub struct Postnet {
+    data: Vec<u8>,
+}
+
+impl Postnet {
+    /// Creates a new barcode. The data must be 5 (ZIP), 9 (ZIP+4) or 11 (Delivery Point) digits.
+    /// Returns Result<Postnet, String> indicating parse success.
+    pub fn new(data: String) -> Result<Postnet, String> {
+        let digits = digits(Postnet::parse(data)?, &POSTNET_LENGTHS)?;
+
+        Ok(Postnet{data: digits})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit, which brings the sum of all digits to a multiple of 10.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum_digit(&self.data)
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full (tall) and Descender (short) bars.
+    pub fn encode(&self) -> EncodedBars {
+        encode_bars(&self.data, false)
+    }
+}
+
+/// The PLANET barcode type.
+pub struct Planet {
+    data: Vec<u8>,
+}
+
+impl Planet {
+    /// Creates a new barcode. The data must be 11 or 13 digits.
+    /// Returns Result<Planet, String> indicating parse success.
+    pub fn new(data: String) -> Result<Planet, String> {
+        let digits = digits(Planet::parse(data)?, &PLANET_LENGTHS)?;
+
+        Ok(Planet{data: digits})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit, which brings the sum of all digits to a multiple of 10.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum_digit(&self.data)
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full (tall) and Descender (short) bars.
+    pub fn encode(&self) -> EncodedBars {
+        encode_bars(&self.data, true)
+    }
+}
+
+impl Parse for Postnet {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        5..=11
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+impl Parse for Planet {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        11..=13
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::postnet::*;
+
+    fn collapse_bars(v: Vec<BarState>) -> String {
+        v.iter().map(|&b| if b == BarState::Full { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn new_postnet() {
+        let postnet1 = Postnet::new("12345".to_string());
+        let postnet2 = Postnet::new("123456789".to_string());
+        let postnet3 = Postnet::new("12345678901".to_string());
+
+        assert!(postnet1.is_ok());
+        assert!(postnet2.is_ok());
+        assert!(postnet3.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_postnet() {
+        let postnet = Postnet::new("1234A".to_string());
+
+        assert!(postnet.is_err());
+    }
+
+    #[test]
+    fn invalid_len_postnet() {
+        let postnet1 = Postnet::new("1234".to_string());
+        let postnet2 = Postnet::new("123456".to_string());
+        let postnet3 = Postnet::new("123456789012".to_string());
+
+        assert!(postnet1.is_err());
+        assert!(postnet2.is_err());
+        assert!(postnet3.is_err());
+    }
+
+    #[test]
+    fn postnet_raw_data() {
+        let postnet = Postnet::new("12345".to_string()).unwrap();
+
+        assert_eq!(postnet.raw_data(), &[1,2,3,4,5]);
+    }
+
+    #[test]
+    fn postnet_checksum_calculation() {
+        let postnet1 = Postnet::new("12345".to_string()).unwrap();
+        let postnet2 = Postnet::new("12345678901".to_string()).unwrap();
+
+        assert_eq!(postnet1.checksum_digit(), 5);
+        assert_eq!(postnet2.checksum_digit(), 4);
+    }
+
+    #[test]
+    fn postnet_encode() {
+        let postnet1 = Postnet::new("12345".to_string()).unwrap();
+        let postnet2 = Postnet::new("123456789".to_string()).unwrap();
+        let postnet3 = Postnet::new("12345678901".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(postnet1.encode()), "10001100101001100100101010010101".to_string());
+        assert_eq!(collapse_bars(postnet2.encode()), "1000110010100110010010101001100100011001010100010101".to_string());
+        assert_eq!(collapse_bars(postnet3.encode()), "10001100101001100100101010011001000110010101001100000011010011".to_string());
+    }
+
+    #[test]
+    fn new_planet() {
+        let planet1 = Planet::new("12345678901".to_string());
+        let planet2 = Planet::new("1234567890123".to_string());
+
+        assert!(planet1.is_ok());
+        assert!(planet2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_planet() {
+        let planet = Planet::new("1234567890A".to_string());
+
+        assert!(planet.is_err());
+    }
+
+    #[test]
+    fn invalid_len_planet() {
+        let planet1 = Planet::new("12345".to_string());
+        let planet2 = Planet::new("123456789012".to_string());
+
+        assert!(planet1.is_err());
+        assert!(planet2.is_err());
+    }
+
+    #[test]
+    fn planet_checksum_calculation() {
+        let planet1 = Planet::new("12345678901".to_string()).unwrap();
+        let planet2 = Planet::new("1234567890123".to_string()).unwrap();
+
+        assert_eq!(planet1.checksum_digit(), 4);
+        assert_eq!(planet2.checksum_digit(), 9);
+    }
+
+    #[test]
+    fn planet_encode() {
+        let planet1 = Planet::new("12345678901".to_string()).unwrap();
+        let planet2 = Planet::new("1234567890123".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(planet1.encode()), "11110011010110011011010101100110111001101010110011111100101101".to_string());
+        assert_eq!(collapse_bars(planet2.encode()), "111100110101100110110101011001101110011010101100111111001101011001010111".to_string());
+    }
+}
@@ -0,0 +1,232 @@
+//! This module provides types for encoding PZN (Pharmazentralnummer) barcodes. The PZN
+//! identifies medicines and other pharmacy products in Germany.
+//!
+//! This module defines types for:
+//!   * PZN7, the original 7-digit number (6 digits and a check digit)
+//!   * PZN8, the current 8-digit number (7 digits and a check digit)
+//!
+//! The number and its modulo-11 check digit are prefixed with '-' and encoded as a Code39
+//! barcode. Numbers whose check digit would be 10 are not issued and are rejected.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::code39::Code39;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The prefix encoded before the digits of the PZN.
+pub const PZN_PREFIX: char = '-';
+
+// Parses the digits of a PZN with `len` digits before the check digit. If the check digit is
+// present it is verified.
+fn digits(data: String, len: usize) -> Result<Vec<u8>, String> {
+    let mut digits: Vec<u8> = data.chars()
+        .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+        .collect();
+
+    if digits.len() == len + 1 {
+        let check = digits.pop().unwrap();
+
+        if checksum(&digits) != Some(check) {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+    }
+
+    if checksum(&digits).is_none() {
+        return Err("Data has no valid check digit".to_string());
+    }
+
+    Ok(digits)
+}
+
+// The digits are weighted from the right, beginning at 7 for the final digit. Returns None if
+// the check value is 10.
+fn checksum(digits: &[u8]) -> Option<u8> {
+    let sum: u32 = digits.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * (7 - i as u32))
+        .sum();
+
+    match sum % 11 {
+        10 => None,
+        n => Some(n as u8),
+    }
+}
+
+fn code39(digits: &[u8]) -> Result<Code39, String> {
+    let data = Some(PZN_PREFIX).into_iter()
+        .chain(digits.iter().chain(checksum(digits).iter()).map(|&d| char::from_digit(d as u32, 10).unwrap()))
+        .collect();
+
+    Code39::new(data)
+}
+
+/// The PZN7 barcode type.
+pub struct PZN7 {
+    data: Vec<u8>,
+    code39: Code39,
+}
+
+impl PZN7 {
+    /// Creates a new barcode from 6 digits, or 7 digits whose final check digit will be
+    /// verified.
+    /// Returns Result<PZN7, String> indicating parse success.
+    pub fn new(data: String) -> Result<PZN7, String> {
+        let digits = digits(PZN7::parse(data)?, 6)?;
+        let code39 = code39(&digits)?;
+
+        Ok(PZN7{data: digits, code39})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit using a modulo-11 algorithm with weights from 2 to 7.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum(&self.data).expect("Invalid check digit")
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code39.encode()
+    }
+}
+
+/// The PZN8 barcode type.
+pub struct PZN8 {
+    data: Vec<u8>,
+    code39: Code39,
+}
+
+impl PZN8 {
+    /// Creates a new barcode from 7 digits, or 8 digits whose final check digit will be
+    /// verified.
+    /// Returns Result<PZN8, String> indicating parse success.
+    pub fn new(data: String) -> Result<PZN8, String> {
+        let digits = digits(PZN8::parse(data)?, 7)?;
+        let code39 = code39(&digits)?;
+
+        Ok(PZN8{data: digits, code39})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit using a modulo-11 algorithm with weights from 1 to 7.
+    pub fn checksum_digit(&self) -> u8 {
+        checksum(&self.data).expect("Invalid check digit")
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code39.encode()
+    }
+}
+
+impl Parse for PZN7 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        6..=7
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+impl Parse for PZN8 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        7..=8
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::pzn::*;
+    use ::sym::code39::Code39;
+
+    #[test]
+    fn new_pzn() {
+        let pzn71 = PZN7::new("123456".to_string());
+        let pzn72 = PZN7::new("1234562".to_string());
+        let pzn81 = PZN8::new("1234567".to_string());
+        let pzn82 = PZN8::new("12345678".to_string());
+
+        assert!(pzn71.is_ok());
+        assert!(pzn72.is_ok());
+        assert!(pzn81.is_ok());
+        assert!(pzn82.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_pzn() {
+        let pzn7 = PZN7::new("12345A".to_string());
+        let pzn8 = PZN8::new("-1234567".to_string());
+
+        assert!(pzn7.is_err());
+        assert!(pzn8.is_err());
+    }
+
+    #[test]
+    fn invalid_len_pzn() {
+        let pzn7 = PZN7::new("12345".to_string());
+        let pzn8 = PZN8::new("123456789".to_string());
+
+        assert!(pzn7.is_err());
+        assert_eq!(pzn8.err(), Some("Data does not fit within range of 7-8".to_string()));
+    }
+
+    #[test]
+    fn invalid_checksum_pzn() {
+        let pzn71 = PZN7::new("1234563".to_string());
+        let pzn72 = PZN7::new("000003".to_string());
+        let pzn81 = PZN8::new("12345679".to_string());
+        let pzn82 = PZN8::new("0000003".to_string());
+
+        assert!(pzn71.is_err());
+        assert!(pzn72.is_err());
+        assert!(pzn81.is_err());
+        assert!(pzn82.is_err());
+    }
+
+    #[test]
+    fn pzn_raw_data() {
+        let pzn7 = PZN7::new("1234562".to_string()).unwrap();
+        let pzn8 = PZN8::new("12345678".to_string()).unwrap();
+
+        assert_eq!(pzn7.raw_data(), &[1,2,3,4,5,6]);
+        assert_eq!(pzn8.raw_data(), &[1,2,3,4,5,6,7]);
+    }
+
+    #[test]
+    fn pzn_checksum_calculation() {
+        let pzn7 = PZN7::new("123456".to_string()).unwrap();
+        let pzn8 = PZN8::new("1234567".to_string()).unwrap();
+
+        assert_eq!(pzn7.checksum_digit(), 2);
+        assert_eq!(pzn8.checksum_digit(), 8);
+    }
+
+    #[test]
+    fn pzn_encode() {
+        let pzn7 = PZN7::new("123456".to_string()).unwrap();
+        let pzn8 = PZN8::new("1234567".to_string()).unwrap();
+
+        assert_eq!(pzn7.encode(), Code39::new("-1234562".to_string()).unwrap().encode());
+        assert_eq!(pzn8.encode(), Code39::new("-12345678".to_string()).unwrap().encode());
+    }
+}
@@ -0,0 +1,240 @@
+//! This module provides types for encoding Royal Mail 4-State Customer Code (RM4SCC) barcodes.
+//! RM4SCC is used by Royal Mail in the United Kingdom to encode postcodes and delivery point
+//! suffixes on mail.
+//!
+//! This module defines types for:
+//!   * RM4SCC
+//!   * KIX (Klant index), the PostNL variant of RM4SCC used in the Netherlands
+//!
+//! Each character is encoded as four bars. The upper (ascender) and lower (descender) halves of
+//! the bars each encode a value between 1 and 6, giving 36 characters (0-9 and A-Z). A checksum
+//! character is calculated from the sums of the upper and lower values. KIX uses the same
+//! character encodings, but has no start bar, stop bar or checksum character.
+
+use ::sym::Parse;
+use ::sym::EncodedBars;
+use ::sym::BarState;
+use std::ops::RangeInclusive;
+
+/// The characters that can be encoded, in order of their values.
+pub const RM4SCC_CHARS: [char; 36] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H',
+    'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+/// RM4SCC barcodes begin with a single ascender bar.
+pub const RM4SCC_START: BarState = BarState::Ascender;
+/// RM4SCC barcodes end with a single full bar.
+pub const RM4SCC_STOP: BarState = BarState::Full;
+
+/// The bar patterns for the values 1-6 of each half of a character. true = the bar extends into
+/// that half.
+const RM4SCC_HALVES: [[bool; 4]; 6] = [
+    [false, false, true, true], [false, true, false, true], [false, true, true, false],
+    [true, false, false, true], [true, false, true, false], [true, true, false, false],
+];
+
+/// The RM4SCC barcode type.
+pub struct RM4SCC {
+    data: String,
+}
+
+impl RM4SCC {
+    /// Creates a new barcode.
+    /// Returns Result<RM4SCC, String> indicating parse success.
+    pub fn new(data: String) -> Result<RM4SCC, String> {
+        RM4SCC::parse(data).map(|d| RM4SCC{data: d})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Calculates the checksum character. Its upper value is the sum of the upper values of the
+    /// data modulo 6, and its lower value the sum of the lower values modulo 6.
+    pub fn checksum_char(&self) -> char {
+        let (upper, lower) = self.data.chars()
+            .map(char_value)
+            .fold((0, 0), |(u, l), v| (u + v / 6 + 1, l + v % 6 + 1));
+
+        let upper = match upper % 6 { 0 => 6, n => n };
+        let lower = match lower % 6 { 0 => 6, n => n };
+
+        RM4SCC_CHARS[(upper - 1) * 6 + lower - 1]
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full, Ascender, Descender and Tracker bars.
+    pub fn encode(&self) -> EncodedBars {
+        let mut bars = vec![RM4SCC_START];
+
+        for c in self.data.chars().chain(Some(self.checksum_char())) {
+            bars.extend(char_encoding(char_value(c)));
+        }
+
+        bars.push(RM4SCC_STOP);
+        bars
+    }
+}
+
+fn char_value(c: char) -> usize {
+    RM4SCC_CHARS.iter().position(|&ch| ch == c).expect("Unknown character")
+}
+
+// Encodes the character with the given value as four bars.
+fn char_encoding(value: usize) -> Vec<BarState> {
+    let upper = RM4SCC_HALVES[value / 6];
+    let lower = RM4SCC_HALVES[value % 6];
+
+    upper.iter().zip(lower.iter())
+        .map(|(&u, &l)| match (u, l) {
+            (true, true) => BarState::Full,
+            (true, false) => BarState::Ascender,
+            (false, true) => BarState::Descender,
+            (false, false) => BarState::Tracker,
+        })
+        .collect()
+}
+
+/// The KIX barcode type.
+pub struct KIX {
+    data: String,
+}
+
+impl KIX {
+    /// Creates a new barcode.
+    /// Returns Result<KIX, String> indicating parse success.
+    pub fn new(data: String) -> Result<KIX, String> {
+        KIX::parse(data).map(|d| KIX{data: d})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Encodes the barcode.
+    /// Returns EncodedBars of Full, Ascender, Descender and Tracker bars.
+    pub fn encode(&self) -> EncodedBars {
+        self.data.chars()
+            .flat_map(|c| char_encoding(char_value(c)))
+            .collect()
+    }
+}
+
+impl Parse for RM4SCC {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=50
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        RM4SCC_CHARS.to_vec()
+    }
+}
+
+impl Parse for KIX {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=50
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        RM4SCC_CHARS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::rm4scc::*;
+
+    fn collapse_bars(v: Vec<BarState>) -> String {
+        v.iter().map(|&b| match b {
+            BarState::Full => 'F',
+            BarState::Ascender => 'A',
+            BarState::Descender => 'D',
+            BarState::Tracker => 'T',
+        }).collect()
+    }
+
+    #[test]
+    fn new_rm4scc() {
+        let rm4scc = RM4SCC::new("SN34RD1A".to_string());
+
+        assert!(rm4scc.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_rm4scc() {
+        let rm4scc1 = RM4SCC::new("SN34rd1a".to_string());
+        let rm4scc2 = RM4SCC::new("SN34 RD1A".to_string());
+
+        assert!(rm4scc1.is_err());
+        assert!(rm4scc2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_rm4scc() {
+        let rm4scc = RM4SCC::new("".to_string());
+
+        assert!(rm4scc.is_err());
+    }
+
+    #[test]
+    fn rm4scc_raw_data() {
+        let rm4scc = RM4SCC::new("SN34RD1A".to_string()).unwrap();
+
+        assert_eq!(rm4scc.raw_data(), "SN34RD1A");
+    }
+
+    #[test]
+    fn rm4scc_checksum_calculation() {
+        let rm4scc1 = RM4SCC::new("SN34RD1A".to_string()).unwrap();
+        let rm4scc2 = RM4SCC::new("BX11LT1A".to_string()).unwrap();
+        let rm4scc3 = RM4SCC::new("1234ABCD".to_string()).unwrap();
+
+        assert_eq!(rm4scc1.checksum_char(), 'K');
+        assert_eq!(rm4scc2.checksum_char(), 'I');
+        assert_eq!(rm4scc3.checksum_char(), '9');
+    }
+
+    #[test]
+    fn rm4scc_encode() {
+        let rm4scc1 = RM4SCC::new("SN34RD1A".to_string()).unwrap();
+        let rm4scc2 = RM4SCC::new("1234ABCD".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(rm4scc1.encode()), "AFTFTFDTADTAFDTFAFTADTFADTDAFDADAADDAF".to_string());
+        assert_eq!(collapse_bars(rm4scc2.encode()), "ATDAFTDFADTAFDTFADADADFTATAFDTFADDATFF".to_string());
+    }
+
+    #[test]
+    fn new_kix() {
+        let kix = KIX::new("1231FZ13XHS".to_string());
+
+        assert!(kix.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_kix() {
+        let kix = KIX::new("1231fz13xhs".to_string());
+
+        assert!(kix.is_err());
+    }
+
+    #[test]
+    fn kix_raw_data() {
+        let kix = KIX::new("1231FZ13XHS".to_string()).unwrap();
+
+        assert_eq!(kix.raw_data(), "1231FZ13XHS");
+    }
+
+    #[test]
+    fn kix_encode() {
+        let kix = KIX::new("1231FZ13XHS".to_string()).unwrap();
+
+        assert_eq!(collapse_bars(kix.encode()), "TDAFTDFADTAFTDAFDAADFFTTTDAFDTAFFATDDFATFTFT".to_string());
+    }
+}
@@ -0,0 +1,123 @@
+//! This module provides types for encoding SSCCs (Serial Shipping Container Codes) as GS1-128
+//! barcodes. The SSCC identifies a logistic unit, such as a pallet or parcel.
+//!
+//! An SSCC is built from an extension digit, chosen by the company to extend the range of serial
+//! references, the GS1 company prefix, and a serial reference, which together make 17 digits.
+//! The modulo-10 check digit is calculated and appended, and the 18 digits are encoded with the
+//! AI (00).
+
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use ::sym::gs1_128::GS1_128;
+use std::char;
+
+/// The AI of an SSCC.
+pub const SSCC_AI: &str = "00";
+
+// The number of digits before the check digit.
+const SSCC_LEN: usize = 17;
+
+/// The SSCC barcode type.
+pub struct SSCC {
+    data: Vec<u8>,
+    gs1_128: GS1_128,
+}
+
+impl SSCC {
+    /// Creates a new barcode from an extension digit, a GS1 company prefix (of 4 to 12 digits)
+    /// and a serial reference, which must make 17 digits in all.
+    /// Returns Result<SSCC, String> indicating success.
+    pub fn new(extension: u8, company_prefix: &str, serial_reference: &str) -> Result<SSCC, String> {
+        if extension > 9 {
+            return Err(format!("Invalid extension digit: {}", extension));
+        }
+
+        if company_prefix.len() < 4 || company_prefix.len() > 12 {
+            return Err("GS1 company prefix must be 4-12 digits".to_string());
+        }
+
+        if 1 + company_prefix.len() + serial_reference.len() != SSCC_LEN {
+            return Err(format!("Serial reference must be {} digits for this company prefix",
+                               SSCC_LEN - 1 - company_prefix.len()));
+        }
+
+        let mut data = vec![extension];
+
+        for c in company_prefix.chars().chain(serial_reference.chars()) {
+            match c.to_digit(10) {
+                Some(d) => data.push(d as u8),
+                None => return Err(format!("Invalid character: {}", c)),
+            }
+        }
+
+        let digits: String = data.iter()
+            .chain([helpers::modulo_10_checksum(&data)].iter())
+            .map(|&d| char::from_digit(d as u32, 10).unwrap())
+            .collect();
+        let gs1_128 = GS1_128::new(format!("({}){}", SSCC_AI, digits))?;
+
+        Ok(SSCC{data, gs1_128})
+    }
+
+    /// Returns the 17 digits of the SSCC before the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Calculates the GS1 modulo-10 check digit of the SSCC.
+    pub fn checksum_digit(&self) -> u8 {
+        helpers::modulo_10_checksum(&self.data)
+    }
+
+    /// Returns the GS1-128 barcode of the SSCC.
+    pub fn gs1_128(&self) -> &GS1_128 {
+        &self.gs1_128
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.gs1_128.encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::sscc::*;
+
+    #[test]
+    fn new_sscc() {
+        let sscc = SSCC::new(1, "0614141", "123456789");
+
+        assert!(sscc.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_sscc() {
+        assert!(SSCC::new(10, "0614141", "123456789").is_err());
+        assert!(SSCC::new(1, "0614141", "12345678X").is_err());
+    }
+
+    #[test]
+    fn invalid_len_sscc() {
+        assert!(SSCC::new(1, "0614141", "12345678").is_err());
+        assert!(SSCC::new(1, "061", "123456789012").is_err());
+        assert!(SSCC::new(1, "0614141234567", "123").is_err());
+    }
+
+    #[test]
+    fn sscc_checksum_calculation() {
+        let sscc = SSCC::new(1, "0614141", "123456789").unwrap();
+
+        assert_eq!(sscc.checksum_digit(), 7);
+    }
+
+    #[test]
+    fn sscc_encode() {
+        let sscc = SSCC::new(1, "0614141", "123456789").unwrap();
+        let gs1 = GS1_128::new("(00)106141411234567897".to_string()).unwrap();
+
+        assert_eq!(sscc.gs1_128().elements(), gs1.elements());
+        assert_eq!(sscc.encode(), gs1.encode());
+    }
+}
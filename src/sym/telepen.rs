@@ -0,0 +1,203 @@
+//! This module provides types for encoding Telepen barcodes. Telepen can encode the full
+//! 128-character ASCII set and is widely used by libraries and universities in the UK.
+//!
+//! Each character is given an even parity bit and its eight bits are encoded, least
+//! significant first, as wide and narrow bars and spaces:
+//!   * a 1 bit is a narrow bar and a narrow space
+//!   * two 0 bits with no 1 bits between them are a wide bar and a narrow space
+//!   * two 0 bits with a single 1 bit between them are a wide bar and a wide space
+//!   * two 0 bits with several 1 bits between them begin with a narrow bar and a wide space,
+//!     continue with a narrow bar and a narrow space for each inner 1 bit, and end with a wide
+//!     bar and a narrow space
+//!
+//! In numeric mode, pairs of digits are compressed into a single character. A modulo-127
+//! check character is always appended.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use std::ops::RangeInclusive;
+use std::iter::repeat_n;
+
+/// Telepen barcodes begin with the '_' character.
+pub const TELEPEN_START: u8 = b'_';
+/// Telepen barcodes end with the 'z' character.
+pub const TELEPEN_STOP: u8 = b'z';
+
+const NARROW: usize = 1;
+const WIDE: usize = 3;
+
+/// The Telepen barcode type.
+pub struct Telepen {
+    data: String,
+    values: Vec<u8>,
+}
+
+impl Telepen {
+    /// Creates a new barcode in full ASCII mode.
+    /// Returns Result<Telepen, String> indicating parse success.
+    pub fn new(data: String) -> Result<Telepen, String> {
+        let data = Telepen::parse(data)?;
+        let values = data.bytes().collect();
+
+        Ok(Telepen{data, values})
+    }
+
+    /// Creates a new barcode in numeric mode. Each pair of digits is encoded as a single
+    /// character; an odd final digit is encoded together with an 'X'.
+    /// Returns Result<Telepen, String> indicating parse success.
+    pub fn numeric(data: String) -> Result<Telepen, String> {
+        let data = Telepen::parse(data)?;
+
+        if let Some(c) = data.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(format!("Invalid character: {}", c));
+        }
+
+        let values = data.as_bytes()
+            .chunks(2)
+            .map(|pair| match *pair {
+                [a, b] => (a - b'0') * 10 + (b - b'0') + 27,
+                [a] => (a - b'0') + 17,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        Ok(Telepen{data, values})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Calculates the check character, which brings the sum of all encoded characters to a
+    /// multiple of 127.
+    pub fn checksum_value(&self) -> u8 {
+        let sum: u32 = self.values.iter().map(|&v| v as u32).sum();
+
+        ((127 - (sum % 127)) % 127) as u8
+    }
+
+    // Returns the widths of the alternating bars and spaces encoding the given character.
+    fn char_widths(&self, value: u8) -> Vec<usize> {
+        let byte = if value.count_ones() % 2 == 1 { value | 0x80 } else { value };
+        let bits: Vec<bool> = (0..8).map(|i| (byte >> i) & 1 == 1).collect();
+        let mut widths = vec![];
+        let mut i = 0;
+
+        while i < bits.len() {
+            if bits[i] {
+                widths.extend(&[NARROW, NARROW]);
+                i += 1;
+                continue;
+            }
+
+            let ones = bits[i + 1..].iter().take_while(|&&b| b).count();
+
+            match ones {
+                0 => widths.extend(&[WIDE, NARROW]),
+                1 => widths.extend(&[WIDE, WIDE]),
+                _ => {
+                    widths.extend(&[NARROW, WIDE]);
+                    widths.extend(repeat_n(NARROW, (ones - 2) * 2));
+                    widths.extend(&[WIDE, NARROW]);
+                },
+            }
+
+            i += ones + 2;
+        }
+
+        widths
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        let mut values = vec![TELEPEN_START];
+        values.extend(self.values.iter());
+        values.push(self.checksum_value());
+        values.push(TELEPEN_STOP);
+
+        values.iter()
+            .flat_map(|&v| self.char_widths(v))
+            .enumerate()
+            .flat_map(|(i, w)| repeat_n(if i % 2 == 0 { 1 } else { 0 }, w))
+            .collect()
+    }
+}
+
+impl Parse for Telepen {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..128u8).map(|b| b as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::telepen::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_telepen() {
+        let telepen1 = Telepen::new("Hello!".to_string());
+        let telepen2 = Telepen::numeric("1234567".to_string());
+
+        assert!(telepen1.is_ok());
+        assert!(telepen2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_telepen() {
+        let telepen1 = Telepen::new("Ünïcödé".to_string());
+        let telepen2 = Telepen::numeric("12A4".to_string());
+
+        assert!(telepen1.is_err());
+        assert!(telepen2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_telepen() {
+        let telepen = Telepen::new("".to_string());
+
+        assert!(telepen.is_err());
+    }
+
+    #[test]
+    fn telepen_raw_data() {
+        let telepen = Telepen::numeric("1234567".to_string()).unwrap();
+
+        assert_eq!(telepen.raw_data(), "1234567");
+    }
+
+    #[test]
+    fn telepen_checksum_calculation() {
+        let telepen1 = Telepen::new("ABC".to_string()).unwrap();
+        let telepen2 = Telepen::new("Hello!".to_string()).unwrap();
+        let telepen3 = Telepen::numeric("1234567".to_string()).unwrap();
+
+        assert_eq!(telepen1.checksum_value(), 56);
+        assert_eq!(telepen2.checksum_value(), 102);
+        assert_eq!(telepen3.checksum_value(), 47);
+    }
+
+    #[test]
+    fn telepen_encode() {
+        let telepen1 = Telepen::new("ABC".to_string()).unwrap();
+        let telepen2 = Telepen::numeric("1234567".to_string()).unwrap();
+        let telepen3 = Telepen::numeric("0099".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(telepen1.encode()), "101010101011100010111011101110001110001110111000101011101110101011101000101110101110001010101010".to_string());
+        assert_eq!(collapse_vec(telepen2.encode()), "1010101010111000101010111010111010100010101110101010111010111000111010001110111010101010111000101110001010101010".to_string());
+        assert_eq!(collapse_vec(telepen3.encode()), "10101010101110001010100011101110100010101010111010111000100011101110001010101010".to_string());
+    }
+}
@@ -0,0 +1,400 @@
+//! This module provides types for encoding the 2 of 5 family of barcodes. These are numeric-only
+//! symbologies in which each digit is represented by five elements, two of which are wide.
+//!
+//! This module defines types for:
+//!   * Interleaved 2 of 5 (ITF)
+//!   * Standard (Industrial) 2 of 5, and its IATA variant
+//!   * Matrix 2 of 5, and its Datalogic variant
+//!
+//! Wide elements are three times the width of narrow elements.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use std::ops::RangeInclusive;
+use std::char;
+use std::iter::repeat_n;
+
+/// The widths of the five elements of each digit. true = wide, false = narrow.
+pub const TF_WIDTHS: [[bool; 5]; 10] = [
+    [false, false, true, true, false],
+    [true, false, false, false, true],
+    [false, true, false, false, true],
+    [true, true, false, false, false],
+    [false, false, true, false, true],
+    [true, false, true, false, false],
+    [false, true, true, false, false],
+    [false, false, false, true, true],
+    [true, false, false, true, false],
+    [false, true, false, true, false],
+];
+
+/// The width of a wide element, in modules.
+pub const TF_WIDE: usize = 3;
+
+/// Interleaved 2 of 5 barcodes begin with two narrow bars and two narrow spaces.
+pub const ITF_START: [u8; 4] = [1,0,1,0];
+/// Interleaved 2 of 5 barcodes end with a wide bar, a narrow space and a narrow bar.
+pub const ITF_STOP: [u8; 5] = [1,1,1,0,1];
+
+/// Standard 2 of 5 barcodes begin with two wide bars and a narrow bar.
+pub const STF_START: [u8; 10] = [1,1,1,0,1,1,1,0,1,0];
+/// Standard 2 of 5 barcodes end with a wide bar, a narrow bar and a wide bar.
+pub const STF_STOP: [u8; 9] = [1,1,1,0,1,0,1,1,1];
+
+/// Matrix 2 of 5 barcodes begin with a bar four modules wide and two narrow bars.
+pub const MTF_START: [u8; 9] = [1,1,1,1,0,1,0,1,0];
+/// Matrix 2 of 5 barcodes end with a bar four modules wide and two narrow bars.
+pub const MTF_STOP: [u8; 8] = [1,1,1,1,0,1,0,1];
+
+/// IATA and Datalogic 2 of 5 barcodes begin with two narrow bars.
+pub const IATA_START: [u8; 4] = [1,0,1,0];
+/// IATA and Datalogic 2 of 5 barcodes end with a wide bar and a narrow bar.
+pub const IATA_STOP: [u8; 5] = [1,1,1,0,1];
+
+fn element(wide: bool, bar: bool) -> Vec<u8> {
+    let width = if wide { TF_WIDE } else { 1 };
+    repeat_n(if bar { 1 } else { 0 }, width).collect()
+}
+
+fn digits(data: String) -> Vec<u8> {
+    data.chars()
+        .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+        .collect()
+}
+
+/// The Interleaved 2 of 5 barcode type.
+pub struct I2of5 {
+    data: Vec<u8>,
+}
+
+impl I2of5 {
+    fn init(data: String, pad: bool) -> Result<I2of5, String> {
+        let mut digits = digits(I2of5::parse(data)?);
+
+        if !digits.len().is_multiple_of(2) {
+            if !pad {
+                return Err("Interleaved 2 of 5 requires an even number of digits".to_string());
+            }
+
+            digits.insert(0, 0);
+        }
+
+        Ok(I2of5{data: digits})
+    }
+
+    /// Creates a new barcode. The data must contain an even number of digits.
+    /// Returns Result<I2of5, String> indicating parse success.
+    pub fn new(data: String) -> Result<I2of5, String> {
+        I2of5::init(data, false)
+    }
+
+    /// Creates a new barcode, prepending a zero if the data contains an odd number of digits.
+    /// Returns Result<I2of5, String> indicating parse success.
+    pub fn with_padding(data: String) -> Result<I2of5, String> {
+        I2of5::init(data, true)
+    }
+
+    /// Returns the data as was passed into the constructor, including any padding.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    // Each pair of digits is encoded together: the first digit in the bars and the second
+    // in the spaces between them.
+    fn payload(&self) -> Vec<u8> {
+        let mut enc = vec![];
+
+        for pair in self.data.chunks(2) {
+            let bars = TF_WIDTHS[pair[0] as usize];
+            let spaces = TF_WIDTHS[pair[1] as usize];
+
+            for (&b, &s) in bars.iter().zip(spaces.iter()) {
+                enc.extend(element(b, true));
+                enc.extend(element(s, false));
+            }
+        }
+
+        enc
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            ITF_START.to_vec(), self.payload(), ITF_STOP.to_vec()][..])
+    }
+}
+
+impl Parse for I2of5 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Interleaved 2 of 5 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+/// The Standard (Industrial) 2 of 5 barcode type.
+/// Only the bars carry information; every space is narrow.
+pub struct S2of5 {
+    data: Vec<u8>,
+    iata: bool,
+}
+
+impl S2of5 {
+    /// Creates a new barcode.
+    /// Returns Result<S2of5, String> indicating parse success.
+    pub fn new(data: String) -> Result<S2of5, String> {
+        Ok(S2of5{data: digits(S2of5::parse(data)?), iata: false})
+    }
+
+    /// Creates a new IATA 2 of 5 barcode, as used on airline baggage and freight labels. The
+    /// digits are encoded as for Standard 2 of 5, between shorter start and stop patterns.
+    /// Returns Result<S2of5, String> indicating parse success.
+    pub fn iata(data: String) -> Result<S2of5, String> {
+        Ok(S2of5{data: digits(S2of5::parse(data)?), iata: true})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut enc = vec![];
+
+        for &d in &self.data {
+            for &w in TF_WIDTHS[d as usize].iter() {
+                enc.extend(element(w, true));
+                enc.extend(element(false, false));
+            }
+        }
+
+        enc
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        if self.iata {
+            helpers::join_vecs(&[
+                IATA_START.to_vec(), self.payload(), IATA_STOP.to_vec()][..])
+        } else {
+            helpers::join_vecs(&[
+                STF_START.to_vec(), self.payload(), STF_STOP.to_vec()][..])
+        }
+    }
+}
+
+impl Parse for S2of5 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Standard 2 of 5 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+/// The Matrix 2 of 5 barcode type.
+/// Both the bars and the spaces carry information: each digit is three bars and two spaces,
+/// followed by a narrow space.
+pub struct M2of5 {
+    data: Vec<u8>,
+    datalogic: bool,
+}
+
+impl M2of5 {
+    /// Creates a new barcode.
+    /// Returns Result<M2of5, String> indicating parse success.
+    pub fn new(data: String) -> Result<M2of5, String> {
+        Ok(M2of5{data: digits(M2of5::parse(data)?), datalogic: false})
+    }
+
+    /// Creates a new Datalogic 2 of 5 barcode. The digits are encoded as for Matrix 2 of 5,
+    /// between the start and stop patterns of IATA 2 of 5.
+    /// Returns Result<M2of5, String> indicating parse success.
+    pub fn datalogic(data: String) -> Result<M2of5, String> {
+        Ok(M2of5{data: digits(M2of5::parse(data)?), datalogic: true})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut enc = vec![];
+
+        for &d in &self.data {
+            for (i, &w) in TF_WIDTHS[d as usize].iter().enumerate() {
+                enc.extend(element(w, i % 2 == 0));
+            }
+
+            enc.extend(element(false, false));
+        }
+
+        enc
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        if self.datalogic {
+            helpers::join_vecs(&[
+                IATA_START.to_vec(), self.payload(), IATA_STOP.to_vec()][..])
+        } else {
+            helpers::join_vecs(&[
+                MTF_START.to_vec(), self.payload(), MTF_STOP.to_vec()][..])
+        }
+    }
+}
+
+impl Parse for M2of5 {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// Matrix 2 of 5 is variable-length.
+    fn valid_len() -> RangeInclusive<u32> {
+        1..=128
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::two_of_five::*;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_i2of5() {
+        let i2of5 = I2of5::new("1234".to_string());
+
+        assert!(i2of5.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_i2of5() {
+        let i2of5 = I2of5::new("12AB".to_string());
+
+        assert!(i2of5.is_err());
+    }
+
+    #[test]
+    fn invalid_len_i2of5() {
+        let i2of51 = I2of5::new("".to_string());
+        let i2of52 = I2of5::new("123".to_string());
+
+        assert!(i2of51.is_err());
+        assert!(i2of52.is_err());
+    }
+
+    #[test]
+    fn i2of5_raw_data() {
+        let i2of51 = I2of5::new("1234".to_string()).unwrap();
+        let i2of52 = I2of5::with_padding("123".to_string()).unwrap();
+
+        assert_eq!(i2of51.raw_data(), &[1,2,3,4]);
+        assert_eq!(i2of52.raw_data(), &[0,1,2,3]);
+    }
+
+    #[test]
+    fn i2of5_encode() {
+        let i2of51 = I2of5::new("12".to_string()).unwrap();
+        let i2of52 = I2of5::with_padding("7".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(i2of51.encode()), "101011101000101011100011101".to_string());
+        assert_eq!(collapse_vec(i2of52.encode()), "101010101110111000100011101".to_string());
+    }
+
+    #[test]
+    fn new_s2of5() {
+        let s2of5 = S2of5::new("12345".to_string());
+
+        assert!(s2of5.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_s2of5() {
+        let s2of5 = S2of5::new("1-2".to_string());
+
+        assert!(s2of5.is_err());
+    }
+
+    #[test]
+    fn invalid_len_s2of5() {
+        let s2of5 = S2of5::new("".to_string());
+
+        assert!(s2of5.is_err());
+    }
+
+    #[test]
+    fn s2of5_raw_data() {
+        let s2of5 = S2of5::new("123".to_string()).unwrap();
+
+        assert_eq!(s2of5.raw_data(), &[1,2,3]);
+    }
+
+    #[test]
+    fn s2of5_encode() {
+        let s2of51 = S2of5::new("1".to_string()).unwrap();
+        let s2of52 = S2of5::new("70".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(s2of51.encode()), "111011101011101010101110111010111".to_string());
+        assert_eq!(collapse_vec(s2of52.encode()), "11101110101010101110111010101110111010111010111".to_string());
+    }
+
+    #[test]
+    fn s2of5_iata_encode() {
+        let iata = S2of5::iata("1".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(iata.encode()), "10101110101010111011101".to_string());
+    }
+
+    #[test]
+    fn new_m2of5() {
+        let m2of51 = M2of5::new("12345".to_string());
+        let m2of52 = M2of5::datalogic("12345".to_string());
+
+        assert!(m2of51.is_ok());
+        assert!(m2of52.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_m2of5() {
+        let m2of5 = M2of5::new("1-2".to_string());
+
+        assert!(m2of5.is_err());
+    }
+
+    #[test]
+    fn invalid_len_m2of5() {
+        let m2of5 = M2of5::datalogic("".to_string());
+
+        assert!(m2of5.is_err());
+    }
+
+    #[test]
+    fn m2of5_encode() {
+        let m2of5 = M2of5::new("1".to_string()).unwrap();
+        let datalogic = M2of5::datalogic("70".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(m2of5.encode()), "111101010111010111011110101".to_string());
+        assert_eq!(collapse_vec(datalogic.encode()), "10101010001110101110001011101".to_string());
+    }
+}
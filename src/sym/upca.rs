@@ -0,0 +1,209 @@
+//! This module provides types for encoding UPC-A barcodes. UPC-A is the standard retail
+//! barcode in the United States and Canada. A UPC-A symbol is equivalent to an EAN-13 symbol
+//! whose first digit is zero.
+//!
+//! The 11 data digits are made up of a number system digit, a 5-digit manufacturer code and a
+//! 5-digit product code, and are followed by a check digit.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::EncodedGuards;
+use ::sym::helpers;
+use ::sym::ean13::EAN_ENCODINGS;
+use ::sym::ean13::EAN_LEFT_GUARD;
+use ::sym::ean13::EAN_MIDDLE_GUARD;
+use ::sym::ean13::EAN_RIGHT_GUARD;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The UPC-A barcode type.
+pub struct UPCA {
+    data: Vec<u8>,
+}
+
+impl UPCA {
+    /// Creates a new barcode from 11 data digits, or 12 digits whose final check digit will be
+    /// verified.
+    /// Returns Result<UPCA, String> indicating parse success.
+    pub fn new(data: String) -> Result<UPCA, String> {
+        let mut digits: Vec<u8> = UPCA::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 12 {
+            let check = digits.pop().unwrap();
+
+            if helpers::modulo_10_checksum(&digits) != check {
+                return Err(format!("Invalid check digit: {}", check));
+            }
+        }
+
+        Ok(UPCA{data: digits})
+    }
+
+    /// Returns the data as was passed into the constructor, without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Returns the number system digit.
+    pub fn number_system_digit(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Returns the 5-digit manufacturer code.
+    pub fn manufacturer_code(&self) -> &[u8] {
+        &self.data[1..6]
+    }
+
+    /// Returns the 5-digit product code.
+    pub fn product_code(&self) -> &[u8] {
+        &self.data[6..11]
+    }
+
+    /// Calculates the checksum digit using a modulo-10 weighting algorithm.
+    pub fn checksum_digit(&self) -> u8 {
+        helpers::modulo_10_checksum(&self.data)
+    }
+
+    fn char_encoding(&self, side: usize, d: &u8) -> [u8; 7] {
+        EAN_ENCODINGS[side][*d as usize]
+    }
+
+    fn left_payload(&self) -> Vec<u8> {
+        self.data[0..6]
+            .iter()
+            .flat_map(|d| self.char_encoding(0, d).to_vec())
+            .collect()
+    }
+
+    fn right_payload(&self) -> Vec<u8> {
+        self.data[6..]
+            .iter()
+            .chain([self.checksum_digit()].iter())
+            .flat_map(|d| self.char_encoding(2, d).to_vec())
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<u8> of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            EAN_LEFT_GUARD.to_vec(), self.left_payload(), EAN_MIDDLE_GUARD.to_vec(),
+            self.right_payload(), EAN_RIGHT_GUARD.to_vec()][..])
+    }
+
+    /// Returns the modules whose bars extend below the human-readable text: the left, middle
+    /// and right guards, and the first and last digits.
+    pub fn encode_guards(&self) -> EncodedGuards {
+        helpers::join_vecs(&[vec![1; 10], vec![0; 35], vec![1; 5], vec![0; 35], vec![1; 10]][..])
+    }
+}
+
+impl Parse for UPCA {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        11..=12
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::upca::*;
+    use ::sym::ean13::EAN13;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_upca() {
+        let upca1 = UPCA::new("03600029145".to_string());
+        let upca2 = UPCA::new("036000291452".to_string());
+
+        assert!(upca1.is_ok());
+        assert!(upca2.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_upca() {
+        let upca = UPCA::new("0360002914A".to_string());
+
+        assert!(upca.is_err());
+    }
+
+    #[test]
+    fn invalid_len_upca() {
+        let upca1 = UPCA::new("0360002914".to_string());
+        let upca2 = UPCA::new("0123451234567".to_string());
+
+        assert!(upca1.is_err());
+        assert!(upca2.is_err());
+    }
+
+    #[test]
+    fn invalid_check_digit_upca() {
+        let upca = UPCA::new("036000291453".to_string());
+
+        assert!(upca.is_err());
+    }
+
+    #[test]
+    fn upca_raw_data() {
+        let upca = UPCA::new("036000291452".to_string()).unwrap();
+
+        assert_eq!(upca.raw_data(), &[0,3,6,0,0,0,2,9,1,4,5]);
+    }
+
+    #[test]
+    fn upca_segments() {
+        let upca = UPCA::new("03600029145".to_string()).unwrap();
+
+        assert_eq!(upca.number_system_digit(), 0);
+        assert_eq!(upca.manufacturer_code(), &[3,6,0,0,0]);
+        assert_eq!(upca.product_code(), &[2,9,1,4,5]);
+    }
+
+    #[test]
+    fn upca_checksum_calculation() {
+        let upca1 = UPCA::new("03600029145".to_string()).unwrap(); // Check digit: 2
+        let upca2 = UPCA::new("12345612345".to_string()).unwrap(); // Check digit: 8
+
+        assert_eq!(upca1.checksum_digit(), 2);
+        assert_eq!(upca2.checksum_digit(), 8);
+    }
+
+    #[test]
+    fn upca_encode() {
+        let upca1 = UPCA::new("12345612345".to_string()).unwrap(); // Check digit: 8
+        let upca2 = UPCA::new("00118999561".to_string()).unwrap(); // Check digit: 3
+
+        assert_eq!(collapse_vec(upca1.encode()), "10100110010010011011110101000110110001010111101010110011011011001000010101110010011101001000101".to_string());
+        assert_eq!(collapse_vec(upca2.encode()), "10100011010001101001100100110010110111000101101010111010011101001001110101000011001101000010101".to_string());
+    }
+
+    #[test]
+    fn upca_encode_guards() {
+        let upca = UPCA::new("12345678999".to_string()).unwrap();
+        let guards = upca.encode_guards();
+
+        assert_eq!(guards.len(), upca.encode().len());
+        assert_eq!(&guards[9..11], &[1, 0]);
+    }
+
+    #[test]
+    fn upca_encode_matches_ean13() {
+        let upca = UPCA::new("03600029145".to_string()).unwrap();
+        let ean13 = EAN13::new("003600029145".to_string()).unwrap();
+
+        assert_eq!(upca.encode(), ean13.encode());
+    }
+}
@@ -0,0 +1,264 @@
+//! This module provides types for encoding UPC-E barcodes. UPC-E is a zero-suppressed version
+//! of UPC-A for use on small retail packaging. Eligible UPC-A numbers are compressed into six
+//! digits by removing runs of zeros from the manufacturer and product codes.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::helpers;
+use ::sym::ean13::EAN_ENCODINGS;
+use ::sym::ean13::EAN_LEFT_GUARD;
+use ::sym::upca::UPCA;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// UPC-E barcodes end with this guard instead of the usual EAN right guard.
+pub const UPCE_RIGHT_GUARD: [u8; 6] = [0,1,0,1,0,1];
+
+/// Maps parity (odd/even) for the six digits based on the check digit, for number system 0.
+/// Number system 1 uses the opposite parity.
+const UPCE_PARITY: [[usize; 6]; 10] = [
+    [1,1,1,0,0,0],
+    [1,1,0,1,0,0],
+    [1,1,0,0,1,0],
+    [1,1,0,0,0,1],
+    [1,0,1,1,0,0],
+    [1,0,0,1,1,0],
+    [1,0,0,0,1,1],
+    [1,0,1,0,1,0],
+    [1,0,1,0,0,1],
+    [1,0,0,1,0,1],
+];
+
+/// The UPC-E barcode type.
+pub struct UPCE {
+    data: Vec<u8>,
+}
+
+impl UPCE {
+    /// Creates a new barcode from the six UPC-E digits (number system 0 is assumed), the
+    /// number system digit followed by the six digits, or all of those followed by a check
+    /// digit, which will be verified.
+    /// Returns Result<UPCE, String> indicating parse success.
+    pub fn new(data: String) -> Result<UPCE, String> {
+        let mut digits: Vec<u8> = UPCE::parse(data)?
+            .chars()
+            .map(|c| c.to_digit(10).expect("Unknown character") as u8)
+            .collect();
+
+        if digits.len() == 6 {
+            digits.insert(0, 0);
+        }
+
+        if digits[0] > 1 {
+            return Err(format!("Invalid number system: {}", digits[0]));
+        }
+
+        let check = if digits.len() == 8 { digits.pop() } else { None };
+        let upce = UPCE{data: digits};
+
+        match check {
+            Some(c) if c != upce.checksum_digit() => Err(format!("Invalid check digit: {}", c)),
+            _ => Ok(upce),
+        }
+    }
+
+    /// Creates a new barcode by zero-suppressing the given UPC-A barcode.
+    /// Returns an error if the UPC-A number is not eligible for compression.
+    pub fn from_upca(upca: &UPCA) -> Result<UPCE, String> {
+        let ns = upca.number_system_digit();
+        let m = upca.manufacturer_code();
+        let p = upca.product_code();
+
+        if ns > 1 {
+            return Err(format!("Invalid number system: {}", ns));
+        }
+
+        let digits = if m[2] <= 2 && m[3..] == [0, 0] && p[..2] == [0, 0] {
+            [m[0], m[1], p[2], p[3], p[4], m[2]]
+        } else if m[3..] == [0, 0] && p[..3] == [0, 0, 0] {
+            [m[0], m[1], m[2], p[3], p[4], 3]
+        } else if m[4] == 0 && p[..4] == [0, 0, 0, 0] {
+            [m[0], m[1], m[2], m[3], p[4], 4]
+        } else if p[..4] == [0, 0, 0, 0] && p[4] >= 5 {
+            [m[0], m[1], m[2], m[3], m[4], p[4]]
+        } else {
+            return Err("UPC-A number cannot be zero-suppressed".to_string());
+        };
+
+        let mut data = vec![ns];
+        data.extend(digits.iter().cloned());
+
+        Ok(UPCE{data})
+    }
+
+    /// Expands the barcode into the equivalent UPC-A barcode.
+    pub fn to_upca(&self) -> UPCA {
+        let d = &self.data[1..];
+        let (manufacturer, product) = match d[5] {
+            0..=2 => ([d[0], d[1], d[5], 0, 0], [0, 0, d[2], d[3], d[4]]),
+            3 => ([d[0], d[1], d[2], 0, 0], [0, 0, 0, d[3], d[4]]),
+            4 => ([d[0], d[1], d[2], d[3], 0], [0, 0, 0, 0, d[4]]),
+            _ => ([d[0], d[1], d[2], d[3], d[4]], [0, 0, 0, 0, d[5]]),
+        };
+
+        let upca: String = [self.data[0]].iter()
+            .chain(manufacturer.iter())
+            .chain(product.iter())
+            .map(|&d| char::from_digit(d as u32, 10).unwrap())
+            .collect();
+
+        UPCA::new(upca).expect("Expanded UPC-E is always a valid UPC-A")
+    }
+
+    /// Returns the data as was passed into the constructor, including the number system digit
+    /// and without the check digit.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    /// Returns the number system digit (0 or 1).
+    pub fn number_system_digit(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Calculates the checksum digit of the equivalent UPC-A barcode.
+    pub fn checksum_digit(&self) -> u8 {
+        self.to_upca().checksum_digit()
+    }
+
+    fn parity_mapping(&self) -> [usize; 6] {
+        let mut parity = UPCE_PARITY[self.checksum_digit() as usize];
+
+        if self.number_system_digit() == 1 {
+            for p in parity.iter_mut() {
+                *p = 1 - *p;
+            }
+        }
+
+        parity
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        self.data[1..]
+            .iter()
+            .zip(self.parity_mapping().iter())
+            .flat_map(|(d, s)| EAN_ENCODINGS[*s][*d as usize].to_vec())
+            .collect()
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<u8> of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        helpers::join_vecs(&[
+            EAN_LEFT_GUARD.to_vec(), self.payload(), UPCE_RIGHT_GUARD.to_vec()][..])
+    }
+}
+
+impl Parse for UPCE {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        6..=8
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::upce::*;
+    use ::sym::upca::UPCA;
+    use std::char;
+
+    fn collapse_vec(v: Vec<u8>) -> String {
+        let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
+        chars.collect()
+    }
+
+    #[test]
+    fn new_upce() {
+        let upce1 = UPCE::new("425261".to_string());
+        let upce2 = UPCE::new("0425261".to_string());
+        let upce3 = UPCE::new("04252614".to_string());
+
+        assert!(upce1.is_ok());
+        assert!(upce2.is_ok());
+        assert!(upce3.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_upce() {
+        let upce1 = UPCE::new("42526A".to_string());
+        let upce2 = UPCE::new("2425261".to_string());
+
+        assert!(upce1.is_err());
+        assert!(upce2.is_err());
+    }
+
+    #[test]
+    fn invalid_len_upce() {
+        let upce1 = UPCE::new("42526".to_string());
+        let upce2 = UPCE::new("012345651".to_string());
+
+        assert!(upce1.is_err());
+        assert!(upce2.is_err());
+    }
+
+    #[test]
+    fn invalid_check_digit_upce() {
+        let upce = UPCE::new("04252615".to_string());
+
+        assert!(upce.is_err());
+    }
+
+    #[test]
+    fn upce_raw_data() {
+        let upce = UPCE::new("04252614".to_string()).unwrap();
+
+        assert_eq!(upce.raw_data(), &[0,4,2,5,2,6,1]);
+        assert_eq!(upce.number_system_digit(), 0);
+    }
+
+    #[test]
+    fn upce_from_upca() {
+        let cases = [("04210000526", "0425261"), ("01230000045", "0123453"),
+                     ("01234000005", "0123454"), ("11234500007", "1123457")];
+
+        for &(upca, upce) in cases.iter() {
+            let upca = UPCA::new(upca.to_string()).unwrap();
+            let upce = UPCE::new(upce.to_string()).unwrap();
+
+            assert_eq!(UPCE::from_upca(&upca).unwrap().raw_data(), upce.raw_data());
+            assert_eq!(upce.to_upca().raw_data(), upca.raw_data());
+        }
+    }
+
+    #[test]
+    fn upce_from_ineligible_upca() {
+        let upca1 = UPCA::new("03600029145".to_string()).unwrap();
+        let upca2 = UPCA::new("21234000005".to_string()).unwrap();
+
+        assert!(UPCE::from_upca(&upca1).is_err());
+        assert!(UPCE::from_upca(&upca2).is_err());
+    }
+
+    #[test]
+    fn upce_checksum_calculation() {
+        let upce1 = UPCE::new("0425261".to_string()).unwrap(); // Check digit: 4
+        let upce2 = UPCE::new("0654321".to_string()).unwrap(); // Check digit: 7
+
+        assert_eq!(upce1.checksum_digit(), 4);
+        assert_eq!(upce2.checksum_digit(), 7);
+    }
+
+    #[test]
+    fn upce_encode() {
+        let upce1 = UPCE::new("0425261".to_string()).unwrap();
+        let upce2 = UPCE::new("1425261".to_string()).unwrap();
+
+        assert_eq!(collapse_vec(upce1.encode()), "101001110100100110111001001101101011110011001010101".to_string());
+        assert_eq!(collapse_vec(upce2.encode()), "101010001100100110111001001001100001010110011010101".to_string());
+    }
+}
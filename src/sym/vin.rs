@@ -0,0 +1,160 @@
+//! This module provides types for encoding VINs (Vehicle Identification Numbers) as Code39
+//! barcodes.
+//!
+//! A VIN is 17 digits and upper-case letters, excluding I, O and Q so that they are not
+//! mistaken for 1 and 0. The ninth character is a check digit, calculated modulo 11 over the
+//! values of the other characters, with 'X' standing for 10. Vehicles imported into the United
+//! States are marked with an 'I' before the VIN.
+
+use ::sym::Parse;
+use ::sym::EncodedBarcode;
+use ::sym::code39::Code39;
+use std::ops::RangeInclusive;
+use std::char;
+
+/// The marker encoded before the VIN of an imported vehicle.
+pub const VIN_IMPORT_MARKER: char = 'I';
+
+/// The weights of each position in the check digit calculation. The check digit itself has a
+/// weight of zero.
+const VIN_WEIGHTS: [u32; 17] = [8, 7, 6, 5, 4, 3, 2, 10, 0, 9, 8, 7, 6, 5, 4, 3, 2];
+
+// The position of the check digit.
+const VIN_CHECK_POSITION: usize = 8;
+
+// The value of each character in the check digit calculation.
+fn value(c: char) -> u32 {
+    match c {
+        '0'..='9' => c.to_digit(10).unwrap(),
+        'A'..='H' => c as u32 - 'A' as u32 + 1,
+        'J'..='N' => c as u32 - 'J' as u32 + 1,
+        'P' => 7,
+        'R' => 9,
+        'S'..='Z' => c as u32 - 'S' as u32 + 2,
+        _ => panic!("Unknown character: {}", c),
+    }
+}
+
+/// The VIN barcode type.
+pub struct VIN {
+    data: Vec<char>,
+    code39: Code39,
+}
+
+impl VIN {
+    fn init(data: String, import: bool) -> Result<VIN, String> {
+        let vin: Vec<char> = VIN::parse(data)?.chars().collect();
+        let check = vin[VIN_CHECK_POSITION];
+
+        if VIN::checksum_char_of(&vin) != check {
+            return Err(format!("Invalid check digit: {}", check));
+        }
+
+        let marker = if import { Some(VIN_IMPORT_MARKER) } else { None };
+        let code39 = Code39::new(marker.into_iter().chain(vin.iter().cloned()).collect())?;
+
+        Ok(VIN{data: vin, code39})
+    }
+
+    /// Creates a new barcode from a 17 character VIN, whose check digit will be verified.
+    /// Returns Result<VIN, String> indicating parse success.
+    pub fn new(data: String) -> Result<VIN, String> {
+        VIN::init(data, false)
+    }
+
+    /// Creates a new barcode of an imported vehicle's VIN, with the 'I' import marker encoded
+    /// before it.
+    /// Returns Result<VIN, String> indicating parse success.
+    pub fn imported(data: String) -> Result<VIN, String> {
+        VIN::init(data, true)
+    }
+
+    fn checksum_char_of(vin: &[char]) -> char {
+        let sum: u32 = vin.iter()
+            .zip(VIN_WEIGHTS.iter())
+            .map(|(&c, &w)| value(c) * w)
+            .sum();
+
+        match sum % 11 {
+            10 => 'X',
+            n => char::from_digit(n, 10).unwrap(),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Calculates the check digit using a modulo-11 algorithm.
+    pub fn checksum_char(&self) -> char {
+        VIN::checksum_char_of(&self.data)
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedBarcode (wrapper type of Vec<u8>) of binary digits.
+    pub fn encode(&self) -> EncodedBarcode {
+        self.code39.encode()
+    }
+}
+
+impl Parse for VIN {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        17..=17
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        ('0'..='9').chain(('A'..='Z').filter(|&c| c != 'I' && c != 'O' && c != 'Q')).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym::vin::*;
+    use ::sym::code39::Code39;
+
+    #[test]
+    fn new_vin() {
+        let vin = VIN::new("1M8GDM9AXKP042788".to_string());
+
+        assert!(vin.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_vin() {
+        assert!(VIN::new("1M8GDM9AXKP04278O".to_string()).is_err());
+        assert!(VIN::new("1M8GDM9AXKP04278Q".to_string()).is_err());
+        assert!(VIN::new("1m8gdm9axkp042788".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_len_vin() {
+        assert_eq!(VIN::new("1M8GDM9AXKP04278".to_string()).err(),
+                   Some("Data does not fit within range of 17-17".to_string()));
+        assert!(VIN::new("1M8GDM9AXKP0427888".to_string()).is_err());
+    }
+
+    #[test]
+    fn invalid_checksum_vin() {
+        let vin = VIN::new("1M8GDM9A1KP042788".to_string());
+
+        assert!(vin.is_err());
+    }
+
+    #[test]
+    fn vin_checksum_calculation() {
+        assert_eq!(VIN::new("1M8GDM9AXKP042788".to_string()).unwrap().checksum_char(), 'X');
+        assert_eq!(VIN::new("11111111111111111".to_string()).unwrap().checksum_char(), '1');
+    }
+
+    #[test]
+    fn vin_encode() {
+        let vin = VIN::new("1M8GDM9AXKP042788".to_string()).unwrap();
+        let imported = VIN::imported("1M8GDM9AXKP042788".to_string()).unwrap();
+
+        assert_eq!(vin.encode(), Code39::new("1M8GDM9AXKP042788".to_string()).unwrap().encode());
+        assert_eq!(imported.encode(), Code39::new("I1M8GDM9AXKP042788".to_string()).unwrap().encode());
+    }
+}
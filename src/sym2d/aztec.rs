@@ -0,0 +1,494 @@
+//! This module provides types for encoding Aztec Code barcodes. Aztec Code is a matrix
+//! symbology built around a central bullseye finder, so it needs no quiet zone, and is used
+//! on transit and airline tickets.
+//!
+//! Compact symbols have 1-4 layers of data around a two-ring bullseye, and full-range symbols
+//! up to 32 layers around a three-ring bullseye with a reference grid. The smallest symbol that
+//! holds the data with the requested share of error correction (33% by default) is used.
+//! Characters are ISO-8859-1; those outside the text modes are encoded in binary shifts.
+
+use ::sym::helpers;
+use ::sym2d::EncodedMatrix;
+
+/// The largest number of layers in a full-range symbol.
+pub const AZTEC_MAX_LAYERS: usize = 32;
+
+/// The default percentage of the symbol given to error correction.
+pub const AZTEC_DEFAULT_ECC_PERCENT: usize = 33;
+
+/// The characters of the mixed mode, with the values from 1.
+const AZTEC_MIXED_CHARS: [u8; 27] = [
+    b' ', 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 27, 28, 29, 30, 31, b'@', b'\\', b'^', b'_',
+    b'`', b'|', b'~', 127,
+];
+
+/// The single characters of the punctuation mode, with the values from 6.
+const AZTEC_PUNCT_CHARS: &str = "!\"#$%&'()*+,-./:;<=>?[]{}";
+
+// The text modes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    Upper,
+    Lower,
+    Mixed,
+    Punct,
+    Digit,
+}
+
+impl Mode {
+    // The number of bits in each of the mode's codes.
+    fn width(self) -> usize {
+        match self {
+            Mode::Digit => 4,
+            _ => 5,
+        }
+    }
+
+    // The code of a character in the mode, if the mode includes it.
+    fn code(self, c: u8) -> Option<u32> {
+        let code = match (self, c) {
+            (Mode::Upper, b' ') | (Mode::Lower, b' ') | (Mode::Digit, b' ') => 1,
+            (Mode::Upper, b'A'..=b'Z') => c - b'A' + 2,
+            (Mode::Lower, b'a'..=b'z') => c - b'a' + 2,
+            (Mode::Digit, b'0'..=b'9') => c - b'0' + 2,
+            (Mode::Digit, b',') => 12,
+            (Mode::Digit, b'.') => 13,
+            (Mode::Punct, b'\r') => 1,
+            (Mode::Punct, _) => return AZTEC_PUNCT_CHARS.bytes().position(|p| p == c).map(|i| i as u32 + 6),
+            (Mode::Mixed, _) => return AZTEC_MIXED_CHARS.iter().position(|&m| m == c).map(|i| i as u32 + 1),
+            _ => return None,
+        };
+
+        Some(code as u32)
+    }
+
+    // The codes (and their widths) that latch from this mode to another.
+    fn latch(self, to: Mode) -> Vec<(u32, usize)> {
+        match (self, to) {
+            _ if self == to => vec![],
+            (Mode::Upper, Mode::Lower) | (Mode::Mixed, Mode::Lower) => vec![(28, 5)],
+            (Mode::Upper, Mode::Mixed) | (Mode::Lower, Mode::Mixed) | (Mode::Mixed, Mode::Upper) => vec![(29, 5)],
+            (Mode::Upper, Mode::Digit) | (Mode::Lower, Mode::Digit) | (Mode::Mixed, Mode::Punct) => vec![(30, 5)],
+            (Mode::Upper, Mode::Punct) | (Mode::Lower, Mode::Punct) | (Mode::Mixed, Mode::Digit) => vec![(29, 5), (30, 5)],
+            (Mode::Lower, Mode::Upper) => vec![(30, 5), (14, 4)],
+            (Mode::Punct, _) => [(31, 5)].iter().cloned().chain(Mode::Upper.latch(to)).collect(),
+            (Mode::Digit, _) => [(14, 4)].iter().cloned().chain(Mode::Upper.latch(to)).collect(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+// Converts bits to words of `word_size` bits.
+fn words(bits: &[u8], word_size: usize) -> Vec<u16> {
+    bits.chunks(word_size).map(|w| w.iter().fold(0, |acc, &b| (acc << 1) | b as u16)).collect()
+}
+
+// The number of bits in the given number of layers.
+fn layer_bits(layers: usize, compact: bool) -> usize {
+    ((if compact { 88 } else { 112 }) + 16 * layers) * layers
+}
+
+// The size of the codewords in the given number of layers.
+fn word_size(layers: usize) -> usize {
+    match layers {
+        1..=2 => 6,
+        3..=8 => 8,
+        9..=22 => 10,
+        _ => 12,
+    }
+}
+
+// The primitive polynomial of the Galois field for codewords of the given size.
+fn gf_poly(word_size: usize) -> u32 {
+    match word_size {
+        4 => 0x13,
+        6 => 0x43,
+        8 => 0x12D,
+        10 => 0x409,
+        _ => 0x1069,
+    }
+}
+
+/// Encodes the data in the text modes, using shifts for single characters and binary shifts
+/// for bytes outside the text modes.
+pub fn data_bits(data: &[u8]) -> Vec<u8> {
+    let modes = [Mode::Upper, Mode::Lower, Mode::Mixed, Mode::Punct, Mode::Digit];
+    let mut bits = vec![];
+    let mut mode = Mode::Upper;
+    let mut i = 0;
+
+    while i < data.len() {
+        let c = data[i];
+
+        if let Some(code) = mode.code(c) {
+            helpers::push_bits(&mut bits, code, mode.width());
+            i += 1;
+            continue;
+        }
+
+        match modes.iter().cloned().find(|m| m.code(c).is_some()) {
+            Some(target) => {
+                let shift = data.get(i + 1).is_none_or(|&n| target.code(n).is_none());
+
+                if shift && target == Mode::Punct {
+                    helpers::push_bits(&mut bits, 0, mode.width());
+                } else if shift && target == Mode::Upper && mode != Mode::Mixed {
+                    helpers::push_bits(&mut bits, if mode == Mode::Digit { 15 } else { 28 }, mode.width());
+                } else {
+                    for (code, width) in mode.latch(target) {
+                        helpers::push_bits(&mut bits, code, width);
+                    }
+
+                    mode = target;
+                }
+
+                helpers::push_bits(&mut bits, target.code(c).unwrap(), target.width());
+                i += 1;
+            },
+            None => {
+                let len = data[i..].iter()
+                    .take_while(|&&b| modes.iter().all(|m| m.code(b).is_none()))
+                    .count()
+                    .min(2078);
+
+                if mode == Mode::Punct || mode == Mode::Digit {
+                    for (code, width) in mode.latch(Mode::Upper) {
+                        helpers::push_bits(&mut bits, code, width);
+                    }
+
+                    mode = Mode::Upper;
+                }
+
+                helpers::push_bits(&mut bits, 31, 5);
+
+                if len <= 31 {
+                    helpers::push_bits(&mut bits, len as u32, 5);
+                } else {
+                    helpers::push_bits(&mut bits, 0, 5);
+                    helpers::push_bits(&mut bits, (len - 31) as u32, 11);
+                }
+
+                for &b in &data[i..(i + len)] {
+                    helpers::push_bits(&mut bits, b as u32, 8);
+                }
+
+                i += len;
+            },
+        }
+    }
+
+    bits
+}
+
+/// Splits the bits into codewords, inserting a complementary bit wherever a codeword would
+/// otherwise be all zeros or all ones. The last codeword is padded with ones.
+pub fn stuff_bits(bits: &[u8], word_size: usize) -> Vec<u8> {
+    let mut stuffed = vec![];
+    let mut i = 0;
+
+    while i < bits.len() {
+        let mut word: Vec<u8> = (0..(word_size - 1)).map(|j| *bits.get(i + j).unwrap_or(&1)).collect();
+
+        if word.iter().all(|&b| b == word[0]) {
+            word.push(1 - word[0]);
+            i += word_size - 1;
+        } else {
+            word.push(*bits.get(i + word_size - 1).unwrap_or(&1));
+            i += word_size;
+        }
+
+        stuffed.extend(word);
+    }
+
+    stuffed
+}
+
+// Appends Reed-Solomon check words to fill `total_bits`, which are preceded by any bits left
+// over after whole codewords.
+fn check_words(bits: &[u8], total_bits: usize, word_size: usize) -> Vec<u8> {
+    let data = words(bits, word_size);
+    let ecc = helpers::reed_solomon(&data, total_bits / word_size - data.len(), gf_poly(word_size), 1);
+    let mut message = vec![0; total_bits % word_size];
+
+    for word in data.iter().chain(ecc.iter()) {
+        helpers::push_bits(&mut message, *word as u32, word_size);
+    }
+
+    message
+}
+
+/// The Aztec Code barcode type.
+pub struct Aztec {
+    data: String,
+    compact: bool,
+    layers: usize,
+    stuffed: Vec<u8>,
+}
+
+impl Aztec {
+    /// Creates a new barcode with the default share of error correction.
+    /// Returns Result<Aztec, String> indicating success.
+    pub fn new(data: String) -> Result<Aztec, String> {
+        Aztec::with_ecc_percent(data, AZTEC_DEFAULT_ECC_PERCENT)
+    }
+
+    /// Creates a new barcode with at least the given percentage of error correction.
+    /// Returns Result<Aztec, String> indicating success.
+    pub fn with_ecc_percent(data: String, ecc_percent: usize) -> Result<Aztec, String> {
+        let bytes: Vec<u8> = data.chars()
+            .map(|c| if (c as u32) < 256 { Ok(c as u8) } else { Err(format!("Character {:?} is not ISO-8859-1", c)) })
+            .collect::<Result<_, _>>()?;
+        let bits = data_bits(&bytes);
+        let ecc_bits = bits.len() * ecc_percent / 100 + 11;
+
+        // Compact symbols of 1-4 layers, then full-range symbols of 4-32 layers.
+        let sizes = (1..=4).map(|l| (true, l)).chain((4..=AZTEC_MAX_LAYERS).map(|l| (false, l)));
+
+        for (compact, layers) in sizes {
+            let total = layer_bits(layers, compact);
+            let word_size = word_size(layers);
+            let stuffed = stuff_bits(&bits, word_size);
+
+            // The mode message of compact symbols can count no more than 64 codewords.
+            if compact && stuffed.len() > word_size * 64 {
+                continue;
+            }
+
+            if bits.len() + ecc_bits <= total && stuffed.len() + ecc_bits <= total - total % word_size {
+                return Ok(Aztec{data, compact, layers, stuffed});
+            }
+        }
+
+        Err("Data too long to encode in an Aztec Code".to_string())
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns true for a compact symbol, or false for a full-range symbol.
+    pub fn is_compact(&self) -> bool {
+        self.compact
+    }
+
+    /// Returns the number of layers of data around the bullseye.
+    pub fn layers(&self) -> usize {
+        self.layers
+    }
+
+    // The size of the symbol without the reference grid of full-range symbols.
+    fn base_size(&self) -> usize {
+        (if self.compact { 11 } else { 14 }) + self.layers * 4
+    }
+
+    /// Returns the width (and height) of the symbol in modules.
+    pub fn size(&self) -> usize {
+        let base = self.base_size();
+
+        if self.compact { base } else { base + 1 + 2 * ((base / 2 - 1) / 15) }
+    }
+
+    /// Returns the number of data codewords, after bit stuffing.
+    pub fn data_words(&self) -> usize {
+        self.stuffed.len() / word_size(self.layers)
+    }
+
+    /// Returns the mode message: the number of layers and data codewords, followed by its
+    /// check words.
+    pub fn mode_message(&self) -> Vec<u8> {
+        let mut bits = vec![];
+
+        if self.compact {
+            helpers::push_bits(&mut bits, self.layers as u32 - 1, 2);
+            helpers::push_bits(&mut bits, self.data_words() as u32 - 1, 6);
+            check_words(&bits, 28, 4)
+        } else {
+            helpers::push_bits(&mut bits, self.layers as u32 - 1, 5);
+            helpers::push_bits(&mut bits, self.data_words() as u32 - 1, 11);
+            check_words(&bits, 40, 4)
+        }
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules. Aztec Code needs no quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        let base = self.base_size();
+        let size = self.size();
+        let center = size / 2;
+        let message = check_words(&self.stuffed, layer_bits(self.layers, self.compact), word_size(self.layers));
+        let mut matrix = EncodedMatrix::new(size, size);
+
+        // Maps positions in the symbol without the reference grid to positions with it.
+        let map: Vec<usize> = (0..base)
+            .map(|i| {
+                if self.compact {
+                    i
+                } else if i < base / 2 {
+                    let offset = base / 2 - 1 - i;
+                    center - offset - offset / 15 - 1
+                } else {
+                    let offset = i - base / 2;
+                    center + offset + offset / 15 + 1
+                }
+            })
+            .collect();
+
+        // The layers spiral outwards to inwards clockwise from the top left, each side two
+        // modules deep.
+        let mut offset = 0;
+
+        for i in 0..self.layers {
+            let row_size = (self.layers - i) * 4 + if self.compact { 9 } else { 12 };
+
+            for j in 0..row_size {
+                for k in 0..2 {
+                    let sides = [
+                        (map[i * 2 + k], map[i * 2 + j]),
+                        (map[i * 2 + j], map[base - 1 - i * 2 - k]),
+                        (map[base - 1 - i * 2 - k], map[base - 1 - i * 2 - j]),
+                        (map[base - 1 - i * 2 - j], map[i * 2 + k]),
+                    ];
+
+                    for (side, &(x, y)) in sides.iter().enumerate() {
+                        if message[offset + row_size * 2 * side + j * 2 + k] == 1 {
+                            matrix.set(x, y, 1);
+                        }
+                    }
+                }
+            }
+
+            offset += row_size * 8;
+        }
+
+        // The mode message runs clockwise around the bullseye, skipping the reference grid.
+        let mode = self.mode_message();
+        let (count, ring) = if self.compact { (7, 5) } else { (10, 7) };
+
+        for i in 0..count {
+            let pos = if self.compact { center - 3 + i } else { center - 5 + i + i / 5 };
+
+            matrix.set(pos, center - ring, mode[i]);
+            matrix.set(center + ring, pos, mode[i + count]);
+            matrix.set(pos, center + ring, mode[count * 3 - 1 - i]);
+            matrix.set(center - ring, pos, mode[count * 4 - 1 - i]);
+        }
+
+        // The bullseye, with orientation marks at its corners.
+        let rings = if self.compact { 5 } else { 7 };
+
+        for r in (0..rings).step_by(2) {
+            for j in (center - r)..=(center + r) {
+                matrix.set(j, center - r, 1);
+                matrix.set(j, center + r, 1);
+                matrix.set(center - r, j, 1);
+                matrix.set(center + r, j, 1);
+            }
+        }
+
+        for &(x, y) in &[(center - rings, center - rings), (center - rings + 1, center - rings),
+                         (center - rings, center - rings + 1), (center + rings, center - rings),
+                         (center + rings, center - rings + 1), (center + rings, center + rings - 1)] {
+            matrix.set(x, y, 1);
+        }
+
+        // The reference grid of full-range symbols: alternating lines every 16 modules from the
+        // centre.
+        if !self.compact {
+            for j in (0..(base / 2 - 1)).step_by(15).map(|i| i / 15 * 16) {
+                for k in ((center & 1)..size).step_by(2) {
+                    matrix.set(center - j, k, 1);
+                    matrix.set(center + j, k, 1);
+                    matrix.set(k, center - j, 1);
+                    matrix.set(k, center + j, 1);
+                }
+            }
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::aztec::*;
+
+    fn collapse_bits(bits: &str) -> Vec<u8> {
+        bits.chars().filter(|&c| c != ' ').map(|c| (c == 'X') as u8).collect()
+    }
+
+    fn with_size(compact: bool, layers: usize, data_words: usize) -> Aztec {
+        let stuffed = vec![0; data_words * word_size(layers)];
+
+        Aztec{data: String::new(), compact, layers, stuffed}
+    }
+
+    #[test]
+    fn new_aztec() {
+        let aztec = Aztec::new("This is an example Aztec symbol for Wikipedia.".to_string());
+
+        assert!(aztec.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_aztec() {
+        let aztec = Aztec::new("€".to_string());
+
+        assert!(aztec.is_err());
+    }
+
+    #[test]
+    fn invalid_len_aztec() {
+        let aztec = Aztec::new("\u{ff}".repeat(2000));
+
+        assert!(aztec.is_err());
+    }
+
+    #[test]
+    fn aztec_data_bits() {
+        // 'A', P/S '.', L/L 'b', D/L '1'
+        assert_eq!(data_bits(b"A.bb1"), collapse_bits("...X. ..... X..XX XXX.. ...XX ...XX XXXX. ..XX"));
+        // B/S of two bytes.
+        assert_eq!(data_bits(&[0xE9, 0xFF]), collapse_bits("XXXXX ...X. XXX.X..X XXXXXXXX"));
+    }
+
+    #[test]
+    fn aztec_stuff_bits() {
+        assert_eq!(stuff_bits(&collapse_bits(".X.X. X.X.X .X.X."), 5), collapse_bits(".X.X. X.X.X .X.X."));
+        assert_eq!(stuff_bits(&collapse_bits(".X.X. ..... .X.X"), 5), collapse_bits(".X.X. ....X ..X.X"));
+        assert_eq!(stuff_bits(&collapse_bits("XX. ... ... ..X XXX .X. .."), 3),
+                   collapse_bits("XX. ..X ..X ..X ..X .XX XX. .X. ..X"));
+    }
+
+    #[test]
+    fn aztec_mode_message() {
+        assert_eq!(with_size(true, 2, 29).mode_message(), collapse_bits(".X .XXX.. ...X XX.. ..X .XX. .XX.X"));
+        assert_eq!(with_size(true, 4, 64).mode_message(), collapse_bits("XX XXXXXX .X.. ...X ..XX .X.. XX.."));
+        assert_eq!(with_size(false, 21, 660).mode_message(),
+                   collapse_bits("X.X.. .X.X..X..XX .XXX ..X.. .XXX. .X... ..XXX"));
+    }
+
+    #[test]
+    fn aztec_size() {
+        let compact = Aztec::new("This is an example Aztec symbol for Wikipedia.".to_string()).unwrap();
+        let full = Aztec::new("Aztec Code is a public domain 2D matrix barcode symbology of nominally square \
+                               symbols built on a square grid with a distinctive square bullseye pattern at \
+                               their center.".to_string()).unwrap();
+
+        assert_eq!((compact.is_compact(), compact.layers(), compact.size()), (true, 3, 23));
+        assert_eq!((full.is_compact(), full.layers(), full.size()), (false, 6, 41));
+        assert_eq!(Aztec::new("A".to_string()).unwrap().size(), 15);
+        assert_eq!(with_size(false, 32, 1).size(), 151);
+    }
+
+    #[test]
+    fn aztec_encode() {
+        let aztec = Aztec::new("This is an example Aztec symbol for Wikipedia.".to_string()).unwrap();
+        let matrix = aztec.encode();
+        let row = |y| (0..23).map(|x| matrix.get(x, y)).collect::<Vec<u8>>();
+
+        assert_eq!(row(0), collapse_bits("X..XX...X..XX..X..X...."));
+        // The centre row of the bullseye.
+        assert_eq!(&row(11)[7..16], &collapse_bits("X.X.X.X.X")[..]);
+    }
+}
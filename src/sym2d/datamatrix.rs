@@ -0,0 +1,596 @@
+//! This module provides types for encoding Data Matrix (ECC 200) barcodes. Data Matrix is a
+//! matrix symbology widely used for direct part marking, where codes are etched or peened onto
+//! the items themselves.
+//!
+//! The data is compacted using the shortest of the ASCII, C40, Text and Base256 encodations,
+//! and placed in the smallest of the 24 square symbol sizes (10x10 to 144x144 modules) that can
+//! hold it. Characters are ISO-8859-1, and the FNC1 function character is given as
+//! `code128::FNC1`. A leading FNC1 marks the data as GS1.
+
+use ::sym::helpers;
+use ::sym::code128::FNC1;
+use ::sym2d::EncodedMatrix;
+
+/// The width (and height) of each square symbol size, in modules.
+const DATAMATRIX_SIZES: [usize; 24] = [
+    10, 12, 14, 16, 18, 20, 22, 24, 26, 32, 36, 40, 44, 48, 52, 64, 72, 80, 88, 96, 104, 120,
+    132, 144,
+];
+
+/// The number of data codewords of each symbol size.
+const DATAMATRIX_DATA_CODEWORDS: [usize; 24] = [
+    3, 5, 8, 12, 18, 22, 30, 36, 44, 62, 86, 114, 144, 174, 204, 280, 368, 456, 576, 696, 816,
+    1050, 1304, 1558,
+];
+
+/// The number of error correction codewords of each symbol size.
+const DATAMATRIX_ECC_CODEWORDS: [usize; 24] = [
+    5, 7, 10, 12, 14, 18, 20, 24, 28, 36, 42, 48, 56, 68, 84, 112, 144, 192, 224, 272, 336, 408,
+    496, 620,
+];
+
+/// The width (and height) of the data regions of each symbol size, in modules.
+const DATAMATRIX_REGION_SIZES: [usize; 24] = [
+    8, 10, 12, 14, 16, 18, 20, 22, 24, 14, 16, 18, 20, 22, 24, 14, 16, 18, 20, 22, 24, 18, 20, 22,
+];
+
+/// The number of interleaved Reed-Solomon blocks of each symbol size.
+const DATAMATRIX_BLOCKS: [usize; 24] = [
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 4, 4, 4, 4, 6, 6, 8, 10,
+];
+
+/// The primitive polynomial of the Galois field used for error correction.
+const DATAMATRIX_GF_POLY: u32 = 0x12D;
+
+// Special codewords.
+const DATAMATRIX_PAD: u8 = 129;
+const DATAMATRIX_LATCH_C40: u8 = 230;
+const DATAMATRIX_LATCH_BASE256: u8 = 231;
+const DATAMATRIX_FNC1: u8 = 232;
+const DATAMATRIX_UPPER_SHIFT: u8 = 235;
+const DATAMATRIX_LATCH_TEXT: u8 = 239;
+const DATAMATRIX_UNLATCH: u8 = 254;
+
+/// The value standing for FNC1 among the ISO-8859-1 character values.
+const FNC1_VALUE: u16 = 256;
+
+/// The encodations in which data may be compacted into codewords.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encodation {
+    /// One character, or two digits, to each codeword.
+    Ascii,
+    /// Three upper case letters, digits or spaces to every two codewords.
+    C40,
+    /// Three lower case letters, digits or spaces to every two codewords.
+    Text,
+    /// One byte of any value to each codeword.
+    Base256,
+}
+
+// Whether a character value is a digit.
+fn is_digit(c: u16) -> bool {
+    (b'0' as u16..=b'9' as u16).contains(&c)
+}
+
+// Encodes data in the ASCII encodation.
+fn ascii_codewords(data: &[u16]) -> Vec<u8> {
+    let mut codewords = vec![];
+    let mut i = 0;
+
+    while i < data.len() {
+        let c = data[i];
+
+        if is_digit(c) && data.get(i + 1).is_some_and(|&d| is_digit(d)) {
+            codewords.push((130 + (c - b'0' as u16) * 10 + (data[i + 1] - b'0' as u16)) as u8);
+            i += 2;
+            continue;
+        }
+
+        if c == FNC1_VALUE {
+            codewords.push(DATAMATRIX_FNC1);
+        } else if c >= 128 {
+            codewords.push(DATAMATRIX_UPPER_SHIFT);
+            codewords.push((c - 127) as u8);
+        } else {
+            codewords.push(c as u8 + 1);
+        }
+
+        i += 1;
+    }
+
+    codewords
+}
+
+// The C40 (or Text) values of a character: a value from the basic set, or a shift and a value.
+fn c40_values(value: u16, text: bool) -> Vec<u8> {
+    if value == FNC1_VALUE {
+        return vec![1, 27];
+    }
+
+    let mut values = vec![];
+    let mut c = value as u8;
+
+    if c >= 128 {
+        values.extend([1, 30]);
+        c -= 128;
+    }
+
+    // Text is C40 with the cases of letters swapped.
+    if text && c.is_ascii_alphabetic() {
+        c ^= 0x20;
+    }
+
+    match c {
+        b' ' => values.push(3),
+        b'0'..=b'9' => values.push(c - b'0' + 4),
+        b'A'..=b'Z' => values.push(c - b'A' + 14),
+        0..=31 => values.extend([0, c]),
+        33..=47 => values.extend([1, c - 33]),
+        58..=64 => values.extend([1, c - 43]),
+        91..=95 => values.extend([1, c - 69]),
+        _ => values.extend([2, c - 96]),
+    }
+
+    values
+}
+
+// Encodes data in the C40 or Text encodation. Characters left over after the last group of
+// three values are encoded in ASCII.
+fn c40_codewords(data: &[u16], text: bool) -> Vec<u8> {
+    let chars: Vec<Vec<u8>> = data.iter().map(|&c| c40_values(c, text)).collect();
+    let mut values: Vec<u8> = chars.iter().flat_map(|v| v.iter().cloned()).collect();
+    let mut tail = data.len();
+
+    while values.len() % 3 == 1 {
+        tail -= 1;
+        values.truncate(values.len() - chars[tail].len());
+    }
+
+    if values.len() % 3 == 2 {
+        values.push(0);
+    }
+
+    let mut codewords = vec![if text { DATAMATRIX_LATCH_TEXT } else { DATAMATRIX_LATCH_C40 }];
+
+    for triple in values.chunks(3) {
+        let value = 1600 * triple[0] as u16 + 40 * triple[1] as u16 + triple[2] as u16 + 1;
+        codewords.push((value >> 8) as u8);
+        codewords.push((value & 0xFF) as u8);
+    }
+
+    codewords.push(DATAMATRIX_UNLATCH);
+    codewords.extend(ascii_codewords(&data[tail..]));
+    codewords
+}
+
+// Encodes data in the Base256 encodation, with a length field. All but the latch are
+// randomised by their position, counting from `start` for the latch.
+fn base256_codewords(data: &[u8], start: usize) -> Vec<u8> {
+    let mut field = vec![];
+
+    if data.len() < 250 {
+        field.push(data.len() as u8);
+    } else {
+        field.push((data.len() / 250 + 249) as u8);
+        field.push((data.len() % 250) as u8);
+    }
+
+    field.extend(data);
+
+    let mut codewords = vec![DATAMATRIX_LATCH_BASE256];
+
+    for (i, &b) in field.iter().enumerate() {
+        let pseudo = (149 * (start + i + 1)) % 255 + 1;
+        codewords.push(((b as usize + pseudo) % 256) as u8);
+    }
+
+    codewords
+}
+
+// Fills the remaining data capacity with pad codewords. All but the first are randomised by
+// their position.
+fn pad_codewords(codewords: &mut Vec<u8>, capacity: usize) {
+    if codewords.len() < capacity {
+        codewords.push(DATAMATRIX_PAD);
+    }
+
+    while codewords.len() < capacity {
+        let pad = DATAMATRIX_PAD as usize + (149 * (codewords.len() + 1)) % 253 + 1;
+        codewords.push(if pad > 254 { pad - 254 } else { pad } as u8);
+    }
+}
+
+// The arrangement of codeword bits in the mapping matrix: the data regions placed side by side
+// without their finder and timing patterns.
+struct Mapping {
+    size: usize,
+    modules: Vec<u8>,
+    filled: Vec<bool>,
+}
+
+impl Mapping {
+    // Places bit `bit` (1-8, most significant first) of a codeword, wrapping positions outside
+    // the matrix around to the opposite side.
+    fn module(&mut self, mut row: i32, mut col: i32, codeword: u8, bit: u8) {
+        let n = self.size as i32;
+
+        if row < 0 {
+            row += n;
+            col += 4 - ((n + 4) % 8);
+        }
+
+        if col < 0 {
+            col += n;
+            row += 4 - ((n + 4) % 8);
+        }
+
+        let i = (row * n + col) as usize;
+        self.modules[i] = (codeword >> (8 - bit)) & 1;
+        self.filled[i] = true;
+    }
+
+    // Places a codeword in the usual L-shaped arrangement, with its last bit at (row, col).
+    fn utah(&mut self, row: i32, col: i32, codeword: u8) {
+        self.module(row - 2, col - 2, codeword, 1);
+        self.module(row - 2, col - 1, codeword, 2);
+        self.module(row - 1, col - 2, codeword, 3);
+        self.module(row - 1, col - 1, codeword, 4);
+        self.module(row - 1, col, codeword, 5);
+        self.module(row, col - 2, codeword, 6);
+        self.module(row, col - 1, codeword, 7);
+        self.module(row, col, codeword, 8);
+    }
+
+    // Places a codeword split between the corners of the matrix, in one of the four special
+    // arrangements.
+    fn corner(&mut self, kind: u8, codeword: u8) {
+        let n = self.size as i32;
+        let positions = match kind {
+            1 => [(n - 1, 0), (n - 1, 1), (n - 1, 2), (0, n - 2), (0, n - 1), (1, n - 1), (2, n - 1), (3, n - 1)],
+            2 => [(n - 3, 0), (n - 2, 0), (n - 1, 0), (0, n - 4), (0, n - 3), (0, n - 2), (0, n - 1), (1, n - 1)],
+            3 => [(n - 3, 0), (n - 2, 0), (n - 1, 0), (0, n - 2), (0, n - 1), (1, n - 1), (2, n - 1), (3, n - 1)],
+            _ => [(n - 1, 0), (n - 1, n - 1), (0, n - 3), (0, n - 2), (0, n - 1), (1, n - 3), (1, n - 2), (1, n - 1)],
+        };
+
+        for (i, &(row, col)) in positions.iter().enumerate() {
+            self.module(row, col, codeword, i as u8 + 1);
+        }
+    }
+
+    // Places the codewords in diagonal sweeps from the top left, returning the modules of the
+    // mapping matrix.
+    fn place(size: usize, codewords: &[u8]) -> Vec<u8> {
+        let mut mapping = Mapping{size, modules: vec![0; size * size], filled: vec![false; size * size]};
+        let mut next = codewords.iter().cloned();
+        let n = size as i32;
+        let (mut row, mut col) = (4i32, 0i32);
+
+        loop {
+            if row == n && col == 0 {
+                mapping.corner(1, next.next().unwrap());
+            }
+
+            if row == n - 2 && col == 0 && n % 4 != 0 {
+                mapping.corner(2, next.next().unwrap());
+            }
+
+            if row == n - 2 && col == 0 && n % 8 == 4 {
+                mapping.corner(3, next.next().unwrap());
+            }
+
+            if row == n + 4 && col == 2 && n % 8 == 0 {
+                mapping.corner(4, next.next().unwrap());
+            }
+
+            // Sweep upwards to the right.
+            loop {
+                if row < n && col >= 0 && !mapping.filled[(row * n + col) as usize] {
+                    mapping.utah(row, col, next.next().unwrap());
+                }
+
+                row -= 2;
+                col += 2;
+
+                if row < 0 || col >= n {
+                    break;
+                }
+            }
+
+            row += 1;
+            col += 3;
+
+            // Sweep downwards to the left.
+            loop {
+                if row >= 0 && col < n && !mapping.filled[(row * n + col) as usize] {
+                    mapping.utah(row, col, next.next().unwrap());
+                }
+
+                row += 2;
+                col -= 2;
+
+                if row >= n || col < 0 {
+                    break;
+                }
+            }
+
+            row += 3;
+            col += 1;
+
+            if row >= n && col >= n {
+                break;
+            }
+        }
+
+        // Sizes with unused modules fill the bottom right corner with a fixed pattern.
+        if !mapping.filled[size * size - 1] {
+            mapping.modules[size * size - 1] = 1;
+            mapping.modules[size * size - size - 2] = 1;
+        }
+
+        mapping.modules
+    }
+}
+
+/// The Data Matrix barcode type.
+pub struct DataMatrix {
+    data: String,
+    encodation: Encodation,
+    codewords: Vec<u8>,
+    size_index: usize,
+}
+
+impl DataMatrix {
+    /// Creates a new barcode, choosing the encodation giving the fewest codewords.
+    /// Returns Result<DataMatrix, String> indicating success.
+    pub fn new(data: String) -> Result<DataMatrix, String> {
+        let values = DataMatrix::values(&data)?;
+        let encodation = [Encodation::Ascii, Encodation::C40, Encodation::Text, Encodation::Base256].iter()
+            .cloned()
+            .filter_map(|e| DataMatrix::stream(&values, e).ok().map(|s| (e, s.len())))
+            .min_by_key(|&(_, len)| len)
+            .unwrap()
+            .0;
+
+        DataMatrix::from_values(data, &values, encodation)
+    }
+
+    /// Creates a new barcode using the given encodation.
+    /// Returns Result<DataMatrix, String> indicating success.
+    pub fn with_encodation(data: String, encodation: Encodation) -> Result<DataMatrix, String> {
+        let values = DataMatrix::values(&data)?;
+
+        DataMatrix::from_values(data, &values, encodation)
+    }
+
+    // Converts the data to ISO-8859-1 character values, with FNC1 as FNC1_VALUE.
+    fn values(data: &str) -> Result<Vec<u16>, String> {
+        data.chars()
+            .map(|c| match c as u32 {
+                _ if c == FNC1 => Ok(FNC1_VALUE),
+                v if v < 256 => Ok(v as u16),
+                _ => Err(format!("Character {:?} is not ISO-8859-1", c)),
+            })
+            .collect()
+    }
+
+    // Encodes the character values in an encodation. A leading FNC1 is always the first
+    // codeword.
+    fn stream(data: &[u16], encodation: Encodation) -> Result<Vec<u8>, String> {
+        let (mut codewords, data) = match data.first() {
+            Some(&FNC1_VALUE) => (vec![DATAMATRIX_FNC1], &data[1..]),
+            _ => (vec![], data),
+        };
+
+        match encodation {
+            Encodation::Ascii => codewords.extend(ascii_codewords(data)),
+            Encodation::C40 => codewords.extend(c40_codewords(data, false)),
+            Encodation::Text => codewords.extend(c40_codewords(data, true)),
+            Encodation::Base256 => {
+                if data.contains(&FNC1_VALUE) {
+                    return Err("FNC1 cannot be encoded in the Base256 encodation".to_string());
+                }
+
+                let bytes: Vec<u8> = data.iter().map(|&c| c as u8).collect();
+                let start = codewords.len() + 1;
+                codewords.extend(base256_codewords(&bytes, start));
+            },
+        }
+
+        Ok(codewords)
+    }
+
+    fn from_values(data: String, values: &[u16], encodation: Encodation) -> Result<DataMatrix, String> {
+        let mut codewords = DataMatrix::stream(values, encodation)?;
+        // An unlatch is not needed when the data fills the symbol exactly.
+        let trimmed = match codewords.last() {
+            Some(&DATAMATRIX_UNLATCH) => codewords.len() - 1,
+            _ => codewords.len(),
+        };
+        let size_index = DATAMATRIX_DATA_CODEWORDS.iter()
+            .position(|&capacity| capacity >= codewords.len() || capacity == trimmed);
+
+        match size_index {
+            Some(size_index) => {
+                codewords.truncate(DATAMATRIX_DATA_CODEWORDS[size_index]);
+                Ok(DataMatrix{data, encodation, codewords, size_index})
+            },
+            None => Err("Data too long to encode in a Data Matrix".to_string()),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the encodation used to compact the data.
+    pub fn encodation(&self) -> Encodation {
+        self.encodation
+    }
+
+    /// Returns the width (and height) of the symbol in modules.
+    pub fn size(&self) -> usize {
+        DATAMATRIX_SIZES[self.size_index]
+    }
+
+    /// Returns the data codewords, including padding.
+    pub fn data_codewords(&self) -> Vec<u8> {
+        let mut codewords = self.codewords.clone();
+        pad_codewords(&mut codewords, DATAMATRIX_DATA_CODEWORDS[self.size_index]);
+        codewords
+    }
+
+    /// Returns the final sequence of codewords: the data codewords followed by the error
+    /// correction codewords, both interleaved between the blocks.
+    pub fn codewords(&self) -> Vec<u8> {
+        let blocks = DATAMATRIX_BLOCKS[self.size_index];
+        let ecc_len = DATAMATRIX_ECC_CODEWORDS[self.size_index] / blocks;
+        let data = self.data_codewords();
+        let mut codewords = data.clone();
+        codewords.extend(vec![0; ecc_len * blocks]);
+
+        for block in 0..blocks {
+            let block_data: Vec<u16> = data.iter().skip(block).step_by(blocks).map(|&d| d as u16).collect();
+            let ecc = helpers::reed_solomon(&block_data, ecc_len, DATAMATRIX_GF_POLY, 1);
+
+            for (i, &e) in ecc.iter().enumerate() {
+                codewords[data.len() + block + i * blocks] = e as u8;
+            }
+        }
+
+        codewords
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules, without a quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        let size = self.size();
+        let region = DATAMATRIX_REGION_SIZES[self.size_index];
+        let regions = size / (region + 2);
+        let mapping_size = region * regions;
+        let mapping = Mapping::place(mapping_size, &self.codewords());
+        let mut matrix = EncodedMatrix::new(size, size);
+
+        // Each data region has a solid finder along its left and bottom edges and alternating
+        // timing patterns along its top and right edges.
+        for y in 0..size {
+            for x in 0..size {
+                let (rx, ry) = (x % (region + 2), y % (region + 2));
+                let dark = if rx == 0 || ry == region + 1 {
+                    true
+                } else if ry == 0 {
+                    rx % 2 == 0
+                } else if rx == region + 1 {
+                    ry % 2 == 1
+                } else {
+                    let (row, col) = (y - 2 * (y / (region + 2)) - 1, x - 2 * (x / (region + 2)) - 1);
+                    mapping[row * mapping_size + col] == 1
+                };
+
+                matrix.set(x, y, dark as u8);
+            }
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::datamatrix::*;
+
+    fn collapse_rows(matrix: &EncodedMatrix) -> Vec<String> {
+        matrix.rows().iter()
+            .map(|r| r.iter().map(|&m| if m == 1 { '1' } else { '0' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn new_datamatrix() {
+        let dm1 = DataMatrix::new("123456".to_string());
+        let dm2 = DataMatrix::with_encodation("Hello World".to_string(), Encodation::Text);
+
+        assert!(dm1.is_ok());
+        assert!(dm2.is_ok());
+    }
+
+    #[test]
+    fn invalid_datamatrix() {
+        let dm1 = DataMatrix::new("\u{20AC}100".to_string());
+        let dm2 = DataMatrix::new("9".repeat(3117));
+
+        assert!(dm1.is_err());
+        assert!(dm2.is_err());
+    }
+
+    #[test]
+    fn datamatrix_raw_data() {
+        let dm = DataMatrix::new("123456".to_string()).unwrap();
+
+        assert_eq!(dm.raw_data(), "123456");
+    }
+
+    #[test]
+    fn datamatrix_encodation_selection() {
+        assert_eq!(DataMatrix::new("123456".to_string()).unwrap().encodation(), Encodation::Ascii);
+        assert_eq!(DataMatrix::new("AIMAIMAIM".to_string()).unwrap().encodation(), Encodation::C40);
+        assert_eq!(DataMatrix::new("abc def ghi".to_string()).unwrap().encodation(), Encodation::Text);
+        assert_eq!(DataMatrix::new("\u{e9}t\u{e9}\u{e0}\u{e8}\u{fc}".to_string()).unwrap().encodation(), Encodation::Base256);
+    }
+
+    #[test]
+    fn datamatrix_size_selection() {
+        assert_eq!(DataMatrix::new("123456".to_string()).unwrap().size(), 10);
+        assert_eq!(DataMatrix::new("9".repeat(3116)).unwrap().size(), 144);
+    }
+
+    #[test]
+    fn datamatrix_codewords() {
+        let dm1 = DataMatrix::new("123456".to_string()).unwrap();
+        let dm2 = DataMatrix::new("Hello World".to_string()).unwrap();
+
+        assert_eq!(dm1.codewords(), vec![142, 164, 186, 114, 25, 5, 88, 102]);
+        assert_eq!(dm2.data_codewords(), vec![73, 102, 109, 109, 112, 33, 88, 112, 115, 109, 101, 129]);
+    }
+
+    #[test]
+    fn datamatrix_c40() {
+        let dm1 = DataMatrix::new("AIMAIMAIM".to_string()).unwrap();
+        let dm2 = DataMatrix::with_encodation("AB".to_string(), Encodation::C40).unwrap();
+        let dm3 = DataMatrix::with_encodation("ABCD".to_string(), Encodation::C40).unwrap();
+
+        assert_eq!(dm1.data_codewords(), vec![230, 91, 11, 91, 11, 91, 11, 254]);
+        assert_eq!(dm2.data_codewords(), vec![230, 89, 217]);
+        assert_eq!(dm3.data_codewords(), vec![230, 89, 233, 254, 69]);
+    }
+
+    #[test]
+    fn datamatrix_base256() {
+        let dm1 = DataMatrix::new("\u{e9}t\u{e9}\u{e0}\u{e8}\u{fc}".to_string()).unwrap();
+        let dm2 = DataMatrix::with_encodation("x".repeat(300), Encodation::Base256).unwrap();
+
+        assert_eq!(dm1.data_codewords()[..3].to_vec(), vec![231, 50, 170]);
+        assert_eq!(dm2.data_codewords()[..4].to_vec(), vec![231, 38, 243, 207]);
+    }
+
+    #[test]
+    fn datamatrix_fnc1() {
+        let dm1 = DataMatrix::new(format!("{}0112{}AB", FNC1, FNC1)).unwrap();
+        let dm2 = DataMatrix::with_encodation(format!("{}ABCDEF", FNC1), Encodation::C40).unwrap();
+        let dm3 = DataMatrix::with_encodation(format!("AB{}CD", FNC1), Encodation::Base256);
+
+        assert_eq!(dm1.data_codewords()[..6].to_vec(), vec![232, 131, 142, 232, 66, 67]);
+        assert_eq!(dm2.data_codewords()[..2].to_vec(), vec![232, 230]);
+        assert!(dm3.is_err());
+    }
+
+    #[test]
+    fn datamatrix_encode() {
+        let dm = DataMatrix::new("123456".to_string()).unwrap();
+
+        assert_eq!(collapse_rows(&dm.encode()), vec![
+            "1010101010", "1100101101", "1100000100", "1100011101", "1100001000",
+            "1000001111", "1110110000", "1111011001", "1001110100", "1111111111",
+        ]);
+    }
+}
@@ -0,0 +1,116 @@
+//! This module provides types for encoding GS1 DataMatrix barcodes. GS1 DataMatrix is Data
+//! Matrix carrying GS1 Application Identifier (AI) elements, and is used for the serialisation
+//! of medicines, such as the packs required by the EU Falsified Medicines Directive.
+//!
+//! Data is given in the human-readable form, with each AI enclosed in parentheses, as for
+//! GS1-128. For example: "(01)09501101530003(17)140704(10)AB-123". The FNC1 characters that
+//! begin the symbol and separate variable-length elements are inserted automatically.
+
+use ::sym::Parse;
+use ::sym::gs1_128::GS1_128;
+use ::sym2d::EncodedMatrix;
+use ::sym2d::datamatrix::DataMatrix;
+use std::ops::RangeInclusive;
+
+/// The GS1 DataMatrix barcode type.
+pub struct GS1DataMatrix {
+    data: Vec<char>,
+    elements: Vec<(String, String)>,
+    datamatrix: DataMatrix,
+}
+
+impl GS1DataMatrix {
+    /// Creates a new barcode from a string of parenthesised AIs and their values.
+    /// Returns Result<GS1DataMatrix, String> indicating parse success.
+    pub fn new(data: String) -> Result<GS1DataMatrix, String> {
+        let d = GS1DataMatrix::parse(data)?;
+        let elements = GS1_128::elements_from(&d)?;
+        let datamatrix = DataMatrix::new(GS1_128::fnc1_data(&elements))?;
+
+        Ok(GS1DataMatrix{data: d.chars().collect(), elements, datamatrix})
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &[char] {
+        &self.data[..]
+    }
+
+    /// Returns the (AI, value) pairs encoded in the barcode.
+    pub fn elements(&self) -> &[(String, String)] {
+        &self.elements[..]
+    }
+
+    /// Returns the width (and height) of the symbol in modules.
+    pub fn size(&self) -> usize {
+        self.datamatrix.size()
+    }
+
+    /// Returns the data codewords, beginning with FNC1.
+    pub fn data_codewords(&self) -> Vec<u8> {
+        self.datamatrix.data_codewords()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules, without a quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        self.datamatrix.encode()
+    }
+}
+
+impl Parse for GS1DataMatrix {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> RangeInclusive<u32> {
+        4..=3116
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        GS1_128::valid_chars()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::gs1_datamatrix::*;
+
+    #[test]
+    fn new_gs1_datamatrix() {
+        let gs1 = GS1DataMatrix::new("(01)09501101530003(17)140704(10)AB-123".to_string());
+
+        assert!(gs1.is_ok());
+    }
+
+    #[test]
+    fn invalid_data_gs1_datamatrix() {
+        let gs1 = GS1DataMatrix::new("01095011015300031714070410AB-123".to_string());
+
+        assert!(gs1.is_err());
+    }
+
+    #[test]
+    fn invalid_element_gs1_datamatrix() {
+        let gs1_1 = GS1DataMatrix::new("(01)09501101530004".to_string());
+        let gs1_2 = GS1DataMatrix::new("(17)1407".to_string());
+
+        assert!(gs1_1.is_err());
+        assert!(gs1_2.is_err());
+    }
+
+    #[test]
+    fn gs1_datamatrix_elements() {
+        let gs1 = GS1DataMatrix::new("(01)09501101530003(21)12345678p901(10)A1B2C3(17)251231".to_string()).unwrap();
+
+        assert_eq!(gs1.elements().len(), 4);
+        assert_eq!(gs1.elements()[1], ("21".to_string(), "12345678p901".to_string()));
+    }
+
+    #[test]
+    fn gs1_datamatrix_codewords() {
+        let gs1 = GS1DataMatrix::new("(01)09501101530003(10)AB-123(17)140704".to_string()).unwrap();
+
+        assert_eq!(gs1.size(), 20);
+        assert_eq!(gs1.data_codewords()[..15].to_vec(), vec![
+            232, 131, 139, 180, 141, 131, 183, 130, 133, 140, 66, 67, 46, 142, 52,
+        ]);
+    }
+}
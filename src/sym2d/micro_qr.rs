@@ -0,0 +1,417 @@
+//! This module provides types for encoding Micro QR Code barcodes. Micro QR Code is a smaller
+//! variant of QR Code with a single finder pattern, suited to marking small items such as
+//! printed circuit boards.
+//!
+//! There are four versions, M1 (11x11 modules) to M4 (17x17 modules), holding up to 35 digits.
+//! Version M1 holds digits only and version M2 digits or alphanumeric characters. The Reed-Solomon
+//! error correction and the mask patterns are shared with QR Code, but the format information,
+//! capacities and mask evaluation differ.
+
+use ::sym::helpers;
+use ::sym2d::EncodedMatrix;
+use ::sym2d::qr::{self, ECLevel, Mode};
+
+/// The largest Micro QR Code version (M4).
+pub const MICRO_QR_MAX_VERSION: u8 = 4;
+
+/// The number of data bits, by version and error correction level (L, M, Q). Zero where the
+/// level is not available.
+const MICRO_QR_DATA_BITS: [[usize; 3]; 4] = [
+    [20, 0, 0],
+    [40, 32, 0],
+    [84, 68, 0],
+    [128, 112, 80],
+];
+
+/// The number of error correction codewords, by version and error correction level (L, M, Q).
+const MICRO_QR_ECC_CODEWORDS: [[usize; 3]; 4] = [
+    [2, 0, 0],
+    [5, 6, 0],
+    [6, 8, 0],
+    [8, 10, 14],
+];
+
+/// The QR Code mask patterns available to Micro QR Code, in order of their Micro QR numbers.
+const MICRO_QR_MASKS: [u8; 4] = [1, 4, 6, 7];
+
+/// The pattern XORed with the format information.
+const MICRO_QR_FORMAT_MASK: u32 = 0x4445;
+
+/// The pad codewords that alternately fill any unused data capacity.
+const MICRO_QR_PAD_CODEWORDS: [u8; 2] = [0xEC, 0x11];
+
+// The index of an error correction level in the capacity tables. Level H is not available.
+fn level_index(level: ECLevel) -> Option<usize> {
+    match level {
+        ECLevel::L => Some(0),
+        ECLevel::M => Some(1),
+        ECLevel::Q => Some(2),
+        ECLevel::H => None,
+    }
+}
+
+// The number of data bits of a version at a level, if the level is available.
+fn data_capacity(version: u8, level: ECLevel) -> Option<usize> {
+    level_index(level)
+        .map(|l| MICRO_QR_DATA_BITS[version as usize - 1][l])
+        .filter(|&bits| bits > 0)
+}
+
+// Whether a version supports a mode.
+fn supports_mode(version: u8, mode: Mode) -> bool {
+    match mode {
+        Mode::Numeric => true,
+        Mode::Alphanumeric => version >= 2,
+        Mode::Byte => version >= 3,
+    }
+}
+
+// The number of bits used for the character count indicator.
+fn count_bits(mode: Mode, version: u8) -> usize {
+    match mode {
+        Mode::Numeric => version as usize + 2,
+        _ => version as usize + 1,
+    }
+}
+
+// The mode indicator, which is `version - 1` bits long.
+fn indicator(mode: Mode) -> u32 {
+    match mode {
+        Mode::Numeric => 0,
+        Mode::Alphanumeric => 1,
+        Mode::Byte => 2,
+    }
+}
+
+// Calculates the penalty score of a masked symbol from the light modules along its right and
+// bottom edges. Lower scores are easier to read.
+fn light_side_penalty(matrix: &EncodedMatrix) -> usize {
+    let last = matrix.width - 1;
+    let bottom = (1..=last).filter(|&x| matrix.get(x, last) == 0).count();
+    let right = (1..=last).filter(|&y| matrix.get(last, y) == 0).count();
+
+    bottom + right + 15 * bottom.max(right)
+}
+
+/// The Micro QR Code barcode type.
+pub struct MicroQRCode {
+    data: String,
+    mode: Mode,
+    version: u8,
+    ec_level: ECLevel,
+}
+
+impl MicroQRCode {
+    /// Creates a new barcode with error correction level L, the only level of version M1.
+    /// Returns Result<MicroQRCode, String> indicating success.
+    pub fn new(data: String) -> Result<MicroQRCode, String> {
+        MicroQRCode::with_ec_level(data, ECLevel::L)
+    }
+
+    /// Creates a new barcode with the given error correction level (L, M or Q).
+    /// Returns Result<MicroQRCode, String> indicating success.
+    pub fn with_ec_level(data: String, ec_level: ECLevel) -> Result<MicroQRCode, String> {
+        if ec_level == ECLevel::H {
+            return Err("Micro QR Code does not support error correction level H".to_string());
+        }
+
+        let mode = Mode::for_data(&data);
+        let len = qr::data_bits(&data, mode).len();
+        let count = qr::char_count(&data, mode);
+
+        let version = (1..=MICRO_QR_MAX_VERSION).find(|&v| {
+            supports_mode(v, mode) && data_capacity(v, ec_level).is_some_and(|capacity| {
+                count < 1 << count_bits(mode, v) && v as usize - 1 + count_bits(mode, v) + len <= capacity
+            })
+        });
+
+        match version {
+            Some(version) => Ok(MicroQRCode{data, mode, version, ec_level}),
+            None => Err("Data too long to encode in a Micro QR Code".to_string()),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the mode used to encode the data.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the version (1-4, for M1-M4) of the symbol, which determines its size.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the error correction level.
+    pub fn ec_level(&self) -> ECLevel {
+        self.ec_level
+    }
+
+    /// Returns the width (and height) of the symbol in modules.
+    pub fn size(&self) -> usize {
+        self.version as usize * 2 + 9
+    }
+
+    // The number of data bits. In versions M1 and M3 the last data codeword is four bits.
+    fn capacity(&self) -> usize {
+        data_capacity(self.version, self.ec_level).unwrap()
+    }
+
+    /// Returns the data codewords: the mode indicator, character count and data, followed by
+    /// padding. A final four-bit codeword is held in the high bits of its byte.
+    pub fn data_codewords(&self) -> Vec<u8> {
+        let capacity = self.capacity();
+        let mut bits = vec![];
+        helpers::push_bits(&mut bits, indicator(self.mode), self.version as usize - 1);
+        helpers::push_bits(&mut bits, qr::char_count(&self.data, self.mode) as u32, count_bits(self.mode, self.version));
+        bits.extend(qr::data_bits(&self.data, self.mode));
+
+        let terminator = (self.version as usize * 2 + 1).min(capacity - bits.len());
+        bits.extend(vec![0; terminator]);
+
+        while !bits.len().is_multiple_of(8) {
+            bits.push(0);
+        }
+
+        let mut codewords: Vec<u8> = bits.chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, &b| (acc << 1) | b))
+            .collect();
+
+        let mut pad = MICRO_QR_PAD_CODEWORDS.iter().cycle();
+
+        while codewords.len() < capacity / 8 {
+            codewords.push(*pad.next().unwrap());
+        }
+
+        if codewords.len() < capacity.div_ceil(8) {
+            codewords.push(0);
+        }
+
+        codewords
+    }
+
+    /// Returns the final sequence of codewords: the data codewords followed by their error
+    /// correction codewords.
+    pub fn codewords(&self) -> Vec<u8> {
+        let level = level_index(self.ec_level).unwrap();
+        let ecc_len = MICRO_QR_ECC_CODEWORDS[self.version as usize - 1][level];
+        let mut codewords = self.data_codewords();
+        let ecc = qr::ecc_codewords(&codewords, ecc_len);
+
+        codewords.extend(ecc);
+        codewords
+    }
+
+    // Draws the finder and timing patterns and reserves the format information area. Returns
+    // the matrix and a mask of the function modules.
+    fn function_patterns(&self) -> (EncodedMatrix, EncodedMatrix) {
+        let size = self.size();
+        let mut matrix = EncodedMatrix::new(size, size);
+        let mut function = EncodedMatrix::new(size, size);
+
+        {
+            let mut set = |x: usize, y: usize, dark: bool| {
+                matrix.set(x, y, dark as u8);
+                function.set(x, y, 1);
+            };
+
+            // The finder pattern, with its separator.
+            for y in 0..8 {
+                for x in 0..8 {
+                    let dist = (x as i32 - 3).abs().max((y as i32 - 3).abs());
+                    set(x, y, dist != 2 && dist != 4);
+                }
+            }
+
+            for i in 8..size {
+                set(i, 0, i % 2 == 0);
+                set(0, i, i % 2 == 0);
+            }
+
+            // The format information is drawn with the mask, but its modules are reserved here.
+            for i in 1..9 {
+                set(8, i, false);
+                set(i, 8, false);
+            }
+        }
+
+        (matrix, function)
+    }
+
+    // Places the codewords in two-module wide columns, zigzagging upwards and downwards from
+    // the bottom right, skipping function modules.
+    fn place_codewords(&self, matrix: &mut EncodedMatrix, function: &EncodedMatrix) {
+        let size = self.size();
+        let data_len = self.data_codewords().len();
+        let half = !self.capacity().is_multiple_of(8);
+        let bits: Vec<u8> = self.codewords().iter().enumerate()
+            .flat_map(|(i, &c)| {
+                let end = if half && i == data_len - 1 { 4 } else { 0 };
+                (end..8).rev().map(move |j| (c >> j) & 1)
+            })
+            .collect();
+        let mut i = 0;
+        let mut right = size - 1;
+        let mut upward = true;
+
+        while right >= 2 {
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let y = if upward { size - 1 - vert } else { vert };
+
+                    if function.get(x, y) == 0 && i < bits.len() {
+                        matrix.set(x, y, bits[i]);
+                        i += 1;
+                    }
+                }
+            }
+
+            upward = !upward;
+            right -= 2;
+        }
+    }
+
+    // Applies a mask pattern (0-3) to the data modules and draws the format information.
+    fn apply_mask(&self, matrix: &EncodedMatrix, function: &EncodedMatrix, mask: u8) -> EncodedMatrix {
+        let size = self.size();
+        let mut masked = matrix.clone();
+
+        for y in 0..size {
+            for x in 0..size {
+                if function.get(x, y) == 0 && qr::mask_bit(MICRO_QR_MASKS[mask as usize], x, y) {
+                    masked.set(x, y, 1 - matrix.get(x, y));
+                }
+            }
+        }
+
+        let symbol = [0, 1, 3, 5][self.version as usize - 1] + level_index(self.ec_level).unwrap() as u32;
+        let format = qr::format_bch(symbol << 2 | mask as u32) ^ MICRO_QR_FORMAT_MASK;
+        let bit = |i: usize| ((format >> i) & 1) as u8;
+
+        for i in 0..8 {
+            masked.set(8, i + 1, bit(i));
+        }
+
+        for i in 8..15 {
+            masked.set(15 - i, 8, bit(i));
+        }
+
+        masked
+    }
+
+    /// Returns the Micro QR Code mask pattern (0-3) applied to the symbol.
+    pub fn mask(&self) -> u8 {
+        self.best_mask().0
+    }
+
+    fn best_mask(&self) -> (u8, EncodedMatrix) {
+        let (mut matrix, function) = self.function_patterns();
+        self.place_codewords(&mut matrix, &function);
+
+        (0..4u8)
+            .map(|mask| (mask, self.apply_mask(&matrix, &function, mask)))
+            .min_by_key(|(_, m)| light_side_penalty(m))
+            .unwrap()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules, without a quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        self.best_mask().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::micro_qr::*;
+
+    fn collapse_rows(matrix: &EncodedMatrix) -> Vec<String> {
+        matrix.rows().iter()
+            .map(|r| r.iter().map(|&m| if m == 1 { '1' } else { '0' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn new_micro_qr() {
+        let mqr1 = MicroQRCode::new("12345".to_string());
+        let mqr2 = MicroQRCode::with_ec_level("hello".to_string(), ECLevel::Q);
+
+        assert!(mqr1.is_ok());
+        assert!(mqr2.is_ok());
+    }
+
+    #[test]
+    fn invalid_micro_qr() {
+        let mqr1 = MicroQRCode::new("9".repeat(36));
+        let mqr2 = MicroQRCode::with_ec_level("hello world".to_string(), ECLevel::Q);
+        let mqr3 = MicroQRCode::with_ec_level("123".to_string(), ECLevel::H);
+
+        assert!(mqr1.is_err());
+        assert!(mqr2.is_err());
+        assert!(mqr3.is_err());
+    }
+
+    #[test]
+    fn micro_qr_raw_data() {
+        let mqr = MicroQRCode::new("12345".to_string()).unwrap();
+
+        assert_eq!(mqr.raw_data(), "12345");
+    }
+
+    #[test]
+    fn micro_qr_version_selection() {
+        let mqr1 = MicroQRCode::new("12345".to_string()).unwrap();
+        let mqr2 = MicroQRCode::new("123456".to_string()).unwrap();
+        let mqr3 = MicroQRCode::new("HELLO".to_string()).unwrap();
+        let mqr4 = MicroQRCode::new("hello".to_string()).unwrap();
+        let mqr5 = MicroQRCode::new("9".repeat(35)).unwrap();
+
+        assert_eq!(mqr1.version(), 1);
+        assert_eq!(mqr2.version(), 2);
+        assert_eq!(mqr3.version(), 2);
+        assert_eq!(mqr4.version(), 3);
+        assert_eq!(mqr4.mode(), Mode::Byte);
+        assert_eq!(mqr5.version(), 4);
+        assert_eq!(mqr5.size(), 17);
+    }
+
+    #[test]
+    fn micro_qr_codewords() {
+        let mqr1 = MicroQRCode::new("01234567".to_string()).unwrap();
+        let mqr2 = MicroQRCode::new("12345".to_string()).unwrap();
+
+        assert_eq!(mqr1.data_codewords(), vec![64, 24, 172, 195, 0]);
+        assert_eq!(mqr1.codewords()[5..].to_vec(), vec![134, 13, 34, 174, 48]);
+        assert_eq!(mqr2.codewords(), vec![163, 218, 208, 110, 199]);
+    }
+
+    #[test]
+    fn micro_qr_encode() {
+        let mqr = MicroQRCode::new("01234567".to_string()).unwrap();
+
+        assert_eq!(mqr.mask(), 1);
+        assert_eq!(collapse_rows(&mqr.encode()), vec![
+            "1111111010101", "1000001011101", "1011101001101", "1011101001111",
+            "1011101011100", "1000001010001", "1111111001111", "0000000001100",
+            "1101000010001", "0110101010101", "1110011111110", "0001010000110",
+            "1110100110111",
+        ]);
+    }
+
+    #[test]
+    fn micro_qr_encode_half_codeword() {
+        let mqr = MicroQRCode::new("12345".to_string()).unwrap();
+
+        assert_eq!(mqr.mask(), 2);
+        assert_eq!(collapse_rows(&mqr.encode()), vec![
+            "11111110101", "10000010110", "10111010100", "10111010000", "10111010111",
+            "10000010011", "11111110100", "00000000011", "11001110011", "01010001100",
+            "11110000011",
+        ]);
+    }
+}
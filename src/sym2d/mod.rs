@@ -0,0 +1,41 @@
+pub mod aztec;
+pub mod datamatrix;
+pub mod gs1_datamatrix;
+pub mod micro_qr;
+pub mod qr;
+pub mod rmqr;
+
+use ::sym::EncodedBarcode;
+
+/// A two-dimensional barcode: a grid of square modules, each either dark or light.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedMatrix {
+    /// The number of modules in each row.
+    pub width: usize,
+    /// The number of rows.
+    pub height: usize,
+    /// The modules, row by row from the top left. 1 = dark, 0 = light.
+    pub modules: Vec<u8>,
+}
+
+impl EncodedMatrix {
+    /// Returns a new matrix of the given size with all modules light.
+    pub fn new(width: usize, height: usize) -> EncodedMatrix {
+        EncodedMatrix{width, height, modules: vec![0; width * height]}
+    }
+
+    /// Returns the module at column `x` of row `y`.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.modules[y * self.width + x]
+    }
+
+    /// Sets the module at column `x` of row `y`.
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.modules[y * self.width + x] = value;
+    }
+
+    /// Returns the rows of the matrix, from top to bottom.
+    pub fn rows(&self) -> Vec<EncodedBarcode> {
+        self.modules.chunks(self.width).map(|r| r.to_vec()).collect()
+    }
+}
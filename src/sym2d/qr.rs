@@ -0,0 +1,741 @@
+//! This module provides types for encoding QR Code (model 2) barcodes. QR Code is a matrix
+//! symbology that can hold up to several thousand characters, and is read by most smartphone
+//! cameras.
+//!
+//! The data is encoded in a single segment using the most compact of the numeric,
+//! alphanumeric and byte (UTF-8) modes that can represent it. The smallest of the 40 versions
+//! (sizes) that can hold the data at the chosen error correction level is used, and the mask
+//! pattern giving the lowest penalty score is applied.
+
+use ::sym::helpers;
+use ::sym2d::EncodedMatrix;
+
+/// The 45 characters of the alphanumeric mode, in order of their values.
+pub const QR_ALPHANUMERIC_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// The number of error correction codewords in each block, by error correction level (L, M,
+/// Q, H) and version.
+const QR_ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+     30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+     28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+     30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+     30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+/// The number of error correction blocks, by error correction level (L, M, Q, H) and version.
+const QR_ECC_BLOCKS: [[u8; 41]; 4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+     14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+     23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27,
+     29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+     35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// The largest QR Code version.
+pub const QR_MAX_VERSION: u8 = 40;
+
+/// The primitive polynomial of the Galois field used for error correction.
+const QR_GF_POLY: u32 = 0x11D;
+
+/// The pad codewords that alternately fill any unused data capacity.
+const QR_PAD_CODEWORDS: [u8; 2] = [0xEC, 0x11];
+
+// Penalty weights used to evaluate mask patterns.
+const PENALTY_N1: i32 = 3;
+const PENALTY_N2: i32 = 3;
+const PENALTY_N3: i32 = 40;
+const PENALTY_N4: i32 = 10;
+
+/// The error correction levels, which can recover from roughly 7%, 15%, 25% and 30% of the
+/// symbol being damaged.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ECLevel {
+    L,
+    M,
+    Q,
+    H,
+}
+
+impl ECLevel {
+    // The index of the level in the error correction tables.
+    fn index(self) -> usize {
+        match self {
+            ECLevel::L => 0,
+            ECLevel::M => 1,
+            ECLevel::Q => 2,
+            ECLevel::H => 3,
+        }
+    }
+
+    // The two bits identifying the level in the format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            ECLevel::L => 1,
+            ECLevel::M => 0,
+            ECLevel::Q => 3,
+            ECLevel::H => 2,
+        }
+    }
+}
+
+/// The modes in which data may be encoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// Digits 0-9, three to every 10 bits.
+    Numeric,
+    /// The characters of QR_ALPHANUMERIC_CHARS, two to every 11 bits.
+    Alphanumeric,
+    /// Bytes of UTF-8 encoded text, 8 bits each.
+    Byte,
+}
+
+impl Mode {
+    /// Returns the most compact mode that can encode all of the given data.
+    pub fn for_data(data: &str) -> Mode {
+        if data.chars().all(|c| c.is_ascii_digit()) {
+            Mode::Numeric
+        } else if data.chars().all(|c| QR_ALPHANUMERIC_CHARS.contains(c)) {
+            Mode::Alphanumeric
+        } else {
+            Mode::Byte
+        }
+    }
+
+    /// Returns the number of bits used for the character count indicator in the given
+    /// version.
+    pub fn count_bits(self, version: u8) -> usize {
+        let column = match version {
+            1..=9 => 0,
+            10..=26 => 1,
+            _ => 2,
+        };
+
+        match self {
+            Mode::Numeric => [10, 12, 14][column],
+            Mode::Alphanumeric => [9, 11, 13][column],
+            Mode::Byte => [8, 16, 16][column],
+        }
+    }
+
+    // The four-bit mode indicator.
+    fn indicator(self) -> u32 {
+        match self {
+            Mode::Numeric => 0b0001,
+            Mode::Alphanumeric => 0b0010,
+            Mode::Byte => 0b0100,
+        }
+    }
+}
+
+/// Encodes the characters of the data (without mode indicator or character count) as bits.
+pub fn data_bits(data: &str, mode: Mode) -> Vec<u8> {
+    let mut bits = vec![];
+
+    match mode {
+        Mode::Numeric => {
+            for chunk in data.as_bytes().chunks(3) {
+                let value = chunk.iter().fold(0, |acc, &b| acc * 10 + (b - b'0') as u32);
+                helpers::push_bits(&mut bits, value, chunk.len() * 3 + 1);
+            }
+        },
+        Mode::Alphanumeric => {
+            let values: Vec<u32> = data.chars()
+                .map(|c| QR_ALPHANUMERIC_CHARS.find(c).unwrap() as u32)
+                .collect();
+
+            for chunk in values.chunks(2) {
+                match *chunk {
+                    [a, b] => helpers::push_bits(&mut bits, a * 45 + b, 11),
+                    [a] => helpers::push_bits(&mut bits, a, 6),
+                    _ => unreachable!(),
+                }
+            }
+        },
+        Mode::Byte => {
+            for &b in data.as_bytes() {
+                helpers::push_bits(&mut bits, b as u32, 8);
+            }
+        },
+    }
+
+    bits
+}
+
+/// Returns the number of characters of the data, as recorded in the character count
+/// indicator.
+pub fn char_count(data: &str, mode: Mode) -> usize {
+    match mode {
+        Mode::Byte => data.len(),
+        _ => data.chars().count(),
+    }
+}
+
+// The number of modules available for data and error correction codewords in a version.
+fn raw_data_modules(version: u8) -> usize {
+    let v = version as usize;
+    let mut modules = (16 * v + 128) * v + 64;
+
+    if v >= 2 {
+        let alignments = v / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+
+        if v >= 7 {
+            modules -= 36;
+        }
+    }
+
+    modules
+}
+
+// The number of data codewords (excluding error correction) in a version.
+fn data_capacity(version: u8, level: ECLevel) -> usize {
+    let blocks = QR_ECC_BLOCKS[level.index()][version as usize] as usize;
+    let ecc = QR_ECC_CODEWORDS_PER_BLOCK[level.index()][version as usize] as usize;
+
+    raw_data_modules(version) / 8 - blocks * ecc
+}
+
+// The positions of the centres of the alignment patterns along each axis.
+fn alignment_positions(version: u8) -> Vec<usize> {
+    if version == 1 {
+        return vec![];
+    }
+
+    let v = version as usize;
+    let count = v / 7 + 2;
+    let step = if v == 32 { 26 } else { (v * 4 + count * 2 + 1) / (count * 2 - 2) * 2 };
+    let last = v * 4 + 10;
+    let mut positions: Vec<usize> = (0..(count - 1)).map(|i| last - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Pads the data bits with a terminator, zero bits to a byte boundary and pad codewords to
+/// fill `capacity` codewords. Returns the data codewords.
+pub fn pad_codewords(bits: &[u8], capacity: usize, terminator_len: usize) -> Vec<u8> {
+    let mut bits = bits.to_vec();
+    let terminator = terminator_len.min(capacity * 8 - bits.len());
+    bits.extend(vec![0; terminator]);
+
+    while !bits.len().is_multiple_of(8) {
+        bits.push(0);
+    }
+
+    let mut codewords: Vec<u8> = bits.chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &b| (acc << 1) | b))
+        .collect();
+
+    let mut pad = QR_PAD_CODEWORDS.iter().cycle();
+
+    while codewords.len() < capacity {
+        codewords.push(*pad.next().unwrap());
+    }
+
+    codewords
+}
+
+/// Calculates the Reed-Solomon error correction codewords for a block of data codewords.
+pub fn ecc_codewords(data: &[u8], ecc_len: usize) -> Vec<u8> {
+    let data: Vec<u16> = data.iter().map(|&d| d as u16).collect();
+
+    helpers::reed_solomon(&data, ecc_len, QR_GF_POLY, 0)
+        .into_iter()
+        .map(|c| c as u8)
+        .collect()
+}
+
+/// Returns the value of a mask pattern at column `x` of row `y`.
+pub fn mask_bit(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        7 => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+        _ => unreachable!(),
+    }
+}
+
+/// Calculates the 15-bit BCH code of five bits of format information, before masking.
+pub fn format_bch(data: u32) -> u32 {
+    let mut rem = data;
+
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+
+    data << 10 | (rem & 0x3FF)
+}
+
+/// Calculates the 15-bit BCH coded format information for the given error correction and
+/// mask bits.
+pub fn format_information(data: u32, mask: u32) -> u32 {
+    format_bch(data << 3 | mask) ^ 0x5412
+}
+
+/// Calculates the 18-bit BCH coded version information. rMQR codes its six bits of format
+/// information in the same way.
+pub fn version_information(version: u8) -> u32 {
+    let mut rem = version as u32;
+
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+
+    (version as u32) << 12 | (rem & 0xFFF)
+}
+
+// Adds a light-module border to the initial run and records a run length.
+fn add_run(mut run: usize, history: &mut [usize; 7], size: usize) {
+    if history[0] == 0 {
+        run += size;
+    }
+
+    for i in (1..7).rev() {
+        history[i] = history[i - 1];
+    }
+
+    history[0] = run;
+}
+
+// Counts the finder-like 1:1:3:1:1 patterns (with four light modules on one side) that end
+// with the most recent run.
+fn count_finder_patterns(history: &[usize; 7]) -> i32 {
+    let n = history[1];
+    let core = n > 0 && history[2] == n && history[3] == n * 3 && history[4] == n && history[5] == n;
+
+    (core && history[0] >= n * 4 && history[6] >= n) as i32
+        + (core && history[6] >= n * 4 && history[0] >= n) as i32
+}
+
+// Calculates the penalty score of a line of modules for runs of the same color and
+// finder-like patterns.
+fn line_penalty(line: &[u8]) -> i32 {
+    let size = line.len();
+    let mut penalty = 0;
+    let mut color = 0;
+    let mut run = 0;
+    let mut history = [0; 7];
+
+    for &m in line {
+        if m == color {
+            run += 1;
+
+            if run == 5 {
+                penalty += PENALTY_N1;
+            } else if run > 5 {
+                penalty += 1;
+            }
+        } else {
+            add_run(run, &mut history, size);
+
+            if color == 0 {
+                penalty += count_finder_patterns(&history) * PENALTY_N3;
+            }
+
+            color = m;
+            run = 1;
+        }
+    }
+
+    // The symbol is bordered by light modules.
+    if color == 1 {
+        add_run(run, &mut history, size);
+        run = 0;
+    }
+
+    add_run(run + size, &mut history, size);
+    penalty + count_finder_patterns(&history) * PENALTY_N3
+}
+
+/// Calculates the penalty score of a masked symbol. Lower scores are easier to read.
+pub fn penalty_score(matrix: &EncodedMatrix) -> i32 {
+    let size = matrix.width;
+    let rows = matrix.rows();
+    let columns: Vec<Vec<u8>> = (0..size).map(|x| (0..size).map(|y| matrix.get(x, y)).collect()).collect();
+    let mut penalty: i32 = rows.iter().chain(columns.iter()).map(|line| line_penalty(line)).sum();
+
+    for y in 0..(size - 1) {
+        for x in 0..(size - 1) {
+            let m = matrix.get(x, y);
+
+            if m == matrix.get(x + 1, y) && m == matrix.get(x, y + 1) && m == matrix.get(x + 1, y + 1) {
+                penalty += PENALTY_N2;
+            }
+        }
+    }
+
+    let dark = matrix.modules.iter().filter(|&&m| m == 1).count() as i32;
+    let total = (size * size) as i32;
+    let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+
+    penalty + k * PENALTY_N4
+}
+
+/// The QR Code barcode type.
+pub struct QRCode {
+    data: String,
+    mode: Mode,
+    version: u8,
+    ec_level: ECLevel,
+}
+
+impl QRCode {
+    /// Creates a new barcode with error correction level M.
+    /// Returns Result<QRCode, String> indicating success.
+    pub fn new(data: String) -> Result<QRCode, String> {
+        QRCode::with_ec_level(data, ECLevel::M)
+    }
+
+    /// Creates a new barcode with the given error correction level.
+    /// Returns Result<QRCode, String> indicating success.
+    pub fn with_ec_level(data: String, ec_level: ECLevel) -> Result<QRCode, String> {
+        let mode = Mode::for_data(&data);
+        let len = data_bits(&data, mode).len();
+        let count = char_count(&data, mode);
+
+        let version = (1..=QR_MAX_VERSION).find(|&v| {
+            count < 1 << mode.count_bits(v) && 4 + mode.count_bits(v) + len <= data_capacity(v, ec_level) * 8
+        });
+
+        match version {
+            Some(version) => Ok(QRCode{data, mode, version, ec_level}),
+            None => Err("Data too long to encode in a QR Code".to_string()),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the mode used to encode the data.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the version (1-40) of the symbol, which determines its size.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the error correction level.
+    pub fn ec_level(&self) -> ECLevel {
+        self.ec_level
+    }
+
+    /// Returns the width (and height) of the symbol in modules.
+    pub fn size(&self) -> usize {
+        self.version as usize * 4 + 17
+    }
+
+    /// Returns the data codewords: the mode indicator, character count and data, followed by
+    /// padding.
+    pub fn data_codewords(&self) -> Vec<u8> {
+        let mut bits = vec![];
+        helpers::push_bits(&mut bits, self.mode.indicator(), 4);
+        helpers::push_bits(&mut bits, char_count(&self.data, self.mode) as u32, self.mode.count_bits(self.version));
+        bits.extend(data_bits(&self.data, self.mode));
+
+        pad_codewords(&bits, data_capacity(self.version, self.ec_level), 4)
+    }
+
+    /// Returns the final sequence of codewords: the data codewords split into blocks, each
+    /// followed by its error correction codewords, interleaved.
+    pub fn codewords(&self) -> Vec<u8> {
+        let level = self.ec_level.index();
+        let block_count = QR_ECC_BLOCKS[level][self.version as usize] as usize;
+        let ecc_len = QR_ECC_CODEWORDS_PER_BLOCK[level][self.version as usize] as usize;
+        let total = raw_data_modules(self.version) / 8;
+        let short_blocks = block_count - total % block_count;
+        let short_len = total / block_count - ecc_len;
+
+        let data = self.data_codewords();
+        let mut blocks = vec![];
+        let mut start = 0;
+
+        for i in 0..block_count {
+            let len = if i < short_blocks { short_len } else { short_len + 1 };
+            blocks.push((&data[start..(start + len)], ecc_codewords(&data[start..(start + len)], ecc_len)));
+            start += len;
+        }
+
+        let mut codewords = vec![];
+
+        for i in 0..(short_len + 1) {
+            codewords.extend(blocks.iter().filter_map(|b| b.0.get(i)));
+        }
+
+        for i in 0..ecc_len {
+            codewords.extend(blocks.iter().map(|b| b.1[i]));
+        }
+
+        codewords
+    }
+
+    // Draws the finder, timing and alignment patterns and the version information, and
+    // reserves the format information area. Returns the matrix and a mask of the function
+    // modules.
+    fn function_patterns(&self) -> (EncodedMatrix, EncodedMatrix) {
+        let size = self.size();
+        let mut matrix = EncodedMatrix::new(size, size);
+        let mut function = EncodedMatrix::new(size, size);
+
+        {
+            let mut set = |x: usize, y: usize, dark: bool| {
+                matrix.set(x, y, dark as u8);
+                function.set(x, y, 1);
+            };
+
+            for i in 0..size {
+                set(6, i, i % 2 == 0);
+                set(i, 6, i % 2 == 0);
+            }
+
+            // Finder patterns, with their separators.
+            for &(cx, cy) in &[(3, 3), (size - 4, 3), (3, size - 4)] {
+                for dy in -4i32..=4 {
+                    for dx in -4i32..=4 {
+                        let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+
+                        if x >= 0 && x < size as i32 && y >= 0 && y < size as i32 {
+                            let dist = dx.abs().max(dy.abs());
+                            set(x as usize, y as usize, dist != 2 && dist != 4);
+                        }
+                    }
+                }
+            }
+
+            let positions = alignment_positions(self.version);
+            let last = positions.len().saturating_sub(1);
+
+            for (i, &cy) in positions.iter().enumerate() {
+                for (j, &cx) in positions.iter().enumerate() {
+                    // Alignment patterns do not overlap the finder patterns.
+                    if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                        continue;
+                    }
+
+                    for dy in -2i32..=2 {
+                        for dx in -2i32..=2 {
+                            set((cx as i32 + dx) as usize, (cy as i32 + dy) as usize, dx.abs().max(dy.abs()) != 1);
+                        }
+                    }
+                }
+            }
+
+            // The format information is drawn with the mask, but its modules are reserved here.
+            for i in (0..9).filter(|&i| i != 6) {
+                set(8, i, false);
+                set(i, 8, false);
+            }
+
+            for i in 0..8 {
+                set(size - 1 - i, 8, false);
+                set(8, size - 1 - i, false);
+            }
+
+            set(8, size - 8, true);
+
+            if self.version >= 7 {
+                let info = version_information(self.version);
+
+                for i in 0..18 {
+                    let bit = (info >> i) & 1 == 1;
+                    let (a, b) = (size - 11 + i % 3, i / 3);
+                    set(a, b, bit);
+                    set(b, a, bit);
+                }
+            }
+        }
+
+        (matrix, function)
+    }
+
+    // Places the codewords in two-module wide columns, zigzagging upwards and downwards from
+    // the bottom right, skipping function modules.
+    fn place_codewords(&self, matrix: &mut EncodedMatrix, function: &EncodedMatrix) {
+        let size = self.size();
+        let bits: Vec<u8> = self.codewords().iter()
+            .flat_map(|&c| (0..8).rev().map(move |i| (c >> i) & 1))
+            .collect();
+        let mut i = 0;
+        let mut right = size - 1;
+
+        while right >= 1 {
+            // Skip the vertical timing pattern.
+            if right == 6 {
+                right = 5;
+            }
+
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+
+                    if function.get(x, y) == 0 && i < bits.len() {
+                        matrix.set(x, y, bits[i]);
+                        i += 1;
+                    }
+                }
+            }
+
+            if right < 2 {
+                break;
+            }
+
+            right -= 2;
+        }
+    }
+
+    // Applies a mask pattern to the data modules and draws the format information.
+    fn apply_mask(&self, matrix: &EncodedMatrix, function: &EncodedMatrix, mask: u8) -> EncodedMatrix {
+        let size = self.size();
+        let mut masked = matrix.clone();
+
+        for y in 0..size {
+            for x in 0..size {
+                if function.get(x, y) == 0 && mask_bit(mask, x, y) {
+                    masked.set(x, y, 1 - matrix.get(x, y));
+                }
+            }
+        }
+
+        let format = format_information(self.ec_level.format_bits(), mask as u32);
+        let bit = |i: usize| ((format >> i) & 1) as u8;
+
+        for i in 0..6 {
+            masked.set(8, i, bit(i));
+        }
+
+        masked.set(8, 7, bit(6));
+        masked.set(8, 8, bit(7));
+        masked.set(7, 8, bit(8));
+
+        for i in 9..15 {
+            masked.set(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            masked.set(size - 1 - i, 8, bit(i));
+        }
+
+        for i in 8..15 {
+            masked.set(8, size - 15 + i, bit(i));
+        }
+
+        masked
+    }
+
+    /// Returns the mask pattern (0-7) applied to the symbol.
+    pub fn mask(&self) -> u8 {
+        self.best_mask().0
+    }
+
+    fn best_mask(&self) -> (u8, EncodedMatrix) {
+        let (mut matrix, function) = self.function_patterns();
+        self.place_codewords(&mut matrix, &function);
+
+        (0..8u8)
+            .map(|mask| (mask, self.apply_mask(&matrix, &function, mask)))
+            .min_by_key(|(_, m)| penalty_score(m))
+            .unwrap()
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules, without a quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        self.best_mask().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::qr::*;
+
+    fn collapse_rows(matrix: &EncodedMatrix) -> Vec<String> {
+        matrix.rows().iter()
+            .map(|r| r.iter().map(|&m| if m == 1 { '1' } else { '0' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn new_qr() {
+        let qr1 = QRCode::new("HELLO WORLD".to_string());
+        let qr2 = QRCode::with_ec_level("https://example.com/".to_string(), ECLevel::H);
+
+        assert!(qr1.is_ok());
+        assert!(qr2.is_ok());
+    }
+
+    #[test]
+    fn invalid_len_qr() {
+        let qr1 = QRCode::with_ec_level("9".repeat(7090), ECLevel::L);
+        let qr2 = QRCode::with_ec_level("x".repeat(1274), ECLevel::H);
+
+        assert!(qr1.is_err());
+        assert!(qr2.is_err());
+    }
+
+    #[test]
+    fn qr_raw_data() {
+        let qr = QRCode::new("HELLO WORLD".to_string()).unwrap();
+
+        assert_eq!(qr.raw_data(), "HELLO WORLD");
+    }
+
+    #[test]
+    fn qr_mode_selection() {
+        assert_eq!(QRCode::new("0123456789".to_string()).unwrap().mode(), Mode::Numeric);
+        assert_eq!(QRCode::new("HELLO WORLD".to_string()).unwrap().mode(), Mode::Alphanumeric);
+        assert_eq!(QRCode::new("Hello World".to_string()).unwrap().mode(), Mode::Byte);
+    }
+
+    #[test]
+    fn qr_version_selection() {
+        let qr1 = QRCode::with_ec_level("9".repeat(7089), ECLevel::L).unwrap();
+        let qr2 = QRCode::with_ec_level("x".repeat(1273), ECLevel::H).unwrap();
+        let qr3 = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::H).unwrap();
+
+        assert_eq!(qr1.version(), 40);
+        assert_eq!(qr2.version(), 40);
+        assert_eq!(qr3.version(), 2);
+        assert_eq!(qr3.size(), 25);
+    }
+
+    #[test]
+    fn qr_codewords() {
+        let qr = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::Q).unwrap();
+
+        assert_eq!(qr.data_codewords(), vec![32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236]);
+        assert_eq!(qr.codewords()[13..].to_vec(), vec![168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]);
+    }
+
+    #[test]
+    fn qr_encode() {
+        let qr = QRCode::with_ec_level("HELLO WORLD".to_string(), ECLevel::Q).unwrap();
+
+        assert_eq!(qr.mask(), 0);
+        assert_eq!(collapse_rows(&qr.encode()), vec![
+            "111111101100001111111", "100000101001001000001", "101110101001101011101",
+            "101110101000001011101", "101110101010001011101", "100000100010001000001",
+            "111111101010101111111", "000000001000000000000", "011010110000101011111",
+            "010000001111000010001", "001101110110001011000", "011011010011010101110",
+            "100010101011101110101", "000000001101001000101", "111111101010000101100",
+            "100000100101101101000", "101110101010001111111", "101110100101010100010",
+            "101110101001011101001", "100000101011110001011", "111111100001011100001",
+        ]);
+    }
+}
@@ -0,0 +1,530 @@
+//! This module provides types for encoding rMQR (rectangular Micro QR Code) barcodes. rMQR is
+//! a rectangular variant of QR Code, 7 to 17 modules high and 27 to 139 modules wide, for narrow
+//! spaces such as label strips where a square symbol does not fit.
+//!
+//! There are 32 versions (sizes), R7x43 to R17x139, holding up to 361 digits at error correction
+//! level M or H. The data segments and Reed-Solomon error correction are shared with QR Code, but
+//! each version has its own capacities, block structure and character count indicator lengths,
+//! and a single mask pattern is always applied. The version of smallest area that holds the data,
+//! optionally within a maximum height and width, is used.
+
+use ::sym::helpers;
+use ::sym2d::EncodedMatrix;
+use ::sym2d::qr::{self, ECLevel, Mode};
+
+/// The height and width of each of the 32 versions (R7x43 to R17x139), in modules.
+pub const RMQR_VERSIONS: [(usize, usize); 32] = [
+    (7, 43), (7, 59), (7, 77), (7, 99), (7, 139),
+    (9, 43), (9, 59), (9, 77), (9, 99), (9, 139),
+    (11, 27), (11, 43), (11, 59), (11, 77), (11, 99), (11, 139),
+    (13, 27), (13, 43), (13, 59), (13, 77), (13, 99), (13, 139),
+    (15, 43), (15, 59), (15, 77), (15, 99), (15, 139),
+    (17, 43), (17, 59), (17, 77), (17, 99), (17, 139),
+];
+
+/// The total number of codewords of each version.
+const RMQR_CODEWORDS: [usize; 32] = [
+    13, 21, 32, 44, 68, 21, 33, 49, 66, 99, 15, 31, 47, 67, 89, 132,
+    21, 41, 60, 85, 113, 166, 51, 74, 103, 136, 199, 61, 88, 122, 160, 232,
+];
+
+/// The number of data codewords, by error correction level (M, H) and version.
+const RMQR_DATA_CODEWORDS: [[usize; 32]; 2] = [
+    [6, 12, 20, 28, 44, 12, 21, 31, 42, 63, 7, 19, 31, 43, 57, 84,
+     12, 27, 38, 53, 73, 106, 33, 48, 67, 88, 127, 39, 56, 78, 100, 152],
+    [3, 7, 10, 14, 24, 7, 11, 17, 22, 33, 5, 11, 15, 23, 29, 42,
+     7, 13, 20, 29, 35, 54, 15, 26, 31, 48, 69, 21, 28, 38, 56, 76],
+];
+
+/// The number of error correction blocks, by error correction level (M, H) and version.
+const RMQR_ECC_BLOCKS: [[usize; 32]; 2] = [
+    [1, 1, 1, 1, 2, 1, 1, 1, 2, 2, 1, 1, 1, 1, 2, 2,
+     1, 1, 1, 2, 2, 3, 1, 1, 2, 2, 3, 1, 1, 2, 2, 4],
+    [1, 1, 1, 1, 2, 1, 1, 2, 2, 3, 1, 1, 2, 2, 2, 3,
+     1, 1, 2, 2, 3, 4, 2, 2, 3, 4, 5, 2, 2, 3, 4, 6],
+];
+
+/// The number of bits of the character count indicator, by mode (numeric, alphanumeric, byte)
+/// and version.
+const RMQR_COUNT_BITS: [[usize; 32]; 3] = [
+    [4, 5, 6, 7, 7, 5, 6, 7, 7, 8, 4, 6, 7, 7, 8, 8, 5, 6, 7, 7, 8, 8, 7, 7, 8, 8, 9, 7, 8, 8, 8, 9],
+    [3, 5, 5, 6, 6, 5, 5, 6, 6, 7, 4, 5, 6, 6, 7, 7, 5, 6, 6, 7, 7, 8, 6, 7, 7, 7, 8, 6, 7, 7, 8, 8],
+    [3, 4, 5, 5, 6, 4, 5, 5, 6, 6, 3, 5, 5, 6, 6, 7, 4, 5, 6, 6, 7, 7, 6, 6, 7, 7, 7, 6, 6, 7, 7, 8],
+];
+
+/// The patterns XORed with the format information beside the finder pattern and beside the
+/// sub-finder pattern.
+const RMQR_FORMAT_MASKS: [u32; 2] = [0x1FAB2, 0x20A7B];
+
+/// The QR Code mask pattern applied to every rMQR symbol.
+const RMQR_MASK: u8 = 4;
+
+/// The largest height and width of a symbol, in modules.
+const RMQR_MAX_SIZE: (usize, usize) = (17, 139);
+
+/// Returns the height and width of a version (1-32), in modules.
+pub fn size(version: u8) -> (usize, usize) {
+    RMQR_VERSIONS[version as usize - 1]
+}
+
+/// Returns the versions that fit within the given height and width, smallest area first.
+pub fn versions_fitting(max_height: usize, max_width: usize) -> Vec<u8> {
+    let mut versions: Vec<u8> = (1..=RMQR_VERSIONS.len() as u8)
+        .filter(|&v| size(v).0 <= max_height && size(v).1 <= max_width)
+        .collect();
+
+    versions.sort_by_key(|&v| size(v).0 * size(v).1);
+    versions
+}
+
+/// Returns the columns of the vertical timing patterns, which have an alignment pattern at
+/// each end.
+pub fn alignment_columns(version: u8) -> &'static [usize] {
+    match size(version).1 {
+        43 => &[21],
+        59 => &[19, 39],
+        77 => &[25, 51],
+        99 => &[23, 49, 75],
+        139 => &[27, 55, 83, 111],
+        _ => &[],
+    }
+}
+
+// The index of an error correction level in the capacity tables. Levels L and Q are not
+// available.
+fn level_index(level: ECLevel) -> Option<usize> {
+    match level {
+        ECLevel::M => Some(0),
+        ECLevel::H => Some(1),
+        _ => None,
+    }
+}
+
+// The number of bits used for the character count indicator.
+fn count_bits(mode: Mode, version: u8) -> usize {
+    let row = match mode {
+        Mode::Numeric => 0,
+        Mode::Alphanumeric => 1,
+        Mode::Byte => 2,
+    };
+
+    RMQR_COUNT_BITS[row][version as usize - 1]
+}
+
+// The three-bit mode indicator.
+fn indicator(mode: Mode) -> u32 {
+    match mode {
+        Mode::Numeric => 0b001,
+        Mode::Alphanumeric => 0b010,
+        Mode::Byte => 0b011,
+    }
+}
+
+/// The rMQR barcode type.
+pub struct RMQR {
+    data: String,
+    mode: Mode,
+    version: u8,
+    ec_level: ECLevel,
+}
+
+impl RMQR {
+    /// Creates a new barcode with error correction level M.
+    /// Returns Result<RMQR, String> indicating success.
+    pub fn new(data: String) -> Result<RMQR, String> {
+        RMQR::with_ec_level(data, ECLevel::M)
+    }
+
+    /// Creates a new barcode with the given error correction level (M or H).
+    /// Returns Result<RMQR, String> indicating success.
+    pub fn with_ec_level(data: String, ec_level: ECLevel) -> Result<RMQR, String> {
+        RMQR::with_max_size(data, ec_level, RMQR_MAX_SIZE.0, RMQR_MAX_SIZE.1)
+    }
+
+    /// Creates a new barcode with the given error correction level (M or H), using a version
+    /// that fits within the given height and width in modules.
+    /// Returns Result<RMQR, String> indicating success.
+    pub fn with_max_size(data: String, ec_level: ECLevel, max_height: usize, max_width: usize) -> Result<RMQR, String> {
+        let level = match level_index(ec_level) {
+            Some(level) => level,
+            None => return Err("rMQR only supports error correction levels M and H".to_string()),
+        };
+
+        let mode = Mode::for_data(&data);
+        let len = qr::data_bits(&data, mode).len();
+        let count = qr::char_count(&data, mode);
+
+        let version = versions_fitting(max_height, max_width).into_iter().find(|&v| {
+            count < 1 << count_bits(mode, v) &&
+                3 + count_bits(mode, v) + len <= RMQR_DATA_CODEWORDS[level][v as usize - 1] * 8
+        });
+
+        match version {
+            Some(version) => Ok(RMQR{data, mode, version, ec_level}),
+            None => Err("Data too long to encode in an rMQR Code of this size".to_string()),
+        }
+    }
+
+    /// Returns the data as was passed into the constructor.
+    pub fn raw_data(&self) -> &str {
+        &self.data[..]
+    }
+
+    /// Returns the mode used to encode the data.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the version (1-32, for R7x43 to R17x139) of the symbol, which determines its
+    /// size.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the error correction level.
+    pub fn ec_level(&self) -> ECLevel {
+        self.ec_level
+    }
+
+    /// Returns the height and width of the symbol in modules.
+    pub fn size(&self) -> (usize, usize) {
+        size(self.version)
+    }
+
+    /// Returns the data codewords: the mode indicator, character count and data, followed by
+    /// padding.
+    pub fn data_codewords(&self) -> Vec<u8> {
+        let level = level_index(self.ec_level).unwrap();
+        let mut bits = vec![];
+        helpers::push_bits(&mut bits, indicator(self.mode), 3);
+        helpers::push_bits(&mut bits, qr::char_count(&self.data, self.mode) as u32, count_bits(self.mode, self.version));
+        bits.extend(qr::data_bits(&self.data, self.mode));
+
+        qr::pad_codewords(&bits, RMQR_DATA_CODEWORDS[level][self.version as usize - 1], 3)
+    }
+
+    /// Returns the final sequence of codewords: the data codewords split into blocks, each
+    /// followed by its error correction codewords, interleaved.
+    pub fn codewords(&self) -> Vec<u8> {
+        let level = level_index(self.ec_level).unwrap();
+        let block_count = RMQR_ECC_BLOCKS[level][self.version as usize - 1];
+        let data = self.data_codewords();
+        let ecc_len = (RMQR_CODEWORDS[self.version as usize - 1] - data.len()) / block_count;
+        let short_blocks = block_count - data.len() % block_count;
+        let short_len = data.len() / block_count;
+
+        let mut blocks = vec![];
+        let mut start = 0;
+
+        for i in 0..block_count {
+            let len = if i < short_blocks { short_len } else { short_len + 1 };
+            blocks.push((&data[start..(start + len)], qr::ecc_codewords(&data[start..(start + len)], ecc_len)));
+            start += len;
+        }
+
+        let mut codewords = vec![];
+
+        for i in 0..(short_len + 1) {
+            codewords.extend(blocks.iter().filter_map(|b| b.0.get(i)));
+        }
+
+        for i in 0..ecc_len {
+            codewords.extend(blocks.iter().map(|b| b.1[i]));
+        }
+
+        codewords
+    }
+
+    // Draws the finder, sub-finder, corner finder, timing and alignment patterns, and reserves
+    // the format information areas. Returns the matrix and a mask of the function modules.
+    fn function_patterns(&self) -> (EncodedMatrix, EncodedMatrix) {
+        let (height, width) = self.size();
+        let columns = alignment_columns(self.version);
+        let mut matrix = EncodedMatrix::new(width, height);
+        let mut function = EncodedMatrix::new(width, height);
+
+        {
+            let mut set = |x: usize, y: usize, dark: bool| {
+                matrix.set(x, y, dark as u8);
+                function.set(x, y, 1);
+            };
+
+            // Timing patterns surround the symbol and run down each alignment column.
+            for x in 0..width {
+                set(x, 0, x % 2 == 0);
+                set(x, height - 1, x % 2 == 0);
+            }
+
+            for y in 0..height {
+                for &x in [0, width - 1].iter().chain(columns) {
+                    set(x, y, y % 2 == 0);
+                }
+            }
+
+            for &cx in columns {
+                for &cy in &[1, height - 2] {
+                    for dy in 0..3 {
+                        for dx in 0..3 {
+                            set(cx + dx - 1, cy + dy - 1, dx != 1 || dy != 1);
+                        }
+                    }
+                }
+            }
+
+            // The finder pattern, with its separator.
+            for y in 0..height.min(8) {
+                for x in 0..8 {
+                    let dist = (x as i32 - 3).abs().max((y as i32 - 3).abs());
+                    set(x, y, dist != 2 && dist != 4);
+                }
+            }
+
+            for dy in 0..5 {
+                for dx in 0..5 {
+                    let dist = (dx as i32 - 2).abs().max((dy as i32 - 2).abs());
+                    set(width - 5 + dx, height - 5 + dy, dist != 1);
+                }
+            }
+
+            // The corner finder patterns. In symbols 7 or 9 modules high, the finder pattern and
+            // its separator take the place of most of the bottom left one.
+            set(width - 2, 0, true);
+            set(width - 2, 1, false);
+            set(width - 1, 1, true);
+            set(1, height - 1, true);
+
+            if height > 9 {
+                set(0, height - 2, true);
+                set(1, height - 2, false);
+            }
+
+            // The format information is drawn after masking, but its modules are reserved here.
+            for i in 0..18 {
+                let (left, right) = format_positions(width, height, i);
+                set(left.0, left.1, false);
+                set(right.0, right.1, false);
+            }
+        }
+
+        (matrix, function)
+    }
+
+    // Places the codewords in two-module wide columns, zigzagging upwards and downwards from
+    // the bottom right, skipping function modules.
+    fn place_codewords(&self, matrix: &mut EncodedMatrix, function: &EncodedMatrix) {
+        let (height, width) = self.size();
+        let bits: Vec<u8> = self.codewords().iter()
+            .flat_map(|&c| (0..8).rev().map(move |i| (c >> i) & 1))
+            .collect();
+        let mut i = 0;
+        let mut right = width - 2;
+        let mut upward = true;
+
+        loop {
+            for vert in 0..height {
+                for x in (right.saturating_sub(1)..=right).rev() {
+                    let y = if upward { height - 1 - vert } else { vert };
+
+                    if function.get(x, y) == 0 && i < bits.len() {
+                        matrix.set(x, y, bits[i]);
+                        i += 1;
+                    }
+                }
+            }
+
+            if right < 2 {
+                break;
+            }
+
+            upward = !upward;
+            right -= 2;
+        }
+    }
+
+    /// Encodes the barcode.
+    /// Returns an EncodedMatrix of the symbol's modules, without a quiet zone.
+    pub fn encode(&self) -> EncodedMatrix {
+        let (height, width) = self.size();
+        let (mut matrix, function) = self.function_patterns();
+        self.place_codewords(&mut matrix, &function);
+
+        for y in 0..height {
+            for x in 0..width {
+                if function.get(x, y) == 0 && qr::mask_bit(RMQR_MASK, x, y) {
+                    let m = matrix.get(x, y);
+                    matrix.set(x, y, 1 - m);
+                }
+            }
+        }
+
+        let info = qr::version_information((self.ec_level == ECLevel::H) as u8 * 32 + self.version - 1);
+        let left = info ^ RMQR_FORMAT_MASKS[0];
+        let right = info ^ RMQR_FORMAT_MASKS[1];
+
+        for i in 0..18 {
+            let (l, r) = format_positions(width, height, i);
+            matrix.set(l.0, l.1, ((left >> i) & 1) as u8);
+            matrix.set(r.0, r.1, ((right >> i) & 1) as u8);
+        }
+
+        matrix
+    }
+}
+
+// The positions of bit `i` of the format information beside the finder pattern and beside the
+// sub-finder pattern. Each is a block of three columns of five bits, then three more bits.
+fn format_positions(width: usize, height: usize, i: usize) -> ((usize, usize), (usize, usize)) {
+    if i < 15 {
+        ((8 + i / 5, 1 + i % 5), (width - 8 + i / 5, height - 6 + i % 5))
+    } else {
+        ((11, i - 14), (width - 20 + i, height - 6))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sym2d::rmqr::*;
+
+    fn collapse_rows(matrix: &EncodedMatrix) -> Vec<String> {
+        matrix.rows().iter()
+            .map(|r| r.iter().map(|&m| if m == 1 { '1' } else { '0' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn rmqr_size() {
+        assert_eq!(size(1), (7, 43));
+        assert_eq!(size(11), (11, 27));
+        assert_eq!(size(32), (17, 139));
+    }
+
+    #[test]
+    fn rmqr_versions_fitting() {
+        assert_eq!(versions_fitting(7, 60), vec![1, 2]);
+        assert_eq!(versions_fitting(11, 43), vec![11, 1, 6, 12]);
+        assert!(versions_fitting(5, 139).is_empty());
+    }
+
+    #[test]
+    fn rmqr_alignment_columns() {
+        assert!(alignment_columns(11).is_empty());
+        assert_eq!(alignment_columns(5), &[27, 55, 83, 111]);
+    }
+
+    #[test]
+    fn new_rmqr() {
+        let rmqr1 = RMQR::new("12345".to_string());
+        let rmqr2 = RMQR::with_ec_level("https://example.com/".to_string(), ECLevel::H);
+
+        assert!(rmqr1.is_ok());
+        assert!(rmqr2.is_ok());
+    }
+
+    #[test]
+    fn invalid_rmqr() {
+        let rmqr1 = RMQR::new("9".repeat(362));
+        let rmqr2 = RMQR::new("x".repeat(151));
+        let rmqr3 = RMQR::with_ec_level("123".to_string(), ECLevel::L);
+        let rmqr4 = RMQR::with_max_size("HELLO WORLD".to_string(), ECLevel::H, 7, 43);
+
+        assert!(rmqr1.is_err());
+        assert!(rmqr2.is_err());
+        assert!(rmqr3.is_err());
+        assert!(rmqr4.is_err());
+    }
+
+    #[test]
+    fn rmqr_raw_data() {
+        let rmqr = RMQR::new("HELLO WORLD".to_string()).unwrap();
+
+        assert_eq!(rmqr.raw_data(), "HELLO WORLD");
+    }
+
+    #[test]
+    fn rmqr_version_selection() {
+        let rmqr1 = RMQR::new("12345".to_string()).unwrap();
+        let rmqr2 = RMQR::with_max_size("12345".to_string(), ECLevel::M, 7, 139).unwrap();
+        let rmqr3 = RMQR::new("9".repeat(361)).unwrap();
+        let rmqr4 = RMQR::with_ec_level("x".repeat(74), ECLevel::H).unwrap();
+
+        assert_eq!(rmqr1.version(), 11);
+        assert_eq!(rmqr1.size(), (11, 27));
+        assert_eq!(rmqr2.version(), 1);
+        assert_eq!(rmqr3.version(), 32);
+        assert_eq!(rmqr4.version(), 32);
+        assert_eq!(rmqr4.mode(), Mode::Byte);
+    }
+
+    #[test]
+    fn rmqr_codewords() {
+        let rmqr1 = RMQR::with_max_size("12345".to_string(), ECLevel::M, 7, 43).unwrap();
+        let rmqr2 = RMQR::with_ec_level("9".repeat(170), ECLevel::H).unwrap();
+
+        assert_eq!(rmqr1.data_codewords(), vec![42, 61, 173, 0, 236, 17]);
+        assert_eq!(rmqr1.codewords()[6..].to_vec(), qr::ecc_codewords(&rmqr1.data_codewords(), 7));
+
+        // Two blocks of 12 and four of 13 data codewords, each with 26 error correction
+        // codewords.
+        let data = rmqr2.data_codewords();
+        let codewords = rmqr2.codewords();
+
+        assert_eq!(rmqr2.version(), 32);
+        assert_eq!(codewords.len(), 232);
+        assert_eq!(codewords[..6].to_vec(), vec![data[0], data[12], data[24], data[37], data[50], data[63]]);
+        assert_eq!(codewords[72..76].to_vec(), vec![data[36], data[49], data[62], data[75]]);
+        assert_eq!(codewords[76..].chunks(6).map(|c| c[5]).collect::<Vec<u8>>(), qr::ecc_codewords(&data[63..], 26));
+    }
+
+    #[test]
+    fn rmqr_function_patterns() {
+        let rows = collapse_rows(&RMQR::with_max_size("12345".to_string(), ECLevel::M, 7, 43).unwrap().encode());
+
+        assert_eq!(rows.len(), 7);
+        assert!(rows.iter().all(|r| r.len() == 43));
+
+        // The finder pattern and separator, the alignment pattern at column 21 and the corner
+        // finder pattern.
+        assert_eq!(&rows[0][..8], "11111110");
+        assert_eq!(&rows[2][..8], "10111010");
+        assert_eq!(&rows[0][20..], "11101010101010101010111");
+        assert_eq!(&rows[1][20..23], "101");
+        assert_eq!(&rows[1][41..], "01");
+
+        // The sub-finder pattern.
+        assert_eq!(&rows[2][38..], "11111");
+        assert_eq!(&rows[3][38..], "10001");
+        assert_eq!(&rows[4][38..], "10101");
+        assert_eq!(&rows[6][20..], "11101010101010101011111");
+    }
+
+    #[test]
+    fn rmqr_format_information() {
+        for &(data, level) in &[("12345", ECLevel::M), ("HELLO WORLD", ECLevel::H)] {
+            let rmqr = RMQR::with_ec_level(data.to_string(), level).unwrap();
+            let matrix = rmqr.encode();
+            let (height, width) = rmqr.size();
+            let (left, right) = (0..18).fold((0, 0), |(l, r), i| {
+                let (a, b) = format_positions(width, height, i);
+                (l | (matrix.get(a.0, a.1) as u32) << i, r | (matrix.get(b.0, b.1) as u32) << i)
+            });
+            let info = left ^ RMQR_FORMAT_MASKS[0];
+
+            assert_eq!(info, right ^ RMQR_FORMAT_MASKS[1]);
+            assert_eq!(info >> 12, (level == ECLevel::H) as u32 * 32 + rmqr.version() as u32 - 1);
+            assert_eq!(info, qr::version_information((info >> 12) as u8));
+        }
+    }
+
+    #[test]
+    fn rmqr_function_modules() {
+        // The modules left for codewords are those of each version's codewords, with fewer than
+        // eight remainder modules.
+        for version in 1..=32 {
+            let rmqr = RMQR{data: String::new(), mode: Mode::Numeric, version, ec_level: ECLevel::M};
+            let (_, function) = rmqr.function_patterns();
+            let free = function.modules.iter().filter(|&&m| m == 0).count();
+
+            assert_eq!(free / 8, RMQR_CODEWORDS[version as usize - 1]);
+        }
+    }
+}